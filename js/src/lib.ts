@@ -30,7 +30,7 @@ function makeApiVersion(major: number, minor: number, patch: number) {
 }
 
 export function voidTargetVersion() {
-  return makeApiVersion(0, 0, 20)
+  return makeApiVersion(0, 0, 44)
 }
 
 export enum EcsType {
@@ -46,6 +46,18 @@ export enum ArgType {
   EventReader,
   EventWriter,
   Query,
+  DataAccessOptionalMut,
+  DataAccessOptionalRef,
+  With,
+  Without,
+  Added,
+  Changed,
+  RemovedComponents,
+  /**
+   * Makes the system exclusive: it executes alone, after other CPU systems, and
+   * its engine calls are applied when it returns.
+   */
+  World,
 }
 
 export enum CreatePendingTexture {
@@ -302,8 +314,15 @@ export interface Engine {
 
   // Engine API's
   spawn(components: FiascoPointer, componentsLength: number): bigint
+  spawnBatch(components: FiascoPointer, componentsLength: number, count: number): bigint[]
+  spawnPrefab(name: string, components: FiascoPointer, componentsLength: number): bigint
   despawn(entityId: bigint): void
-  loadScene(json: string): void
+  despawnNonRecursive(entityId: bigint): void
+  loadScene(json: string): bigint
+  loadSceneFromPath(assetPath: string): bigint
+  unloadScene(sceneHandle: bigint): void
+  registerPrefab(name: string, json: string): void
+  registerPrefabFromPath(assetPath: string): void
   addComponents(entityId: bigint, components: FiascoPointer, size: number): void
   removeComponents(entityId: bigint, componentIds: ArrayBuffer): void
   getEntityLabel(entityId: bigint): string | undefined
@@ -312,6 +331,7 @@ export interface Engine {
   queryGet(queryPtr: FiascoPointer, index: number, componentPtrs: FiascoPointer): boolean
   queryGetEntity(queryPtr: FiascoPointer, entityId: bigint, componentPtrs: FiascoPointer): boolean
   queryGetLabel(queryPtr: FiascoPointer, label: string, componentPtrs: FiascoPointer): boolean
+  queryIsChanged(queryPtr: FiascoPointer): boolean
   setSystemEnabled(systemName: string, enabled: boolean): void
   eventCount(eventReaderPtr: FiascoPointer): number
   eventGet(eventReaderPtr: FiascoPointer, index: number): FiascoPointer
@@ -319,6 +339,7 @@ export interface Engine {
   setParent(entityId: bigint, parentId: bigint, keepWorldSpaceTransform: boolean): void
   clearParent(entityId: bigint, keepWorldSpaceTransform: boolean): void
   getParent(entityId: bigint): bigint | "no_parent" | "invalid_id"
+  getChildren(entityId: bigint): bigint[] | "invalid_id"
 
   // Texture Asset Manager
   gpuInterfaceGetTextureAssetManagerMut(gpuInterface: FiascoPointer): FiascoPointer
@@ -547,6 +568,12 @@ export interface EcsModule {
    */
   componentDeserializeJson(stringId: string, ptr: FiascoPointer, json: string): number
 
+  /**
+   * When saving a scene, the engine will pass a pointer to a component for the module to serialize into the
+   * JSON representation read by `componentDeserializeJson`.
+   */
+  componentSerializeJson(stringId: string, ptr: FiascoPointer): string
+
   /**
    * Returns the component size in bytes, for each component returned by `componentStringId`.
    * Called for both components and resources.
@@ -7,7 +7,7 @@ use void_public::{
 };
 
 #[repr(C)]
-#[derive(Clone, Copy, Default, serde::Deserialize)]
+#[derive(Clone, Copy, Default, serde::Deserialize, serde::Serialize)]
 pub enum PlaybackDirection {
     #[default]
     Forward,
@@ -15,7 +15,7 @@ pub enum PlaybackDirection {
 }
 
 #[repr(C)]
-#[derive(Component, Default, serde::Deserialize)]
+#[derive(Component, Default, serde::Deserialize, serde::Serialize)]
 pub struct SpriteAnimation {
     #[serde(default)]
     pub playback_direction: PlaybackDirection,
@@ -2,9 +2,9 @@ use game_module_macro::Component;
 use void_public::{Component, ComponentId, EcsType};
 
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct BoxCollider;
 
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct CircleCollider;
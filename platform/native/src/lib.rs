@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use native_common::game_engine::void_public::{self, event::input::KeyboardInput};
 use winit::{
@@ -29,6 +31,20 @@ pub struct EnvArgs {
     /// The folder that contains the built JS ECS Modules.
     #[arg(short, long, default_value = "modules")]
     pub modules_dir: String,
+
+    /// Enables profiling, and writes a Chrome trace of the last frames to this
+    /// file on exit.
+    #[arg(long)]
+    pub trace_file: Option<PathBuf>,
+
+    /// Converts this JSON scene to the binary scene format with the loaded
+    /// modules' components, writes it to `--output`, and exits.
+    #[arg(long, requires = "output")]
+    pub convert_scene: Option<PathBuf>,
+
+    /// The file to write the converted binary scene to.
+    #[arg(long)]
+    pub output: Option<PathBuf>,
 }
 
 pub fn to_engine_keyboard_input(input: &KeyEvent) -> Result<KeyboardInput, Error> {
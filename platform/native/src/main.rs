@@ -1,3 +1,5 @@
+use std::{error::Error, fs, path::Path, process::ExitCode};
+
 use clap::Parser;
 use log::LevelFilter;
 use native_common::{
@@ -18,7 +20,7 @@ use winit::{
     window::WindowBuilder,
 };
 
-fn main() {
+fn main() -> ExitCode {
     #[cfg(debug_assertions)]
     env_logger::builder().filter_level(LevelFilter::Info).init();
 
@@ -30,6 +32,7 @@ fn main() {
     let event_loop = EventLoop::new().unwrap();
     let window = WindowBuilder::new()
         .with_title(&env_args.window_title)
+        .with_visible(env_args.convert_scene.is_none())
         .build(&event_loop)
         .unwrap();
 
@@ -46,14 +49,37 @@ fn main() {
 
     let mut engine = NativeGameEngine::new(gpu, width, height, &js_options);
 
+    if let (Some(scene_path), Some(output)) = (&env_args.convert_scene, &env_args.output) {
+        return match convert_scene(&engine, scene_path, output) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                log::error!("could not convert {}: {err}", scene_path.display());
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if env_args.trace_file.is_some() {
+        engine.set_profiling_enabled(true);
+    }
+
     event_loop.set_control_flow(ControlFlow::Poll);
 
+    let mut exit_code = ExitCode::SUCCESS;
+    let exit_code_ref = &mut exit_code;
+
     event_loop
         .run(move |event, window| match event {
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
             } => {
+                if let Some(trace_file) = &env_args.trace_file {
+                    if let Err(err) = fs::write(trace_file, engine.chrome_trace()) {
+                        log::error!("could not write trace to {}: {err}", trace_file.display());
+                    }
+                }
+
                 window.exit();
             }
             Event::WindowEvent {
@@ -121,8 +147,27 @@ fn main() {
             }
             Event::AboutToWait => {
                 engine.frame();
+
+                if engine.is_aborted() {
+                    *exit_code_ref = ExitCode::FAILURE;
+                    window.exit();
+                }
             }
             _ => {}
         })
         .unwrap();
+
+    exit_code
+}
+
+/// Converts a JSON scene to the binary scene format.
+fn convert_scene(
+    engine: &NativeGameEngine,
+    scene_path: &Path,
+    output: &Path,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let scene_file = fs::read_to_string(scene_path)?;
+    let binary_scene = engine.convert_scene_to_binary(&scene_file)?;
+    fs::write(output, binary_scene)?;
+    Ok(())
 }
@@ -39,8 +39,15 @@ class NativeEngine implements Engine {
 
   // Engine API's
   readonly spawn = Extension.spawn
+  readonly spawnBatch = Extension.spawnBatch
+  readonly spawnPrefab = Extension.spawnPrefab
   readonly despawn = Extension.despawn
+  readonly despawnNonRecursive = Extension.despawnNonRecursive
   readonly loadScene = Extension.loadScene
+  readonly loadSceneFromPath = Extension.loadSceneFromPath
+  readonly unloadScene = Extension.unloadScene
+  readonly registerPrefab = Extension.registerPrefab
+  readonly registerPrefabFromPath = Extension.registerPrefabFromPath
   readonly inputBufferPointer = Extension.inputBufferPointer
   readonly inputBufferLength = Extension.inputBufferLength
   readonly addComponents = Extension.addComponents
@@ -51,10 +58,12 @@ class NativeEngine implements Engine {
   readonly queryGet = Extension.queryGet
   readonly queryGetEntity = Extension.queryGetEntity
   readonly queryGetLabel = Extension.queryGetLabel
+  readonly queryIsChanged = Extension.queryIsChanged
   readonly setSystemEnabled = Extension.setSystemEnabled
   readonly setParent = Extension.setParent
   readonly clearParent = Extension.clearParent
   readonly getParent = Extension.getParent
+  readonly getChildren = Extension.getChildren
   readonly eventCount = Extension.eventCount
   readonly eventGet = Extension.eventGet
   readonly eventSend = Extension.eventSend
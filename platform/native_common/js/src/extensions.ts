@@ -45,8 +45,15 @@ globalThis.Extension = {
 
   // Engine API's
   spawn: ops.op_fiasco_spawn,
+  spawnBatch: ops.op_fiasco_spawn_batch,
+  spawnPrefab: ops.op_fiasco_spawn_prefab,
   despawn: ops.op_fiasco_despawn,
+  despawnNonRecursive: ops.op_fiasco_despawn_non_recursive,
   loadScene: ops.op_fiasco_load_scene,
+  loadSceneFromPath: ops.op_fiasco_load_scene_from_path,
+  unloadScene: ops.op_fiasco_unload_scene,
+  registerPrefab: ops.op_fiasco_register_prefab,
+  registerPrefabFromPath: ops.op_fiasco_register_prefab_from_path,
   inputBufferPointer: ops.op_fiasco_input_buffer_ptr,
   inputBufferLength: ops.op_fiasco_input_buffer_len,
   addComponents: ops.op_fiasco_add_components,
@@ -57,10 +64,12 @@ globalThis.Extension = {
   queryGet: ops.op_fiasco_query_get,
   queryGetEntity: ops.op_fiasco_query_get_entity,
   queryGetLabel: ops.op_fiasco_query_get_label,
+  queryIsChanged: ops.op_fiasco_query_is_changed,
   setSystemEnabled: ops.op_fiasco_set_system_enabled,
   setParent: ops.op_fiasco_set_parent,
   clearParent: ops.op_fiasco_clear_parent,
   getParent: ops.op_fiasco_get_parent,
+  getChildren: ops.op_fiasco_get_children,
   eventCount: ops.op_fiasco_event_count,
   eventGet: ops.op_fiasco_event_get,
   eventSend: ops.op_fiasco_event_send,
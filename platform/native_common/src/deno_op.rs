@@ -6,6 +6,7 @@ use std::{
     mem::MaybeUninit,
     num::NonZero,
     ptr::{self, null_mut},
+    slice,
 };
 
 use deno_core::v8;
@@ -23,7 +24,7 @@ use game_engine::{
     game_ecs::{EcsSystemExecuteResources, system_execute_resources},
     module_api,
     void_public::{
-        ComponentId, ComponentRef,
+        ComponentId, ComponentRef, SceneHandle,
         graphics::{TextureId, TextureType},
         text::{TextId, TextType},
     },
@@ -70,8 +71,15 @@ deno_core::extension!(
 
     // Engine API's
     op_fiasco_load_scene,
+    op_fiasco_load_scene_from_path,
+    op_fiasco_unload_scene,
+    op_fiasco_register_prefab,
+    op_fiasco_register_prefab_from_path,
     op_fiasco_spawn,
+    op_fiasco_spawn_batch,
+    op_fiasco_spawn_prefab,
     op_fiasco_despawn,
+    op_fiasco_despawn_non_recursive,
     op_fiasco_add_components,
     op_fiasco_remove_components,
     op_fiasco_get_entity_label,
@@ -80,6 +88,7 @@ deno_core::extension!(
     op_fiasco_query_get,
     op_fiasco_query_get_entity,
     op_fiasco_query_get_label,
+    op_fiasco_query_is_changed,
     op_fiasco_set_system_enabled,
     op_fiasco_event_count,
     op_fiasco_event_get,
@@ -87,6 +96,7 @@ deno_core::extension!(
     op_fiasco_set_parent,
     op_fiasco_clear_parent,
     op_fiasco_get_parent,
+    op_fiasco_get_children,
     op_fiasco_input_buffer_ptr,
     op_fiasco_input_buffer_len,
 
@@ -298,9 +308,43 @@ pub fn op_fiasco_write_ptr(ptr: *mut c_void, #[number] offset: isize, val: *mut
 }
 
 #[op2(fast, stack_trace)]
-pub fn op_fiasco_load_scene(#[string] scene_json: &str) {
-    let scene_str = CString::new(scene_json).unwrap();
-    module_api::load_scene::<Platform, GpuWeb>(&scene_str);
+#[bigint]
+pub fn op_fiasco_load_scene(#[string] scene_json: &str) -> u64 {
+    module_api::load_scene::<Platform, GpuWeb>(scene_json.as_bytes())
+        .0
+        .get()
+}
+
+#[op2(fast, stack_trace)]
+#[bigint]
+pub fn op_fiasco_load_scene_from_path(#[string] asset_path: &str) -> u64 {
+    let asset_path = CString::new(asset_path).unwrap();
+    module_api::load_scene_from_path::<Platform, GpuWeb>(&asset_path)
+        .0
+        .get()
+}
+
+#[op2(fast, stack_trace)]
+pub fn op_fiasco_unload_scene(#[bigint] scene_handle: u64) {
+    let Some(scene_handle) = NonZero::new(scene_handle) else {
+        log::warn!("passed SceneHandle of zero");
+        return;
+    };
+
+    module_api::unload_scene::<Platform, GpuWeb>(SceneHandle(scene_handle));
+}
+
+#[op2(fast, stack_trace)]
+pub fn op_fiasco_register_prefab(#[string] name: &str, #[string] prefab_json: &str) {
+    let name = CString::new(name).unwrap();
+    let prefab_str = CString::new(prefab_json).unwrap();
+    module_api::register_prefab::<Platform, GpuWeb>(&name, &prefab_str);
+}
+
+#[op2(fast, stack_trace)]
+pub fn op_fiasco_register_prefab_from_path(#[string] asset_path: &str) {
+    let asset_path = CString::new(asset_path).unwrap();
+    module_api::register_prefab_from_path::<Platform, GpuWeb>(&asset_path);
 }
 
 #[op2(fast, stack_trace)]
@@ -316,6 +360,60 @@ pub fn op_fiasco_spawn(components: *const c_void, #[number] components_len: usiz
     }
 }
 
+#[op2(stack_trace)]
+pub fn op_fiasco_spawn_batch<'scope>(
+    scope: &mut v8::HandleScope<'scope>,
+    components: *const c_void,
+    #[number] components_len: usize,
+    #[number] count: usize,
+) -> v8::Local<'scope, v8::Value> {
+    let components = components.cast::<ComponentRef<'_>>();
+
+    let entity_ids = unsafe {
+        module_api::spawn_batch::<Platform, GpuWeb>(
+            slice::from_raw_parts(components, components_len),
+            count,
+        )
+    };
+
+    match entity_ids {
+        Ok(entity_ids) => {
+            let elements = entity_ids
+                .into_iter()
+                .map(|id| v8::BigInt::new_from_u64(scope, NonZero::from(id).get()).into())
+                .collect::<Vec<_>>();
+
+            v8::Array::new_with_elements(scope, &elements).into()
+        }
+        Err(err) => {
+            log::warn!("{err}");
+            v8::Array::new(scope, 0).into()
+        }
+    }
+}
+
+#[op2(fast, stack_trace)]
+#[bigint]
+pub fn op_fiasco_spawn_prefab(
+    #[string] name: &str,
+    components: *const c_void,
+    #[number] components_len: usize,
+) -> u64 {
+    let name = CString::new(name).unwrap();
+    let components = components.cast::<ComponentRef<'_>>();
+
+    unsafe {
+        c_api::engine_core::spawn_prefab::<Platform, GpuWeb>(
+            name.as_ptr(),
+            components,
+            components_len,
+        )
+        .map_or(0, |entity_id| {
+            NonZero::from(EntityId::from(entity_id)).get()
+        })
+    }
+}
+
 #[op2(fast, stack_trace)]
 pub fn op_fiasco_despawn(#[bigint] entity_id: u64) {
     let Ok(entity_id) = entity_id.try_into() else {
@@ -326,6 +424,16 @@ pub fn op_fiasco_despawn(#[bigint] entity_id: u64) {
     module_api::despawn::<Platform, GpuWeb>(entity_id);
 }
 
+#[op2(fast, stack_trace)]
+pub fn op_fiasco_despawn_non_recursive(#[bigint] entity_id: u64) {
+    let Ok(entity_id) = entity_id.try_into() else {
+        log::warn!("passed EntityId of zero");
+        return;
+    };
+
+    module_api::despawn_non_recursive::<Platform, GpuWeb>(entity_id);
+}
+
 /// All of the following conditions must be met:
 ///
 /// * `align` must not be zero,
@@ -449,6 +557,11 @@ pub fn op_fiasco_query_len(query: *const c_void) -> usize {
     unsafe { module_api::query_len::<GpuWeb>(query) }
 }
 
+#[op2(fast, stack_trace)]
+pub fn op_fiasco_query_is_changed(query: *const c_void) -> bool {
+    unsafe { module_api::query_is_changed::<GpuWeb>(query) }
+}
+
 #[op2(fast, stack_trace)]
 pub fn op_fiasco_query_get(
     query: *const c_void,
@@ -575,6 +688,30 @@ pub fn op_fiasco_get_parent<'scope>(
     }
 }
 
+#[op2(stack_trace)]
+pub fn op_fiasco_get_children<'scope>(
+    scope: &mut v8::HandleScope<'scope>,
+    #[bigint] entity_id: u64,
+) -> v8::Local<'scope, v8::Value> {
+    let entity_id = EntityId::try_from(entity_id).unwrap();
+
+    let child_ids = module_api::get_children::<Platform, GpuWeb, _, _>(entity_id, |child_ids| {
+        child_ids.to_vec()
+    });
+
+    match child_ids {
+        Ok(child_ids) => {
+            let elements = child_ids
+                .into_iter()
+                .map(|id| v8::BigInt::new_from_u64(scope, NonZero::from(id).get()).into())
+                .collect::<Vec<_>>();
+
+            v8::Array::new_with_elements(scope, &elements).into()
+        }
+        Err(_) => v8::String::new(scope, "invalid_id").unwrap().into(),
+    }
+}
+
 #[op2(fast, stack_trace)]
 pub fn op_fiasco_input_buffer_ptr() -> *const c_void {
     system_execute_resources(
@@ -4,22 +4,29 @@ use std::{
     error::Error,
     ffi::{CStr, c_char, c_void},
     mem::MaybeUninit,
+    slice,
     sync::Arc,
 };
 
 use game_engine::{
     c_api::get_module_api_proc_addr,
     include_module,
-    platform::{DeserializeReadFn, EcsModule, EcsSystemFn, SerializeWriteFn},
-    void_public::{ArgType, ComponentId, ComponentType},
+    platform::{self, DeserializeReadFn, EcsModule, EcsSystemFn, SerializeWriteFn},
+    void_public::{
+        ArgType, ComponentId, ComponentType,
+        reflect::{ComponentField, FieldInfo},
+        state::StateCondition,
+        system::SystemOrdering,
+    },
 };
 use gpu_web::GpuWeb;
 use libloading::{Library, Symbol};
 use void_public_module::{
-    component_deserialize_json_ffi, resource_deserialize_ffi, resource_serialize_ffi,
+    component_deserialize_json_ffi, component_serialize_json_ffi, resource_deserialize_ffi,
+    resource_serialize_ffi,
 };
 
-use crate::{GameEngine, Platform, get_procedure};
+use crate::{GameEngine, Platform, get_optional_procedure, get_procedure};
 
 struct EcsSystemFnDynamic {
     func: unsafe extern "C" fn(*const *const c_void) -> i32,
@@ -34,13 +41,7 @@ impl EcsSystemFn for EcsSystemFnDynamic {
         &mut self,
         ptr: *const *const c_void,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let res = unsafe { (self.func)(ptr) };
-
-        if res == 0 {
-            Ok(())
-        } else {
-            Err(format!("error code ({res})").into())
-        }
+        platform::module_fn_result(unsafe { (self.func)(ptr) })
     }
 }
 
@@ -120,16 +121,53 @@ pub struct EcsModuleDynamic {
             json_len: usize,
         ) -> i32,
     >,
+    // Optional, for modules built before component JSON serialization was
+    // introduced.
+    component_serialize_json: Option<
+        Symbol<
+            'static,
+            unsafe extern "C" fn(
+                string_id: *const c_char,
+                val: *const c_void,
+                writer: *mut c_void,
+                write: unsafe extern "C" fn(
+                    writer: *mut c_void,
+                    buf: *const c_void,
+                    len: usize,
+                ) -> isize,
+            ) -> i32,
+        >,
+    >,
     component_string_id: Symbol<'static, unsafe extern "C" fn(usize) -> *const c_char>,
     component_size: Symbol<'static, unsafe extern "C" fn(*const c_char) -> usize>,
     component_align: Symbol<'static, unsafe extern "C" fn(*const c_char) -> usize>,
     component_type: Symbol<'static, unsafe extern "C" fn(*const c_char) -> ComponentType>,
+    // Optional, for modules built before component reflection was introduced.
+    component_fields: Option<
+        Symbol<'static, unsafe extern "C" fn(*const c_char, *mut usize) -> *const FieldInfo>,
+    >,
     component_async_completion_callable:
         Symbol<'static, unsafe extern "C" fn(*const c_char) -> *const c_char>,
 
     systems_len: Symbol<'static, unsafe extern "C" fn() -> usize>,
     system_name: Symbol<'static, unsafe extern "C" fn(usize) -> *const c_char>,
     system_is_once: Symbol<'static, unsafe extern "C" fn(usize) -> bool>,
+    // Optional, for modules built before fixed systems were introduced.
+    system_is_fixed: Option<Symbol<'static, unsafe extern "C" fn(usize) -> bool>>,
+    // Ordering functions are optional, for modules built before they were introduced.
+    system_orderings_len: Option<Symbol<'static, unsafe extern "C" fn(usize) -> usize>>,
+    system_ordering_type:
+        Option<Symbol<'static, unsafe extern "C" fn(usize, usize) -> SystemOrdering>>,
+    system_ordering_target:
+        Option<Symbol<'static, unsafe extern "C" fn(usize, usize) -> *const c_char>>,
+    // State condition functions are optional, for the same reason.
+    system_state_conditions_len: Option<Symbol<'static, unsafe extern "C" fn(usize) -> usize>>,
+    system_state_condition_type:
+        Option<Symbol<'static, unsafe extern "C" fn(usize, usize) -> StateCondition>>,
+    system_state_condition_state:
+        Option<Symbol<'static, unsafe extern "C" fn(usize, usize) -> *const c_char>>,
+    system_state_condition_value:
+        Option<Symbol<'static, unsafe extern "C" fn(usize, usize) -> u32>>,
     system_fn: Symbol<
         'static,
         unsafe extern "C" fn(usize) -> unsafe extern "C" fn(*const *const c_void) -> i32,
@@ -191,14 +229,32 @@ impl EcsModuleDynamic {
         let set_component_id = unsafe { get_procedure(&library, c"set_component_id") };
         let component_deserialize_json =
             unsafe { get_procedure(&library, c"component_deserialize_json") };
+        let component_serialize_json =
+            unsafe { get_optional_procedure(&library, c"component_serialize_json") };
         let component_string_id = unsafe { get_procedure(&library, c"component_string_id") };
         let component_size = unsafe { get_procedure(&library, c"component_size") };
         let component_align = unsafe { get_procedure(&library, c"component_align") };
         let component_type = unsafe { get_procedure(&library, c"component_type") };
+        let component_fields = unsafe { get_optional_procedure(&library, c"component_fields") };
         let component_async_completion_callable =
             unsafe { get_procedure(&library, c"component_async_completion_callable") };
         let systems_len = unsafe { get_procedure(&library, c"systems_len") };
         let system_is_once = unsafe { get_procedure(&library, c"system_is_once") };
+        let system_is_fixed = unsafe { get_optional_procedure(&library, c"system_is_fixed") };
+        let system_orderings_len =
+            unsafe { get_optional_procedure(&library, c"system_orderings_len") };
+        let system_ordering_type =
+            unsafe { get_optional_procedure(&library, c"system_ordering_type") };
+        let system_ordering_target =
+            unsafe { get_optional_procedure(&library, c"system_ordering_target") };
+        let system_state_conditions_len =
+            unsafe { get_optional_procedure(&library, c"system_state_conditions_len") };
+        let system_state_condition_type =
+            unsafe { get_optional_procedure(&library, c"system_state_condition_type") };
+        let system_state_condition_state =
+            unsafe { get_optional_procedure(&library, c"system_state_condition_state") };
+        let system_state_condition_value =
+            unsafe { get_optional_procedure(&library, c"system_state_condition_value") };
         let system_args_len = unsafe { get_procedure(&library, c"system_args_len") };
         let system_fn = unsafe { get_procedure(&library, c"system_fn") };
         let system_name = unsafe { get_procedure(&library, c"system_name") };
@@ -228,14 +284,24 @@ impl EcsModuleDynamic {
             resource_serialize,
             set_component_id,
             component_deserialize_json,
+            component_serialize_json,
             component_string_id,
             component_size,
             component_align,
             component_type,
+            component_fields,
             component_async_completion_callable,
             systems_len,
             system_name,
             system_is_once,
+            system_is_fixed,
+            system_orderings_len,
+            system_ordering_type,
+            system_ordering_target,
+            system_state_conditions_len,
+            system_state_condition_type,
+            system_state_condition_state,
+            system_state_condition_value,
             system_fn,
             system_args_len,
             system_arg_type,
@@ -255,23 +321,11 @@ impl EcsModule for EcsModuleDynamic {
     }
 
     fn init(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let res = (self.init)();
-
-        if res == 0 {
-            Ok(())
-        } else {
-            Err(format!("error code ({res})").into())
-        }
+        platform::module_fn_result((self.init)())
     }
 
     fn deinit(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let res = (self.deinit)();
-
-        if res == 0 {
-            Ok(())
-        } else {
-            Err(format!("error code ({res})").into())
-        }
+        platform::module_fn_result((self.deinit)())
     }
 
     fn module_name(&self) -> Cow<'_, str> {
@@ -330,6 +384,21 @@ impl EcsModule for EcsModuleDynamic {
         )
     }
 
+    fn component_serialize_json(
+        &self,
+        string_id: &CStr,
+        val: &[MaybeUninit<u8>],
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let Some(component_serialize_json) = &self.component_serialize_json else {
+            return Err(format!(
+                "module does not support serializing component {string_id:?} to JSON"
+            )
+            .into());
+        };
+
+        component_serialize_json_ffi(**component_serialize_json, string_id, val)
+    }
+
     fn component_string_id(&self, index: usize) -> Option<Cow<'_, CStr>> {
         unsafe {
             let ptr = (self.component_string_id)(index);
@@ -353,6 +422,21 @@ impl EcsModule for EcsModuleDynamic {
         unsafe { (self.component_type)(string_id.as_ptr()) }
     }
 
+    fn component_fields(&self, string_id: &CStr) -> Vec<ComponentField> {
+        let Some(component_fields) = &self.component_fields else {
+            return Vec::new();
+        };
+
+        unsafe {
+            let mut fields_len = 0;
+            let fields = component_fields(string_id.as_ptr(), &mut fields_len);
+            slice::from_raw_parts(fields, fields_len)
+                .iter()
+                .map(|field| ComponentField::from_field_info(field))
+                .collect()
+        }
+    }
+
     fn component_async_completion_callable(&self, string_id: &CStr) -> Cow<'_, CStr> {
         unsafe {
             let ptr = (self.component_async_completion_callable)(string_id.as_ptr());
@@ -379,6 +463,65 @@ impl EcsModule for EcsModuleDynamic {
         unsafe { (self.system_is_once)(system_index) }
     }
 
+    fn system_is_fixed(&self, system_index: usize) -> bool {
+        self.system_is_fixed
+            .as_ref()
+            .is_some_and(|f| unsafe { f(system_index) })
+    }
+
+    fn system_orderings_len(&self, system_index: usize) -> usize {
+        self.system_orderings_len
+            .as_ref()
+            .map_or(0, |f| unsafe { f(system_index) })
+    }
+
+    fn system_ordering_type(&self, system_index: usize, ordering_index: usize) -> SystemOrdering {
+        unsafe { (self.system_ordering_type.as_ref().unwrap())(system_index, ordering_index) }
+    }
+
+    fn system_ordering_target(&self, system_index: usize, ordering_index: usize) -> Cow<'_, CStr> {
+        unsafe {
+            let ptr = (self.system_ordering_target.as_ref().unwrap())(system_index, ordering_index);
+            CStr::from_ptr(ptr).into()
+        }
+    }
+
+    fn system_state_conditions_len(&self, system_index: usize) -> usize {
+        self.system_state_conditions_len
+            .as_ref()
+            .map_or(0, |f| unsafe { f(system_index) })
+    }
+
+    fn system_state_condition_type(
+        &self,
+        system_index: usize,
+        condition_index: usize,
+    ) -> StateCondition {
+        unsafe {
+            (self.system_state_condition_type.as_ref().unwrap())(system_index, condition_index)
+        }
+    }
+
+    fn system_state_condition_state(
+        &self,
+        system_index: usize,
+        condition_index: usize,
+    ) -> Cow<'_, CStr> {
+        unsafe {
+            let ptr = (self.system_state_condition_state.as_ref().unwrap())(
+                system_index,
+                condition_index,
+            );
+            CStr::from_ptr(ptr).into()
+        }
+    }
+
+    fn system_state_condition_value(&self, system_index: usize, condition_index: usize) -> u32 {
+        unsafe {
+            (self.system_state_condition_value.as_ref().unwrap())(system_index, condition_index)
+        }
+    }
+
     fn system_fn(&self, system_index: usize) -> Box<dyn EcsSystemFn> {
         let func = unsafe { (self.system_fn)(system_index) };
         Box::new(EcsSystemFnDynamic {
@@ -25,7 +25,10 @@ use deno_runtime::{
 };
 use game_engine::{
     platform::{DeserializeReadFn, EcsModule, EcsSystemFn, SerializeWriteFn},
-    void_public::{ArgType, ComponentId, ComponentType},
+    void_public::{
+        ArgType, ComponentId, ComponentType, reflect::ComponentField, state::StateCondition,
+        system::SystemOrdering,
+    },
 };
 use sys_traits::impls::RealSys;
 use tokio::runtime::Runtime as TokioRuntime;
@@ -395,6 +398,30 @@ impl SyncIsolateInner {
             .ok_or("failed to call componentDeserializeJson".into())
     }
 
+    fn component_serialize_json(
+        &mut self,
+        module_id: usize,
+        string_id: &CStr,
+        val: &[MaybeUninit<u8>],
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let (mut scope, default) = self.scope_default(module_id);
+
+        let args = &[
+            v8::String::new(&mut scope, string_id.to_str().unwrap())
+                .unwrap()
+                .into(),
+            v8::External::new(&mut scope, val.as_ptr() as *mut c_void).into(),
+        ];
+
+        // modules built against older engines do not export a serializer
+        try_get_js_fn("componentSerializeJson", &mut scope, &default)
+            .ok_or("module does not export componentSerializeJson")?
+            .call(&mut scope, default.into(), args)
+            .filter(|value| value.is_string())
+            .map(|value| value.to_rust_string_lossy(&mut scope))
+            .ok_or("failed to call componentSerializeJson".into())
+    }
+
     fn resource_deserialize(
         &mut self,
         module_id: usize,
@@ -524,6 +551,7 @@ impl SyncIsolateInner {
                 0 => ComponentType::AsyncCompletion,
                 1 => ComponentType::Component,
                 2 => ComponentType::Resource,
+                3 => ComponentType::SparseComponent,
                 _ => panic!(
                     "unknown component type {} returned from componentType",
                     value
@@ -615,6 +643,14 @@ impl SyncIsolateInner {
                 3 => ArgType::EventReader,
                 4 => ArgType::EventWriter,
                 5 => ArgType::Query,
+                6 => ArgType::DataAccessOptionalMut,
+                7 => ArgType::DataAccessOptionalRef,
+                8 => ArgType::With,
+                9 => ArgType::Without,
+                10 => ArgType::Added,
+                11 => ArgType::Changed,
+                12 => ArgType::RemovedComponents,
+                13 => ArgType::World,
                 _ => panic!("unknown arg type {} returned from systemArgType", value),
             })
             .unwrap()
@@ -704,6 +740,12 @@ impl SyncIsolateInner {
                 3 => ArgType::EventReader,
                 4 => ArgType::EventWriter,
                 5 => ArgType::Query,
+                6 => ArgType::DataAccessOptionalMut,
+                7 => ArgType::DataAccessOptionalRef,
+                8 => ArgType::With,
+                9 => ArgType::Without,
+                10 => ArgType::Added,
+                11 => ArgType::Changed,
                 _ => panic!("unknown arg type {} returned from systemArgType", value),
             })
             .unwrap()
@@ -801,6 +843,19 @@ fn get_js_fn<'a>(
         )
 }
 
+/// Returns the function exported by a module with the key `str`, or `None` if
+/// the module does not export a function with that key.
+fn try_get_js_fn<'a>(
+    str: &str,
+    scope: &mut v8::HandleScope<'a>,
+    obj: &v8::Local<'_, v8::Object>,
+) -> Option<v8::Local<'a, v8::Function>> {
+    v8::String::new(scope, str)
+        .and_then(|id| obj.get(scope, id.into()))
+        .filter(|value| value.is_function())
+        .map(|value| value.cast::<v8::Function>())
+}
+
 pub struct IsolateGuard<'a>(MutexGuard<'a, SyncIsolateInner>);
 
 impl Drop for IsolateGuard<'_> {
@@ -881,6 +936,16 @@ impl EcsModule for JsEcsModule {
         )
     }
 
+    fn component_serialize_json(
+        &self,
+        string_id: &CStr,
+        val: &[MaybeUninit<u8>],
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.isolate
+            .lock()
+            .component_serialize_json(self.module_id, string_id, val)
+    }
+
     fn resource_deserialize(
         &self,
         string_id: &CStr,
@@ -928,6 +993,11 @@ impl EcsModule for JsEcsModule {
             .component_type(self.module_id, string_id)
     }
 
+    fn component_fields(&self, _string_id: &CStr) -> Vec<ComponentField> {
+        // JS components do not declare their layout to the engine.
+        Vec::new()
+    }
+
     fn component_async_completion_callable(&self, string_id: &CStr) -> Cow<'_, CStr> {
         string_id.to_owned().into()
     }
@@ -950,6 +1020,53 @@ impl EcsModule for JsEcsModule {
             .system_is_once(self.module_id, system_index)
     }
 
+    fn system_is_fixed(&self, _system_index: usize) -> bool {
+        // fixed systems are not yet supported for JS modules
+        false
+    }
+
+    fn system_orderings_len(&self, _system_index: usize) -> usize {
+        // ordering constraints are not yet supported for JS modules
+        0
+    }
+
+    fn system_ordering_type(&self, _system_index: usize, _ordering_index: usize) -> SystemOrdering {
+        unreachable!("JS modules do not declare ordering constraints")
+    }
+
+    fn system_ordering_target(
+        &self,
+        _system_index: usize,
+        _ordering_index: usize,
+    ) -> Cow<'_, CStr> {
+        unreachable!("JS modules do not declare ordering constraints")
+    }
+
+    fn system_state_conditions_len(&self, _system_index: usize) -> usize {
+        // state conditions are not yet supported for JS modules
+        0
+    }
+
+    fn system_state_condition_type(
+        &self,
+        _system_index: usize,
+        _condition_index: usize,
+    ) -> StateCondition {
+        unreachable!("JS modules do not declare state conditions")
+    }
+
+    fn system_state_condition_state(
+        &self,
+        _system_index: usize,
+        _condition_index: usize,
+    ) -> Cow<'_, CStr> {
+        unreachable!("JS modules do not declare state conditions")
+    }
+
+    fn system_state_condition_value(&self, _system_index: usize, _condition_index: usize) -> u32 {
+        unreachable!("JS modules do not declare state conditions")
+    }
+
     fn system_fn(&self, system_index: usize) -> Box<dyn EcsSystemFn> {
         let system = JsEcsSystemFn {
             isolate: self.isolate.clone(),
@@ -1027,6 +1144,10 @@ impl EcsModule for JsEcsModule {
     }
 }
 
+/// A system of a JS module. The engine may call systems on any thread of its
+/// executor, concurrently with other systems. Each call locks the module's
+/// [`SyncIsolate`], so JS systems are executed one at a time, on whichever
+/// thread holds the lock, while other modules' systems execute alongside them.
 pub struct JsEcsSystemFn {
     isolate: Arc<SyncIsolate>,
     module_id: usize,
@@ -12,16 +12,17 @@ use std::{
     mem::{MaybeUninit, transmute},
     ops::{Deref, DerefMut},
     path::Path,
-    pin::Pin,
     slice,
     sync::{
-        Arc, Mutex,
+        Arc, Mutex, OnceLock,
         mpsc::{Receiver, Sender, channel},
     },
-    time::Instant,
+    thread,
+    time::{Duration, Instant},
 };
 
 use ecs_module::register_ecs_modules;
+use executor_native::TaskExecutor;
 pub use game_engine;
 use game_engine::{
     platform,
@@ -40,7 +41,6 @@ pub use js::JsOptions;
 use js::{SyncIsolate, register_js_ecs_modules};
 use libloading::{Library, Symbol};
 use platform_library::register_platform_libraries;
-use pollster::FutureExt;
 use tokio::runtime::Runtime as TokioRuntime;
 
 mod deno_op;
@@ -86,7 +86,10 @@ impl NativeGameEngine {
         let (sender, platform_events_receiver) = channel();
         *PLATFORM_EVENT_QUEUE.lock().unwrap() = Some(sender);
 
-        let mut engine = GameEngine::new(Executor, width, height, gpu);
+        // SAFETY: `NativeGameEngine` is only created once, as it also sets up
+        // the global completion and platform event queues.
+        let executor = unsafe { TaskExecutor::new() };
+        let mut engine = GameEngine::new(executor, width, height, gpu);
 
         let tokio_runtime = TokioRuntime::new().unwrap();
         let js_isolate = Arc::new(SyncIsolate::new(js_options, &tokio_runtime));
@@ -215,7 +218,8 @@ impl DerefMut for NativeGameEngine {
 pub struct Platform;
 
 impl platform::Platform for Platform {
-    type Executor = Executor;
+    type Clock = Clock;
+    type Executor = TaskExecutor;
     type Filesystem = Filesystem;
 }
 
@@ -263,42 +267,69 @@ fn to_web_standard_axis_and_value(axis: &Axis, value: f32) -> Option<(usize, f32
     Some(index)
 }
 
-pub struct Executor;
+pub struct Clock;
 
-impl platform::Executor for Executor {
-    fn available_parallelism() -> std::num::NonZero<usize> {
-        std::num::NonZero::new(1).unwrap()
+impl platform::Clock for Clock {
+    fn now() -> Duration {
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed()
     }
+}
 
-    #[inline]
-    fn thread_index() -> usize {
-        0
-    }
+/// The number of threads which read files for `Filesystem::read_async()`.
+const READ_THREAD_COUNT: usize = 4;
 
-    fn parallel_iter<F>(len: usize, f: F)
-    where
-        F: Fn(usize, usize) + Send + Sync,
-    {
-        for i in 0..len {
-            f(i, 0);
-        }
-    }
+type ReadTask = Box<dyn FnOnce() + Send>;
+
+/// Reads files on a small pool of threads, which are started on the first
+/// read.
+pub struct Filesystem;
 
-    fn execute_blocking(&mut self, future: Pin<&mut (dyn Future<Output = ()> + Send)>) {
-        future.block_on();
+impl Filesystem {
+    fn read_tasks() -> &'static Sender<ReadTask> {
+        static READ_TASKS: OnceLock<Sender<ReadTask>> = OnceLock::new();
+
+        READ_TASKS.get_or_init(|| {
+            let (sender, receiver) = channel::<ReadTask>();
+            let receiver = Arc::new(Mutex::new(receiver));
+
+            for index in 0..READ_THREAD_COUNT {
+                let receiver = receiver.clone();
+
+                thread::Builder::new()
+                    .name(format!("read_{index}"))
+                    .spawn(move || {
+                        loop {
+                            // the lock is released before the task is run
+                            let task = receiver.lock().unwrap().recv();
+                            let Ok(task) = task else {
+                                break;
+                            };
+                            task();
+                        }
+                    })
+                    .unwrap();
+            }
+
+            sender
+        })
     }
 }
 
-pub struct Filesystem;
-
 impl platform::Filesystem for Filesystem {
-    fn read_async<P, T>(path: P, user_data: Arc<T>, completion: fn(Arc<T>, io::Result<Vec<u8>>))
-    where
+    fn read_async<P, T: 'static>(
+        path: P,
+        user_data: Arc<T>,
+        completion: fn(Arc<T>, io::Result<Vec<u8>>),
+    ) where
         P: AsRef<Path>,
         Arc<T>: Send,
     {
-        // todo: move to background thread
-        completion(user_data, read(path));
+        let path = path.as_ref().to_path_buf();
+
+        Self::read_tasks()
+            .send(Box::new(move || completion(user_data, read(path))))
+            .unwrap();
     }
 }
 
@@ -315,6 +346,16 @@ unsafe fn get_procedure<T>(library: &Library, procedure_name: &CStr) -> Symbol<'
     }
 }
 
+/// Like [`get_procedure`], but returns `None` if the procedure does not exist.
+unsafe fn get_optional_procedure<T>(
+    library: &Library,
+    procedure_name: &CStr,
+) -> Option<Symbol<'static, T>> {
+    unsafe { library.get::<T>(procedure_name.to_bytes_with_nul()) }
+        .ok()
+        .map(|procedure| unsafe { transmute::<Symbol<'_, T>, Symbol<'static, T>>(procedure) })
+}
+
 /// This queue tracks platform library completion events, which are applied at
 /// the start of the next frame.
 static ASYNC_COMPLETION_QUEUE: Mutex<Option<Sender<AsyncCompletion>>> = Mutex::new(None);
@@ -14,7 +14,7 @@
 
 use flatbuffers::FlatBufferBuilder;
 use libloading::Library;
-use native_common::{GameEngine, ecs_module::EcsModuleDynamic};
+use native_common::{GameEngine, ecs_module::EcsModuleDynamic, game_engine::game_ecs::EcsTypeInfo};
 
 /// Messages sent from the remote client to this code.
 pub mod tooling_messages {
@@ -51,7 +51,31 @@ fn handle_request(engine: &mut GameEngine, message: &IpcToHostT) -> Option<Vec<u
         IpcToHostT::ListSystems(_) => {
             return Some(systems_flat_buffer(engine));
         }
-        IpcToHostT::NONE | IpcToHostT::EnginePause(_) => unreachable!(),
+        IpcToHostT::ListComponents(_) => {
+            return Some(components_flat_buffer(engine));
+        }
+        IpcToHostT::DumpTrace(message) => {
+            let Some(path) = message.path.as_deref() else {
+                return Some(trace_dumped_flat_buffer(None, Some("missing path".into())));
+            };
+            log::info!("dump trace {path}");
+
+            if !engine.profiling_enabled() {
+                let error = "profiling is not enabled".into();
+                return Some(trace_dumped_flat_buffer(Some(path), Some(error)));
+            }
+
+            let error = std::fs::write(path, engine.chrome_trace())
+                .err()
+                .map(|error| error.to_string());
+            return Some(trace_dumped_flat_buffer(Some(path), error));
+        }
+        IpcToHostT::EnableProfiling(message) => {
+            engine.set_profiling_enabled(message.enabled);
+        }
+        IpcToHostT::NONE | IpcToHostT::EnginePause(_) | IpcToHostT::SubscribeEngineErrors(_) => {
+            unreachable!()
+        }
     }
     None
 }
@@ -126,6 +150,134 @@ fn systems_flat_buffer(engine: &mut GameEngine) -> Vec<u8> {
     builder.finished_data().to_vec()
 }
 
+/// The result of writing a trace as a flat buffer.
+fn trace_dumped_flat_buffer(path: Option<&str>, error: Option<String>) -> Vec<u8> {
+    let mut builder = FlatBufferBuilder::new();
+    let path = path.map(|path| builder.create_string(path));
+    let error = error.map(|error| builder.create_string(&error));
+
+    let offset = tooling_messages::TraceDumped::create(
+        &mut builder,
+        &tooling_messages::TraceDumpedArgs { path, error },
+    );
+    let offset = tooling_messages::MessageToClient::create(
+        &mut builder,
+        &tooling_messages::MessageToClientArgs {
+            message_type: tooling_messages::IpcToClient::TraceDumped,
+            message: Some(offset.as_union_value()),
+        },
+    );
+    builder.finish_minimal(offset);
+    builder.finished_data().to_vec()
+}
+
+/// The errors returned by systems during the last frame as a flat buffer.
+fn engine_errors_flat_buffer(engine: &GameEngine) -> Vec<u8> {
+    let mut builder = FlatBufferBuilder::new();
+    let mut entries = Vec::new();
+    for engine_error in engine.engine_errors() {
+        let system_name = engine_error
+            .system_name
+            .as_deref()
+            .map(|name| builder.create_string(name));
+        let module_name = engine_error
+            .module_name
+            .as_deref()
+            .map(|name| builder.create_string(name));
+        let message = engine_error
+            .message
+            .as_deref()
+            .map(|message| builder.create_string(message));
+        let error = tooling_messages::EngineError::create(
+            &mut builder,
+            &tooling_messages::EngineErrorArgs {
+                system_name,
+                module_name,
+                message,
+                frame: engine_error.frame,
+            },
+        );
+        entries.push(error);
+    }
+    let errors = builder.create_vector(&entries);
+
+    let offset = tooling_messages::EngineErrors::create(
+        &mut builder,
+        &tooling_messages::EngineErrorsArgs { list: Some(errors) },
+    );
+    let offset = tooling_messages::MessageToClient::create(
+        &mut builder,
+        &tooling_messages::MessageToClientArgs {
+            message_type: tooling_messages::IpcToClient::EngineErrors,
+            message: Some(offset.as_union_value()),
+        },
+    );
+    builder.finish_minimal(offset);
+    builder.finished_data().to_vec()
+}
+
+/// A list of entity components and their reflected fields as a flat buffer.
+fn components_flat_buffer(engine: &mut GameEngine) -> Vec<u8> {
+    let mut builder = FlatBufferBuilder::new();
+    let mut entries = Vec::new();
+    for component_id in engine.component_ids() {
+        let info = engine.component_info(&component_id).unwrap();
+        if !matches!(info.ecs_type_info, EcsTypeInfo::Component(_)) {
+            continue;
+        }
+
+        let fields = info
+            .fields
+            .iter()
+            .map(|field| {
+                let name = Some(builder.create_string(&field.name.to_string_lossy()));
+                let type_name = Some(builder.create_string(&field.type_name.to_string_lossy()));
+                tooling_messages::ComponentField::create(
+                    &mut builder,
+                    &tooling_messages::ComponentFieldArgs {
+                        name,
+                        type_name,
+                        field_type: tooling_messages::FieldType(field.field_type as u8),
+                        offset: field.offset as u64,
+                        size: field.size as u64,
+                        parent: field.parent.map_or(-1, |parent| parent as i32),
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        let fields = Some(builder.create_vector(&fields));
+        let name = Some(builder.create_string(&info.name.to_string_lossy()));
+        let component = tooling_messages::Component::create(
+            &mut builder,
+            &tooling_messages::ComponentArgs {
+                name,
+                id: component_id.get(),
+                size: info.size as u64,
+                align: info.align as u64,
+                fields,
+            },
+        );
+        entries.push(component);
+    }
+    let components = builder.create_vector(&entries);
+
+    let offset = tooling_messages::Components::create(
+        &mut builder,
+        &tooling_messages::ComponentsArgs {
+            list: Some(components),
+        },
+    );
+    let offset = tooling_messages::MessageToClient::create(
+        &mut builder,
+        &tooling_messages::MessageToClientArgs {
+            message_type: tooling_messages::IpcToClient::Components,
+            message: Some(offset.as_union_value()),
+        },
+    );
+    builder.finish_minimal(offset);
+    builder.finished_data().to_vec()
+}
+
 pub mod tooling {
     use core::cmp::PartialEq;
     use std::{
@@ -348,6 +500,13 @@ pub mod tooling {
 
         /// Control whether the main game loop should execute `engine::frame()`.
         should_run_frame: bool,
+
+        /// Whether the client asked to receive the errors returned by systems.
+        subscribed_to_errors: bool,
+
+        /// The frame of the engine errors last sent to the client, so that
+        /// they are not sent again while the engine is paused.
+        errors_sent_frame: Option<u64>,
     }
 
     impl ToolingIpc {
@@ -367,6 +526,8 @@ pub mod tooling {
                 to_listener: Some(to_listener),
                 from_threads: Some(from_threads),
                 should_run_frame: true,
+                subscribed_to_errors: false,
+                errors_sent_frame: None,
             })
         }
 
@@ -375,6 +536,15 @@ pub mod tooling {
         /// Return true to run the `engine::frame()` as normal, false to prevent
         /// `engine::frame()` execution (ask again for the next frame).
         pub fn process_ipc_messages(&mut self, engine: &mut GameEngine) -> bool {
+            // Errors returned during the last frame, unless they were already
+            // sent.
+            if let Some(engine_error) = engine.engine_errors().first() {
+                if self.subscribed_to_errors && self.errors_sent_frame != Some(engine_error.frame) {
+                    self.errors_sent_frame = Some(engine_error.frame);
+                    send_to_client(&mut self.client, engine_errors_flat_buffer(engine));
+                }
+            }
+
             if let Some(from_threads) = &self.from_threads {
                 loop {
                     match from_threads.try_recv() {
@@ -400,16 +570,16 @@ pub mod tooling {
                                         self.should_run_frame = !message.paused;
                                         println!("should_run_frame {}", self.should_run_frame);
                                     }
+                                    tooling_messages::IpcToHostT::SubscribeEngineErrors(
+                                        message,
+                                    ) => {
+                                        self.subscribed_to_errors = message.subscribed;
+                                    }
                                     _ => {
                                         if let Some(response) =
                                             handle_request(engine, &msg.unpack().message)
                                         {
-                                            if let Some(connection) = &self.client {
-                                                connection
-                                                    .to_client()
-                                                    .send(ToClient::Message(response))
-                                                    .unwrap();
-                                            }
+                                            send_to_client(&mut self.client, response);
                                         }
                                     }
                                 }
@@ -430,6 +600,21 @@ pub mod tooling {
         }
     }
 
+    /// Sends a message to the client, if connected. The connection is dropped
+    /// if the client has gone away.
+    fn send_to_client(client: &mut Option<IpcConnection>, message: Vec<u8>) {
+        if let Some(connection) = client {
+            if connection
+                .to_client()
+                .send(ToClient::Message(message))
+                .is_err()
+            {
+                log::warn!("client disconnected, dropping connection");
+                *client = None;
+            }
+        }
+    }
+
     /// The thread(s) created by the `ToolingIpc` need to be cleaned up (i.e.
     /// returning limited resources).
     ///
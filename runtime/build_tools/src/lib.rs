@@ -23,9 +23,14 @@ use proc_macro2::TokenStream;
 use quote::{ToTokens, format_ident, quote};
 use regex::Regex;
 use syn::{
-    Attribute, File, FnArg, GenericArgument, GenericParam, Ident, ImplItem, Index, Item, ItemFn,
-    ItemImpl, ItemMod, LitCStr, PathArguments, Type, TypeParamBound, parse_quote, parse2,
-    punctuated::Punctuated, spanned::Spanned,
+    Attribute, Expr, ExprCall, File, FnArg, GenericArgument, GenericParam, Ident, ImplItem, Index,
+    Item, ItemFn, ItemImpl, ItemMod, LitCStr, LitStr, Meta, PathArguments, Token, Type,
+    TypeParamBound,
+    meta::ParseNestedMeta,
+    parse::{ParseStream, Parser},
+    parse_quote, parse2,
+    punctuated::Punctuated,
+    spanned::Spanned,
 };
 
 mod iterator_helper;
@@ -316,7 +321,7 @@ fn write_ffi(
 #[derive(Debug)]
 enum EcsType {
     AsyncCompletion { callable: syn::Path },
-    Component,
+    Component { sparse: bool },
     Resource,
 }
 
@@ -324,7 +329,8 @@ impl EcsType {
     pub fn to_ecs_type_variant(&self) -> syn::Ident {
         match self {
             EcsType::AsyncCompletion { callable: _ } => parse_quote! { AsyncCompletion },
-            EcsType::Component => parse_quote! { Component },
+            EcsType::Component { sparse: false } => parse_quote! { Component },
+            EcsType::Component { sparse: true } => parse_quote! { SparseComponent },
             EcsType::Resource => parse_quote! { Resource },
         }
     }
@@ -334,9 +340,16 @@ impl EcsType {
 enum ArgType {
     Completion,
     DataAccessDirect,
+    DataAccessOptional,
     EventReader { input: syn::Path },
     EventWriter { input: syn::Path },
     Query { inputs: Vec<SystemInputInfo> },
+    RemovedComponents { input: syn::Path },
+    World,
+    With,
+    Without,
+    Added,
+    Changed,
 }
 
 /// A collection of items this FFI is interested in.
@@ -385,8 +398,164 @@ impl ParsedInfo {
 struct SystemInfo {
     path: syn::Path,
     is_once: bool,
+    is_fixed: bool,
     takes_platform_generic: bool,
     inputs: Vec<SystemInputInfo>,
+    ordering: Vec<SystemOrderingInfo>,
+    state_conditions: Vec<StateConditionInfo>,
+}
+
+/// The options declared in a system's attributes.
+#[derive(Debug, Default)]
+struct SystemAttrInfo {
+    is_fixed: bool,
+    ordering: Vec<SystemOrderingInfo>,
+    state_conditions: Vec<StateConditionInfo>,
+}
+
+/// An ordering constraint from `#[system(before = ..., after = ..., set = ...)]`.
+#[derive(Debug)]
+enum SystemOrderingInfo {
+    Before(syn::Path),
+    After(syn::Path),
+    BeforeSet(String),
+    AfterSet(String),
+    InSet(String),
+}
+
+/// A state condition from `#[system(run_if = in_state(...))]`,
+/// `#[system_on_enter(...)]` or `#[system_on_exit(...)]`.
+#[derive(Debug)]
+struct StateConditionInfo {
+    condition: Ident,
+    /// The path to the state variant, i.e. `GameState::Playing`.
+    state: syn::Path,
+}
+
+impl StateConditionInfo {
+    /// The path to the state type, i.e. `GameState`.
+    fn state_type(&self) -> syn::Path {
+        let mut state_type = self.state.clone();
+        state_type.segments.pop();
+        state_type.segments.pop_punct();
+        state_type
+    }
+}
+
+/// Parse the attributes of a `#[system(...)]`, `#[system_once(...)]`,
+/// `#[system_on_enter(...)]` or `#[system_on_exit(...)]` attribute. Ordering
+/// paths refer to systems in the same module, string literals refer to set
+/// labels.
+fn parse_system_attr(ident: &Ident, attr: &Attribute, attr_info: &mut SystemAttrInfo) {
+    if !matches!(attr.meta, Meta::List(_)) {
+        return;
+    }
+
+    let is_on_enter = attr.path().is_ident("system_on_enter");
+
+    if is_on_enter || attr.path().is_ident("system_on_exit") {
+        let res = attr.parse_args_with(|input: ParseStream<'_>| {
+            attr_info.state_conditions.push(StateConditionInfo {
+                condition: if is_on_enter {
+                    parse_quote!(OnEnter)
+                } else {
+                    parse_quote!(OnExit)
+                },
+                state: input.parse()?,
+            });
+
+            if input.is_empty() {
+                return Ok(());
+            }
+
+            input.parse::<Token![,]>()?;
+
+            syn::meta::parser(|meta| parse_system_meta(&meta, attr_info)).parse2(input.parse()?)
+        });
+
+        res.unwrap_or_else(|err| panic!("fn {ident}(): invalid system attribute: {err}"));
+    } else {
+        attr.parse_nested_meta(|meta| parse_system_meta(&meta, attr_info))
+            .unwrap_or_else(|err| panic!("fn {ident}(): invalid system attribute: {err}"));
+    }
+}
+
+fn parse_system_meta(
+    meta: &ParseNestedMeta<'_>,
+    attr_info: &mut SystemAttrInfo,
+) -> syn::Result<()> {
+    let is_before = meta.path.is_ident("before");
+
+    if is_before || meta.path.is_ident("after") {
+        let value = meta.value()?;
+
+        attr_info.ordering.push(if value.peek(LitStr) {
+            let label = value.parse::<LitStr>()?.value();
+
+            if is_before {
+                SystemOrderingInfo::BeforeSet(label)
+            } else {
+                SystemOrderingInfo::AfterSet(label)
+            }
+        } else {
+            let path = value.parse::<syn::Path>()?;
+
+            if is_before {
+                SystemOrderingInfo::Before(path)
+            } else {
+                SystemOrderingInfo::After(path)
+            }
+        });
+
+        Ok(())
+    } else if meta.path.is_ident("set") {
+        let label = meta.value()?.parse::<LitStr>()?.value();
+        attr_info.ordering.push(SystemOrderingInfo::InSet(label));
+        Ok(())
+    } else if meta.path.is_ident("run_if") {
+        let condition = meta.value()?.parse::<ExprCall>()?;
+
+        let (Expr::Path(func), Some(Expr::Path(state)), 1) = (
+            condition.func.as_ref(),
+            condition.args.first(),
+            condition.args.len(),
+        ) else {
+            return Err(meta.error("unsupported run condition"));
+        };
+
+        if !func.path.is_ident("in_state") {
+            return Err(meta.error("unsupported run condition"));
+        }
+
+        attr_info.state_conditions.push(StateConditionInfo {
+            condition: parse_quote!(InState),
+            state: state.path.clone(),
+        });
+
+        Ok(())
+    } else if meta.path.is_ident("fixed") {
+        attr_info.is_fixed = true;
+        Ok(())
+    } else {
+        Err(meta.error("unsupported system attribute"))
+    }
+}
+
+/// Returns `true` if a component is declared with `#[component(sparse)]`.
+fn is_sparse_component(attributes: &[Attribute]) -> bool {
+    let mut sparse = false;
+
+    for attr in attributes
+        .iter()
+        .filter(|attr| attr.path().is_ident("component"))
+    {
+        let _ = attr.parse_nested_meta(|meta| {
+            sparse |= meta.path.is_ident("sparse");
+            Ok(())
+        });
+    }
+
+    sparse
 }
 
 fn generate_ffi_function_name_from_path(path: &syn::Path) -> Ident {
@@ -401,6 +570,18 @@ fn generate_ffi_function_name_from_path(path: &syn::Path) -> Ident {
 }
 
 impl SystemInfo {
+    /// Returns `true` if `path` refers to this system, i.e. `path` matches the
+    /// trailing segments of the system's path.
+    fn matches_path(&self, path: &syn::Path) -> bool {
+        let segments = path.segments.iter().map(|segment| &segment.ident);
+        let system_segments = self.path.segments.iter().map(|segment| &segment.ident);
+
+        path.segments.len() <= self.path.segments.len()
+            && system_segments
+                .skip(self.path.segments.len() - path.segments.len())
+                .eq(segments)
+    }
+
     fn name(&self) -> LitCStr {
         let path = self.path.to_token_stream().to_string().replace(" ", "");
 
@@ -425,6 +606,73 @@ impl SystemInputInfo {
             && self.path.segments.last().unwrap().ident != "Query"
             && self.path.segments.last().unwrap().ident != "EventReader"
             && self.path.segments.last().unwrap().ident != "EventWriter"
+            && self.path.segments.last().unwrap().ident != "RemovedComponents"
+            && self.path.segments.last().unwrap().ident != "World"
+    }
+}
+
+/// Parse a single `Query` input, i.e. `&T`, `&mut T`, `Option<&T>`,
+/// `Option<&mut T>`, `With<T>`, `Without<T>`, `Added<T>` or `Changed<T>`.
+fn parse_query_input(ident: &Ident, input: &Type) -> SystemInputInfo {
+    match input {
+        Type::Reference(ty) => {
+            let Type::Path(component) = ty.elem.as_ref() else {
+                panic!("fn {ident}(): unsupported query input type")
+            };
+
+            SystemInputInfo {
+                path: component.path.clone(),
+                arg_type: ArgType::DataAccessDirect,
+                mutable: ty.mutability.is_some(),
+            }
+        }
+        Type::Path(filter) => {
+            let segment = filter.path.segments.last().unwrap();
+
+            let PathArguments::AngleBracketed(generics) = &segment.arguments else {
+                panic!("fn {ident}(): invalid {} generics", segment.ident)
+            };
+
+            let Some(GenericArgument::Type(generic)) = generics.args.first() else {
+                panic!("fn {ident}(): invalid {} generics", segment.ident)
+            };
+
+            match segment.ident.to_string().as_str() {
+                "Option" => {
+                    let Type::Reference(ty) = generic else {
+                        panic!("fn {ident}(): optional query inputs must be references")
+                    };
+
+                    let Type::Path(component) = ty.elem.as_ref() else {
+                        panic!("fn {ident}(): unsupported query input type")
+                    };
+
+                    SystemInputInfo {
+                        path: component.path.clone(),
+                        arg_type: ArgType::DataAccessOptional,
+                        mutable: ty.mutability.is_some(),
+                    }
+                }
+                filter @ ("With" | "Without" | "Added" | "Changed") => {
+                    let Type::Path(component) = generic else {
+                        panic!("fn {ident}(): {filter} generic must be a path")
+                    };
+
+                    SystemInputInfo {
+                        path: component.path.clone(),
+                        arg_type: match filter {
+                            "With" => ArgType::With,
+                            "Without" => ArgType::Without,
+                            "Added" => ArgType::Added,
+                            _ => ArgType::Changed,
+                        },
+                        mutable: false,
+                    }
+                }
+                other => panic!("fn {ident}(): unsupported query input type: {other}"),
+            }
+        }
+        _ => panic!("fn {ident}(): unsupported query input type"),
     }
 }
 
@@ -460,7 +708,11 @@ impl ParsedInfo {
     /// Collect information from a function (fn) which are marked `#[system]` or
     /// `#[system_once]`.
     fn parse_fn(&mut self, item: &ItemFn, mod_path: &syn::Path) {
-        let is_system = item.attrs.iter().any(|attr| attr.path().is_ident("system"));
+        let is_system = item.attrs.iter().any(|attr| {
+            attr.path().is_ident("system")
+                || attr.path().is_ident("system_on_enter")
+                || attr.path().is_ident("system_on_exit")
+        });
         let is_system_once = item
             .attrs
             .iter()
@@ -478,6 +730,20 @@ impl ParsedInfo {
     }
 
     fn parse_system_fn(&mut self, item: &ItemFn, mod_path: &syn::Path, is_system_once: bool) {
+        let mut attr_info = SystemAttrInfo::default();
+
+        for attr in &item.attrs {
+            let path = attr.path();
+
+            if path.is_ident("system")
+                || path.is_ident("system_once")
+                || path.is_ident("system_on_enter")
+                || path.is_ident("system_on_exit")
+            {
+                parse_system_attr(&item.sig.ident, attr, &mut attr_info);
+            }
+        }
+
         let takes_platform_generic = item
             .sig
             .generics
@@ -561,50 +827,24 @@ impl ParsedInfo {
                             };
 
                             let inputs = query_inputs
-                            .args
-                            .iter()
-                            .flat_map(|input| {
-                                let GenericArgument::Type(input) = input else {
-                                    panic!("fn {ident}(): invalid query generics")
-                                };
-
-                                if let Type::Reference(ty) = input {
-                                    let Type::Path(component) = ty.elem.as_ref() else {
-                                        panic!("fn {ident}(): unsupported query input type")
-                                    };
-
-                                    Vec::from([SystemInputInfo {
-                                        path: component.path.clone(),
-                                        arg_type: ArgType::DataAccessDirect,
-                                        mutable: ty.mutability.is_some(),
-                                    }])
-                                } else {
-                                    let Type::Tuple(tuple) = input else {
-                                        panic!("fn {ident}(): unsupported query input type")
+                                .args
+                                .iter()
+                                .flat_map(|input| {
+                                    let GenericArgument::Type(input) = input else {
+                                        panic!("fn {ident}(): invalid query generics")
                                     };
 
-                                    tuple
-                                        .elems
-                                        .iter()
-                                        .map(|elem| {
-                                            let Type::Reference(ty) = elem else {
-                                                panic!("fn {ident}(): system inputs must be references")
-                                            };
-
-                                            let Type::Path(component) = ty.elem.as_ref() else {
-                                                panic!("fn {ident}(): unsupported system input type")
-                                            };
-
-                                            SystemInputInfo {
-                                                path: component.path.clone(),
-                                                arg_type: ArgType::DataAccessDirect,
-                                                mutable: ty.mutability.is_some(),
-                                            }
-                                        })
-                                        .collect()
-                                }
-                            })
-                            .collect();
+                                    if let Type::Tuple(tuple) = input {
+                                        tuple
+                                            .elems
+                                            .iter()
+                                            .map(|elem| parse_query_input(&ident, elem))
+                                            .collect()
+                                    } else {
+                                        Vec::from([parse_query_input(&ident, input)])
+                                    }
+                                })
+                                .collect();
 
                             SystemInputInfo {
                                 path: component.path.clone(),
@@ -617,6 +857,36 @@ impl ParsedInfo {
                             arg_type: ArgType::Completion,
                             mutable: false,
                         },
+                        // optional resource
+                        "Option" => parse_query_input(&ident, input.ty.as_ref()),
+                        "RemovedComponents" => {
+                            let PathArguments::AngleBracketed(inputs) =
+                                &component.path.segments.last().unwrap().arguments
+                            else {
+                                panic!("fn {ident}(): invalid RemovedComponents generics")
+                            };
+
+                            let (Some(GenericArgument::Type(Type::Path(input))), 1) =
+                                (inputs.args.first(), inputs.args.len())
+                            else {
+                                panic!(
+                                    "fn {ident}(): RemovedComponents must take a single component type"
+                                );
+                            };
+
+                            SystemInputInfo {
+                                path: component.path.clone(),
+                                arg_type: ArgType::RemovedComponents {
+                                    input: input.path.clone(),
+                                },
+                                mutable: false,
+                            }
+                        }
+                        "World" => SystemInputInfo {
+                            path: component.path.clone(),
+                            arg_type: ArgType::World,
+                            mutable: false,
+                        },
                         _ => {
                             panic!(
                                 "fn {ident}(): unsupported system input type: {param_type}. Hint: resource inputs must be references."
@@ -646,6 +916,14 @@ impl ParsedInfo {
                         panic!("fn {ident}(): EventWriter inputs must be taken by value");
                     }
 
+                    if param_type == "RemovedComponents" {
+                        panic!("fn {ident}(): RemovedComponents inputs must be taken by value");
+                    }
+
+                    if param_type == "World" {
+                        panic!("fn {ident}(): World inputs must be taken by value");
+                    }
+
                     SystemInputInfo {
                         path: component.path.clone(),
                         arg_type: ArgType::DataAccessDirect,
@@ -658,14 +936,25 @@ impl ParsedInfo {
             inputs.push(system_input);
         }
 
+        if inputs.len() > 1
+            && inputs
+                .iter()
+                .any(|input| matches!(input.arg_type, ArgType::World))
+        {
+            panic!("fn {ident}(): systems taking World may not take other inputs");
+        }
+
         let mut path = mod_path.clone();
         path.segments.push(ident.into());
 
         self.systems.push(SystemInfo {
             path,
             is_once: is_system_once,
+            is_fixed: attr_info.is_fixed,
             takes_platform_generic,
             inputs,
+            ordering: attr_info.ordering,
+            state_conditions: attr_info.state_conditions,
         });
     }
 
@@ -752,7 +1041,9 @@ impl ParsedInfo {
                 let mut ecs_type = None;
                 attr.parse_nested_meta(|meta| {
                     if meta.path.is_ident("Component") {
-                        ecs_type = Some(EcsType::Component);
+                        ecs_type = Some(EcsType::Component {
+                            sparse: is_sparse_component(item_attributes),
+                        });
                     }
 
                     if meta.path.is_ident("Resource")
@@ -884,6 +1175,7 @@ impl ParsedInfo {
             (self, gen_component_size, "gen_component_size"),
             (self, gen_component_align, "gen_component_align"),
             (self, gen_component_type, "gen_component_type"),
+            (self, gen_component_fields, "gen_component_fields"),
             (
                 self,
                 gen_component_async_completion_callable,
@@ -892,6 +1184,34 @@ impl ParsedInfo {
             (self, gen_systems_len, "gen_systems_len"),
             (self, gen_system_name, "gen_system_name"),
             (self, gen_system_is_once, "gen_system_is_once"),
+            (self, gen_system_is_fixed, "gen_system_is_fixed"),
+            (self, gen_system_orderings_len, "gen_system_orderings_len"),
+            (self, gen_system_ordering_type, "gen_system_ordering_type"),
+            (
+                self,
+                gen_system_ordering_target,
+                "gen_system_ordering_target"
+            ),
+            (
+                self,
+                gen_system_state_conditions_len,
+                "gen_system_state_conditions_len"
+            ),
+            (
+                self,
+                gen_system_state_condition_type,
+                "gen_system_state_condition_type"
+            ),
+            (
+                self,
+                gen_system_state_condition_state,
+                "gen_system_state_condition_state"
+            ),
+            (
+                self,
+                gen_system_state_condition_value,
+                "gen_system_state_condition_value"
+            ),
             (self, gen_system_fn, "gen_system_fn"),
             (self, gen_system_args_len, "gen_system_args_len"),
             (self, gen_system_arg_type, "gen_system_arg_type"),
@@ -955,11 +1275,14 @@ impl ParsedInfo {
             #optional_add_no_mangle
             #allow_attrs
             pub extern "C" fn init() -> i32 {
-                ::std::panic::catch_unwind(|| {
+                ::void_public::panic::catch_unwind(|| {
                     #body
                 })
                 .map(|_| 0)
-                .unwrap_or(1)
+                .unwrap_or_else(|message| {
+                    ::void_public::panic::set_module_error(&message);
+                    1
+                })
             }
         }
     }
@@ -981,11 +1304,14 @@ impl ParsedInfo {
             #optional_add_no_mangle
             #allow_attrs
             pub extern "C" fn deinit() -> i32 {
-                ::std::panic::catch_unwind(|| {
+                ::void_public::panic::catch_unwind(|| {
                     #body
                 })
                 .map(|_| 0)
-                .unwrap_or(1)
+                .unwrap_or_else(|message| {
+                    ::void_public::panic::set_module_error(&message);
+                    1
+                })
             }
         }
     }
@@ -1013,20 +1339,24 @@ impl ParsedInfo {
     fn gen_components(&self, add_no_mangle: bool) -> TokenStream {
         let gen_set_component_id = self.gen_set_component_id(add_no_mangle);
         let gen_component_deserialize_json = self.gen_component_deserialize_json(add_no_mangle);
+        let gen_component_serialize_json = self.gen_component_serialize_json(add_no_mangle);
         let gen_component_string_id = self.gen_component_string_id(add_no_mangle);
         let gen_component_size = self.gen_component_size(add_no_mangle);
         let gen_component_align = self.gen_component_align(add_no_mangle);
         let gen_component_type = self.gen_component_type(add_no_mangle);
+        let gen_component_fields = self.gen_component_fields(add_no_mangle);
         let gen_component_async_completion_callable =
             self.gen_component_async_completion_callable(add_no_mangle);
 
         quote! {
             #gen_set_component_id
             #gen_component_deserialize_json
+            #gen_component_serialize_json
             #gen_component_string_id
             #gen_component_size
             #gen_component_align
             #gen_component_type
+            #gen_component_fields
             #gen_component_async_completion_callable
         }
     }
@@ -1192,6 +1522,53 @@ impl ParsedInfo {
         }
     }
 
+    fn gen_component_fields(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+
+        let body = if self.ecs_types.is_empty() {
+            quote! {
+                ::std::process::abort();
+            }
+        } else {
+            let mut ecs_type_path = self
+                .ecs_types
+                .iter()
+                .map(|ecs_type_info| &ecs_type_info.path);
+            let first_ecs_type_path = ecs_type_path.next().unwrap();
+
+            // Resources and async completions do not implement `Reflect`, and
+            // are described without fields.
+            quote! {
+                use ::void_public::reflect::{ReflectProbe, ReflectProbeOpaque as _, ReflectProbeReflect as _};
+
+                let string_id = ::std::ffi::CStr::from_ptr(string_id);
+
+                let fields = if string_id == #first_ecs_type_path::string_id() {
+                    (&ReflectProbe::<#first_ecs_type_path>::new()).fields()
+                } #(else if string_id == #ecs_type_path::string_id() {
+                    (&ReflectProbe::<#ecs_type_path>::new()).fields()
+                })* else {
+                    ::std::process::abort();
+                };
+
+                *fields_len = fields.len();
+                fields.as_ptr()
+            }
+        };
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub unsafe extern "C" fn component_fields(
+                string_id: *const ::std::ffi::c_char,
+                fields_len: *mut usize,
+            ) -> *const ::void_public::reflect::FieldInfo {
+                #body
+            }
+        }
+    }
+
     fn gen_component_async_completion_callable(&self, add_no_mangle: bool) -> TokenStream {
         let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
         let allow_attr = allow_attr();
@@ -1403,7 +1780,7 @@ impl ParsedInfo {
         let components = self
             .ecs_types
             .iter()
-            .filter(|s| matches!(s.ecs_type, EcsType::Component))
+            .filter(|s| matches!(s.ecs_type, EcsType::Component { .. }))
             .map(|ecs_type_info| &ecs_type_info.path)
             .collect::<Vec<_>>();
 
@@ -1463,6 +1840,71 @@ impl ParsedInfo {
         }
     }
 
+    fn gen_component_serialize_json(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+
+        let components = self
+            .ecs_types
+            .iter()
+            .filter(|s| matches!(s.ecs_type, EcsType::Component { .. }))
+            .map(|ecs_type_info| &ecs_type_info.path)
+            .collect::<Vec<_>>();
+
+        let body = if components.is_empty() {
+            quote! {
+                eprintln!("Unknown component {:?}", string_id);
+                1
+            }
+        } else {
+            let mut components = components.into_iter();
+            let first_component = components.next().unwrap();
+            quote! {
+                let string_id = ::std::ffi::CStr::from_ptr(string_id);
+
+                let res = if string_id == #first_component::string_id() {
+                    serde_json::to_string(val.cast::<#first_component>().as_ref().unwrap())
+                } #(else if string_id == #components::string_id() {
+                    serde_json::to_string(val.cast::<#components>().as_ref().unwrap())
+                })* else {
+                    eprintln!("Unknown component {:?}", string_id);
+                    return 1;
+                };
+
+                let json = match res {
+                    Ok(json) => json,
+                    Err(e) => {
+                        eprintln!("Error: {e}");
+                        return 1;
+                    }
+                };
+
+                if write(writer, json.as_ptr().cast(), json.len()) == json.len() as isize {
+                    0
+                } else {
+                    1
+                }
+            }
+        };
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub unsafe extern "C" fn component_serialize_json(
+                string_id: *const ::std::ffi::c_char,
+                val: *const ::std::ffi::c_void,
+                writer: *mut ::std::ffi::c_void,
+                write: unsafe extern "C" fn(
+                    writer: *mut ::std::ffi::c_void,
+                    buf: *const ::std::ffi::c_void,
+                    len: usize
+                ) -> isize,
+            ) -> i32 {
+                #body
+            }
+        }
+    }
+
     fn gen_resource_serialize(&self, add_no_mangle: bool) -> TokenStream {
         let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
         let allow_attr = allow_attr();
@@ -1533,6 +1975,14 @@ impl ParsedInfo {
         let gen_systems_len = self.gen_systems_len(add_no_mangle);
         let gen_system_name = self.gen_system_name(add_no_mangle);
         let gen_system_is_once = self.gen_system_is_once(add_no_mangle);
+        let gen_system_is_fixed = self.gen_system_is_fixed(add_no_mangle);
+        let gen_system_orderings_len = self.gen_system_orderings_len(add_no_mangle);
+        let gen_system_ordering_type = self.gen_system_ordering_type(add_no_mangle);
+        let gen_system_ordering_target = self.gen_system_ordering_target(add_no_mangle);
+        let gen_system_state_conditions_len = self.gen_system_state_conditions_len(add_no_mangle);
+        let gen_system_state_condition_type = self.gen_system_state_condition_type(add_no_mangle);
+        let gen_system_state_condition_state = self.gen_system_state_condition_state(add_no_mangle);
+        let gen_system_state_condition_value = self.gen_system_state_condition_value(add_no_mangle);
         let gen_system_fn = self.gen_system_fn(add_no_mangle);
         let gen_system_args_len = self.gen_system_args_len(add_no_mangle);
         let gen_system_arg_type = self.gen_system_arg_type(add_no_mangle);
@@ -1548,6 +1998,14 @@ impl ParsedInfo {
             #gen_systems_len
             #gen_system_name
             #gen_system_is_once
+            #gen_system_is_fixed
+            #gen_system_orderings_len
+            #gen_system_ordering_type
+            #gen_system_ordering_target
+            #gen_system_state_conditions_len
+            #gen_system_state_condition_type
+            #gen_system_state_condition_state
+            #gen_system_state_condition_value
             #gen_system_fn
             #gen_system_args_len
             #gen_system_arg_type
@@ -1593,9 +2051,26 @@ impl ParsedInfo {
 
                         quote! {#reference *(*data.offset(#index) as *#pointer_type #ident),}
                     },
+                    ArgType::DataAccessOptional => {
+                        let ident = input.path.clone();
+
+                        if input.mutable {
+                            quote! {(*data.offset(#index) as *mut #ident).as_mut(),}
+                        } else {
+                            quote! {(*data.offset(#index) as *const #ident).as_ref(),}
+                        }
+                    },
                     ArgType::EventReader { .. } => quote! {::void_public::EventReader::new(*data.offset(#index)),},
                     ArgType::EventWriter { .. } => quote! {::void_public::EventWriter::new(*data.offset(#index)),},
                     ArgType::Query { .. } => quote! {::void_public::Query::new(*data.offset(#index) as *mut ::std::ffi::c_void),},
+                    ArgType::RemovedComponents { .. } => quote! {::void_public::RemovedComponents::new(*data.offset(#index)),},
+                    ArgType::World => quote! {::void_public::World::new(*data.offset(#index) as *mut ::std::ffi::c_void),},
+                    ArgType::With
+                    | ArgType::Without
+                    | ArgType::Added
+                    | ArgType::Changed => {
+                        unreachable!("query-only input used as a system input")
+                    }
                 };
 
                 quote! {
@@ -1609,13 +2084,16 @@ impl ParsedInfo {
 
                 #allow_attr
                 unsafe extern "C" fn #ffi_ident(data: *const *const ::std::ffi::c_void) -> i32 {
-                        ::std::panic::catch_unwind(||{
+                    ::void_public::panic::catch_unwind(|| {
                         #function_path(
                             #args
                         );
                     })
                     .map(|_| 0)
-                    .unwrap_or(1)
+                    .unwrap_or_else(|message| {
+                        ::void_public::panic::set_module_error(&message);
+                        1
+                    })
                 }
             }
         })
@@ -1680,6 +2158,297 @@ impl ParsedInfo {
         }
     }
 
+    fn gen_system_is_fixed(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, is_system_fixed) = self
+            .systems
+            .iter()
+            .map(|system| system.is_fixed)
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_is_fixed(system_index: usize) -> bool {
+                match system_index {
+                    #(#index => #is_system_fixed,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
+    fn gen_system_orderings_len(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, orderings_len) = self
+            .systems
+            .iter()
+            .map(|system| system.ordering.len())
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_orderings_len(system_index: usize) -> usize {
+                match system_index {
+                    #(#index => #orderings_len,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
+    fn gen_system_ordering_type(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, system_orderings) = self
+            .systems
+            .iter()
+            .map(|system| {
+                let (index, ordering_type) = system
+                    .ordering
+                    .iter()
+                    .map(|ordering| -> Ident {
+                        match ordering {
+                            SystemOrderingInfo::Before(_) => parse_quote!(Before),
+                            SystemOrderingInfo::After(_) => parse_quote!(After),
+                            SystemOrderingInfo::BeforeSet(_) => parse_quote!(BeforeSet),
+                            SystemOrderingInfo::AfterSet(_) => parse_quote!(AfterSet),
+                            SystemOrderingInfo::InSet(_) => parse_quote!(InSet),
+                        }
+                    })
+                    .enumerate()
+                    .split();
+
+                quote! {
+                    match ordering_index {
+                        #(#index => ::void_public::system::SystemOrdering::#ordering_type,)*
+                        _ => ::std::process::abort(),
+                    }
+                }
+            })
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_ordering_type(
+                system_index: usize,
+                ordering_index: usize,
+            ) -> ::void_public::system::SystemOrdering {
+                match system_index {
+                    #(#index => #system_orderings,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
+    fn gen_system_ordering_target(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, system_orderings) = self
+            .systems
+            .iter()
+            .map(|system| {
+                let (index, target) = system
+                    .ordering
+                    .iter()
+                    .map(|ordering| match ordering {
+                        SystemOrderingInfo::Before(path) | SystemOrderingInfo::After(path) => {
+                            let mut targets = self
+                                .systems
+                                .iter()
+                                .filter(|target| target.matches_path(path));
+
+                            let (Some(target), None) = (targets.next(), targets.next()) else {
+                                panic!(
+                                    "fn {}(): ordering target `{}` must refer to exactly one system in this module",
+                                    system.path.segments.last().unwrap().ident,
+                                    path.to_token_stream().to_string().replace(' ', ""),
+                                );
+                            };
+
+                            target.name()
+                        }
+                        SystemOrderingInfo::BeforeSet(label)
+                        | SystemOrderingInfo::AfterSet(label)
+                        | SystemOrderingInfo::InSet(label) => {
+                            LitCStr::new(&CString::new(label.as_str()).unwrap(), system.path.span())
+                        }
+                    })
+                    .enumerate()
+                    .split();
+
+                quote! {
+                    match ordering_index {
+                        #(#index => #target.as_ptr(),)*
+                        _ => ::std::process::abort(),
+                    }
+                }
+            })
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_ordering_target(
+                system_index: usize,
+                ordering_index: usize,
+            ) -> *const ::std::ffi::c_char {
+                match system_index {
+                    #(#index => #system_orderings,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
+    fn gen_system_state_conditions_len(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, conditions_len) = self
+            .systems
+            .iter()
+            .map(|system| system.state_conditions.len())
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_state_conditions_len(system_index: usize) -> usize {
+                match system_index {
+                    #(#index => #conditions_len,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
+    fn gen_system_state_condition_type(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, system_conditions) = self
+            .systems
+            .iter()
+            .map(|system| {
+                let (index, condition) = system
+                    .state_conditions
+                    .iter()
+                    .map(|condition| &condition.condition)
+                    .enumerate()
+                    .split();
+
+                quote! {
+                    match condition_index {
+                        #(#index => ::void_public::state::StateCondition::#condition,)*
+                        _ => ::std::process::abort(),
+                    }
+                }
+            })
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_state_condition_type(
+                system_index: usize,
+                condition_index: usize,
+            ) -> ::void_public::state::StateCondition {
+                match system_index {
+                    #(#index => #system_conditions,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
+    fn gen_system_state_condition_state(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, system_conditions) = self
+            .systems
+            .iter()
+            .map(|system| {
+                let (index, state_type) = system
+                    .state_conditions
+                    .iter()
+                    .map(StateConditionInfo::state_type)
+                    .enumerate()
+                    .split();
+
+                quote! {
+                    match condition_index {
+                        #(#index => <#state_type as ::void_public::state::States>::string_id().as_ptr(),)*
+                        _ => ::std::process::abort(),
+                    }
+                }
+            })
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_state_condition_state(
+                system_index: usize,
+                condition_index: usize,
+            ) -> *const ::std::ffi::c_char {
+                match system_index {
+                    #(#index => #system_conditions,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
+    fn gen_system_state_condition_value(&self, add_no_mangle: bool) -> TokenStream {
+        let optional_no_mangle = generate_optional_no_mangle(add_no_mangle);
+        let allow_attr = allow_attr();
+        let (index, system_conditions) = self
+            .systems
+            .iter()
+            .map(|system| {
+                let (index, state) = system
+                    .state_conditions
+                    .iter()
+                    .map(|condition| &condition.state)
+                    .enumerate()
+                    .split();
+
+                quote! {
+                    match condition_index {
+                        #(#index => ::void_public::state::States::index(#state),)*
+                        _ => ::std::process::abort(),
+                    }
+                }
+            })
+            .enumerate()
+            .split();
+
+        quote! {
+            #optional_no_mangle
+            #allow_attr
+            pub extern "C" fn system_state_condition_value(
+                system_index: usize,
+                condition_index: usize,
+            ) -> u32 {
+                match system_index {
+                    #(#index => #system_conditions,)*
+                    _ => ::std::process::abort(),
+                }
+            }
+        }
+    }
+
     fn gen_system_fn(&self, add_no_mangle: bool) -> TokenStream {
         let has_any_generic = self
             .systems
@@ -1763,6 +2532,18 @@ impl ParsedInfo {
                             ArgType::EventReader { .. } => parse_quote!(EventReader),
                             ArgType::EventWriter { .. } => parse_quote!(EventWriter),
                             ArgType::Query { .. } => parse_quote!(Query),
+                            ArgType::RemovedComponents { .. } => parse_quote!(RemovedComponents),
+                            ArgType::World => parse_quote!(World),
+                            ArgType::DataAccessOptional if input.mutable => {
+                                parse_quote!(DataAccessOptionalMut)
+                            }
+                            ArgType::DataAccessOptional => parse_quote!(DataAccessOptionalRef),
+                            ArgType::With
+                            | ArgType::Without
+                            | ArgType::Added
+                            | ArgType::Changed => {
+                                unreachable!("query-only input used as a system input")
+                            }
                         };
                         parse_quote!(::void_public::ArgType::#arg_type)
                     })
@@ -1801,14 +2582,19 @@ impl ParsedInfo {
                     .inputs
                     .iter()
                     .enumerate()
-                    .filter_map(|(index, system_input_info)| {
-                        if matches!(system_input_info.arg_type, ArgType::DataAccessDirect) {
-                            let ident = system_input_info.path.clone();
-                            Some((index, quote! { #ident::string_id().as_ptr()}))
-                        } else {
-                            None
-                        }
-                    })
+                    .filter_map(
+                        |(index, system_input_info)| match &system_input_info.arg_type {
+                            ArgType::DataAccessDirect | ArgType::DataAccessOptional => {
+                                let ident = system_input_info.path.clone();
+                                Some((index, quote! { #ident::string_id().as_ptr()}))
+                            }
+                            ArgType::RemovedComponents { input } => Some((
+                                index,
+                                quote! { <#input as ::void_public::EcsType>::string_id().as_ptr()},
+                            )),
+                            _ => None,
+                        },
+                    )
                     .split();
 
                 quote! {
@@ -1950,14 +2736,21 @@ impl ParsedInfo {
                             let (index, query_types) = inputs
                                 .iter()
                                 .map(|system_input_info| -> syn::Path {
-                                    let ident = format_ident!(
-                                        "DataAccess{}",
-                                        if system_input_info.mutable {
-                                            "Mut"
-                                        } else {
-                                            "Ref"
+                                    let mutability = if system_input_info.mutable {
+                                        "Mut"
+                                    } else {
+                                        "Ref"
+                                    };
+                                    let ident = match system_input_info.arg_type {
+                                        ArgType::DataAccessOptional => {
+                                            format_ident!("DataAccessOptional{mutability}")
                                         }
-                                    );
+                                        ArgType::With => format_ident!("With"),
+                                        ArgType::Without => format_ident!("Without"),
+                                        ArgType::Added => format_ident!("Added"),
+                                        ArgType::Changed => format_ident!("Changed"),
+                                        _ => format_ident!("DataAccess{mutability}"),
+                                    };
                                     parse_quote!(::void_public::ArgType::#ident)
                                 })
                                 .enumerate()
@@ -2017,7 +2810,13 @@ impl ParsedInfo {
                     let (query_input_params, input_type) = query_inputs.iter().map(|input| {
                         let mut_or_const = if input.mutable { quote!(*mut) } else { quote!(*const) };
                         let input_ident = input.path.clone();
-                        let query_input_params = quote!(#mut_or_const #input_ident);
+                        let query_input_params = match input.arg_type {
+                            // filters are passed as null pointers
+                            ArgType::With | ArgType::Without | ArgType::Added | ArgType::Changed => {
+                                quote!(*const ::std::ffi::c_void)
+                            }
+                            _ => quote!(#mut_or_const #input_ident),
+                        };
                         (query_input_params, quote!(::std::ptr::null_mut()))
                     }).split();
                     let layout_check_index = (1..query_inputs.len()).map(Index::from);
@@ -2110,24 +2909,53 @@ impl ParsedInfo {
                 _CALL_ASYNC_FN = transmute(get_proc_addr(c"call_async".as_ptr()));
                 _COMPLETION_COUNT_FN = transmute(get_proc_addr(c"completion_count".as_ptr()));
                 _COMPLETION_GET_FN = transmute(get_proc_addr(c"completion_get".as_ptr()));
+                _COMPONENT_FIELD_FN = transmute(get_proc_addr(c"component_field".as_ptr()));
+                _COMPONENT_FIELDS_LEN_FN = transmute(get_proc_addr(c"component_fields_len".as_ptr()));
                 _DESPAWN = transmute(get_proc_addr(c"despawn".as_ptr()));
+                _DESPAWN_NON_RECURSIVE = transmute(get_proc_addr(c"despawn_non_recursive".as_ptr()));
                 _ENTITY_LABEL_FN = transmute(get_proc_addr(c"entity_label".as_ptr()));
                 _EVENT_COUNT_FN = transmute(get_proc_addr(c"event_count".as_ptr()));
                 _EVENT_GET_FN = transmute(get_proc_addr(c"event_get".as_ptr()));
                 _EVENT_SEND_FN = transmute(get_proc_addr(c"event_send".as_ptr()));
+                _GET_CHILDREN_FN = transmute(get_proc_addr(c"get_children".as_ptr()));
                 _GET_PARENT_FN = transmute(get_proc_addr(c"get_parent".as_ptr()));
+                _INSERT_RESOURCE_FN = transmute(get_proc_addr(c"insert_resource".as_ptr()));
                 _LOAD_SCENE = transmute(get_proc_addr(c"load_scene".as_ptr()));
+                _LOAD_SCENE_FROM_PATH = transmute(get_proc_addr(c"load_scene_from_path".as_ptr()));
+                _UNLOAD_SCENE = transmute(get_proc_addr(c"unload_scene".as_ptr()));
+                _REGISTER_SCENE_MIGRATION = transmute(get_proc_addr(c"register_scene_migration".as_ptr()));
                 _SET_ENTITY_LABEL_FN = transmute(get_proc_addr(c"set_entity_label".as_ptr()));
                 _SET_PARENT_FN = transmute(get_proc_addr(c"set_parent".as_ptr()));
+                _SET_NEXT_STATE_FN = transmute(get_proc_addr(c"set_next_state".as_ptr()));
+                _SET_MODULE_ERROR_FN = transmute(get_proc_addr(c"set_module_error".as_ptr()));
                 _SET_SYSTEM_ENABLED_FN = transmute(get_proc_addr(c"set_system_enabled".as_ptr()));
                 _SPAWN = transmute(get_proc_addr(c"spawn".as_ptr()));
+                _SPAWN_BATCH = transmute(get_proc_addr(c"spawn_batch".as_ptr()));
+                _SPAWN_PREFAB = transmute(get_proc_addr(c"spawn_prefab".as_ptr()));
+                _SPAWN_PREFAB_WITH_OVERRIDES = transmute(get_proc_addr(c"spawn_prefab_with_overrides".as_ptr()));
                 _QUERY_FOR_EACH_FN = transmute(get_proc_addr(c"query_for_each".as_ptr()));
                 _QUERY_GET_FN = transmute(get_proc_addr(c"query_get".as_ptr()));
                 _QUERY_GET_ENTITY_FN = transmute(get_proc_addr(c"query_get_entity".as_ptr()));
+                _QUERY_GET_MANY_FN = transmute(get_proc_addr(c"query_get_many".as_ptr()));
                 _QUERY_GET_LABEL_FN = transmute(get_proc_addr(c"query_get_label".as_ptr()));
                 _QUERY_LEN_FN = transmute(get_proc_addr(c"query_len".as_ptr()));
+                _QUERY_IS_CHANGED_FN = transmute(get_proc_addr(c"query_is_changed".as_ptr()));
                 _QUERY_PAR_FOR_EACH_FN = transmute(get_proc_addr(c"query_par_for_each".as_ptr()));
+                _REGISTER_PREFAB = transmute(get_proc_addr(c"register_prefab".as_ptr()));
+                _REGISTER_PREFAB_FROM_PATH = transmute(get_proc_addr(c"register_prefab_from_path".as_ptr()));
                 _REMOVE_COMPONENTS_FN = transmute(get_proc_addr(c"remove_components".as_ptr()));
+                _REMOVE_RESOURCE_FN = transmute(get_proc_addr(c"remove_resource".as_ptr()));
+                _STATE_GET_FN = transmute(get_proc_addr(c"state_get".as_ptr()));
+                _WORLD_ADD_COMPONENTS_FN = transmute(get_proc_addr(c"world_add_components".as_ptr()));
+                _WORLD_ARCHETYPE_COMPONENTS_FN = transmute(get_proc_addr(c"world_archetype_components".as_ptr()));
+                _WORLD_ARCHETYPE_ENTITIES_FN = transmute(get_proc_addr(c"world_archetype_entities".as_ptr()));
+                _WORLD_ARCHETYPES_LEN_FN = transmute(get_proc_addr(c"world_archetypes_len".as_ptr()));
+                _WORLD_COMPONENT_FN = transmute(get_proc_addr(c"world_component".as_ptr()));
+                _WORLD_DESPAWN_FN = transmute(get_proc_addr(c"world_despawn".as_ptr()));
+                _WORLD_ENTITIES_FN = transmute(get_proc_addr(c"world_entities".as_ptr()));
+                _WORLD_ENTITY_COMPONENTS_FN = transmute(get_proc_addr(c"world_entity_components".as_ptr()));
+                _WORLD_REMOVE_COMPONENTS_FN = transmute(get_proc_addr(c"world_remove_components".as_ptr()));
+                _WORLD_SPAWN_FN = transmute(get_proc_addr(c"world_spawn".as_ptr()));
             }
         }
     }
@@ -48,7 +48,7 @@ pub fn write_ffi(
         }
     }
 
-    let token_stream = gen_ffi(&parsed_info, add_no_mangle);
+    let token_stream = gen_ffi(crate_name, &parsed_info, add_no_mangle);
 
     let syn_file = match parse2(token_stream) {
         Ok(file) => file,
@@ -328,24 +328,22 @@ fn function_iter(
     )
 }
 
-fn gen_ffi(parsed_info: &ParsedInfo, add_no_mangle: bool) -> TokenStream {
+fn gen_ffi(crate_name: &str, parsed_info: &ParsedInfo, add_no_mangle: bool) -> TokenStream {
     let gen_set_completion_callback = gen_set_completion_callback(add_no_mangle);
     let gen_set_platform_event_callback = gen_set_platform_event_callback(add_no_mangle);
     let gen_version = gen_version(add_no_mangle);
-    let gen_init = gen_init(parsed_info, add_no_mangle);
+    let gen_init = gen_init(crate_name, parsed_info, add_no_mangle);
     let gen_function_count = gen_function_count(parsed_info, add_no_mangle);
     let gen_function_name = gen_function_name(parsed_info, add_no_mangle);
     let gen_function_is_sync = gen_function_is_sync(parsed_info, add_no_mangle);
     let gen_function_ptr = gen_function_ptr(parsed_info, add_no_mangle);
-    let functions = parsed_info
-        .functions
-        .iter()
-        .map(|platform_function| gen_free_function_ffi(platform_function, add_no_mangle));
+    let functions = parsed_info.functions.iter().map(|platform_function| {
+        gen_free_function_ffi(crate_name, platform_function, add_no_mangle)
+    });
     let platform_functions = parsed_info.structs.iter().flat_map(|platform_struct| {
-        platform_struct
-            .functions
-            .iter()
-            .map(|function| gen_struct_function_ffi(platform_struct, function, add_no_mangle))
+        platform_struct.functions.iter().map(|function| {
+            gen_struct_function_ffi(crate_name, platform_struct, function, add_no_mangle)
+        })
     });
 
     quote! {
@@ -404,7 +402,7 @@ fn gen_version(add_no_mangle: bool) -> TokenStream {
     }
 }
 
-fn gen_init(parsed_info: &ParsedInfo, add_no_mangle: bool) -> TokenStream {
+fn gen_init(crate_name: &str, parsed_info: &ParsedInfo, add_no_mangle: bool) -> TokenStream {
     let no_mangle = generate_optional_no_mangle(add_no_mangle);
     let allow_attr = allow_attr();
 
@@ -426,8 +424,14 @@ fn gen_init(parsed_info: &ParsedInfo, add_no_mangle: bool) -> TokenStream {
         #no_mangle
         #allow_attr
         pub extern "C" fn init() -> u32 {
-            #(#static_idents2 = Some(#struct_paths2::default());)*
-            0
+            ::platform_public::catch_unwind(|| {
+                #(#static_idents2 = Some(#struct_paths2::default());)*
+            })
+            .map(|_| 0)
+            .unwrap_or_else(|message| {
+                ::platform_public::report_panic(#crate_name, c"init", message);
+                1
+            })
         }
     }
 }
@@ -535,11 +539,16 @@ fn gen_function_ptr(parsed_info: &ParsedInfo, add_no_mangle: bool) -> TokenStrea
     }
 }
 
-fn gen_free_function_ffi(function: &PlatformFunction, add_no_mangle: bool) -> TokenStream {
+fn gen_free_function_ffi(
+    crate_name: &str,
+    function: &PlatformFunction,
+    add_no_mangle: bool,
+) -> TokenStream {
     let no_mangle = generate_optional_no_mangle(add_no_mangle);
     let allow_attr = allow_attr();
     let function_ident = function.path.clone();
     let function_name = function.ffi_fn_ident();
+    let name = &function.name;
 
     let parameter_data = if let Some(param) = function
         .params
@@ -582,15 +591,22 @@ fn gen_free_function_ffi(function: &PlatformFunction, add_no_mangle: bool) -> To
             parameter_data_ptr: *const ::std::ffi::c_void,
             parameter_data_size: usize,
         ) {
-            #parameter_data
-            #function_ident(
-                #(#params,)*
-            );
+            let res = ::platform_public::catch_unwind(|| {
+                #parameter_data
+                #function_ident(
+                    #(#params,)*
+                );
+            });
+
+            if let Err(message) = res {
+                ::platform_public::report_panic(#crate_name, #name, message);
+            }
         }
     }
 }
 
 fn gen_struct_function_ffi(
+    crate_name: &str,
     platform_struct: &PlatformStruct,
     function: &PlatformFunction,
     add_no_mangle: bool,
@@ -607,6 +623,7 @@ fn gen_struct_function_ffi(
         .to_string()
         .to_case(Case::Snake);
     let ffi_ident = format_ident!("{struct_ident}_{}", function.ffi_fn_ident());
+    let name = &function.name;
 
     let parameter_data = if let Some(param) = function
         .params
@@ -662,10 +679,16 @@ fn gen_struct_function_ffi(
             parameter_data_ptr: *const ::std::ffi::c_void,
             parameter_data_size: usize,
         ) {
-            #parameter_data
-            #function_ident(
-                #(#params,)*
-            );
+            let res = ::platform_public::catch_unwind(|| {
+                #parameter_data
+                #function_ident(
+                    #(#params,)*
+                );
+            });
+
+            if let Err(message) = res {
+                ::platform_public::report_panic(#crate_name, #name, message);
+            }
         }
     }
 }
@@ -3,26 +3,32 @@ use std::{
     borrow::Borrow,
     cell::UnsafeCell,
     collections::HashMap,
-    ffi::{CStr, CString},
+    ffi::{CStr, CString, c_void},
     hash::Hash,
     marker::PhantomData,
     mem::{MaybeUninit, size_of},
     num::NonZero,
     ops::{Deref, DerefMut},
     ptr, slice,
+    sync::atomic::{AtomicU64, Ordering},
 };
 
 use aligned_vec::AVec;
 use atomic_refcell::{AtomicRef, AtomicRefCell, AtomicRefMut};
 use events_generated::{
-    Despawn, LoadScene, LoadSceneBuilder, RemoveComponents, RemoveComponentsBuilder,
-    SetEntityLabel, SetEntityLabelBuilder, SetParent, SetSystemEnabled, SetSystemEnabledBuilder,
+    Despawn, InsertResource, LoadScene, LoadSceneBuilder, LoadSceneFromPath,
+    LoadSceneFromPathBuilder, RegisterPrefab, RegisterPrefabBuilder, RegisterPrefabFromPath,
+    RegisterPrefabFromPathBuilder, RegisterSceneMigration, RegisterSceneMigrationBuilder,
+    RemoveComponents, RemoveComponentsBuilder, RemoveResource, SetEntityLabel,
+    SetEntityLabelBuilder, SetNextState, SetNextStateBuilder, SetParent, SetSystemEnabled,
+    SetSystemEnabledBuilder, SpawnBatch, SpawnBatchBuilder, SpawnPrefab, SpawnPrefabBuilder,
+    UnloadScene,
 };
 use flatbuffers::{FlatBufferBuilder, Follow, Push, root_unchecked};
 use game_entity::EntityId;
 use num_enum::TryFromPrimitive;
 use platform::{Executor, Platform};
-use void_public::ComponentId;
+use void_public::{ComponentId, SceneHandle, SceneMigrationCallback};
 
 pub mod events_generated {
     #![allow(clippy::all, clippy::pedantic, warnings, unused, unused_imports)]
@@ -54,12 +60,27 @@ macro_rules! platform_event_iter {
 pub enum CommandRef<'a> {
     AddComponents(AddComponents<'a>),
     Despawn(&'a Despawn),
+    /// The resource, and its data.
+    InsertResource(&'a InsertResource, &'a [MaybeUninit<u8>]),
     LoadScene(LoadScene<'a>),
+    LoadSceneFromPath(LoadSceneFromPath<'a>),
+    RegisterPrefab(RegisterPrefab<'a>),
+    RegisterPrefabFromPath(RegisterPrefabFromPath<'a>),
+    RegisterSceneMigration(RegisterSceneMigration<'a>),
     RemoveComponents(RemoveComponents<'a>),
+    RemoveResource(&'a RemoveResource),
     SetEntityLabel(SetEntityLabel<'a>),
+    SetNextState(SetNextState<'a>),
     SetParent(&'a SetParent),
     SetSystemEnabled(SetSystemEnabled<'a>),
     Spawn(AddComponents<'a>),
+    /// The entities of the batch, and the components they are all spawned
+    /// with. The entity id of the `AddComponents` is the first of the batch.
+    SpawnBatch(SpawnBatch<'a>, AddComponents<'a>),
+    /// The prefab, the component overrides of its root entity, and the
+    /// component overrides of each scene id of `override_scene_ids()`.
+    SpawnPrefab(SpawnPrefab<'a>, AddComponents<'a>, Vec<AddComponents<'a>>),
+    UnloadScene(&'a UnloadScene),
 }
 
 /// This enum is binary-encoded into the event data buffer, to indicate the
@@ -69,12 +90,22 @@ pub enum CommandRef<'a> {
 enum CommandTag {
     AddComponents,
     Despawn,
+    InsertResource,
     LoadScene,
+    LoadSceneFromPath,
+    RegisterPrefab,
+    RegisterPrefabFromPath,
+    RegisterSceneMigration,
     RemoveComponents,
+    RemoveResource,
     SetEntityLabel,
+    SetNextState,
     SetParent,
     SetSystemEnabled,
     Spawn,
+    SpawnBatch,
+    SpawnPrefab,
+    UnloadScene,
 }
 
 #[cfg_attr(feature = "state_snapshots", derive(snapshot::Deserialize))]
@@ -100,6 +131,9 @@ pub struct EventManager<P: Platform> {
     /// data for the command. Most of the command data is flatbuffers events,
     /// but some commands like `Spawn` have custom (de)serialization logic.
     command_data: CommandData,
+
+    /// The next handle to be reserved by `reserve_scene_handle()`.
+    next_scene_handle: AtomicU64,
 }
 
 /// Newtype pattern used for trait implementations.
@@ -172,6 +206,7 @@ impl<P: Platform> Default for EventManager<P> {
             platform_event_data: PlatformEventData(HashMap::new()),
             module_event_data: ModuleEventData(HashMap::new()),
             command_data: CommandData(command_data),
+            next_scene_handle: AtomicU64::new(1),
         }
     }
 }
@@ -183,27 +218,10 @@ impl<P: Platform> EventManager<P> {
         event_ident: E,
         system_name: S,
     ) {
-        let per_thread_buffers = (0..P::Executor::available_parallelism().get())
-            .map(|_| {
-                ThreadBuffer {
-                    data: Vec::new(),
-                    event_count: 0,
-                }
-                .into()
-            })
-            .collect();
-
-        let inner = EventWriterStorageInner { per_thread_buffers }.into();
-
-        let event_buffer = EventWriterStorage {
-            inner,
-            marker: PhantomData,
-        };
-
         self.module_event_data
             .entry(event_ident.into())
             .or_default()
-            .insert(system_name.into(), event_buffer);
+            .insert(system_name.into(), EventWriterStorage::default());
     }
 
     /// Used during frame processing to read module and platform events across
@@ -260,10 +278,12 @@ impl<P: Platform> EventManager<P> {
         write_add_components_in_place(buffer, entity_id, components_len, f);
     }
 
-    pub fn command_despawn(&self, entity_id: EntityId) {
+    /// If `recursive` is `true`, the descendants of the entity are despawned
+    /// too.
+    pub fn command_despawn(&self, entity_id: EntityId, recursive: bool) {
         let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
 
-        let event = Despawn::new(NonZero::from(entity_id).get());
+        let event = Despawn::new(NonZero::from(entity_id).get(), recursive);
 
         buffer.push(MaybeUninit::new(CommandTag::Despawn as u8));
         write_struct_event_in_place(buffer, event);
@@ -284,6 +304,26 @@ impl<P: Platform> EventManager<P> {
         write_table_event_bytes_in_place(buffer, fbb.finished_data());
     }
 
+    /// Inserts a resource, replacing its current value if it is present.
+    pub fn command_insert_resource(&self, component_id: ComponentId, data: &[MaybeUninit<u8>]) {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let event = InsertResource::new(component_id.get(), data.len().try_into().unwrap());
+
+        buffer.push(MaybeUninit::new(CommandTag::InsertResource as u8));
+        write_struct_event_in_place(buffer, event);
+        buffer.extend_from_slice(data);
+    }
+
+    pub fn command_remove_resource(&self, component_id: ComponentId) {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let event = RemoveResource::new(component_id.get());
+
+        buffer.push(MaybeUninit::new(CommandTag::RemoveResource as u8));
+        write_struct_event_in_place(buffer, event);
+    }
+
     pub fn command_set_system_enabled(&self, system_name: &str, enabled: bool) {
         let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
 
@@ -299,6 +339,22 @@ impl<P: Platform> EventManager<P> {
         write_table_event_bytes_in_place(buffer, fbb.finished_data());
     }
 
+    /// Requests a transition of the state machine identified by `state_id`.
+    pub fn command_set_next_state(&self, state_id: &CStr, state: u32) {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let mut fbb = FlatBufferBuilder::new();
+        let state_id = fbb.create_string(&state_id.to_string_lossy());
+        let mut builder = SetNextStateBuilder::new(&mut fbb);
+        builder.add_state_id(state_id);
+        builder.add_state(state);
+        let offset = builder.finish();
+        fbb.finish_minimal(offset);
+
+        buffer.push(MaybeUninit::new(CommandTag::SetNextState as u8));
+        write_table_event_bytes_in_place(buffer, fbb.finished_data());
+    }
+
     /// Associates an entity a some string label.
     pub fn command_set_entity_label(&self, entity_id: EntityId, label: Option<&CStr>) {
         let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
@@ -337,18 +393,61 @@ impl<P: Platform> EventManager<P> {
         write_struct_event_in_place(buffer, event);
     }
 
-    pub fn command_load_scene(&self, scene_str: &CStr) {
+    /// Reserves a handle for a scene instance. Handles are never reused.
+    pub fn reserve_scene_handle(&self) -> SceneHandle {
+        let handle = self.next_scene_handle.fetch_add(1, Ordering::Relaxed);
+        SceneHandle(NonZero::new(handle).unwrap())
+    }
+
+    /// Loads a scene as a new scene instance, returning the handle reserved
+    /// for the instance.
+    pub fn command_load_scene(&self, scene_data: &[u8]) -> SceneHandle {
+        let scene_handle = self.reserve_scene_handle();
+
         let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
 
         let mut fbb = FlatBufferBuilder::new();
-        let scene_str = fbb.create_string(&scene_str.to_string_lossy());
+        let scene_data = fbb.create_vector(scene_data);
         let mut builder = LoadSceneBuilder::new(&mut fbb);
-        builder.add_scene_json(scene_str);
+        builder.add_handle(scene_handle.0.get());
+        builder.add_scene_data(scene_data);
         let offset = builder.finish();
         fbb.finish_minimal(offset);
 
         buffer.push(MaybeUninit::new(CommandTag::LoadScene as u8));
         write_table_event_bytes_in_place(buffer, fbb.finished_data());
+
+        scene_handle
+    }
+
+    /// Loads a scene from an asset path as a new scene instance, returning the
+    /// handle reserved for the instance.
+    pub fn command_load_scene_from_path(&self, asset_path: &CStr) -> SceneHandle {
+        let scene_handle = self.reserve_scene_handle();
+
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let mut fbb = FlatBufferBuilder::new();
+        let asset_path = fbb.create_string(&asset_path.to_string_lossy());
+        let mut builder = LoadSceneFromPathBuilder::new(&mut fbb);
+        builder.add_handle(scene_handle.0.get());
+        builder.add_asset_path(asset_path);
+        let offset = builder.finish();
+        fbb.finish_minimal(offset);
+
+        buffer.push(MaybeUninit::new(CommandTag::LoadSceneFromPath as u8));
+        write_table_event_bytes_in_place(buffer, fbb.finished_data());
+
+        scene_handle
+    }
+
+    pub fn command_unload_scene(&self, scene_handle: SceneHandle) {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let event = UnloadScene::new(scene_handle.0.get());
+
+        buffer.push(MaybeUninit::new(CommandTag::UnloadScene as u8));
+        write_struct_event_in_place(buffer, event);
     }
 
     pub fn command_spawn<'a, F>(&self, entity_id: EntityId, components_len: usize, f: F)
@@ -361,6 +460,128 @@ impl<P: Platform> EventManager<P> {
         write_add_components_in_place(buffer, entity_id, components_len, f);
     }
 
+    /// Spawns one entity per id of `entity_ids`, all with the same components.
+    /// `entity_ids` must not be empty.
+    pub fn command_spawn_batch<'a, F>(&self, entity_ids: &[EntityId], components_len: usize, f: F)
+    where
+        F: Fn(usize) -> ComponentData<'a>,
+    {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let mut fbb = FlatBufferBuilder::new();
+        let ids = fbb.create_vector_from_iter(entity_ids.iter().map(|id| NonZero::from(*id).get()));
+        let mut builder = SpawnBatchBuilder::new(&mut fbb);
+        builder.add_entity_ids(ids);
+        let offset = builder.finish();
+        fbb.finish_minimal(offset);
+
+        buffer.push(MaybeUninit::new(CommandTag::SpawnBatch as u8));
+        write_table_event_bytes_in_place(buffer, fbb.finished_data());
+        write_add_components_in_place(buffer, entity_ids[0], components_len, f);
+    }
+
+    /// Registers a prefab from a JSON scene containing a single root entity.
+    pub fn command_register_prefab(&self, name: &CStr, prefab_json: &CStr) {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let mut fbb = FlatBufferBuilder::new();
+        let name = fbb.create_string(&name.to_string_lossy());
+        let prefab_json = fbb.create_string(&prefab_json.to_string_lossy());
+        let mut builder = RegisterPrefabBuilder::new(&mut fbb);
+        builder.add_name(name);
+        builder.add_prefab_json(prefab_json);
+        let offset = builder.finish();
+        fbb.finish_minimal(offset);
+
+        buffer.push(MaybeUninit::new(CommandTag::RegisterPrefab as u8));
+        write_table_event_bytes_in_place(buffer, fbb.finished_data());
+    }
+
+    /// Registers the prefab read from `asset_path`, under its asset path.
+    pub fn command_register_prefab_from_path(&self, asset_path: &CStr) {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let mut fbb = FlatBufferBuilder::new();
+        let asset_path = fbb.create_string(&asset_path.to_string_lossy());
+        let mut builder = RegisterPrefabFromPathBuilder::new(&mut fbb);
+        builder.add_asset_path(asset_path);
+        let offset = builder.finish();
+        fbb.finish_minimal(offset);
+
+        buffer.push(MaybeUninit::new(CommandTag::RegisterPrefabFromPath as u8));
+        write_table_event_bytes_in_place(buffer, fbb.finished_data());
+    }
+
+    /// Registers a migration of a component's JSON, from scenes of
+    /// `from_version` to `to_version`.
+    pub fn command_register_scene_migration(
+        &self,
+        from_version: &CStr,
+        to_version: &CStr,
+        component_name: &CStr,
+        migrate_fn: SceneMigrationCallback,
+        user_data: *const c_void,
+    ) {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let mut fbb = FlatBufferBuilder::new();
+        let from_version = fbb.create_string(&from_version.to_string_lossy());
+        let to_version = fbb.create_string(&to_version.to_string_lossy());
+        let component_name = fbb.create_string(&component_name.to_string_lossy());
+        let mut builder = RegisterSceneMigrationBuilder::new(&mut fbb);
+        builder.add_from_version(from_version);
+        builder.add_to_version(to_version);
+        builder.add_component_name(component_name);
+        builder.add_migrate_fn(migrate_fn as usize as u64);
+        builder.add_user_data(user_data as usize as u64);
+        let offset = builder.finish();
+        fbb.finish_minimal(offset);
+
+        buffer.push(MaybeUninit::new(CommandTag::RegisterSceneMigration as u8));
+        write_table_event_bytes_in_place(buffer, fbb.finished_data());
+    }
+
+    /// Spawns an instance of a prefab, with `entity_id` as its root entity.
+    /// The components provided by `f` are added to the root entity, and the
+    /// components of each of `overrides` are added to the prefab entity of the
+    /// given scene id, replacing the prefab's components of the same type.
+    pub fn command_spawn_prefab<'a, F>(
+        &self,
+        name: &CStr,
+        entity_id: EntityId,
+        components_len: usize,
+        f: F,
+        overrides: &[(&CStr, &[ComponentData<'_>])],
+    ) where
+        F: Fn(usize) -> ComponentData<'a>,
+    {
+        let buffer = unsafe { &mut *self.command_data[P::Executor::thread_index()].get() };
+
+        let mut fbb = FlatBufferBuilder::new();
+        let name = fbb.create_string(&name.to_string_lossy());
+        let override_scene_ids = overrides
+            .iter()
+            .map(|(scene_id, _)| fbb.create_string(&scene_id.to_string_lossy()))
+            .collect::<Vec<_>>();
+        let override_scene_ids = fbb.create_vector(&override_scene_ids);
+        let mut builder = SpawnPrefabBuilder::new(&mut fbb);
+        builder.add_name(name);
+        builder.add_override_scene_ids(override_scene_ids);
+        let offset = builder.finish();
+        fbb.finish_minimal(offset);
+
+        buffer.push(MaybeUninit::new(CommandTag::SpawnPrefab as u8));
+        write_table_event_bytes_in_place(buffer, fbb.finished_data());
+        write_add_components_in_place(buffer, entity_id, components_len, f);
+
+        for (_, components) in overrides {
+            write_add_components_in_place(buffer, entity_id, components.len(), |i| ComponentData {
+                component_id: components[i].component_id,
+                component_data: components[i].component_data,
+            });
+        }
+    }
+
     pub fn drain_commands<F>(&mut self, mut f: F)
     where
         F: FnMut(CommandRef<'_>),
@@ -391,24 +612,68 @@ impl<P: Platform> EventManager<P> {
                         f(CommandRef::Despawn(event));
                         buffer_remainder
                     }
+                    CommandTag::InsertResource => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_struct_event::<InsertResource>(buffer) };
+                        let (data, buffer_remainder) =
+                            buffer_remainder.split_at(event.len() as usize);
+                        f(CommandRef::InsertResource(event, data));
+                        buffer_remainder
+                    }
                     CommandTag::LoadScene => {
                         let (buffer_remainder, event) =
                             unsafe { decode_table_event::<LoadScene<'_>>(buffer) };
                         f(CommandRef::LoadScene(event));
                         buffer_remainder
                     }
+                    CommandTag::LoadSceneFromPath => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_table_event::<LoadSceneFromPath<'_>>(buffer) };
+                        f(CommandRef::LoadSceneFromPath(event));
+                        buffer_remainder
+                    }
+                    CommandTag::RegisterPrefab => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_table_event::<RegisterPrefab<'_>>(buffer) };
+                        f(CommandRef::RegisterPrefab(event));
+                        buffer_remainder
+                    }
+                    CommandTag::RegisterPrefabFromPath => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_table_event::<RegisterPrefabFromPath<'_>>(buffer) };
+                        f(CommandRef::RegisterPrefabFromPath(event));
+                        buffer_remainder
+                    }
+                    CommandTag::RegisterSceneMigration => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_table_event::<RegisterSceneMigration<'_>>(buffer) };
+                        f(CommandRef::RegisterSceneMigration(event));
+                        buffer_remainder
+                    }
                     CommandTag::RemoveComponents => {
                         let (buffer_remainder, event) =
                             unsafe { decode_table_event::<RemoveComponents<'_>>(buffer) };
                         f(CommandRef::RemoveComponents(event));
                         buffer_remainder
                     }
+                    CommandTag::RemoveResource => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_struct_event::<RemoveResource>(buffer) };
+                        f(CommandRef::RemoveResource(event));
+                        buffer_remainder
+                    }
                     CommandTag::SetEntityLabel => {
                         let (buffer_remainder, event) =
                             unsafe { decode_table_event::<SetEntityLabel<'_>>(buffer) };
                         f(CommandRef::SetEntityLabel(event));
                         buffer_remainder
                     }
+                    CommandTag::SetNextState => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_table_event::<SetNextState<'_>>(buffer) };
+                        f(CommandRef::SetNextState(event));
+                        buffer_remainder
+                    }
                     CommandTag::SetParent => {
                         let (buffer_remainder, event) =
                             unsafe { decode_struct_event::<SetParent>(buffer) };
@@ -426,6 +691,37 @@ impl<P: Platform> EventManager<P> {
                         f(CommandRef::Spawn(event));
                         buffer_remainder
                     }
+                    CommandTag::SpawnBatch => {
+                        let (buffer_remainder, batch) =
+                            unsafe { decode_table_event::<SpawnBatch<'_>>(buffer) };
+                        let (buffer_remainder, components) =
+                            unsafe { decode_add_components(buffer_remainder) };
+                        f(CommandRef::SpawnBatch(batch, components));
+                        buffer_remainder
+                    }
+                    CommandTag::SpawnPrefab => {
+                        let (buffer_remainder, prefab) =
+                            unsafe { decode_table_event::<SpawnPrefab<'_>>(buffer) };
+                        let (mut buffer_remainder, components) =
+                            unsafe { decode_add_components(buffer_remainder) };
+                        let overrides_len = prefab.override_scene_ids().map_or(0, |ids| ids.len());
+                        let overrides = (0..overrides_len)
+                            .map(|_| {
+                                let (remainder, overrides) =
+                                    unsafe { decode_add_components(buffer_remainder) };
+                                buffer_remainder = remainder;
+                                overrides
+                            })
+                            .collect();
+                        f(CommandRef::SpawnPrefab(prefab, components, overrides));
+                        buffer_remainder
+                    }
+                    CommandTag::UnloadScene => {
+                        let (buffer_remainder, event) =
+                            unsafe { decode_struct_event::<UnloadScene>(buffer) };
+                        f(CommandRef::UnloadScene(event));
+                        buffer_remainder
+                    }
                 }
             }
 
@@ -441,27 +737,7 @@ impl<P: Platform> PlatformEventDelegate<'_, P> {
     pub unsafe fn send(&mut self, ident: &CStr, data: &[u8]) {
         // just get the first buffer and use it for writing
 
-        let storage = self
-            .platform_event_data
-            .entry(ident.into())
-            .or_insert_with(|| {
-                let per_thread_buffers = (0..P::Executor::available_parallelism().get())
-                    .map(|_| {
-                        ThreadBuffer {
-                            data: Vec::new(),
-                            event_count: 0,
-                        }
-                        .into()
-                    })
-                    .collect();
-
-                let inner = EventWriterStorageInner { per_thread_buffers }.into();
-
-                EventWriterStorage {
-                    inner,
-                    marker: PhantomData,
-                }
-            });
+        let storage = self.platform_event_data.entry(ident.into()).or_default();
 
         let buffer = storage.inner.get_mut().per_thread_buffers[0].get_mut();
 
@@ -577,6 +853,25 @@ pub struct EventWriterStorageMut<'a, P: Platform> {
     marker: PhantomData<P>,
 }
 
+impl<P: Platform> Default for EventWriterStorage<P> {
+    fn default() -> Self {
+        let per_thread_buffers = (0..P::Executor::available_parallelism().get())
+            .map(|_| {
+                ThreadBuffer {
+                    data: Vec::new(),
+                    event_count: 0,
+                }
+                .into()
+            })
+            .collect();
+
+        Self {
+            inner: EventWriterStorageInner { per_thread_buffers }.into(),
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<P: Platform> EventWriterStorage<P> {
     pub fn borrow(&self) -> EventWriterStorageRef<'_> {
         EventWriterStorageRef {
@@ -917,7 +1212,7 @@ const unsafe fn slice_assume_init_mut(slice: &mut [MaybeUninit<u8>]) -> &mut [u8
 
 #[cfg(test)]
 mod test {
-    use std::slice;
+    use std::{ffi::c_char, slice};
 
     use platform::test::TestPlatform;
 
@@ -1146,4 +1441,222 @@ mod test {
             assert_eq!(data, &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
         }
     }
+
+    #[test]
+    fn spawn_prefab_command() {
+        let mut event_manager = EventManager::<TestPlatform>::default();
+
+        let entity_id = EntityId::new(3, 1);
+        let component_id = ComponentId::new(7).unwrap();
+        let component_data = [
+            MaybeUninit::new(1),
+            MaybeUninit::new(2),
+            MaybeUninit::new(3),
+        ];
+
+        let child_component_data = [MaybeUninit::new(4), MaybeUninit::new(5)];
+
+        event_manager.command_spawn_prefab(
+            c"enemy",
+            entity_id,
+            1,
+            |_| ComponentData {
+                component_id,
+                component_data: &component_data,
+            },
+            &[(
+                c"weapon",
+                &[ComponentData {
+                    component_id,
+                    component_data: &child_component_data,
+                }],
+            )],
+        );
+        event_manager.command_despawn(entity_id, false);
+
+        let mut commands = 0;
+
+        event_manager.drain_commands(|command| {
+            match command {
+                CommandRef::SpawnPrefab(prefab, command, overrides) => {
+                    assert_eq!(prefab.name(), Some("enemy"));
+                    assert_eq!(*command.entity_id, entity_id);
+                    assert_eq!(command.sorted_component_ids(), &[component_id]);
+
+                    let data = command.component_data(component_id).unwrap();
+                    assert_eq!(unsafe { slice_assume_init_ref(data) }, &[1, 2, 3]);
+
+                    let override_scene_ids = prefab.override_scene_ids().unwrap();
+                    assert_eq!(override_scene_ids.len(), 1);
+                    assert_eq!(override_scene_ids.get(0), "weapon");
+                    assert_eq!(overrides.len(), 1);
+
+                    let data = overrides[0].component_data(component_id).unwrap();
+                    assert_eq!(unsafe { slice_assume_init_ref(data) }, &[4, 5]);
+                }
+                CommandRef::Despawn(command) => {
+                    assert_eq!(command.entity_id(), NonZero::from(entity_id).get());
+                }
+                _ => panic!("unexpected command"),
+            }
+
+            commands += 1;
+        });
+
+        assert_eq!(commands, 2);
+    }
+
+    #[test]
+    fn spawn_batch_command() {
+        let mut event_manager = EventManager::<TestPlatform>::default();
+
+        let entity_ids = [
+            EntityId::new(3, 1),
+            EntityId::new(5, 0),
+            EntityId::new(6, 0),
+        ];
+        let component_id = ComponentId::new(7).unwrap();
+        let component_data = [MaybeUninit::new(1), MaybeUninit::new(2)];
+
+        event_manager.command_spawn_batch(&entity_ids, 1, |_| ComponentData {
+            component_id,
+            component_data: &component_data,
+        });
+
+        let mut commands = 0;
+
+        event_manager.drain_commands(|command| {
+            let CommandRef::SpawnBatch(batch, command) = command else {
+                panic!("unexpected command");
+            };
+
+            let batch_ids = batch.entity_ids().unwrap().iter().collect::<Vec<_>>();
+            let expected_ids = entity_ids
+                .iter()
+                .map(|id| NonZero::from(*id).get())
+                .collect::<Vec<_>>();
+            assert_eq!(batch_ids, expected_ids);
+
+            assert_eq!(*command.entity_id, entity_ids[0]);
+            let data = command.component_data(component_id).unwrap();
+            assert_eq!(unsafe { slice_assume_init_ref(data) }, &[1, 2]);
+
+            commands += 1;
+        });
+
+        assert_eq!(commands, 1);
+    }
+
+    #[test]
+    fn scene_commands() {
+        let mut event_manager = EventManager::<TestPlatform>::default();
+
+        let first = event_manager.command_load_scene(b"{}");
+        let second = event_manager.command_load_scene(b"VSCN");
+        let third = event_manager.command_load_scene_from_path(c"scenes/level.json");
+        event_manager.command_unload_scene(first);
+
+        assert_ne!(first, second);
+        assert_ne!(second, third);
+
+        let mut handles = Vec::new();
+
+        event_manager.drain_commands(|command| match command {
+            CommandRef::LoadScene(command) => {
+                let scene_data = command.scene_data().unwrap().bytes();
+                assert!(scene_data == b"{}" || scene_data == b"VSCN");
+                handles.push(command.handle());
+            }
+            CommandRef::LoadSceneFromPath(command) => {
+                assert_eq!(command.asset_path(), Some("scenes/level.json"));
+                handles.push(command.handle());
+            }
+            CommandRef::UnloadScene(command) => {
+                handles.push(command.handle());
+            }
+            _ => panic!("unexpected command"),
+        });
+
+        assert_eq!(
+            handles,
+            [first.0.get(), second.0.get(), third.0.get(), first.0.get()]
+        );
+    }
+
+    #[test]
+    fn register_scene_migration_command() {
+        unsafe extern "C" fn migrate(
+            _user_data: *const c_void,
+            _component_json: *const c_char,
+            _output: *mut c_void,
+            _write_output: unsafe extern "C" fn(*mut c_void, *const c_char),
+        ) -> bool {
+            true
+        }
+
+        let mut event_manager = EventManager::<TestPlatform>::default();
+
+        event_manager.command_register_scene_migration(
+            c"0.0.1",
+            c"0.0.2",
+            c"Health",
+            migrate,
+            ptr::dangling(),
+        );
+
+        let mut commands = 0;
+
+        event_manager.drain_commands(|command| {
+            let CommandRef::RegisterSceneMigration(command) = command else {
+                panic!("unexpected command");
+            };
+
+            assert_eq!(command.from_version(), Some("0.0.1"));
+            assert_eq!(command.to_version(), Some("0.0.2"));
+            assert_eq!(command.component_name(), Some("Health"));
+            assert_eq!(command.migrate_fn(), migrate as usize as u64);
+            assert_eq!(
+                command.user_data(),
+                ptr::dangling::<c_void>() as usize as u64
+            );
+
+            commands += 1;
+        });
+
+        assert_eq!(commands, 1);
+    }
+
+    #[test]
+    fn resource_commands() {
+        let mut event_manager = EventManager::<TestPlatform>::default();
+
+        let component_id = ComponentId::new(4).unwrap();
+        let data = [
+            MaybeUninit::new(5),
+            MaybeUninit::new(6),
+            MaybeUninit::new(7),
+        ];
+
+        event_manager.command_insert_resource(component_id, &data);
+        event_manager.command_remove_resource(component_id);
+        event_manager.command_despawn(EntityId::new(1, 0), false);
+
+        let mut commands = Vec::new();
+
+        event_manager.drain_commands(|command| match command {
+            CommandRef::InsertResource(command, data) => {
+                assert_eq!(command.component_id(), component_id.get());
+                assert_eq!(unsafe { slice_assume_init_ref(data) }, &[5, 6, 7]);
+                commands.push("insert");
+            }
+            CommandRef::RemoveResource(command) => {
+                assert_eq!(command.component_id(), component_id.get());
+                commands.push("remove");
+            }
+            CommandRef::Despawn(_) => commands.push("despawn"),
+            _ => panic!("unexpected command"),
+        });
+
+        assert_eq!(commands, ["insert", "remove", "despawn"]);
+    }
 }
@@ -38,6 +38,9 @@ static EXECUTOR_STATE: Mutex<ExecutorState> = Mutex::new(ExecutorState {
 
 static EXECUTOR_CONDVAR: Condvar = Condvar::new();
 
+/// The number of threads started by `TaskExecutor::with_thread_count()`.
+static THREAD_COUNT: AtomicUsize = AtomicUsize::new(0);
+
 #[derive(Default)]
 struct BlockingTaskInfo {
     task: AtomicPtr<Task<'static>>,
@@ -59,7 +62,20 @@ impl TaskExecutor {
     ///
     /// `TaskExecutor` is global. `TaskExecutor::new()` may only be called once.
     pub unsafe fn new() -> Self {
-        let thread_count = TaskExecutor::available_parallelism();
+        let thread_count =
+            thread::available_parallelism().expect("unable to determine available parallelism");
+
+        unsafe { Self::with_thread_count(thread_count) }
+    }
+
+    /// Starts `thread_count` threads, regardless of the available parallelism.
+    ///
+    /// # Safety
+    ///
+    /// `TaskExecutor` is global. Only one of `TaskExecutor::new()` and
+    /// `TaskExecutor::with_thread_count()` may be called, and only once.
+    pub unsafe fn with_thread_count(thread_count: NonZeroUsize) -> Self {
+        THREAD_COUNT.store(thread_count.get(), Ordering::Relaxed);
 
         EXECUTOR_STATE
             .lock()
@@ -183,7 +199,8 @@ impl TaskExecutor {
 
 impl Executor for TaskExecutor {
     fn available_parallelism() -> NonZeroUsize {
-        thread::available_parallelism().expect("unable to determine available parallelism")
+        NonZeroUsize::new(THREAD_COUNT.load(Ordering::Relaxed))
+            .expect("TaskExecutor has not been created")
     }
 
     #[inline]
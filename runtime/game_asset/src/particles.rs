@@ -1,4 +1,6 @@
-use glam::{Mat3, Mat4, Vec3, Vec4, quat};
+use std::error::Error;
+
+use glam::{Mat3, Mat4, Quat, Vec3, Vec4, quat};
 
 /// See `EmittorDescriptor::amount` for more about how these modes work.
 #[derive(Debug, Clone, Copy)]
@@ -158,44 +160,66 @@ pub struct ParticleEffectDescriptor {
 
 impl ParticleEffectDescriptor {
     /// This parses the JSON format output by the VFX tool and returns a `ParticleEffectDescriptor`
-    /// that can be cached/reused to create the same effect multiple times.
+    /// that can be cached/reused to create the same effect multiple times. Fails if the JSON is
+    /// invalid, or if a required field is missing.
     ///
     /// TODO(miketuritzin): move this parsing code to Typescript, as we want descriptors to
     /// be created/modified on that side.
-    pub fn from_json(json: &str) -> ParticleEffectDescriptor {
+    pub fn from_json(json: &str) -> Result<ParticleEffectDescriptor, Box<dyn Error + Send + Sync>> {
         let mut emitter_descriptors = Vec::<EmitterDescriptor>::new();
 
-        let parsed_json: serde_json::Value = serde_json::from_str(json).unwrap();
-        let parsed_json_emitters = parsed_json["emitters"].as_array().unwrap();
+        let parsed_json: serde_json::Value = serde_json::from_str(json)?;
+        let parsed_json_emitters = parsed_json["emitters"]
+            .as_array()
+            .ok_or("missing 'emitters' array")?;
 
-        // Emitter parsing code
+        // Field parsing code
 
-        let vector3_from_parsed_json = |v: &serde_json::Value| {
-            Vec3::new(
-                v["x"].as_f64().unwrap() as f32,
-                v["y"].as_f64().unwrap() as f32,
-                v["z"].as_f64().unwrap() as f32,
-            )
+        let f32_from_parsed_json = |v: &serde_json::Value, key: &str| {
+            v[key]
+                .as_f64()
+                .map(|value| value as f32)
+                .ok_or_else(|| format!("missing number '{key}'"))
         };
 
-        let quaternion_from_parsed_json = |v: &serde_json::Value| {
-            quat(
-                v["x"].as_f64().unwrap() as f32,
-                v["y"].as_f64().unwrap() as f32,
-                v["z"].as_f64().unwrap() as f32,
-                v["w"].as_f64().unwrap() as f32,
-            )
+        let str_from_parsed_json = |v: &serde_json::Value, key: &str| {
+            v[key]
+                .as_str()
+                .map(str::to_owned)
+                .ok_or_else(|| format!("missing string '{key}'"))
         };
 
-        let modulation_texture_from_parsed_json = |mt: &serde_json::Value| -> ModulationTexture {
-            ModulationTexture {
-                texture_name: String::from(mt["texture_name"].as_str().unwrap()),
-                tile_x: mt["tile_x"].as_f64().unwrap() as f32,
-                tile_y: mt["tile_y"].as_f64().unwrap() as f32,
-                scroll_speed_x: mt["speed_x"].as_f64().unwrap() as f32,
-                scroll_speed_y: mt["speed_y"].as_f64().unwrap() as f32,
-            }
-        };
+        // Emitter parsing code
+
+        let vector3_from_parsed_json =
+            |v: &serde_json::Value| -> Result<Vec3, Box<dyn Error + Send + Sync>> {
+                Ok(Vec3::new(
+                    f32_from_parsed_json(v, "x")?,
+                    f32_from_parsed_json(v, "y")?,
+                    f32_from_parsed_json(v, "z")?,
+                ))
+            };
+
+        let quaternion_from_parsed_json =
+            |v: &serde_json::Value| -> Result<Quat, Box<dyn Error + Send + Sync>> {
+                Ok(quat(
+                    f32_from_parsed_json(v, "x")?,
+                    f32_from_parsed_json(v, "y")?,
+                    f32_from_parsed_json(v, "z")?,
+                    f32_from_parsed_json(v, "w")?,
+                ))
+            };
+
+        let modulation_texture_from_parsed_json =
+            |mt: &serde_json::Value| -> Result<ModulationTexture, Box<dyn Error + Send + Sync>> {
+                Ok(ModulationTexture {
+                    texture_name: str_from_parsed_json(mt, "texture_name")?,
+                    tile_x: f32_from_parsed_json(mt, "tile_x")?,
+                    tile_y: f32_from_parsed_json(mt, "tile_y")?,
+                    scroll_speed_x: f32_from_parsed_json(mt, "speed_x")?,
+                    scroll_speed_y: f32_from_parsed_json(mt, "speed_y")?,
+                })
+            };
 
         for parsed_json_emitter in parsed_json_emitters {
             // Use a default of 'false' for use_world_space_emission, as it's
@@ -215,9 +239,9 @@ impl ParticleEffectDescriptor {
                     .as_bool()
                     .unwrap_or(true);
 
-            let position = vector3_from_parsed_json(&parsed_json_emitter["transform_position"]);
-            let rotation = quaternion_from_parsed_json(&parsed_json_emitter["transform_rotation"]);
-            let scale = vector3_from_parsed_json(&parsed_json_emitter["transform_scale"]);
+            let position = vector3_from_parsed_json(&parsed_json_emitter["transform_position"])?;
+            let rotation = quaternion_from_parsed_json(&parsed_json_emitter["transform_rotation"])?;
+            let scale = vector3_from_parsed_json(&parsed_json_emitter["transform_scale"])?;
 
             // The JSON format specifies 3D position/rotation/scale even for 2D emitters (though the transform in
             // practice should end up as 2D-only). Because quaternions are involved, create a 4x4 matrix and then
@@ -230,61 +254,79 @@ impl ParticleEffectDescriptor {
                 transform_mat4.w_axis.x, transform_mat4.w_axis.y, 1.0,
             ]);
 
-            let spawning_mode = match parsed_json_emitter["spawning_mode"].as_str().unwrap() {
-                // "BURST_REPEAT" is a legacy mode that's still output by the VFX tool - it should be treated
-                // identically to "BURST".
-                "BURST" | "BURST_REPEAT" => SpawningMode::Burst,
-                "FLOW" => SpawningMode::Flow,
-                _ => panic!("Unrecognized 'spawning_mode'"),
-            };
+            let spawning_mode =
+                match str_from_parsed_json(parsed_json_emitter, "spawning_mode")?.as_str() {
+                    // "BURST_REPEAT" is a legacy mode that's still output by the VFX tool - it should be treated
+                    // identically to "BURST".
+                    "BURST" | "BURST_REPEAT" => SpawningMode::Burst,
+                    "FLOW" => SpawningMode::Flow,
+                    spawning_mode => {
+                        return Err(format!("unrecognized 'spawning_mode' {spawning_mode}").into());
+                    }
+                };
 
-            let spawn_pattern = match parsed_json_emitter["pattern"].as_str().unwrap() {
+            let spawn_pattern = match str_from_parsed_json(parsed_json_emitter, "pattern")?.as_str()
+            {
                 "SPHERE" => SpawnPattern::Circle,
                 "SPHERE_SURFACE" => SpawnPattern::CircleBoundary,
                 "CONE" => SpawnPattern::Cone,
                 "BOX" => SpawnPattern::Square,
-                _ => panic!("Unrecognized 'pattern'"),
+                pattern => return Err(format!("unrecognized 'pattern' {pattern}").into()),
             };
 
             let cone_spread_angle = match spawn_pattern {
                 // Convert all angles from degrees to radians
                 SpawnPattern::Cone => {
-                    parsed_json_emitter["spread"].as_f64().unwrap().to_radians() as f32
+                    f32_from_parsed_json(parsed_json_emitter, "spread")?.to_radians()
                 }
                 _ => 0.0,
             };
 
             // "color" is a string that is 8 chars long, in RGBA format with two (hex) chars for
             // each channel.
-            let color_hex_str = parsed_json_emitter["color"].as_str().unwrap();
+            let color_hex_str = str_from_parsed_json(parsed_json_emitter, "color")?;
+            let color_channel = |index: usize| -> Result<f32, Box<dyn Error + Send + Sync>> {
+                let channel_hex_str = color_hex_str
+                    .get(index * 2..index * 2 + 2)
+                    .ok_or("'color' must have 8 hex digits")?;
+                Ok(f32::from(u8::from_str_radix(channel_hex_str, 16)?) / 255.0)
+            };
             let color = Vec4::new(
-                (i32::from_str_radix(&color_hex_str[0..2], 16).unwrap() as f32) / 255.0,
-                (i32::from_str_radix(&color_hex_str[2..4], 16).unwrap() as f32) / 255.0,
-                (i32::from_str_radix(&color_hex_str[4..6], 16).unwrap() as f32) / 255.0,
-                (i32::from_str_radix(&color_hex_str[6..8], 16).unwrap() as f32) / 255.0,
+                color_channel(0)?,
+                color_channel(1)?,
+                color_channel(2)?,
+                color_channel(3)?,
             );
 
-            let textures = parsed_json_emitter["textures"].as_array().unwrap();
+            let textures = parsed_json_emitter["textures"]
+                .as_array()
+                .ok_or("missing 'textures' array")?;
             // At least 1 texture must be provided, and the first is the "main" particle texture.
             // Though the JSON format includes modulation-texture-related fields for this
             // texture, they are always set to defaults and should be ignored.
-            let texture_name = String::from(textures[0]["texture_name"].as_str().unwrap());
+            let texture_name = str_from_parsed_json(
+                textures
+                    .first()
+                    .ok_or("at least 1 texture must be provided")?,
+                "texture_name",
+            )?;
             // The VFX tool supports up to 2 additional textures, which are "modulation" textures
             // that are treated differently from the "main" texture.
-            let modulation_texture1 = if textures.len() > 1 {
-                Some(modulation_texture_from_parsed_json(&textures[1]))
-            } else {
-                None
-            };
-            let modulation_texture2 = if textures.len() > 2 {
-                Some(modulation_texture_from_parsed_json(&textures[2]))
-            } else {
-                None
-            };
+            let modulation_texture1 = textures
+                .get(1)
+                .map(modulation_texture_from_parsed_json)
+                .transpose()?;
+            let modulation_texture2 = textures
+                .get(2)
+                .map(modulation_texture_from_parsed_json)
+                .transpose()?;
 
             let amount = match spawning_mode {
-                SpawningMode::Burst => parsed_json_emitter["burst"].as_i64().unwrap() as f32,
-                SpawningMode::Flow => parsed_json_emitter["rate"].as_f64().unwrap() as f32,
+                SpawningMode::Burst => parsed_json_emitter["burst"]
+                    .as_i64()
+                    .ok_or("missing integer 'burst'")?
+                    as f32,
+                SpawningMode::Flow => f32_from_parsed_json(parsed_json_emitter, "rate")?,
             };
 
             emitter_descriptors.push(EmitterDescriptor {
@@ -295,43 +337,119 @@ impl ParticleEffectDescriptor {
                 spawn_pattern,
                 cone_spread_angle,
                 color,
-                glow: (parsed_json_emitter["glow"].as_f64().unwrap() as f32),
+                glow: f32_from_parsed_json(parsed_json_emitter, "glow")?,
                 texture_name,
                 modulation_texture1,
                 modulation_texture2,
                 amount,
-                lifetime: (parsed_json_emitter["lifetime"].as_f64().unwrap() as f32),
-                start_speed: (parsed_json_emitter["speed"].as_f64().unwrap() as f32),
-                gravity: (parsed_json_emitter["gravity"].as_f64().unwrap() as f32),
-                start_size: (parsed_json_emitter["start_size"].as_f64().unwrap() as f32),
-                end_size: (parsed_json_emitter["end_size"].as_f64().unwrap() as f32),
-                size_multiplier: (parsed_json_emitter["size_multiplier"].as_f64().unwrap() as f32),
-                end_fade_in: (parsed_json_emitter["fade_in_time"].as_f64().unwrap() as f32),
-                start_fade_out: (parsed_json_emitter["fade_out_time"].as_f64().unwrap() as f32),
+                lifetime: f32_from_parsed_json(parsed_json_emitter, "lifetime")?,
+                start_speed: f32_from_parsed_json(parsed_json_emitter, "speed")?,
+                gravity: f32_from_parsed_json(parsed_json_emitter, "gravity")?,
+                start_size: f32_from_parsed_json(parsed_json_emitter, "start_size")?,
+                end_size: f32_from_parsed_json(parsed_json_emitter, "end_size")?,
+                size_multiplier: f32_from_parsed_json(parsed_json_emitter, "size_multiplier")?,
+                end_fade_in: f32_from_parsed_json(parsed_json_emitter, "fade_in_time")?,
+                start_fade_out: f32_from_parsed_json(parsed_json_emitter, "fade_out_time")?,
                 // Convert all angles from degrees to radians
                 //
                 // Negate these values because the VFX tool intends positive rotations
                 // to be clockwise. Also note that we intentionally swap min/max_rotation_s
                 // because of this sign flipping.
-                start_rotation: -(parsed_json_emitter["start_rotation"]
-                    .as_f64()
-                    .unwrap()
-                    .to_radians() as f32),
-                min_random_spin: -(parsed_json_emitter["max_rotation_s"]
-                    .as_f64()
-                    .unwrap()
-                    .to_radians() as f32),
-                max_random_spin: -(parsed_json_emitter["min_rotation_s"]
-                    .as_f64()
-                    .unwrap()
-                    .to_radians() as f32),
-                trail_lifetime: parsed_json_emitter["trail_lifetime"].as_f64().unwrap() as f32,
-                trail_width: parsed_json_emitter["trail_width"].as_f64().unwrap() as f32,
+                start_rotation: -f32_from_parsed_json(parsed_json_emitter, "start_rotation")?
+                    .to_radians(),
+                min_random_spin: -f32_from_parsed_json(parsed_json_emitter, "max_rotation_s")?
+                    .to_radians(),
+                max_random_spin: -f32_from_parsed_json(parsed_json_emitter, "min_rotation_s")?
+                    .to_radians(),
+                trail_lifetime: f32_from_parsed_json(parsed_json_emitter, "trail_lifetime")?,
+                trail_width: f32_from_parsed_json(parsed_json_emitter, "trail_width")?,
             });
         }
 
-        ParticleEffectDescriptor {
+        Ok(ParticleEffectDescriptor {
             emitters: emitter_descriptors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ParticleEffectDescriptor, SpawnPattern, SpawningMode};
+
+    fn emitter_json() -> serde_json::Value {
+        serde_json::json!({
+            "transform_position": { "x": 1.0, "y": 2.0, "z": 0.0 },
+            "transform_rotation": { "x": 0.0, "y": 0.0, "z": 0.0, "w": 1.0 },
+            "transform_scale": { "x": 1.0, "y": 1.0, "z": 1.0 },
+            "spawning_mode": "BURST_REPEAT",
+            "pattern": "CONE",
+            "spread": 90.0,
+            "color": "ff000080",
+            "glow": 0.0,
+            "textures": [{ "texture_name": "spark.png" }],
+            "burst": 10,
+            "lifetime": 1.0,
+            "speed": 2.0,
+            "gravity": 0.0,
+            "start_size": 1.0,
+            "end_size": 0.5,
+            "size_multiplier": 1.0,
+            "fade_in_time": 0.0,
+            "fade_out_time": 1.0,
+            "start_rotation": 0.0,
+            "min_rotation_s": 0.0,
+            "max_rotation_s": 0.0,
+            "trail_lifetime": 0.0,
+            "trail_width": 0.0,
+        })
+    }
+
+    fn descriptor_json(emitter: &serde_json::Value) -> String {
+        serde_json::json!({ "emitters": [emitter] }).to_string()
+    }
+
+    #[test]
+    fn descriptor_is_parsed() {
+        let descriptor = ParticleEffectDescriptor::from_json(&descriptor_json(&emitter_json()))
+            .expect("descriptor is valid");
+
+        let [emitter] = descriptor.emitters.as_slice() else {
+            panic!("expected one emitter");
+        };
+        assert!(matches!(emitter.spawning_mode, SpawningMode::Burst));
+        assert!(matches!(emitter.spawn_pattern, SpawnPattern::Cone));
+        assert_eq!(emitter.cone_spread_angle, 90f32.to_radians());
+        assert_eq!(emitter.color.x, 1.0);
+        assert_eq!(emitter.color.w, 128.0 / 255.0);
+        assert_eq!(emitter.texture_name, "spark.png");
+        assert!(emitter.modulation_texture1.is_none());
+        assert_eq!(emitter.amount, 10.0);
+        assert_eq!(emitter.transform.z_axis.x, 1.0);
+        assert_eq!(emitter.transform.z_axis.y, 2.0);
+    }
+
+    #[test]
+    fn invalid_descriptors_are_errors() {
+        assert!(ParticleEffectDescriptor::from_json("not json").is_err());
+        assert!(ParticleEffectDescriptor::from_json("{}").is_err());
+
+        let invalid_emitters = [
+            ("spawning_mode", serde_json::json!("TRICKLE")),
+            ("pattern", serde_json::json!("TORUS")),
+            ("color", serde_json::json!("ff00")),
+            ("color", serde_json::json!("gg000000")),
+            ("textures", serde_json::json!([])),
+            ("lifetime", serde_json::json!("long")),
+            ("transform_scale", serde_json::json!({ "x": 1.0 })),
+        ];
+
+        for (key, value) in invalid_emitters {
+            let mut emitter = emitter_json();
+            emitter[key] = value;
+            assert!(
+                ParticleEffectDescriptor::from_json(&descriptor_json(&emitter)).is_err(),
+                "invalid '{key}' is an error"
+            );
         }
     }
 }
@@ -293,6 +293,22 @@ impl MaterialManager {
     pub fn get_material_id_from_text_id(&self, text_id: TextId) -> Option<&MaterialId> {
         self.text_id_to_material_id_map.get(&text_id)
     }
+
+    pub fn get_material_id_by_name(&self, material_name: &str) -> Option<MaterialId> {
+        self.material_names_to_id.get(material_name).copied()
+    }
+
+    /// Returns whether the definition of a [`Material`] is being read, by
+    /// [`Self::load_material_from_path`].
+    pub fn is_material_pending(&self, material_name: &str) -> bool {
+        self.pending_texts.values().any(|pending_text| {
+            matches!(
+                pending_text,
+                MaterialManagerTextTypes::MaterialDefinition(_, pending_material_name)
+                    if pending_material_name == material_name
+            )
+        })
+    }
 }
 
 #[cfg(feature = "internal_features")]
@@ -100,6 +100,14 @@ impl TextAssetManager {
         }
     }
 
+    /// Returns whether a text finished loading, successfully or not. Batched
+    /// and pending text is not resolved yet.
+    pub fn is_text_resolved(&self, text_id: TextId) -> bool {
+        self.text
+            .get(&text_id)
+            .is_some_and(|text| text.text_type() != TextType::Pending)
+    }
+
     pub fn are_all_ids_loaded<'a, I>(&self, ids: I) -> bool
     where
         I: IntoIterator<Item = &'a TextId>,
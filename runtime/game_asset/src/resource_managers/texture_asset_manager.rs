@@ -152,6 +152,14 @@ impl TextureAssetManager {
         })
     }
 
+    /// Returns whether a texture finished loading, successfully or not.
+    /// Batched and pending textures are not resolved yet.
+    pub fn is_texture_resolved(&self, texture_id: TextureId) -> bool {
+        self.textures
+            .get(&texture_id)
+            .is_some_and(|texture| texture.texture_type() != TextureType::Pending)
+    }
+
     /// Creates a new [`PendingTexture`], and sends the [`NewTexture`] event to
     /// load the texture. Like
     /// [`TextureAssetManager::load_texture_by_pending_texture`], each platform should
@@ -3,7 +3,9 @@ use std::{
         HashMap,
         hash_map::{Entry, Iter},
     },
+    mem::{MaybeUninit, align_of, size_of},
     ops::{Index, IndexMut},
+    slice,
 };
 
 use void_public::{ComponentId, EcsType, EntityId};
@@ -94,6 +96,9 @@ impl IndexMut<&ArchetypeKey> for ArchetypeStorageMap {
 pub struct ArchetypeStorage {
     pub cpu: ArchetypeStorageInfo,
     pub gpu: Vec<ArchetypeStorageInfo>,
+    /// The byte offsets of each component's [`ComponentTicks`] within the CPU
+    /// buffer entry. This includes components stored in GPU buffers.
+    pub ticks: Vec<ComponentOffsetInfo>,
 }
 
 impl ArchetypeStorage {
@@ -105,6 +110,8 @@ impl ArchetypeStorage {
         cpu_data: &mut CpuFrameData,
         gpu_data: &mut G,
     ) -> Self {
+        let mut ticks = Vec::with_capacity(component_ids.len());
+
         let cpu = {
             let mut storage_info = ArchetypeStorageInfo::default();
 
@@ -131,7 +138,20 @@ impl ArchetypeStorage {
                 offset += info.size;
             }
 
-            let align = components.last().map_or(1, |(_, info)| info.align);
+            // change ticks for all components are appended to the cpu entry
+            offset += (offset as *const u8).align_offset(align_of::<ComponentTicks>());
+            for id in component_ids {
+                ticks.push(ComponentOffsetInfo {
+                    component_id: *id,
+                    offset,
+                });
+                offset += size_of::<ComponentTicks>();
+            }
+
+            let align = components
+                .last()
+                .map_or(1, |(_, info)| info.align)
+                .max(align_of::<ComponentTicks>());
             let align_offset = (offset as *const u8).align_offset(align);
 
             storage_info.align = align;
@@ -213,7 +233,37 @@ impl ArchetypeStorage {
             })
             .collect();
 
-        Self { cpu, gpu }
+        Self { cpu, gpu, ticks }
+    }
+
+    /// Return the offset in bytes of the [`ComponentTicks`] for the given
+    /// component id within the CPU buffer entry. Returns `None` if the given
+    /// `component_id` isn't found.
+    pub fn get_component_ticks_offset(&self, component_id: &ComponentId) -> Option<usize> {
+        self.ticks
+            .iter()
+            .find(|c| c.component_id == *component_id)
+            .map(|c| c.offset)
+    }
+
+    /// Writes the [`ComponentTicks`] of every component into a CPU buffer
+    /// entry, as returned by `ticks`.
+    pub fn write_component_ticks<F>(&self, cpu_entry: &mut [MaybeUninit<u8>], mut ticks: F)
+    where
+        F: FnMut(ComponentId) -> ComponentTicks,
+    {
+        for ticks_offset_info in &self.ticks {
+            let component_ticks = ticks(ticks_offset_info.component_id);
+            let bytes = unsafe {
+                slice::from_raw_parts(
+                    (&component_ticks as *const ComponentTicks).cast::<MaybeUninit<u8>>(),
+                    size_of::<ComponentTicks>(),
+                )
+            };
+
+            cpu_entry[ticks_offset_info.offset..ticks_offset_info.offset + bytes.len()]
+                .copy_from_slice(bytes);
+        }
     }
 
     /// Return the offset in bytes of the data for the given component id for this archetype storage.
@@ -242,6 +292,25 @@ impl ArchetypeStorage {
     }
 }
 
+/// Records when a component was added to an entity, and when it was last
+/// accessed mutably. See [`SystemGraph::change_tick()`](crate::SystemGraph::change_tick)
+/// for the meaning of tick values.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComponentTicks {
+    pub added: u64,
+    pub changed: u64,
+}
+
+impl ComponentTicks {
+    pub fn new(change_tick: u64) -> Self {
+        Self {
+            added: change_tick,
+            changed: change_tick,
+        }
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "state_snapshots", derive(snapshot::Deserialize))]
 #[cfg_attr(feature = "state_snapshots", derive(snapshot::Serialize))]
@@ -1,10 +1,11 @@
 use std::{
+    collections::HashMap,
     ffi::{CStr, CString},
     mem::MaybeUninit,
     ops::Index,
 };
 
-use void_public::{ComponentId, Resource};
+use void_public::{ComponentId, Resource, reflect::ComponentField};
 
 use crate::{CpuFrameData, FrameDataBufferRefMut};
 
@@ -15,6 +16,9 @@ pub struct ComponentInfo {
     pub name: CString,
     pub size: usize,
     pub align: usize,
+    /// The reflected fields of an entity component, for tools. Empty for other
+    /// types, and for components whose module does not describe them.
+    pub fields: Vec<ComponentField>,
     pub gpu_compatible: bool,
     pub is_freely_mutable: bool,
     pub ecs_type_info: EcsTypeInfo,
@@ -36,6 +40,9 @@ pub enum EcsTypeInfo {
 pub struct EntityComponentInfo {
     /// The name of the module which declares this entity component.
     pub declaring_module_name: String,
+    /// Sparse components are zero-sized tags which are stored in
+    /// `SparseComponentStorage` rather than in archetypes.
+    pub sparse: bool,
 }
 
 #[derive(Debug)]
@@ -67,17 +74,25 @@ pub struct AsyncCompletionInfo {
 #[cfg_attr(feature = "state_snapshots", derive(snapshot::Serialize))]
 pub struct ComponentRegistry {
     components: Vec<ComponentInfo>,
+    /// The ids of the components, by string id.
+    component_ids: HashMap<CString, ComponentId>,
 }
 
 impl ComponentRegistry {
     pub fn register(&mut self, info: ComponentInfo) -> ComponentId {
+        let name = info.name.clone();
         self.components.push(info);
 
         // we are off-by-one when indexing into `components`, because component ids start at 1
-        u16::try_from(self.components.len())
+        let component_id = u16::try_from(self.components.len())
             .expect("cannot allocate any more ComponentIds")
             .try_into()
-            .unwrap()
+            .unwrap();
+
+        // string ids are expected to be unique, the first registration is found by string id
+        self.component_ids.entry(name).or_insert(component_id);
+
+        component_id
     }
 
     pub fn get(&self, component_id: &ComponentId) -> Option<&ComponentInfo> {
@@ -87,13 +102,17 @@ impl ComponentRegistry {
     }
 
     pub fn get_with_string_id(&self, string_id: &CStr) -> Option<(ComponentId, &ComponentInfo)> {
-        self.components.iter().enumerate().find_map(|(i, c)| {
-            if c.name.as_c_str() == string_id {
-                // we are off-by-one when indexing into `components`, because component ids start at 1
-                Some((((i + 1) as u16).try_into().unwrap(), c))
-            } else {
-                None
-            }
+        let component_id = *self.component_ids.get(string_id)?;
+        Some((component_id, &self[&component_id]))
+    }
+
+    /// Returns `true` if `component_id` is a sparse entity component.
+    pub fn is_sparse(&self, component_id: &ComponentId) -> bool {
+        self.get(component_id).is_some_and(|info| {
+            matches!(
+                &info.ecs_type_info,
+                EcsTypeInfo::Component(EntityComponentInfo { sparse: true, .. })
+            )
         })
     }
 
@@ -151,6 +170,7 @@ pub fn manually_register_resource<R: Resource>(
         name: R::string_id().to_owned(),
         size: size_of::<R>(),
         align: align_of::<R>(),
+        fields: Vec::new(),
         gpu_compatible: false,
         is_freely_mutable: true,
         ecs_type_info: EcsTypeInfo::Resource(ResourceInfo {
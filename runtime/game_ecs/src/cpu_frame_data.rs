@@ -102,7 +102,7 @@ impl CpuFrameData {
         };
 
         let buffer = self.get_buffer_mut(buffer_index);
-        let resource = unsafe { buffer.get_as(0).unwrap() };
+        let resource = unsafe { buffer.get_as(0).expect("resource has been removed") };
         f(resource)
     }
 
@@ -118,7 +118,7 @@ impl CpuFrameData {
         };
 
         let mut buffer = self.get_buffer_mut(buffer_index);
-        let resource = unsafe { buffer.get_mut_as(0).unwrap() };
+        let resource = unsafe { buffer.get_mut_as(0).expect("resource has been removed") };
         f(resource)
     }
 }
@@ -231,6 +231,13 @@ impl FrameDataBufferBorrowRefMut for CpuDataBuffer {
 #[derive(Debug)]
 pub struct CpuDataBufferRefMut<'a>(&'a mut CpuDataBuffer);
 
+impl CpuDataBufferRefMut<'_> {
+    /// Reserves capacity for at least `additional` more entries.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.data.reserve(additional * self.0.stride);
+    }
+}
+
 impl FrameDataBufferBorrowRef for CpuDataBufferRefMut<'_> {
     unsafe fn get_as<T>(&self, index: usize) -> Option<&T> {
         unsafe { self.0.get_as(index) }
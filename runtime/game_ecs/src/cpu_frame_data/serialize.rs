@@ -103,6 +103,14 @@ impl CpuDataBuffer {
                 }
             })
         {
+            // Removed resources have an empty buffer.
+            let present = self.len != 0;
+            present.serialize(serializer)?;
+
+            if !present {
+                return Ok(());
+            }
+
             assert_eq!(
                 self.data.len(),
                 component_info.size,
@@ -160,6 +168,11 @@ impl CpuDataBuffer {
             })
         {
             let mut buffer = CpuDataBuffer::new(component_info.size, component_info.align);
+
+            if !unsafe { bool::deserialize(deserializer) }? {
+                return Ok(buffer);
+            }
+
             let mut buffer_mut = CpuDataBufferRefMut(&mut buffer);
             let data = buffer_mut.grow();
 
@@ -209,6 +222,21 @@ impl CpuDataBuffer {
                 }
             })
         {
+            let present = unsafe { bool::deserialize(deserializer) }?;
+            let mut buffer_mut = CpuDataBufferRefMut(self);
+
+            if !present {
+                if !buffer_mut.is_empty() {
+                    buffer_mut.swap_remove(0);
+                }
+
+                return Ok(());
+            }
+
+            if buffer_mut.is_empty() {
+                buffer_mut.grow();
+            }
+
             unsafe {
                 deserialize_resource(
                     deserializer,
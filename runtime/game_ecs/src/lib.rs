@@ -7,7 +7,9 @@
 use std::mem::MaybeUninit;
 
 pub use crate::{
-    archetype::{ArchetypeKey, ArchetypeStorage, ArchetypeStorageMap},
+    archetype::{
+        ArchetypeKey, ArchetypeStorage, ArchetypeStorageMap, ComponentOffsetInfo, ComponentTicks,
+    },
     callables::Callables,
     component::{
         AsyncCompletionInfo, CallableInfo, ComponentBundle, ComponentDefault, ComponentInfo,
@@ -15,9 +17,15 @@ pub use crate::{
         manually_register_resource,
     },
     cpu_frame_data::{CpuDataBuffer, CpuFrameData},
+    profiler::{Profiler, ScopeId, ScopeStats},
+    removed_components::RemovedComponentStorage,
+    sparse_components::SparseComponentStorage,
+    state::{StateRegistry, StateTransition, SystemStateCondition},
     system::{
-        EcsSystem, EcsSystemExecuteResources, SystemGraph, WorldDelegate, add_components_helper,
-        bundle_required_components, system_execute_resources,
+        EcsSystem, EcsSystemExecuteResources, QueryAccess, SystemAccess, SystemConstraint,
+        SystemError, SystemErrorPolicy, SystemGraph, SystemInfo, WorldDelegate,
+        add_components_helper, bundle_required_components, system_execute_resources,
+        with_system_execute_resources,
     },
 };
 
@@ -25,6 +33,10 @@ mod archetype;
 mod callables;
 mod component;
 pub mod cpu_frame_data;
+mod profiler;
+mod removed_components;
+mod sparse_components;
+mod state;
 mod system;
 
 pub trait GpuFrameData: std::fmt::Debug + Send + Sync + 'static {
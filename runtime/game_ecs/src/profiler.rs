@@ -0,0 +1,330 @@
+//! Timing of systems and engine work.
+//!
+//! Each timed scope is recorded twice: as rolling per-frame statistics of the
+//! scope, and as an event of a trace of the last frames, which may be exported
+//! in the Chrome `trace_event` format and viewed in `chrome://tracing` or
+//! Perfetto.
+//!
+//! Profiling is disabled by default, in which case recording a scope does
+//! nothing. Scopes are registered once, and recorded by their [`ScopeId`].
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+use json::{JsonValue, object};
+use platform::{Clock, Executor, Platform};
+
+/// The number of frames of which scope statistics are kept.
+const STATS_FRAMES: usize = 120;
+
+/// The number of frames of which scope trace events are kept.
+const TRACE_FRAMES: usize = 300;
+
+/// The scopes of engine work, registered by every profiler. Their ids are the
+/// associated constants of [`ScopeId`].
+const ENGINE_SCOPES: [&str; 5] = [
+    "engine::execute_cpu",
+    "engine::execute_fixed",
+    "engine::execute_gpu",
+    "engine::apply_commands",
+    "engine::update_transforms",
+];
+
+#[derive(Debug)]
+pub struct Profiler {
+    /// Scopes are only recorded while profiling is enabled.
+    enabled: bool,
+    /// The names of the registered scopes, indexed by `ScopeId`.
+    scope_names: Vec<String>,
+    scope_ids: HashMap<String, ScopeId>,
+    data: Mutex<ProfilerData>,
+}
+
+/// Identifies a scope registered with [`Profiler::register_scope()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScopeId(usize);
+
+#[derive(Debug, Default)]
+struct ProfilerData {
+    /// The statistics of each scope, indexed by `ScopeId`. `None` if the scope
+    /// has not been recorded yet.
+    stats: Vec<Option<ScopeStats>>,
+    /// Events recorded during the current frame.
+    frame_events: Vec<TraceEvent>,
+    /// Events of the previous frames, oldest first.
+    trace: VecDeque<Vec<TraceEvent>>,
+}
+
+/// The time spent in a scope during each of the last [`STATS_FRAMES`] frames.
+#[derive(Clone, Debug, Default)]
+pub struct ScopeStats {
+    /// The total time of each frame, oldest first.
+    frames: VecDeque<Duration>,
+    /// The total time of the current frame.
+    current: Duration,
+}
+
+#[derive(Clone, Debug)]
+struct TraceEvent {
+    scope: ScopeId,
+    thread_index: usize,
+    start: Duration,
+    duration: Duration,
+}
+
+impl ScopeId {
+    pub const EXECUTE_CPU: Self = Self(0);
+    pub const EXECUTE_FIXED: Self = Self(1);
+    pub const EXECUTE_GPU: Self = Self(2);
+    pub const APPLY_COMMANDS: Self = Self(3);
+    pub const UPDATE_TRANSFORMS: Self = Self(4);
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        let mut profiler = Self {
+            enabled: false,
+            scope_names: Vec::new(),
+            scope_ids: HashMap::new(),
+            data: Mutex::default(),
+        };
+
+        for name in ENGINE_SCOPES {
+            profiler.register_scope(name);
+        }
+
+        profiler
+    }
+}
+
+impl Profiler {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables the recording of scopes. Recorded statistics and
+    /// trace events are kept while disabled.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Registers a scope, or returns the id of the scope already registered
+    /// with `name`. Scopes are named like systems, e.g. `my_module::my_system`
+    /// or `engine::apply_commands`.
+    pub fn register_scope(&mut self, name: &str) -> ScopeId {
+        if let Some(scope) = self.scope_ids.get(name) {
+            return *scope;
+        }
+
+        let scope = ScopeId(self.scope_names.len());
+        self.scope_names.push(name.to_owned());
+        self.scope_ids.insert(name.to_owned(), scope);
+        self.data.get_mut().unwrap().stats.push(None);
+
+        scope
+    }
+
+    /// Calls `f`, recording its duration in `scope`.
+    pub fn scope<P: Platform, R>(&self, scope: ScopeId, f: impl FnOnce() -> R) -> R {
+        if !self.enabled {
+            return f();
+        }
+
+        let start = P::Clock::now();
+        let res = f();
+        self.record::<P>(scope, start);
+        res
+    }
+
+    /// Records a scope which started at `start`, and ends now.
+    pub fn record<P: Platform>(&self, scope: ScopeId, start: Duration) {
+        if !self.enabled {
+            return;
+        }
+
+        let end = P::Clock::now();
+        let duration = end.saturating_sub(start);
+
+        let mut data = self.data.lock().unwrap();
+
+        data.stats[scope.0]
+            .get_or_insert_with(ScopeStats::default)
+            .current += duration;
+
+        data.frame_events.push(TraceEvent {
+            scope,
+            thread_index: P::Executor::thread_index(),
+            start,
+            duration,
+        });
+    }
+
+    /// Ends the current frame, and begins the next one.
+    pub fn begin_frame(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        let data = self.data.get_mut().unwrap();
+
+        for stats in data.stats.iter_mut().flatten() {
+            if stats.frames.len() == STATS_FRAMES {
+                stats.frames.pop_front();
+            }
+            stats.frames.push_back(std::mem::take(&mut stats.current));
+        }
+
+        if data.trace.len() == TRACE_FRAMES {
+            data.trace.pop_front();
+        }
+        let frame_events = std::mem::take(&mut data.frame_events);
+        data.trace.push_back(frame_events);
+    }
+
+    /// Returns the statistics of each scope, sorted by name.
+    pub fn scope_stats(&self) -> Vec<(String, ScopeStats)> {
+        let data = self.data.lock().unwrap();
+
+        let mut scope_stats = self
+            .scope_names
+            .iter()
+            .zip(&data.stats)
+            .filter_map(|(name, stats)| Some((name.clone(), stats.clone()?)))
+            .collect::<Vec<_>>();
+        scope_stats.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        scope_stats
+    }
+
+    /// Returns the events of the last [`TRACE_FRAMES`] frames in the Chrome
+    /// `trace_event` JSON format. Scopes are categorized by the module part of
+    /// their name.
+    pub fn chrome_trace(&self) -> String {
+        let data = self.data.lock().unwrap();
+
+        let mut trace_events = JsonValue::new_array();
+        for event in data.trace.iter().flatten() {
+            let name = self.scope_names[event.scope.0].as_str();
+            let category = name.split_once("::").map_or("", |(category, _)| category);
+
+            trace_events
+                .push(object! {
+                    name: name,
+                    cat: category,
+                    ph: "X",
+                    ts: event.start.as_secs_f64() * 1e6,
+                    dur: event.duration.as_secs_f64() * 1e6,
+                    pid: 0,
+                    tid: event.thread_index,
+                })
+                .unwrap();
+        }
+
+        object! {
+            traceEvents: trace_events,
+            displayTimeUnit: "ms",
+        }
+        .dump()
+    }
+}
+
+impl ScopeStats {
+    /// The time spent in the scope during the last frame.
+    pub fn last(&self) -> Duration {
+        self.frames.back().copied().unwrap_or_default()
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.frames.is_empty() {
+            Duration::ZERO
+        } else {
+            self.frames.iter().sum::<Duration>() / self.frames.len() as u32
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        self.frames.iter().max().copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use platform::test::TestPlatform;
+
+    use super::*;
+
+    #[test]
+    fn scope_stats_roll_over_frames() {
+        let mut profiler = Profiler::default();
+        profiler.set_enabled(true);
+        let a = profiler.register_scope("m::a");
+
+        for _ in 0..STATS_FRAMES + 10 {
+            profiler.scope::<TestPlatform, _>(a, || {});
+            profiler.scope::<TestPlatform, _>(a, || {});
+            profiler.begin_frame();
+        }
+        profiler.scope::<TestPlatform, _>(ScopeId::APPLY_COMMANDS, || {});
+        profiler.begin_frame();
+
+        let scope_stats = profiler.scope_stats();
+        let names = scope_stats
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["engine::apply_commands", "m::a"]);
+        assert_eq!(scope_stats[0].1.frames.len(), 1);
+        assert_eq!(scope_stats[1].1.frames.len(), STATS_FRAMES);
+        assert_eq!(scope_stats[1].1.last(), Duration::ZERO);
+    }
+
+    #[test]
+    fn scopes_are_registered_once() {
+        let mut profiler = Profiler::default();
+        let a = profiler.register_scope("m::a");
+
+        assert_eq!(profiler.register_scope("m::a"), a);
+        assert_ne!(profiler.register_scope("m::b"), a);
+        assert_eq!(
+            profiler.register_scope("engine::execute_gpu"),
+            ScopeId::EXECUTE_GPU
+        );
+    }
+
+    #[test]
+    fn disabled_profiler_records_nothing() {
+        let mut profiler = Profiler::default();
+        let a = profiler.register_scope("m::a");
+
+        assert_eq!(profiler.scope::<TestPlatform, _>(a, || 1), 1);
+        profiler.begin_frame();
+
+        assert!(profiler.scope_stats().is_empty());
+        assert_eq!(
+            json::parse(&profiler.chrome_trace()).unwrap()["traceEvents"].len(),
+            0
+        );
+    }
+
+    #[test]
+    fn chrome_trace_contains_complete_events() {
+        let mut profiler = Profiler::default();
+        profiler.set_enabled(true);
+        let a = profiler.register_scope("m::a");
+
+        profiler.scope::<TestPlatform, _>(a, || {});
+        profiler.begin_frame();
+
+        let trace = json::parse(&profiler.chrome_trace()).unwrap();
+        let event = &trace["traceEvents"][0];
+
+        assert_eq!(trace["traceEvents"].len(), 1);
+        assert_eq!(event["name"], "m::a");
+        assert_eq!(event["cat"], "m");
+        assert_eq!(event["ph"], "X");
+    }
+}
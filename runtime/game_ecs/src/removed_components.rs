@@ -0,0 +1,81 @@
+use std::{collections::HashMap, num::NonZero};
+
+use event::EventWriterStorage;
+use game_entity::EntityId;
+use platform::Platform;
+use void_public::ComponentId;
+
+/// Records components which are removed from entities, including components of
+/// despawned entities, for systems taking `RemovedComponents<T>` inputs.
+///
+/// Only registered component types are recorded. Removals are readable during
+/// the frame following the one in which they occurred.
+pub struct RemovedComponentStorage<P: Platform> {
+    components: HashMap<ComponentId, RemovedComponentBuffers<P>>,
+    /// Scratch buffer used to assemble entries.
+    entry: Vec<u8>,
+}
+
+struct RemovedComponentBuffers<P: Platform> {
+    readable: EventWriterStorage<P>,
+    pending: EventWriterStorage<P>,
+}
+
+impl<P: Platform> Default for RemovedComponentStorage<P> {
+    fn default() -> Self {
+        Self {
+            components: HashMap::new(),
+            entry: Vec::new(),
+        }
+    }
+}
+
+impl<P: Platform> RemovedComponentStorage<P> {
+    /// Starts recording removals of a component type.
+    pub fn register(&mut self, component_id: ComponentId) {
+        self.components
+            .entry(component_id)
+            .or_insert_with(|| RemovedComponentBuffers {
+                readable: EventWriterStorage::default(),
+                pending: EventWriterStorage::default(),
+            });
+    }
+
+    pub fn is_registered(&self, component_id: &ComponentId) -> bool {
+        self.components.contains_key(component_id)
+    }
+
+    /// Records the removal of a component. Each entry holds the `EntityId`,
+    /// followed by the component data. Does nothing if the component type is
+    /// not registered.
+    pub fn write(&mut self, component_id: &ComponentId, entity_id: EntityId, data: &[u8]) {
+        let Some(buffers) = self.components.get_mut(component_id) else {
+            return;
+        };
+
+        self.entry.clear();
+        self.entry
+            .extend_from_slice(&NonZero::<u64>::from(entity_id).get().to_ne_bytes());
+        self.entry.extend_from_slice(data);
+
+        unsafe {
+            buffers.pending.borrow_mut().write_module_event(&self.entry);
+        }
+    }
+
+    /// Makes the removals recorded since the previous call readable, and
+    /// discards the removals which were readable.
+    pub fn swap_buffers(&mut self) {
+        for buffers in self.components.values_mut() {
+            buffers.readable.borrow_mut().clear();
+            std::mem::swap(&mut buffers.readable, &mut buffers.pending);
+        }
+    }
+
+    /// Returns the readable removals of a component type.
+    pub fn storage(&self, component_id: &ComponentId) -> Option<&EventWriterStorage<P>> {
+        self.components
+            .get(component_id)
+            .map(|buffers| &buffers.readable)
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use game_entity::EntityId;
+use void_public::ComponentId;
+
+/// Stores sparse components, which are zero-sized tags declared with
+/// `#[component(sparse)]`. Sparse components are not part of an entity's
+/// archetype, so adding or removing them does not move the entity's data.
+/// Queries filter archetype rows by sparse set membership instead.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "state_snapshots", derive(snapshot::Deserialize))]
+#[cfg_attr(feature = "state_snapshots", derive(snapshot::Serialize))]
+pub struct SparseComponentStorage {
+    sets: HashMap<ComponentId, SparseSet>,
+}
+
+/// A set of entities, with constant time insertion, removal and lookup.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "state_snapshots", derive(snapshot::Deserialize))]
+#[cfg_attr(feature = "state_snapshots", derive(snapshot::Serialize))]
+struct SparseSet {
+    /// Indices into `dense`, by entity index.
+    sparse: Vec<Option<u32>>,
+    dense: Vec<EntityId>,
+}
+
+impl SparseComponentStorage {
+    /// Adds a sparse component to an entity. Returns `false` if the entity
+    /// already had the component.
+    pub fn insert(&mut self, component_id: ComponentId, entity_id: EntityId) -> bool {
+        self.sets.entry(component_id).or_default().insert(entity_id)
+    }
+
+    /// Removes a sparse component from an entity. Returns `false` if the
+    /// entity did not have the component.
+    pub fn remove(&mut self, component_id: &ComponentId, entity_id: EntityId) -> bool {
+        self.sets
+            .get_mut(component_id)
+            .is_some_and(|set| set.remove(entity_id))
+    }
+
+    /// Removes all sparse components from an entity, returning the removed
+    /// component ids.
+    pub fn remove_entity(&mut self, entity_id: EntityId) -> Vec<ComponentId> {
+        self.sets
+            .iter_mut()
+            .filter_map(|(component_id, set)| set.remove(entity_id).then_some(*component_id))
+            .collect()
+    }
+
+    pub fn contains(&self, component_id: &ComponentId, entity_id: EntityId) -> bool {
+        self.sets
+            .get(component_id)
+            .is_some_and(|set| set.contains(entity_id))
+    }
+
+    /// Returns the ids of all sparse components of an entity.
+    pub fn components(&self, entity_id: EntityId) -> impl Iterator<Item = ComponentId> + '_ {
+        self.sets
+            .iter()
+            .filter(move |(_, set)| set.contains(entity_id))
+            .map(|(component_id, _)| *component_id)
+    }
+}
+
+impl SparseSet {
+    fn insert(&mut self, entity_id: EntityId) -> bool {
+        if self.contains(entity_id) {
+            return false;
+        }
+
+        let index = entity_id.as_index();
+
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, None);
+        }
+
+        self.sparse[index] = Some(self.dense.len().try_into().unwrap());
+        self.dense.push(entity_id);
+
+        true
+    }
+
+    fn remove(&mut self, entity_id: EntityId) -> bool {
+        if !self.contains(entity_id) {
+            return false;
+        }
+
+        let dense_index = self.sparse[entity_id.as_index()].take().unwrap() as usize;
+        self.dense.swap_remove(dense_index);
+
+        if let Some(moved) = self.dense.get(dense_index) {
+            self.sparse[moved.as_index()] = Some(dense_index as u32);
+        }
+
+        true
+    }
+
+    fn contains(&self, entity_id: EntityId) -> bool {
+        self.sparse
+            .get(entity_id.as_index())
+            .copied()
+            .flatten()
+            .is_some_and(|dense_index| self.dense[dense_index as usize] == entity_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let tag = ComponentId::new(1).unwrap();
+        let a = EntityId::new(1, 0);
+        let b = EntityId::new(5, 0);
+        let c = EntityId::new(3, 0);
+
+        let mut storage = SparseComponentStorage::default();
+        assert!(storage.insert(tag, a));
+        assert!(storage.insert(tag, b));
+        assert!(storage.insert(tag, c));
+        assert!(!storage.insert(tag, b));
+
+        assert!(storage.remove(&tag, a));
+        assert!(!storage.remove(&tag, a));
+        assert!(!storage.contains(&tag, a));
+        assert!(storage.contains(&tag, b));
+        assert!(storage.contains(&tag, c));
+
+        // a stale id, which shares an index with a live entity
+        assert!(!storage.contains(&tag, EntityId::new(5, 1)));
+
+        assert_eq!(storage.remove_entity(b), [tag]);
+        assert!(!storage.contains(&tag, b));
+        assert!(storage.contains(&tag, c));
+        assert_eq!(storage.components(c).collect::<Vec<_>>(), [tag]);
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+};
+
+use void_public::state::StateCondition;
+
+/// Stores the current value of each state type, by its string id. State values
+/// are the variant indices of the state enum.
+#[derive(Debug, Default)]
+pub struct StateRegistry {
+    states: HashMap<CString, StateValue>,
+}
+
+#[derive(Debug, Default)]
+struct StateValue {
+    /// `None` until the initial state has been entered.
+    current: Option<u32>,
+    next: Option<u32>,
+}
+
+/// A transition applied by [`StateRegistry::apply_transitions()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateTransition {
+    pub state_id: CString,
+    /// `None` when entering the initial state.
+    pub from: Option<u32>,
+    pub to: u32,
+}
+
+/// A state condition declared on a system.
+#[derive(Clone, Debug)]
+pub struct SystemStateCondition {
+    pub condition: StateCondition,
+    pub state_id: CString,
+    pub state: u32,
+}
+
+impl StateRegistry {
+    /// Registers a state type. Its initial state (index 0) is entered on the
+    /// next call to [`StateRegistry::apply_transitions()`]. Registering a state
+    /// type more than once has no effect.
+    pub fn register(&mut self, state_id: &CStr) {
+        if !self.states.contains_key(state_id) {
+            self.states.insert(
+                state_id.to_owned(),
+                StateValue {
+                    current: None,
+                    next: Some(0),
+                },
+            );
+        }
+    }
+
+    /// Returns the current state, or the initial state (index 0) if the state
+    /// type is unknown or has not been entered yet.
+    pub fn get(&self, state_id: &CStr) -> u32 {
+        self.states
+            .get(state_id)
+            .and_then(|value| value.current)
+            .unwrap_or_default()
+    }
+
+    /// Queues a transition, applied on the next call to
+    /// [`StateRegistry::apply_transitions()`]. Overrides any previously queued
+    /// transition.
+    pub fn set_next(&mut self, state_id: &CStr, state: u32) {
+        self.register(state_id);
+        self.states.get_mut(state_id).unwrap().next = Some(state);
+    }
+
+    /// Applies all queued transitions, and returns them. Transitions to the
+    /// current state are discarded.
+    pub fn apply_transitions(&mut self) -> Vec<StateTransition> {
+        let mut transitions = Vec::new();
+
+        for (state_id, value) in &mut self.states {
+            let Some(next) = value.next.take() else {
+                continue;
+            };
+
+            if value.current == Some(next) {
+                continue;
+            }
+
+            transitions.push(StateTransition {
+                state_id: state_id.clone(),
+                from: value.current.replace(next),
+                to: next,
+            });
+        }
+
+        transitions
+    }
+}
+
+impl SystemStateCondition {
+    /// Returns `true` if the condition permits the system to run during regular
+    /// frame execution.
+    pub fn is_satisfied(&self, states: &StateRegistry) -> bool {
+        match self.condition {
+            StateCondition::InState => states.get(&self.state_id) == self.state,
+            StateCondition::OnEnter | StateCondition::OnExit => false,
+        }
+    }
+
+    /// Returns `true` if the condition is triggered by `transition`.
+    pub fn is_triggered_by(&self, transition: &StateTransition) -> bool {
+        if self.state_id != transition.state_id {
+            return false;
+        }
+
+        match self.condition {
+            StateCondition::InState => false,
+            StateCondition::OnEnter => transition.to == self.state,
+            StateCondition::OnExit => transition.from == Some(self.state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_transitions() {
+        let mut states = StateRegistry::default();
+        states.register(c"GameState");
+
+        assert_eq!(
+            states.apply_transitions(),
+            [StateTransition {
+                state_id: c"GameState".into(),
+                from: None,
+                to: 0,
+            }]
+        );
+        assert!(states.apply_transitions().is_empty());
+
+        states.set_next(c"GameState", 2);
+        states.set_next(c"GameState", 1);
+        assert_eq!(states.get(c"GameState"), 0);
+
+        assert_eq!(
+            states.apply_transitions(),
+            [StateTransition {
+                state_id: c"GameState".into(),
+                from: Some(0),
+                to: 1,
+            }]
+        );
+        assert_eq!(states.get(c"GameState"), 1);
+
+        states.set_next(c"GameState", 1);
+        assert!(states.apply_transitions().is_empty());
+    }
+}
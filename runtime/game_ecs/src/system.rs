@@ -1,19 +1,23 @@
 use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
     error::Error,
-    ffi::CStr,
+    ffi::{CStr, CString},
     mem::MaybeUninit,
     ptr::{self, NonNull},
     slice,
+    sync::Mutex,
 };
 
 use event::{ComponentData, EventManager};
 use game_entity::{EntityId, ParentType};
-use platform::Platform;
-use void_public::{ComponentId, ComponentRef};
+use platform::{Clock, Executor, Platform};
+use void_public::{ComponentId, ComponentRef, state::StateCondition, system::SystemOrdering};
 
 use crate::{
     ArchetypeKey, ArchetypeStorage, Callables, ComponentBundle, ComponentDefault,
-    ComponentRegistry, CpuFrameData, GpuFrameData,
+    ComponentRegistry, CpuFrameData, GpuFrameData, Profiler, RemovedComponentStorage, ScopeId,
+    SparseComponentStorage, StateRegistry, StateTransition, SystemStateCondition,
 };
 
 #[cfg(feature = "state_snapshots")]
@@ -31,6 +35,12 @@ pub struct EcsSystemExecuteResources<'a, P: Platform, G: GpuFrameData> {
     pub component_bundles: &'a [ComponentBundle],
     pub component_registry: &'a ComponentRegistry,
     pub callables: &'a Callables,
+    pub states: &'a StateRegistry,
+    pub removed_components: &'a RemovedComponentStorage<P>,
+    pub sparse_components: &'a SparseComponentStorage,
+    /// The names of the prefabs which are registered, or being read from their
+    /// asset paths.
+    pub prefab_names: &'a HashSet<CString>,
 }
 
 /// This is set to point to `EcsSystemExecuteResources` at the start of frame
@@ -61,14 +71,40 @@ where
     f(resources)
 }
 
+/// Calls `f`, during which `resources` are accessible through
+/// [`system_execute_resources()`]. This is used to execute systems outside of
+/// the [`SystemGraph`], such as exclusive systems.
+pub fn with_system_execute_resources<T, P: Platform, G: GpuFrameData>(
+    resources: &EcsSystemExecuteResources<'_, P, G>,
+    f: impl FnOnce() -> T,
+) -> T {
+    unsafe {
+        SYSTEM_EXECUTE_RESOURCES = (resources as *const EcsSystemExecuteResources<'_, P, G>).cast();
+    }
+
+    let res = f();
+
+    unsafe {
+        SYSTEM_EXECUTE_RESOURCES = ptr::null();
+    }
+
+    res
+}
+
 pub trait EcsSystem<P: Platform, G: GpuFrameData>: Send {
     fn name(&self) -> &str;
 
+    /// The data accessed by the system, used to determine which systems may
+    /// execute concurrently.
+    fn access(&self) -> &SystemAccess;
+
     fn add_archetype_input(&mut self, archetype: &ArchetypeKey, storage: &ArchetypeStorage);
 
     fn clear_archetype_inputs(&mut self);
 
-    fn execute(&mut self) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Executes the system. `change_tick` is recorded for all components the
+    /// system accesses mutably. See [`SystemGraph::change_tick()`].
+    fn execute(&mut self, change_tick: u64) -> Result<(), Box<dyn Error + Send + Sync>>;
 
     /// Prompts the system to clear all of its `EventWriter` buffers.
     ///
@@ -84,6 +120,10 @@ pub trait EcsSystem<P: Platform, G: GpuFrameData>: Send {
 pub trait WorldDelegate: Sync {
     fn allocate_entity_id(&self) -> EntityId;
 
+    /// Allocates `count` entity ids at once, which is cheaper than allocating
+    /// them one by one.
+    fn allocate_entity_ids(&self, count: usize) -> Vec<EntityId>;
+
     /// Returns the `EntityId` associated with this label.
     fn label_entity(&self, label: &CStr) -> Option<EntityId>;
 
@@ -91,6 +131,10 @@ pub trait WorldDelegate: Sync {
     fn entity_label(&self, entity_id: EntityId) -> Option<&CStr>;
 
     fn get_parent_type(&self, entity_id: EntityId) -> Option<ParentType>;
+
+    /// Returns the children of this `EntityId`, or `None` if the entity does
+    /// not exist.
+    fn children(&self, entity_id: EntityId) -> Option<&[EntityId]>;
 }
 
 /// Checks for valid component ids, bundles required components, and provides
@@ -177,16 +221,157 @@ where
     bundled_components
 }
 
+/// Describes the data a system accesses.
+#[derive(Clone, Debug, Default)]
+pub struct SystemAccess {
+    pub queries: Vec<QueryAccess>,
+    pub resources_ref: Vec<ComponentId>,
+    pub resources_mut: Vec<ComponentId>,
+    pub events_read: Vec<CString>,
+    pub events_write: Vec<CString>,
+}
+
+/// Describes the components a query accesses, and which archetypes it may
+/// match.
+#[derive(Clone, Debug, Default)]
+pub struct QueryAccess {
+    pub components_ref: Vec<ComponentId>,
+    pub components_mut: Vec<ComponentId>,
+    /// Components which must be present in a matching archetype.
+    pub required: Vec<ComponentId>,
+    /// Components which must not be present in a matching archetype.
+    pub excluded: Vec<ComponentId>,
+}
+
+impl SystemAccess {
+    /// Returns `true` if the systems may not execute concurrently.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        let overlaps = |a: &[ComponentId], b: &[ComponentId]| a.iter().any(|id| b.contains(id));
+
+        if overlaps(&self.resources_mut, &other.resources_mut)
+            || overlaps(&self.resources_mut, &other.resources_ref)
+            || overlaps(&self.resources_ref, &other.resources_mut)
+        {
+            return true;
+        }
+
+        // Each writing system has its own event buffer, so only readers
+        // conflict with writers.
+        if self
+            .events_read
+            .iter()
+            .any(|event| other.events_write.contains(event))
+            || self
+                .events_write
+                .iter()
+                .any(|event| other.events_read.contains(event))
+        {
+            return true;
+        }
+
+        self.queries.iter().any(|query| {
+            other
+                .queries
+                .iter()
+                .any(|other| query.conflicts_with(other))
+        })
+    }
+}
+
+impl QueryAccess {
+    /// Returns `true` if the queries may not execute concurrently.
+    ///
+    /// Component data is stored per archetype, and mutably accessing any
+    /// component locks the archetype's buffer. Thus queries conflict if either
+    /// of them mutably accesses a component, and they may match a common
+    /// archetype.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        if self.components_mut.is_empty() && other.components_mut.is_empty() {
+            return false;
+        }
+
+        let disjoint = self.required.iter().any(|id| other.excluded.contains(id))
+            || other.required.iter().any(|id| self.excluded.contains(id));
+
+        !disjoint
+    }
+}
+
 pub struct SystemGraph<P: Platform, G: GpuFrameData> {
     // separate CPU and GPU systems for now, to simulate a graph barrier
     cpu_systems: Vec<SystemInfo<P, G>>,
     gpu_systems: Vec<SystemInfo<P, G>>,
+    /// Systems taking a `World` input, which execute one at a time between CPU
+    /// and GPU systems. See [`SystemGraph::take_exclusive_systems()`].
+    exclusive_systems: Vec<SystemInfo<P, G>>,
+    change_tick: u64,
+    /// Whether a fixed timestep has executed since the start of the frame.
+    fixed_executed: bool,
+    /// Debug flag which disables concurrent execution of CPU systems.
+    sequential_execution: bool,
+    /// The error policy of systems which do not set their own.
+    error_policy: SystemErrorPolicy,
+    profiler: Profiler,
 }
 
 pub struct SystemInfo<P: Platform, G: GpuFrameData> {
     system: Box<dyn EcsSystem<P, G>>,
     enabled: bool,
     is_once: bool,
+    /// Fixed systems execute in [`SystemGraph::execute_fixed()`], rather than
+    /// [`SystemGraph::execute_cpu()`].
+    is_fixed: bool,
+    constraints: Vec<SystemConstraint>,
+    state_conditions: Vec<SystemStateCondition>,
+    /// Systems in the same stage do not conflict, and may execute
+    /// concurrently. Stages execute in ascending order.
+    stage: usize,
+    /// Overrides the error policy of the graph for this system.
+    error_policy: Option<SystemErrorPolicy>,
+    /// Errors returned since the last [`SystemGraph::take_errors()`], up to
+    /// [`MAX_SYSTEM_ERRORS`].
+    errors: Vec<String>,
+    /// Set if the system executed in this frame's transitions, so that its
+    /// events remain readable by the systems executing after them.
+    transition_executed: bool,
+    profiler_scope: ScopeId,
+}
+
+/// How the engine handles a system returning an error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SystemErrorPolicy {
+    /// The system is disabled, until it is enabled again with
+    /// [`SystemGraph::set_system_enabled()`].
+    #[default]
+    Disable,
+    /// The system stays enabled, and executes again on its next run.
+    Retry,
+    /// The engine stops executing frames at the end of the frame, so that the
+    /// platform may shut down.
+    Abort,
+}
+
+/// The number of errors kept per system between calls to
+/// [`SystemGraph::take_errors()`]. Further errors are only logged, so that a
+/// system failing on every fixed step does not grow without bound.
+const MAX_SYSTEM_ERRORS: usize = 16;
+
+/// An error returned by a system. See [`SystemGraph::take_errors()`].
+#[derive(Clone, Debug)]
+pub struct SystemError {
+    /// The namespaced system name, e.g. `MyModule::my_system`.
+    pub system_name: String,
+    pub message: String,
+    /// The policy the error was handled with.
+    pub policy: SystemErrorPolicy,
+}
+
+/// An ordering constraint declared on a system.
+#[derive(Clone, Debug)]
+pub struct SystemConstraint {
+    pub ordering: SystemOrdering,
+    /// The namespaced name of the target system, or the set label.
+    pub target: String,
 }
 
 impl<P: Platform, G: GpuFrameData> Default for SystemGraph<P, G> {
@@ -194,127 +379,392 @@ impl<P: Platform, G: GpuFrameData> Default for SystemGraph<P, G> {
         Self {
             cpu_systems: Default::default(),
             gpu_systems: Default::default(),
+            exclusive_systems: Default::default(),
+            change_tick: 0,
+            fixed_executed: false,
+            sequential_execution: false,
+            error_policy: SystemErrorPolicy::default(),
+            profiler: Profiler::default(),
         }
     }
 }
 
 impl<P: Platform, G: GpuFrameData> SystemGraph<P, G> {
-    pub fn add_cpu_system(&mut self, system: Box<dyn EcsSystem<P, G>>, is_once: bool) {
-        self.cpu_systems.push(SystemInfo {
+    /// Adds a CPU system, and re-sorts all CPU systems according to their
+    /// ordering constraints. Returns an error, and does not add the system, if
+    /// its constraints would introduce a cycle.
+    pub fn add_cpu_system(
+        &mut self,
+        system: Box<dyn EcsSystem<P, G>>,
+        is_once: bool,
+        is_fixed: bool,
+        constraints: Vec<SystemConstraint>,
+        state_conditions: Vec<SystemStateCondition>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        add_system(
+            &mut self.cpu_systems,
+            &mut self.profiler,
             system,
-            enabled: true,
             is_once,
-        });
+            is_fixed,
+            constraints,
+            state_conditions,
+        )
+    }
+
+    /// Adds a GPU system, and re-sorts all GPU systems according to their
+    /// ordering constraints. Returns an error, and does not add the system, if
+    /// its constraints would introduce a cycle.
+    pub fn add_gpu_system(
+        &mut self,
+        system: Box<dyn EcsSystem<P, G>>,
+        is_once: bool,
+        constraints: Vec<SystemConstraint>,
+        state_conditions: Vec<SystemStateCondition>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        add_system(
+            &mut self.gpu_systems,
+            &mut self.profiler,
+            system,
+            is_once,
+            false,
+            constraints,
+            state_conditions,
+        )
     }
 
-    pub fn add_gpu_system(&mut self, system: Box<dyn EcsSystem<P, G>>, is_once: bool) {
-        self.gpu_systems.push(SystemInfo {
+    /// Adds an exclusive system, which takes a `World` input, and re-sorts all
+    /// exclusive systems according to their ordering constraints. Returns an
+    /// error, and does not add the system, if its constraints would introduce
+    /// a cycle.
+    pub fn add_exclusive_system(
+        &mut self,
+        system: Box<dyn EcsSystem<P, G>>,
+        is_once: bool,
+        constraints: Vec<SystemConstraint>,
+        state_conditions: Vec<SystemStateCondition>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        add_system(
+            &mut self.exclusive_systems,
+            &mut self.profiler,
             system,
-            enabled: true,
             is_once,
-        });
+            false,
+            constraints,
+            state_conditions,
+        )
     }
 
+    /// Returns the names of all systems, in execution order.
     pub fn system_names(&self) -> impl Iterator<Item = &str> {
         self.cpu_systems
             .iter()
+            .chain(&self.exclusive_systems)
             .chain(&self.gpu_systems)
             .map(|system_info| system_info.system.name())
     }
 
     pub fn system_enabled(&self, system_name: &str) -> Option<bool> {
-        if let Some(system_info) = self
-            .cpu_systems
+        self.cpu_systems
             .iter()
+            .chain(&self.exclusive_systems)
+            .chain(&self.gpu_systems)
             .find(|system_info| system_info.system.name() == system_name)
-        {
-            Some(system_info.enabled)
-        } else {
-            self.gpu_systems
-                .iter()
-                .find(|system_info| system_info.system.name() == system_name)
-                .map(|system_info| system_info.enabled)
-        }
+            .map(|system_info| system_info.enabled)
     }
 
     pub fn set_system_enabled(&mut self, system_name: &str, enabled: bool) {
         if let Some(system_info) = self
             .cpu_systems
             .iter_mut()
+            .chain(&mut self.exclusive_systems)
+            .chain(&mut self.gpu_systems)
             .find(|system_info| system_info.system.name() == system_name)
         {
             system_info.enabled = enabled;
-        } else if let Some(system_info) = self
-            .gpu_systems
+        } else {
+            log::warn!("SystemGraph::set_system_enabled(): {system_name:?} not found");
+        }
+    }
+
+    pub fn error_policy(&self) -> SystemErrorPolicy {
+        self.error_policy
+    }
+
+    /// Sets how errors returned by systems are handled, unless overridden per
+    /// system with [`SystemGraph::set_system_error_policy()`].
+    pub fn set_error_policy(&mut self, error_policy: SystemErrorPolicy) {
+        self.error_policy = error_policy;
+    }
+
+    /// Overrides the error policy for a single system. `None` restores the
+    /// policy of the graph.
+    pub fn set_system_error_policy(
+        &mut self,
+        system_name: &str,
+        error_policy: Option<SystemErrorPolicy>,
+    ) {
+        if let Some(system_info) = self
+            .cpu_systems
             .iter_mut()
+            .chain(&mut self.exclusive_systems)
+            .chain(&mut self.gpu_systems)
             .find(|system_info| system_info.system.name() == system_name)
         {
-            system_info.enabled = enabled;
+            system_info.error_policy = error_policy;
         } else {
-            log::warn!("SystemGraph::set_system_enabled(): {system_name:?} not found");
+            log::warn!("SystemGraph::set_system_error_policy(): {system_name:?} not found");
         }
     }
 
-    /// Executes all CPU systems.
-    pub async fn execute_cpu(&mut self, resources: &EcsSystemExecuteResources<'_, P, G>) {
+    /// Returns the errors returned by systems since the last call, in
+    /// execution order.
+    pub fn take_errors(&mut self) -> Vec<SystemError> {
+        let error_policy = self.error_policy;
+
+        self.cpu_systems
+            .iter_mut()
+            .chain(&mut self.exclusive_systems)
+            .chain(&mut self.gpu_systems)
+            .flat_map(|system_info| {
+                let system_name = system_info.system.name();
+                let policy = system_info.error_policy.unwrap_or(error_policy);
+
+                system_info
+                    .errors
+                    .drain(..)
+                    .map(move |message| SystemError {
+                        system_name: system_name.to_owned(),
+                        message,
+                        policy,
+                    })
+            })
+            .collect()
+    }
+
+    /// Returns the most recently issued change tick.
+    ///
+    /// Change ticks are used to detect when components have been added or
+    /// mutably accessed. The upper 32 bits hold the `FrameConstants::tick_count`
+    /// of the frame, and the lower 32 bits are incremented for each system
+    /// execution (or batch of applied commands) within that frame, so that
+    /// changes made earlier in the same frame can be told apart.
+    pub fn change_tick(&self) -> u64 {
+        self.change_tick
+    }
+
+    /// Issues a new change tick. See [`SystemGraph::change_tick()`].
+    pub fn increment_change_tick(&mut self) -> u64 {
+        self.change_tick += 1;
+        self.change_tick
+    }
+
+    /// Resets the change tick for a new frame. See [`SystemGraph::change_tick()`].
+    pub fn begin_frame(&mut self, tick_count: u64) {
+        self.change_tick = self.change_tick.max(tick_count << 32);
+        self.fixed_executed = false;
+        self.profiler.begin_frame();
+    }
+
+    /// Times the execution of each system, and of engine work between them.
+    pub fn profiler(&self) -> &Profiler {
+        &self.profiler
+    }
+
+    pub fn profiler_mut(&mut self) -> &mut Profiler {
+        &mut self.profiler
+    }
+
+    pub fn sequential_execution(&self) -> bool {
+        self.sequential_execution
+    }
+
+    /// When set, CPU systems execute one at a time, in the order returned by
+    /// [`SystemGraph::system_names()`]. This is intended for debugging.
+    pub fn set_sequential_execution(&mut self, sequential_execution: bool) {
+        self.sequential_execution = sequential_execution;
+    }
+
+    /// Executes the `system_on_exit` systems of each transition's previous
+    /// state, followed by the `system_on_enter` systems of each transition's
+    /// next state. CPU systems execute before GPU systems, and systems execute
+    /// one at a time.
+    pub async fn execute_transitions(
+        &mut self,
+        resources: &EcsSystemExecuteResources<'_, P, G>,
+        transitions: &[StateTransition],
+    ) {
         unsafe {
             SYSTEM_EXECUTE_RESOURCES =
                 (resources as *const EcsSystemExecuteResources<'_, P, G>).cast();
         }
 
-        for system_info in &mut self.cpu_systems {
-            if system_info.enabled {
-                if let Err(err) = system_info.system.execute() {
-                    log::error!(
-                        "system `{}` returned error: {err:?}",
-                        system_info.system.name()
+        for condition in [StateCondition::OnExit, StateCondition::OnEnter] {
+            for system_info in self.cpu_systems.iter_mut().chain(&mut self.gpu_systems) {
+                let triggered = system_info.state_conditions.iter().any(|state_condition| {
+                    state_condition.condition == condition
+                        && transitions
+                            .iter()
+                            .any(|transition| state_condition.is_triggered_by(transition))
+                });
+
+                if system_info.enabled && triggered {
+                    self.change_tick += 1;
+                    execute_profiled_system(
+                        system_info,
+                        self.change_tick,
+                        self.error_policy,
+                        &self.profiler,
                     );
-                    system_info.enabled = false;
+                    system_info.transition_executed = true;
                 }
+            }
+        }
 
-                if system_info.is_once {
-                    system_info.enabled = false;
+        unsafe {
+            SYSTEM_EXECUTE_RESOURCES = ptr::null();
+        }
+    }
+
+    /// Executes all CPU systems, except fixed systems. Systems within the same
+    /// stage are executed with `Executor::parallel_iter()`, unless sequential
+    /// execution is set. They only execute concurrently if the platform's
+    /// executor is multi-threaded.
+    pub async fn execute_cpu(&mut self, resources: &EcsSystemExecuteResources<'_, P, G>) {
+        unsafe {
+            SYSTEM_EXECUTE_RESOURCES =
+                (resources as *const EcsSystemExecuteResources<'_, P, G>).cast();
+        }
+
+        // Events written by fixed systems are only readable until the next
+        // frame, or the next fixed timestep.
+        if !self.fixed_executed {
+            for system_info in &mut self.cpu_systems {
+                if system_info.is_fixed {
+                    system_info.skip_execution();
                 }
-            } else {
-                system_info.system.clear_event_writer_buffers();
             }
         }
 
+        let start = P::Clock::now();
+        self.execute_cpu_systems(resources, false);
+        self.profiler.record::<P>(ScopeId::EXECUTE_CPU, start);
+
         unsafe {
             SYSTEM_EXECUTE_RESOURCES = ptr::null();
         }
     }
 
-    /// Executes all GPU systems.
-    pub async fn execute_gpu(&mut self, resources: &EcsSystemExecuteResources<'_, P, G>) {
+    /// Executes one fixed timestep of the fixed CPU systems. Systems are
+    /// executed in stages, as in [`SystemGraph::execute_cpu()`].
+    pub async fn execute_fixed(&mut self, resources: &EcsSystemExecuteResources<'_, P, G>) {
         unsafe {
             SYSTEM_EXECUTE_RESOURCES =
                 (resources as *const EcsSystemExecuteResources<'_, P, G>).cast();
         }
 
-        for system_info in &mut self.gpu_systems {
-            if system_info.enabled {
-                if let Err(err) = system_info.system.execute() {
-                    log::error!(
-                        "system `{}` returned error: {err:?}",
-                        system_info.system.name()
-                    );
-                    system_info.enabled = false;
+        let start = P::Clock::now();
+        self.execute_cpu_systems(resources, true);
+        self.profiler.record::<P>(ScopeId::EXECUTE_FIXED, start);
+        self.fixed_executed = true;
+
+        unsafe {
+            SYSTEM_EXECUTE_RESOURCES = ptr::null();
+        }
+    }
+
+    fn execute_cpu_systems(
+        &mut self,
+        resources: &EcsSystemExecuteResources<'_, P, G>,
+        fixed: bool,
+    ) {
+        let stage_count = self
+            .cpu_systems
+            .iter()
+            .map(|system_info| system_info.stage + 1)
+            .max()
+            .unwrap_or_default();
+
+        let error_policy = self.error_policy;
+        let profiler = &self.profiler;
+
+        let systems = self
+            .cpu_systems
+            .iter_mut()
+            .filter(|system_info| system_info.is_fixed == fixed);
+
+        if self.sequential_execution {
+            for system_info in systems {
+                if system_info.should_run(resources.states) {
+                    self.change_tick += 1;
+                    execute_profiled_system(system_info, self.change_tick, error_policy, profiler);
+                } else {
+                    system_info.skip_execution();
                 }
+            }
+        } else {
+            let mut stages: Vec<Vec<_>> = (0..stage_count).map(|_| Vec::new()).collect();
 
-                if system_info.is_once {
-                    system_info.enabled = false;
+            for system_info in systems {
+                if system_info.should_run(resources.states) {
+                    self.change_tick += 1;
+                    stages[system_info.stage].push(Mutex::new((self.change_tick, system_info)));
+                } else {
+                    system_info.skip_execution();
                 }
+            }
+
+            for stage in &stages {
+                P::Executor::parallel_iter(stage.len(), |i, _| {
+                    let (change_tick, system_info) = &mut *stage[i].lock().unwrap();
+                    execute_profiled_system(system_info, *change_tick, error_policy, profiler);
+                });
+            }
+        }
+    }
+
+    /// Executes all GPU systems.
+    pub async fn execute_gpu(&mut self, resources: &EcsSystemExecuteResources<'_, P, G>) {
+        unsafe {
+            SYSTEM_EXECUTE_RESOURCES =
+                (resources as *const EcsSystemExecuteResources<'_, P, G>).cast();
+        }
+
+        let start = P::Clock::now();
+
+        for system_info in &mut self.gpu_systems {
+            if system_info.should_run(resources.states) {
+                self.change_tick += 1;
+                execute_profiled_system(
+                    system_info,
+                    self.change_tick,
+                    self.error_policy,
+                    &self.profiler,
+                );
             } else {
-                system_info.system.clear_event_writer_buffers();
+                system_info.skip_execution();
             }
         }
 
+        self.profiler.record::<P>(ScopeId::EXECUTE_GPU, start);
+
         unsafe {
             SYSTEM_EXECUTE_RESOURCES = ptr::null();
         }
     }
 
+    /// Removes the exclusive systems from the graph, in execution order, so
+    /// that they may execute while the rest of the engine is mutably borrowed.
+    /// They must be returned with [`SystemGraph::restore_exclusive_systems()`].
+    pub fn take_exclusive_systems(&mut self) -> Vec<SystemInfo<P, G>> {
+        std::mem::take(&mut self.exclusive_systems)
+    }
+
+    pub fn restore_exclusive_systems(&mut self, exclusive_systems: Vec<SystemInfo<P, G>>) {
+        debug_assert!(self.exclusive_systems.is_empty());
+        self.exclusive_systems = exclusive_systems;
+    }
+
     pub fn add_archetype_input(
         &mut self,
         archetype_key: &ArchetypeKey,
@@ -343,3 +793,441 @@ impl<P: Platform, G: GpuFrameData> SystemGraph<P, G> {
         }
     }
 }
+
+impl<P: Platform, G: GpuFrameData> SystemInfo<P, G> {
+    pub fn name(&self) -> &str {
+        self.system.name()
+    }
+
+    /// The profiler scope in which the execution of the system is recorded.
+    pub fn profiler_scope(&self) -> ScopeId {
+        self.profiler_scope
+    }
+
+    /// Returns `true` if the system should run during regular frame execution.
+    pub fn should_run(&self, states: &StateRegistry) -> bool {
+        self.enabled
+            && self
+                .state_conditions
+                .iter()
+                .all(|state_condition| state_condition.is_satisfied(states))
+    }
+
+    /// Clears the events written by the system in its last execution, as it is
+    /// not executing in this frame. Events written in this frame's transitions
+    /// are kept until the next frame.
+    fn skip_execution(&mut self) {
+        if !std::mem::take(&mut self.transition_executed) {
+            self.system.clear_event_writer_buffers();
+        }
+    }
+
+    /// Executes the system, disabling it if it is a `system_once`. If the
+    /// system returns an error, it is handled according to its error policy,
+    /// or `error_policy` if it does not set one.
+    pub fn execute(&mut self, change_tick: u64, error_policy: SystemErrorPolicy) {
+        execute_system(self, change_tick, error_policy);
+    }
+}
+
+fn execute_profiled_system<P: Platform, G: GpuFrameData>(
+    system_info: &mut SystemInfo<P, G>,
+    change_tick: u64,
+    error_policy: SystemErrorPolicy,
+    profiler: &Profiler,
+) {
+    let start = P::Clock::now();
+    execute_system(system_info, change_tick, error_policy);
+    profiler.record::<P>(system_info.profiler_scope, start);
+}
+
+fn execute_system<P: Platform, G: GpuFrameData>(
+    system_info: &mut SystemInfo<P, G>,
+    change_tick: u64,
+    error_policy: SystemErrorPolicy,
+) {
+    if let Err(err) = system_info.system.execute(change_tick) {
+        log::error!(
+            "system `{}` returned error: {err:?}",
+            system_info.system.name()
+        );
+
+        if system_info.error_policy.unwrap_or(error_policy) == SystemErrorPolicy::Disable {
+            system_info.enabled = false;
+        }
+
+        if system_info.errors.len() < MAX_SYSTEM_ERRORS {
+            system_info.errors.push(err.to_string());
+        }
+    }
+
+    if system_info.is_once {
+        system_info.enabled = false;
+    }
+}
+
+fn add_system<P: Platform, G: GpuFrameData>(
+    systems: &mut Vec<SystemInfo<P, G>>,
+    profiler: &mut Profiler,
+    system: Box<dyn EcsSystem<P, G>>,
+    is_once: bool,
+    is_fixed: bool,
+    constraints: Vec<SystemConstraint>,
+    state_conditions: Vec<SystemStateCondition>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let profiler_scope = profiler.register_scope(system.name());
+
+    systems.push(SystemInfo {
+        system,
+        enabled: true,
+        is_once,
+        is_fixed,
+        constraints,
+        state_conditions,
+        stage: 0,
+        error_policy: None,
+        errors: Vec::new(),
+        transition_executed: false,
+        profiler_scope,
+    });
+
+    let names: Vec<_> = systems.iter().map(|info| info.system.name()).collect();
+    let constraints: Vec<_> = systems.iter().map(|info| &info.constraints[..]).collect();
+
+    match sort_systems(&names, &constraints) {
+        Ok(order) => {
+            let mut unsorted: Vec<_> = systems.drain(..).map(Some).collect();
+            systems.extend(order.into_iter().map(|i| unsorted[i].take().unwrap()));
+
+            assign_stages(systems);
+
+            Ok(())
+        }
+        Err(cycle) => {
+            let cycle = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|i| names[*i])
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            systems.pop();
+
+            Err(format!("system ordering cycle detected: {cycle}").into())
+        }
+    }
+}
+
+/// Topologically sorts systems by their ordering constraints, returning the
+/// system indices in execution order. Systems which are not constrained
+/// relative to each other keep their current relative order. Constraints
+/// targeting unknown systems or empty sets are ignored, as the targets may be
+/// registered later.
+///
+/// If the constraints contain a cycle, the indices of the systems forming the
+/// cycle are returned as an error.
+fn sort_systems(
+    names: &[&str],
+    constraints: &[&[SystemConstraint]],
+) -> Result<Vec<usize>, Vec<usize>> {
+    let len = names.len();
+    let edges = ordering_edges(names, constraints);
+
+    let mut in_degree = vec![0; len];
+    for to in edges.iter().flatten() {
+        in_degree[*to] += 1;
+    }
+
+    // Kahn's algorithm, preferring the lowest index among ready systems
+    let mut ready: BinaryHeap<_> = (0..len)
+        .filter(|index| in_degree[*index] == 0)
+        .map(Reverse)
+        .collect();
+    let mut order = Vec::with_capacity(len);
+
+    while let Some(Reverse(index)) = ready.pop() {
+        order.push(index);
+
+        for to in &edges[index] {
+            in_degree[*to] -= 1;
+
+            if in_degree[*to] == 0 {
+                ready.push(Reverse(*to));
+            }
+        }
+    }
+
+    if order.len() == len {
+        return Ok(order);
+    }
+
+    // Every unsorted system has an unsorted predecessor, so walking backwards
+    // through predecessors must eventually revisit a system.
+    let mut path = vec![(0..len).find(|index| in_degree[*index] > 0).unwrap()];
+    loop {
+        let current = *path.last().unwrap();
+        let predecessor = (0..len)
+            .find(|from| in_degree[*from] > 0 && edges[*from].contains(&current))
+            .unwrap();
+
+        if let Some(position) = path.iter().position(|index| *index == predecessor) {
+            let mut cycle = path.split_off(position);
+            cycle.reverse();
+            return Err(cycle);
+        }
+
+        path.push(predecessor);
+    }
+}
+
+/// Returns the edges implied by the systems' ordering constraints, indexed by
+/// the system which must execute first.
+fn ordering_edges(names: &[&str], constraints: &[&[SystemConstraint]]) -> Vec<Vec<usize>> {
+    let len = names.len();
+
+    let in_set = |index: usize, label: &str| {
+        constraints[index].iter().any(|constraint| {
+            constraint.ordering == SystemOrdering::InSet && constraint.target == label
+        })
+    };
+
+    // build edges, from systems which must run first to systems which must run after
+    let mut edges = vec![Vec::new(); len];
+    for (index, system_constraints) in constraints.iter().enumerate() {
+        for constraint in *system_constraints {
+            let targets = (0..len).filter(|target| {
+                *target != index
+                    && match constraint.ordering {
+                        SystemOrdering::Before | SystemOrdering::After => {
+                            names[*target] == constraint.target
+                        }
+                        SystemOrdering::BeforeSet | SystemOrdering::AfterSet => {
+                            in_set(*target, &constraint.target)
+                        }
+                        SystemOrdering::InSet => false,
+                    }
+            });
+
+            for target in targets {
+                let (from, to) = match constraint.ordering {
+                    SystemOrdering::Before | SystemOrdering::BeforeSet => (index, target),
+                    _ => (target, index),
+                };
+
+                if !edges[from].contains(&to) {
+                    edges[from].push(to);
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+/// Assigns each system its stage, see [`system_stages()`]. Systems must
+/// already be sorted.
+fn assign_stages<P: Platform, G: GpuFrameData>(systems: &mut [SystemInfo<P, G>]) {
+    let names: Vec<_> = systems.iter().map(|info| info.system.name()).collect();
+    let constraints: Vec<_> = systems.iter().map(|info| &info.constraints[..]).collect();
+    let accesses: Vec<_> = systems.iter().map(|info| info.system.access()).collect();
+
+    let stages = system_stages(&names, &constraints, &accesses);
+
+    for (system_info, stage) in systems.iter_mut().zip(stages) {
+        system_info.stage = stage;
+    }
+}
+
+/// Returns the earliest stage of each system which executes after all
+/// systems it conflicts with, or is ordered after. Systems must already be
+/// sorted.
+fn system_stages(
+    names: &[&str],
+    constraints: &[&[SystemConstraint]],
+    accesses: &[&SystemAccess],
+) -> Vec<usize> {
+    let edges = ordering_edges(names, constraints);
+
+    let mut stages = vec![0; names.len()];
+    for index in 0..names.len() {
+        stages[index] = (0..index)
+            .filter(|prev| {
+                edges[*prev].contains(&index) || accesses[*prev].conflicts_with(accesses[index])
+            })
+            .map(|prev| stages[prev] + 1)
+            .max()
+            .unwrap_or_default();
+    }
+
+    stages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraint(ordering: SystemOrdering, target: &str) -> SystemConstraint {
+        SystemConstraint {
+            ordering,
+            target: target.to_string(),
+        }
+    }
+
+    #[test]
+    fn sort_systems_orders_by_constraints() {
+        let names = ["m::a", "m::b", "m::c", "n::d"];
+        let a = [constraint(SystemOrdering::After, "m::b")];
+        let b = [constraint(SystemOrdering::InSet, "physics")];
+        let c = [];
+        let d = [constraint(SystemOrdering::BeforeSet, "physics")];
+
+        let order = sort_systems(&names, &[&a, &b, &c, &d]).unwrap();
+
+        assert_eq!(order, [2, 3, 1, 0]);
+    }
+
+    #[test]
+    fn sort_systems_reports_cycles() {
+        let names = ["m::a", "m::b", "m::c"];
+        let a = [constraint(SystemOrdering::Before, "m::b")];
+        let b = [constraint(SystemOrdering::BeforeSet, "late")];
+        let c = [
+            constraint(SystemOrdering::InSet, "late"),
+            constraint(SystemOrdering::Before, "m::a"),
+        ];
+
+        let cycle = sort_systems(&names, &[&a, &b, &c]).unwrap_err();
+
+        assert_eq!(cycle.len(), 3);
+        for (index, next) in cycle.iter().zip(cycle.iter().cycle().skip(1)) {
+            let is_edge = matches!((*index, *next), (0, 1) | (1, 2) | (2, 0));
+            assert!(is_edge, "{index} -> {next} is not an edge");
+        }
+    }
+
+    #[test]
+    fn query_access_conflicts() {
+        let id = |id| ComponentId::new(id).unwrap();
+
+        let write_a = QueryAccess {
+            components_mut: vec![id(1)],
+            required: vec![id(1)],
+            ..Default::default()
+        };
+        let read_b = QueryAccess {
+            components_ref: vec![id(2)],
+            required: vec![id(2)],
+            ..Default::default()
+        };
+        let read_b_without_a = QueryAccess {
+            excluded: vec![id(1)],
+            ..read_b.clone()
+        };
+
+        // entities with both `a` and `b` share an archetype buffer
+        assert!(write_a.conflicts_with(&read_b));
+        assert!(!write_a.conflicts_with(&read_b_without_a));
+        assert!(!read_b.conflicts_with(&read_b_without_a));
+    }
+
+    #[test]
+    fn system_access_conflicts() {
+        let id = |id| ComponentId::new(id).unwrap();
+
+        let read_resource = SystemAccess {
+            resources_ref: vec![id(1)],
+            ..Default::default()
+        };
+        let write_resource = SystemAccess {
+            resources_mut: vec![id(1)],
+            ..Default::default()
+        };
+        let write_other_resource = SystemAccess {
+            resources_mut: vec![id(2)],
+            ..Default::default()
+        };
+
+        assert!(!read_resource.conflicts_with(&read_resource));
+        assert!(read_resource.conflicts_with(&write_resource));
+        assert!(write_resource.conflicts_with(&read_resource));
+        assert!(write_resource.conflicts_with(&write_resource));
+        assert!(!write_resource.conflicts_with(&write_other_resource));
+
+        let read_event = SystemAccess {
+            events_read: vec![c"event".into()],
+            ..Default::default()
+        };
+        let write_event = SystemAccess {
+            events_write: vec![c"event".into()],
+            ..Default::default()
+        };
+
+        // each writer has its own buffer
+        assert!(!write_event.conflicts_with(&write_event));
+        assert!(!read_event.conflicts_with(&read_event));
+        assert!(read_event.conflicts_with(&write_event));
+        assert!(write_event.conflicts_with(&read_event));
+
+        let read_query = SystemAccess {
+            queries: vec![QueryAccess {
+                components_ref: vec![id(3)],
+                required: vec![id(3)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let write_query = SystemAccess {
+            queries: vec![QueryAccess {
+                components_mut: vec![id(3)],
+                required: vec![id(3)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(!read_query.conflicts_with(&read_query));
+        assert!(read_query.conflicts_with(&write_query));
+        assert!(!read_query.conflicts_with(&write_resource));
+    }
+
+    #[test]
+    fn system_stages_follow_conflicts_and_ordering() {
+        let id = |id| ComponentId::new(id).unwrap();
+
+        let read_resource = SystemAccess {
+            resources_ref: vec![id(1)],
+            ..Default::default()
+        };
+        let write_resource = SystemAccess {
+            resources_mut: vec![id(1)],
+            ..Default::default()
+        };
+        let read_query = SystemAccess {
+            queries: vec![QueryAccess {
+                components_ref: vec![id(2)],
+                required: vec![id(2)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let names = ["m::a", "m::b", "m::c", "m::d", "m::e", "m::f"];
+        let d = [constraint(SystemOrdering::After, "m::c")];
+        let constraints: [&[SystemConstraint]; 6] = [&[], &[], &[], &d, &[], &[]];
+        let accesses = [
+            &write_resource,
+            &read_resource,
+            &read_query,
+            &SystemAccess::default(),
+            &read_resource,
+            &write_resource,
+        ];
+
+        // `b` and `e` read what `a` writes, `d` is ordered after `c`, and `f`
+        // writes what `a`, `b` and `e` access
+        assert_eq!(
+            system_stages(&names, &constraints, &accesses),
+            [0, 1, 0, 1, 1, 2]
+        );
+    }
+}
@@ -19,7 +19,8 @@ impl<P: Platform, G: GpuFrameData> Serialize for SystemGraph<P, G> {
         };
 
         serialize(&self.cpu_systems)?;
-        serialize(&self.gpu_systems)
+        serialize(&self.gpu_systems)?;
+        serialize(&self.exclusive_systems)
     }
 }
 
@@ -59,6 +60,7 @@ impl<P: Platform, G: GpuFrameData> Deserialize for SystemGraph<P, G> {
         };
 
         deserialize(&mut self.cpu_systems)?;
-        deserialize(&mut self.gpu_systems)
+        deserialize(&mut self.gpu_systems)?;
+        deserialize(&mut self.exclusive_systems)
     }
 }
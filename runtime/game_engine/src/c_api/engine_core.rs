@@ -1,14 +1,18 @@
 use std::{
     ffi::{CStr, c_char, c_int, c_void},
-    slice,
+    mem::MaybeUninit,
+    ptr, slice,
 };
 
 use game_ecs::GpuFrameData;
 use gpu_common::Gpu;
 use platform::Platform;
-use void_public::{ComponentId, ComponentRef, EntityId, callable::AsyncCompletionValue};
+use void_public::{
+    ComponentId, ComponentRef, EntityId, PrefabOverride, SceneHandle, SceneMigrationCallback,
+    callable::AsyncCompletionValue, reflect::FieldInfo,
+};
 
-use crate::module_api;
+use crate::module_api::{self, QueryGetManyError};
 
 /// Spawns an entity with the given set of `components`.
 ///
@@ -38,11 +42,121 @@ pub unsafe extern "C" fn spawn<P: Platform, G: GpuFrameData>(
     }
 }
 
-/// Despawns an entity.
+/// Spawns `count` entities, all with the given set of `components`, and writes
+/// their `EntityId`s to `entity_ids`.
+///
+/// Returns `false` if the entities could not be spawned, in which case
+/// `entity_ids` is left untouched.
+///
+/// # Safety
+///
+/// A valid slice of `&[ComponentRef]` must be able to be constructed from
+/// `components` and `components_len`, and `entity_ids` must be valid for
+/// writing `count` entity ids.
+///
+/// The contents of `components` objects are type-erased, and cannot be verified
+/// by the engine. Callers must ensure they contain valid component data.
+pub unsafe extern "C" fn spawn_batch<P: Platform, G: GpuFrameData>(
+    components: *const ComponentRef<'_>,
+    components_len: usize,
+    count: usize,
+    entity_ids: *mut EntityId,
+) -> bool {
+    unsafe {
+        match module_api::spawn_batch::<P, G>(
+            slice::from_raw_parts(components, components_len),
+            count,
+        ) {
+            Ok(spawned_ids) => {
+                for (i, entity_id) in spawned_ids.into_iter().enumerate() {
+                    entity_ids.add(i).write(entity_id.into());
+                }
+                true
+            }
+            Err(err) => {
+                log::warn!("{err}");
+                false
+            }
+        }
+    }
+}
+
+/// Despawns an entity, along with all of its descendants.
 pub extern "C" fn despawn<P: Platform, G: GpuFrameData>(entity_id: EntityId) {
     module_api::despawn::<P, G>(entity_id.into());
 }
 
+/// Despawns an entity. Its children become root entities.
+pub extern "C" fn despawn_non_recursive<P: Platform, G: GpuFrameData>(entity_id: EntityId) {
+    module_api::despawn_non_recursive::<P, G>(entity_id.into());
+}
+
+/// Inserts a resource, replacing its current value if it is present. The
+/// insertion is applied at the end of the frame.
+///
+/// # Safety
+///
+/// `data` must point to `data_len` bytes of valid resource data.
+pub unsafe extern "C" fn insert_resource<P: Platform, G: GpuFrameData>(
+    component_id: Option<ComponentId>,
+    data: *const MaybeUninit<u8>,
+    data_len: usize,
+) {
+    let Some(component_id) = component_id else {
+        log::warn!("insert_resource: invalid ComponentId, has it been set via set_component_id()?");
+        return;
+    };
+
+    let data = unsafe { slice::from_raw_parts(data, data_len) };
+
+    module_api::insert_resource::<P, G>(component_id, data);
+}
+
+/// Removes a resource. The removal is applied at the end of the frame.
+pub extern "C" fn remove_resource<P: Platform, G: GpuFrameData>(component_id: Option<ComponentId>) {
+    let Some(component_id) = component_id else {
+        log::warn!("remove_resource: invalid ComponentId, has it been set via set_component_id()?");
+        return;
+    };
+
+    module_api::remove_resource::<P, G>(component_id);
+}
+
+/// Returns the number of reflected fields of a component. See
+/// `void_public::reflect` for the layout of the field table.
+pub extern "C" fn component_fields_len<P: Platform, G: GpuFrameData>(
+    component_id: Option<ComponentId>,
+) -> usize {
+    component_id.map_or(0, module_api::component_fields_len::<P, G>)
+}
+
+/// Writes the reflected field at `index` of a component to `out_field`. The
+/// strings referenced by the field are valid for the lifetime of the engine.
+///
+/// Returns `false` if `index` is out of range, in which case `out_field` is not
+/// written to.
+///
+/// # Safety
+///
+/// `out_field` must be valid for writes.
+pub unsafe extern "C" fn component_field<P: Platform, G: GpuFrameData>(
+    component_id: Option<ComponentId>,
+    index: usize,
+    out_field: *mut FieldInfo,
+) -> bool {
+    let Some(field) = component_id
+        .and_then(|component_id| module_api::component_field::<P, G>(component_id, index))
+    else {
+        return false;
+    };
+
+    unsafe {
+        out_field.write(field);
+    }
+
+    true
+}
+
 /// Returns the label associated with this entity, if it exists.
 ///
 /// If no label is associated with this entity, `null` is returned.
@@ -160,6 +274,44 @@ pub unsafe extern "C" fn set_system_enabled<P: Platform, G: GpuFrameData>(
     module_api::set_system_enabled::<P, G>(&system_name, enabled);
 }
 
+/// Reports the error of the module function executing on this thread, such as
+/// a caught panic. The error replaces the error code returned by the function.
+///
+/// # Safety
+///
+/// `message` must point to `message_len` bytes of valid UTF-8.
+pub unsafe extern "C" fn set_module_error(message: *const c_char, message_len: usize) {
+    let message = unsafe { slice::from_raw_parts(message.cast::<u8>(), message_len) };
+
+    platform::set_module_error(String::from_utf8_lossy(message).into_owned());
+}
+
+/// Returns the variant index of the current state of a state type.
+///
+/// # Safety
+///
+/// `state_id` must point to a valid C string.
+pub unsafe extern "C" fn state_get<P: Platform, G: GpuFrameData>(state_id: *const c_char) -> u32 {
+    let state_id = unsafe { CStr::from_ptr(state_id) };
+
+    module_api::state_get::<P, G>(state_id)
+}
+
+/// Requests a transition of a state type to the state with variant index
+/// `state`. The transition is applied at the start of the next frame.
+///
+/// # Safety
+///
+/// `state_id` must point to a valid C string.
+pub unsafe extern "C" fn set_next_state<P: Platform, G: GpuFrameData>(
+    state_id: *const c_char,
+    state: u32,
+) {
+    let state_id = unsafe { CStr::from_ptr(state_id) };
+
+    module_api::set_next_state::<P, G>(state_id, state);
+}
+
 /// Looks up an entity's parent, writing the parent to `out_parent_id`. If the
 /// entity has no parent, `None` (represented by `0`) will be written.
 ///
@@ -186,6 +338,46 @@ pub unsafe extern "C" fn get_parent<P: Platform, G: GpuFrameData>(
     }
 }
 
+/// Looks up an entity's children, writing up to `out_children_capacity`
+/// children to `out_children`, and the total number of children to
+/// `out_children_len`. If the total exceeds the capacity, call again with a
+/// larger buffer.
+///
+/// Returns `true` if the outputs have been written, or `false` if the entity
+/// lookup failed and the outputs were not written to.
+///
+///  # Safety
+///
+/// `out_children` must point to an array of `out_children_capacity` 64-bit
+/// `EntityId` values. It may be null if `out_children_capacity` is zero.
+///
+/// `out_children_len` must be valid for writes and must be properly aligned.
+pub unsafe extern "C" fn get_children<P: Platform, G: GpuFrameData>(
+    entity_id: EntityId,
+    out_children: *mut EntityId,
+    out_children_capacity: usize,
+    out_children_len: *mut usize,
+) -> bool {
+    let result = module_api::get_children::<P, G, _, _>(entity_id.into(), |child_ids| {
+        for (i, child_id) in child_ids.iter().take(out_children_capacity).enumerate() {
+            unsafe { out_children.add(i).write((*child_id).into()) };
+        }
+
+        child_ids.len()
+    });
+
+    match result {
+        Ok(len) => {
+            unsafe { out_children_len.write(len) };
+            true
+        }
+        Err(err) => {
+            log::warn!("{err:?}");
+            false
+        }
+    }
+}
+
 /// Returns the number of entities captured by a query.
 ///
 /// # Safety
@@ -195,6 +387,17 @@ pub unsafe extern "C" fn query_len<G: Gpu>(query: *const c_void) -> usize {
     unsafe { module_api::query_len::<G>(query) }
 }
 
+/// Returns whether the entity most recently returned by `query_get`,
+/// `query_get_entity` or `query_get_label` had any of its accessed components
+/// added or changed since the current system last ran.
+///
+/// # Safety
+///
+/// `query` must point to a valid query, provided as a system input.
+pub unsafe extern "C" fn query_is_changed<G: Gpu>(query: *const c_void) -> bool {
+    unsafe { module_api::query_is_changed::<G>(query) }
+}
+
 /// Gets a set of components for a given index. An index of `0` will return the
 /// first entity captured by a query, `1` the second, etc.
 ///
@@ -238,6 +441,44 @@ pub unsafe extern "C" fn query_get_entity<G: Gpu>(
     }
 }
 
+/// Gets the sets of components of several entities captured by the query. The
+/// entities must be distinct, so that all of their components may be mutably
+/// accessed at once.
+///
+/// # Safety
+///
+/// `query` must point to a valid query, provided as a system input.
+///
+/// `entity_ids` must point to `entity_ids_len` entity ids, and `changed` to
+/// `entity_ids_len` bools, to which is written whether the components of each
+/// entity were added or changed since the current system last ran.
+///
+/// `component_ptrs` must be a pointer to an array of pointers, sized to the
+/// number of components in the query times `entity_ids_len`. The components of
+/// each entity are written one after the other.
+///
+/// Returns `1` if an entity is not captured by the query, and `2` if an entity
+/// id is passed more than once.
+pub unsafe extern "C" fn query_get_many<G: Gpu>(
+    query: *const c_void,
+    entity_ids: *const EntityId,
+    entity_ids_len: usize,
+    component_ptrs: *mut *const c_void,
+    changed: *mut bool,
+) -> i32 {
+    let entity_ids = unsafe { slice::from_raw_parts(entity_ids, entity_ids_len) }
+        .iter()
+        .map(|entity_id| (*entity_id).into())
+        .collect::<Vec<_>>();
+    let changed = unsafe { slice::from_raw_parts_mut(changed, entity_ids_len) };
+
+    match unsafe { module_api::query_get_many::<G>(query, &entity_ids, component_ptrs, changed) } {
+        Ok(()) => 0,
+        Err(QueryGetManyError::NotFound(_)) => 1,
+        Err(QueryGetManyError::NotDisjoint(_)) => 2,
+    }
+}
+
 /// Gets a set of components for a given entity id captured by the query.
 ///
 /// # Safety
@@ -432,12 +673,467 @@ pub extern "C" fn completion_get<P: Platform, G: GpuFrameData>(
     module_api::completion_get::<P, G>(completion_id, index)
 }
 
-/// Loads a string of JSON that represents a scene of entities into the engine
+/// Loads a JSON or binary scene of entities into the engine.
+///
+/// Returns the handle of the new scene instance.
 ///
 /// # Safety
 ///
-/// The pointer `scene_json` must not be null and its memory must be
+/// The pointer `scene_data` must not be null and must point to
+/// `scene_data_len` bytes.
+pub unsafe extern "C" fn load_scene<P: Platform, G: Gpu>(
+    scene_data: *const u8,
+    scene_data_len: usize,
+) -> SceneHandle {
+    module_api::load_scene::<P, G>(unsafe { slice::from_raw_parts(scene_data, scene_data_len) })
+}
+
+/// Loads a scene from an asset path into the engine. The scene's entities are
+/// spawned once the scene is read and its assets are loaded.
+///
+/// Returns the handle of the new scene instance.
+///
+/// # Safety
+///
+/// The pointer `asset_path` must not be null and its memory must be
 /// null-terminated.
-pub unsafe extern "C" fn load_scene<P: Platform, G: Gpu>(scene_json: *const c_char) {
-    module_api::load_scene::<P, G>(unsafe { CStr::from_ptr(scene_json) });
+pub unsafe extern "C" fn load_scene_from_path<P: Platform, G: Gpu>(
+    asset_path: *const c_char,
+) -> SceneHandle {
+    module_api::load_scene_from_path::<P, G>(unsafe { CStr::from_ptr(asset_path) })
+}
+
+/// Despawns the entities spawned from a scene instance.
+pub extern "C" fn unload_scene<P: Platform, G: Gpu>(scene_handle: SceneHandle) {
+    module_api::unload_scene::<P, G>(scene_handle);
+}
+
+/// Registers a string of JSON that represents a scene with a single root entity
+/// as a prefab, which may be spawned by `name`.
+///
+/// # Safety
+///
+/// The pointers `name` and `prefab_json` must not be null and their memory
+/// must be null-terminated.
+pub unsafe extern "C" fn register_prefab<P: Platform, G: Gpu>(
+    name: *const c_char,
+    prefab_json: *const c_char,
+) {
+    module_api::register_prefab::<P, G>(unsafe { CStr::from_ptr(name) }, unsafe {
+        CStr::from_ptr(prefab_json)
+    });
+}
+
+/// Registers the prefab read from the scene file at `asset_path`, which may be
+/// spawned by its asset path.
+///
+/// # Safety
+///
+/// The pointer `asset_path` must not be null and its memory must be
+/// null-terminated.
+pub unsafe extern "C" fn register_prefab_from_path<P: Platform, G: Gpu>(asset_path: *const c_char) {
+    module_api::register_prefab_from_path::<P, G>(unsafe { CStr::from_ptr(asset_path) });
+}
+
+/// Registers a migration of the JSON of the component `component_name`, from
+/// scenes of `from_version` to `to_version`. `migrate_fn` is called with
+/// `user_data` for each entity which has the component.
+///
+/// # Safety
+///
+/// The pointers `from_version`, `to_version` and `component_name` must not be
+/// null and their memory must be null-terminated. `migrate_fn` must be safe to
+/// call with `user_data` from any thread, for as long as the module is loaded.
+pub unsafe extern "C" fn register_scene_migration<P: Platform, G: Gpu>(
+    from_version: *const c_char,
+    to_version: *const c_char,
+    component_name: *const c_char,
+    migrate_fn: SceneMigrationCallback,
+    user_data: *const c_void,
+) {
+    module_api::register_scene_migration::<P, G>(
+        unsafe { CStr::from_ptr(from_version) },
+        unsafe { CStr::from_ptr(to_version) },
+        unsafe { CStr::from_ptr(component_name) },
+        migrate_fn,
+        user_data,
+    );
+}
+
+/// Spawns an instance of the prefab registered as `name`, including its child
+/// entities. The given set of `components` are added to the root entity of the
+/// instance, replacing the prefab's components of the same type.
+///
+/// Returns the `EntityId` of the root entity, or `None` (represented by `0`)
+/// on error.
+///
+/// # Safety
+///
+/// The pointer `name` must not be null and its memory must be null-terminated.
+///
+/// A valid slice of `&[ComponentRef]` must be able to be constructed from
+/// `components` and `components_len`.
+///
+/// The contents of `components` objects are type-erased, and cannot be verified
+/// by the engine. Callers must ensure they contain valid component data.
+pub unsafe extern "C" fn spawn_prefab<P: Platform, G: GpuFrameData>(
+    name: *const c_char,
+    components: *const ComponentRef<'_>,
+    components_len: usize,
+) -> Option<EntityId> {
+    unsafe {
+        match module_api::spawn_prefab::<P, G>(
+            CStr::from_ptr(name),
+            slice::from_raw_parts(components, components_len),
+            &[],
+        ) {
+            Ok(entity_id) => Some(entity_id.into()),
+            Err(err) => {
+                log::warn!("{err}");
+                None
+            }
+        }
+    }
+}
+
+/// Spawns an instance of the prefab registered as `name`, like `spawn_prefab`.
+/// The components of each of `overrides` are added to the prefab entity with
+/// the override's scene id, replacing the prefab's components of the same type.
+///
+/// Returns the `EntityId` of the root entity, or `None` (represented by `0`)
+/// on error.
+///
+/// # Safety
+///
+/// The pointer `name` must not be null and its memory must be null-terminated.
+///
+/// Valid slices of `&[ComponentRef]` and `&[PrefabOverride]` must be able to
+/// be constructed from `components` and `components_len`, and from `overrides`
+/// and `overrides_len`. The scene id of each override must not be null and its
+/// memory must be null-terminated.
+///
+/// The contents of `components` objects are type-erased, and cannot be verified
+/// by the engine. Callers must ensure they contain valid component data.
+pub unsafe extern "C" fn spawn_prefab_with_overrides<P: Platform, G: GpuFrameData>(
+    name: *const c_char,
+    components: *const ComponentRef<'_>,
+    components_len: usize,
+    overrides: *const PrefabOverride<'_>,
+    overrides_len: usize,
+) -> Option<EntityId> {
+    unsafe {
+        match module_api::spawn_prefab::<P, G>(
+            CStr::from_ptr(name),
+            slice::from_raw_parts(components, components_len),
+            slice::from_raw_parts(overrides, overrides_len),
+        ) {
+            Ok(entity_id) => Some(entity_id.into()),
+            Err(err) => {
+                log::warn!("{err}");
+                None
+            }
+        }
+    }
+}
+
+/// Spawns an entity with the given set of `components`. Unlike `spawn`, the
+/// entity is spawned immediately.
+///
+/// Returns the `EntityId` of the spawned entity, if it was successfully spawned.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+///
+/// A valid slice of `&[ComponentRef]` must be able to be constructed from
+/// `components` and `components_len`.
+///
+/// The contents of `components` objects are type-erased, and cannot be verified
+/// by the engine. Callers must ensure they contain valid component data.
+pub unsafe extern "C" fn world_spawn<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    components: *const ComponentRef<'_>,
+    components_len: usize,
+) -> Option<EntityId> {
+    unsafe {
+        match module_api::world_spawn::<P, G>(
+            world,
+            slice::from_raw_parts(components, components_len),
+        ) {
+            Ok(entity_id) => Some(entity_id.into()),
+            Err(err) => {
+                log::warn!("{err}");
+                None
+            }
+        }
+    }
+}
+
+/// Immediately despawns an entity. If `recursive` is `false`, its children
+/// become root entities, otherwise they are despawned too.
+///
+/// Returns `false` if the entity does not exist.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+pub unsafe extern "C" fn world_despawn<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    entity_id: EntityId,
+    recursive: bool,
+) -> bool {
+    unsafe { module_api::world_despawn::<P, G>(world, entity_id.into(), recursive) }
+}
+
+/// Immediately adds components to an entity.
+///
+/// Returns `false` if the entity does not exist, or the component data is
+/// invalid.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+///
+/// A valid slice of `&[ComponentRef]` must be able to be constructed from
+/// `components` and `components_len`.
+pub unsafe extern "C" fn world_add_components<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    entity_id: EntityId,
+    components: *const ComponentRef<'_>,
+    components_len: usize,
+) -> bool {
+    let result = unsafe {
+        module_api::world_add_components::<P, G>(
+            world,
+            entity_id.into(),
+            slice::from_raw_parts(components, components_len),
+        )
+    };
+
+    match result {
+        Ok(()) => true,
+        Err(err) => {
+            log::warn!("{err}");
+            false
+        }
+    }
+}
+
+/// Immediately removes components from an entity.
+///
+/// Returns `false` if the entity does not exist.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+///
+/// A valid slice of `&[ComponentId]` must be able to be constructed from
+/// `component_ids` and `component_ids_len`.
+pub unsafe extern "C" fn world_remove_components<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    entity_id: EntityId,
+    component_ids: *const ComponentId,
+    component_ids_len: usize,
+) -> bool {
+    unsafe {
+        module_api::world_remove_components::<P, G>(
+            world,
+            entity_id.into(),
+            slice::from_raw_parts(component_ids, component_ids_len),
+        )
+    }
+}
+
+/// Returns a pointer to the data of a component of an entity, or null if the
+/// entity does not have the component. If `mutable` is `true`, the component
+/// is marked as changed.
+///
+/// The pointer is valid until the next structural change made through
+/// `world`.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+pub unsafe extern "C" fn world_component<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    entity_id: EntityId,
+    component_id: Option<ComponentId>,
+    mutable: bool,
+) -> *mut c_void {
+    let Some(component_id) = component_id else {
+        log::warn!("world_component: invalid ComponentId, has it been set via set_component_id()?");
+        return ptr::null_mut();
+    };
+
+    unsafe {
+        module_api::world_component::<P, G>(world, entity_id.into(), component_id, mutable).cast()
+    }
+}
+
+/// Writes up to `out_entities_capacity` entities to `out_entities`, and the
+/// total number of entities to `out_entities_len`. If the total exceeds the
+/// capacity, call again with a larger buffer.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+///
+/// `out_entities` must point to an array of `out_entities_capacity` 64-bit
+/// `EntityId` values. It may be null if `out_entities_capacity` is zero.
+///
+/// `out_entities_len` must be valid for writes and must be properly aligned.
+pub unsafe extern "C" fn world_entities<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    out_entities: *mut EntityId,
+    out_entities_capacity: usize,
+    out_entities_len: *mut usize,
+) {
+    let len = unsafe {
+        module_api::world_entities::<P, G, _, _>(world, |entities| {
+            let mut len = 0;
+
+            for entity_id in entities {
+                if len < out_entities_capacity {
+                    out_entities.add(len).write(entity_id.into());
+                }
+
+                len += 1;
+            }
+
+            len
+        })
+    };
+
+    unsafe { out_entities_len.write(len) };
+}
+
+/// Writes up to `out_component_ids_capacity` components of an entity to
+/// `out_component_ids`, and the total number of components to
+/// `out_component_ids_len`. If the total exceeds the capacity, call again with
+/// a larger buffer. Sparse components are included.
+///
+/// Returns `false` if the entity does not exist, and the outputs were not
+/// written to.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+///
+/// `out_component_ids` must point to an array of `out_component_ids_capacity`
+/// `ComponentId` values. It may be null if `out_component_ids_capacity` is
+/// zero.
+///
+/// `out_component_ids_len` must be valid for writes and must be properly
+/// aligned.
+pub unsafe extern "C" fn world_entity_components<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    entity_id: EntityId,
+    out_component_ids: *mut ComponentId,
+    out_component_ids_capacity: usize,
+    out_component_ids_len: *mut usize,
+) -> bool {
+    let Some(component_ids) =
+        (unsafe { module_api::world_entity_components::<P, G>(world, entity_id.into()) })
+    else {
+        return false;
+    };
+
+    unsafe {
+        write_component_ids(
+            &component_ids,
+            out_component_ids,
+            out_component_ids_capacity,
+            out_component_ids_len,
+        );
+    }
+
+    true
+}
+
+/// Returns the number of archetypes. Archetype indices are only stable until
+/// the next structural change made through `world`.
+///
+/// # Safety
+///
+/// `world` must point to a valid `World`, provided as a system input.
+pub unsafe extern "C" fn world_archetypes_len<P: Platform, G: Gpu>(world: *mut c_void) -> usize {
+    unsafe { module_api::world_archetypes_len::<P, G>(world) }
+}
+
+/// Writes the components of an archetype, in the same way as
+/// `world_entity_components`. Sparse components are not part of archetypes.
+///
+/// Returns `false` if `archetype_index` is out of bounds.
+///
+/// # Safety
+///
+/// See `world_entity_components`.
+pub unsafe extern "C" fn world_archetype_components<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    archetype_index: usize,
+    out_component_ids: *mut ComponentId,
+    out_component_ids_capacity: usize,
+    out_component_ids_len: *mut usize,
+) -> bool {
+    unsafe {
+        module_api::world_archetype_components::<P, G, _, _>(
+            world,
+            archetype_index,
+            |component_ids| {
+                write_component_ids(
+                    component_ids,
+                    out_component_ids,
+                    out_component_ids_capacity,
+                    out_component_ids_len,
+                );
+            },
+        )
+        .is_some()
+    }
+}
+
+/// Writes the entities of an archetype, in the same way as `world_entities`.
+///
+/// Returns `false` if `archetype_index` is out of bounds.
+///
+/// # Safety
+///
+/// See `world_entities`.
+pub unsafe extern "C" fn world_archetype_entities<P: Platform, G: Gpu>(
+    world: *mut c_void,
+    archetype_index: usize,
+    out_entities: *mut EntityId,
+    out_entities_capacity: usize,
+    out_entities_len: *mut usize,
+) -> bool {
+    let Some(entities) =
+        (unsafe { module_api::world_archetype_entities::<P, G>(world, archetype_index) })
+    else {
+        return false;
+    };
+
+    for (i, entity_id) in entities.iter().take(out_entities_capacity).enumerate() {
+        unsafe { out_entities.add(i).write((*entity_id).into()) };
+    }
+
+    unsafe { out_entities_len.write(entities.len()) };
+
+    true
+}
+
+unsafe fn write_component_ids(
+    component_ids: &[ComponentId],
+    out_component_ids: *mut ComponentId,
+    out_component_ids_capacity: usize,
+    out_component_ids_len: *mut usize,
+) {
+    for (i, component_id) in component_ids
+        .iter()
+        .take(out_component_ids_capacity)
+        .enumerate()
+    {
+        unsafe { out_component_ids.add(i).write(*component_id) };
+    }
+
+    unsafe { out_component_ids_len.write(component_ids.len()) };
 }
@@ -12,10 +12,16 @@ use std::{
 };
 
 use engine_core::{
-    add_components, call, call_async, completion_count, completion_get, despawn, entity_label,
-    event_count, event_get, event_send, get_parent, load_scene, query_for_each, query_get,
-    query_get_entity, query_get_label, query_len, query_par_for_each, remove_components,
-    set_entity_label, set_parent, set_system_enabled, spawn,
+    add_components, call, call_async, completion_count, completion_get, component_field,
+    component_fields_len, despawn, despawn_non_recursive, entity_label, event_count, event_get,
+    event_send, get_children, get_parent, insert_resource, load_scene, load_scene_from_path,
+    query_for_each, query_get, query_get_entity, query_get_label, query_get_many, query_is_changed,
+    query_len, query_par_for_each, register_prefab, register_prefab_from_path,
+    register_scene_migration, remove_components, remove_resource, set_entity_label,
+    set_module_error, set_next_state, set_parent, set_system_enabled, spawn, spawn_batch,
+    spawn_prefab, spawn_prefab_with_overrides, state_get, unload_scene, world_add_components,
+    world_archetype_components, world_archetype_entities, world_archetypes_len, world_component,
+    world_despawn, world_entities, world_entity_components, world_remove_components, world_spawn,
 };
 use gpu_common::Gpu;
 use material_manager::*;
@@ -54,8 +60,14 @@ pub fn get_module_api_proc_addr<P: Platform, G: Gpu>(proc_name: &CStr) -> *const
         completion_count::<P, G> as *const c_void
     } else if proc_name == c"completion_get" {
         completion_get::<P, G> as *const c_void
+    } else if proc_name == c"component_field" {
+        component_field::<P, G> as *const c_void
+    } else if proc_name == c"component_fields_len" {
+        component_fields_len::<P, G> as *const c_void
     } else if proc_name == c"despawn" {
         despawn::<P, G> as *const c_void
+    } else if proc_name == c"despawn_non_recursive" {
+        despawn_non_recursive::<P, G> as *const c_void
     } else if proc_name == c"entity_label" {
         entity_label::<P, G> as *const c_void
     } else if proc_name == c"event_count" {
@@ -64,32 +76,84 @@ pub fn get_module_api_proc_addr<P: Platform, G: Gpu>(proc_name: &CStr) -> *const
         event_get as *const c_void
     } else if proc_name == c"event_send" {
         event_send::<P> as *const c_void
+    } else if proc_name == c"get_children" {
+        get_children::<P, G> as *const c_void
     } else if proc_name == c"get_parent" {
         get_parent::<P, G> as *const c_void
+    } else if proc_name == c"insert_resource" {
+        insert_resource::<P, G> as *const c_void
     } else if proc_name == c"load_scene" {
         load_scene::<P, G> as *const c_void
+    } else if proc_name == c"load_scene_from_path" {
+        load_scene_from_path::<P, G> as *const c_void
     } else if proc_name == c"query_for_each" {
         query_for_each::<G> as *const c_void
     } else if proc_name == c"query_get" {
         query_get::<G> as *const c_void
     } else if proc_name == c"query_get_entity" {
         query_get_entity::<G> as *const c_void
+    } else if proc_name == c"query_get_many" {
+        query_get_many::<G> as *const c_void
     } else if proc_name == c"query_get_label" {
         query_get_label::<P, G> as *const c_void
+    } else if proc_name == c"query_is_changed" {
+        query_is_changed::<G> as *const c_void
     } else if proc_name == c"query_len" {
         query_len::<G> as *const c_void
     } else if proc_name == c"query_par_for_each" {
         query_par_for_each::<P, G> as *const c_void
+    } else if proc_name == c"register_prefab" {
+        register_prefab::<P, G> as *const c_void
+    } else if proc_name == c"register_prefab_from_path" {
+        register_prefab_from_path::<P, G> as *const c_void
+    } else if proc_name == c"register_scene_migration" {
+        register_scene_migration::<P, G> as *const c_void
     } else if proc_name == c"remove_components" {
         remove_components::<P, G> as *const c_void
+    } else if proc_name == c"remove_resource" {
+        remove_resource::<P, G> as *const c_void
     } else if proc_name == c"set_entity_label" {
         set_entity_label::<P, G> as *const c_void
+    } else if proc_name == c"set_module_error" {
+        set_module_error as *const c_void
+    } else if proc_name == c"set_next_state" {
+        set_next_state::<P, G> as *const c_void
     } else if proc_name == c"set_parent" {
         set_parent::<P, G> as *const c_void
     } else if proc_name == c"set_system_enabled" {
         set_system_enabled::<P, G> as *const c_void
     } else if proc_name == c"spawn" {
         spawn::<P, G> as *const c_void
+    } else if proc_name == c"spawn_batch" {
+        spawn_batch::<P, G> as *const c_void
+    } else if proc_name == c"spawn_prefab" {
+        spawn_prefab::<P, G> as *const c_void
+    } else if proc_name == c"spawn_prefab_with_overrides" {
+        spawn_prefab_with_overrides::<P, G> as *const c_void
+    } else if proc_name == c"state_get" {
+        state_get::<P, G> as *const c_void
+    } else if proc_name == c"unload_scene" {
+        unload_scene::<P, G> as *const c_void
+    } else if proc_name == c"world_add_components" {
+        world_add_components::<P, G> as *const c_void
+    } else if proc_name == c"world_archetype_components" {
+        world_archetype_components::<P, G> as *const c_void
+    } else if proc_name == c"world_archetype_entities" {
+        world_archetype_entities::<P, G> as *const c_void
+    } else if proc_name == c"world_archetypes_len" {
+        world_archetypes_len::<P, G> as *const c_void
+    } else if proc_name == c"world_component" {
+        world_component::<P, G> as *const c_void
+    } else if proc_name == c"world_despawn" {
+        world_despawn::<P, G> as *const c_void
+    } else if proc_name == c"world_entities" {
+        world_entities::<P, G> as *const c_void
+    } else if proc_name == c"world_entity_components" {
+        world_entity_components::<P, G> as *const c_void
+    } else if proc_name == c"world_remove_components" {
+        world_remove_components::<P, G> as *const c_void
+    } else if proc_name == c"world_spawn" {
+        world_spawn::<P, G> as *const c_void
     } else if proc_name == c"texture_asset_manager_white_texture_id" {
         texture_asset_manager_white_texture_id as *const c_void
     } else if proc_name == c"texture_asset_manager_missing_texture_id" {
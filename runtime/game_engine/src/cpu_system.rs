@@ -1,5 +1,5 @@
 use std::{
-    cell::UnsafeCell,
+    cell::{Cell, UnsafeCell},
     cmp::Ordering,
     error::Error,
     ffi::{CString, c_void},
@@ -13,21 +13,25 @@ use std::{
 use atomic_refcell::{AtomicRef, AtomicRefMut};
 use event::{EventManager, EventWriterStorageMut, EventWriterStorageRef};
 use game_ecs::{
-    ArchetypeKey, ArchetypeStorage, ComponentRegistry, CpuDataBuffer, CpuFrameData, EcsSystem,
-    EcsSystemExecuteResources, EcsTypeInfo, FrameDataBufferBorrowRef, FrameDataBufferBorrowRefMut,
-    GpuFrameData, PartitionIndex, system_execute_resources,
+    ArchetypeKey, ArchetypeStorage, ComponentRegistry, ComponentTicks, CpuDataBuffer, CpuFrameData,
+    EcsSystem, EcsSystemExecuteResources, EcsTypeInfo, FrameDataBufferBorrowRef,
+    FrameDataBufferBorrowRefMut, GpuFrameData, PartitionIndex, QueryAccess, SparseComponentStorage,
+    SystemAccess, system_execute_resources,
 };
 use game_entity::{EntityId, ParentType};
 use gpu_common::Gpu;
 use platform::{EcsModule, EcsSystemFn, Executor, Platform};
 use void_public::{
-    ArgType, ComponentId, ComponentRef, ForEachResult, callable::AsyncCompletionValue,
-    system::system_name_generator,
+    ArgType, ComponentId, ComponentRef, ForEachResult, SceneHandle, SceneMigrationCallback,
+    callable::AsyncCompletionValue, system::system_name_generator,
 };
 
+use crate::frame_update::ExclusiveWorld;
+
 pub struct CpuSystem<P: Platform, G: Gpu> {
     /// The system name, namespaced by the module name. For example: `MyModule::my_system`.
     name: String,
+    access: SystemAccess,
     queries: Vec<Query<G>>,
     cpu_resources_ref: Vec<CpuBufferInput<Option<AtomicRef<'static, CpuDataBuffer>>>>,
     cpu_resources_mut: Vec<CpuBufferInput<Option<AtomicRefMut<'static, CpuDataBuffer>>>>,
@@ -35,16 +39,41 @@ pub struct CpuSystem<P: Platform, G: Gpu> {
     gpu_resources_mut: Vec<GpuBufferInputMut<G>>,
     event_read_buffers: Vec<EventBufferReaderInfo>,
     event_write_buffers: Vec<EventBufferMut<P>>,
+    removed_component_read_buffers: Vec<RemovedComponentsReaderInfo>,
+    /// Update data indices of `Option<&R>` and `Option<&mut R>` resource inputs.
+    optional_resources: Vec<usize>,
     update: Box<dyn EcsSystemFn>,
     update_data: UpdateDataSingle,
+    /// The change tick of the previous execution of this system.
+    last_run_tick: u64,
     marker: PhantomData<P>,
 }
 
 struct Query<G: Gpu> {
     components: Vec<SystemComponent>,
+    /// Components which must be present in a matching archetype, but are not accessed.
+    with: Vec<SystemComponent>,
+    /// Components which must not be present in a matching archetype.
+    without: Vec<SystemComponent>,
+    /// Components which must have been changed since the system last ran, but are not accessed.
+    changed: Vec<SystemComponent>,
+    /// Components which must have been added since the system last ran, but are not accessed.
+    added: Vec<SystemComponent>,
+    /// Sparse components which must be present. These are not part of any archetype, so they
+    /// filter the rows of each matching archetype instead.
+    sparse_with: Vec<SystemComponent>,
+    /// Sparse components which must not be present.
+    sparse_without: Vec<SystemComponent>,
+    args_len: usize,
     archetypes: Vec<QueryArchetype<G>>,
     update_data_index: usize,
     update_data: UpdateData,
+    /// The change tick of the current system execution.
+    change_tick: u64,
+    /// The change tick of the previous system execution.
+    last_run_tick: u64,
+    /// Whether the entity most recently accessed through a `get` function had changed.
+    last_get_changed: Cell<bool>,
 }
 
 #[derive(Debug)]
@@ -52,6 +81,20 @@ struct QueryArchetype<G: Gpu> {
     cpu_buffer_input: CpuBufferInput<CpuBufferInputBuffer>,
     gpu_buffer_inputs_ref: Vec<GpuBufferInputRef<G>>,
     gpu_buffer_inputs_mut: Vec<GpuBufferInputMut<G>>,
+    /// Update data indices which are always null for this archetype, i.e. filters and absent
+    /// optional components.
+    null_update_data_indices: Vec<usize>,
+    /// CPU buffer offsets of the `ComponentTicks` of all accessed components.
+    accessed_ticks_offsets: Vec<usize>,
+    /// CPU buffer offsets of the `ComponentTicks` of mutably accessed components.
+    mutable_ticks_offsets: Vec<usize>,
+    /// CPU buffer offsets of the `ComponentTicks` of `Changed` filter components.
+    changed_ticks_offsets: Vec<usize>,
+    /// CPU buffer offsets of the `ComponentTicks` of `Added` filter components.
+    added_ticks_offsets: Vec<usize>,
+    /// The buffer rows matching the query's `Changed`, `Added` and sparse component filters, if it
+    /// has any. This is recomputed each time the buffers are locked.
+    filtered_rows: Option<Vec<usize>>,
 }
 
 type EventReaderHandle = *const Vec<EventWriterStorageRef<'static>>;
@@ -64,7 +107,7 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
         component_registry: &ComponentRegistry,
         event_manager: &mut EventManager<P>,
         module: &dyn EcsModule,
-    ) -> Self {
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let name = system_name_generator(
             &module.module_name(),
             &module.system_name(system_index).as_ref().to_string_lossy(),
@@ -75,6 +118,7 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
 
         let mut system = Self {
             name,
+            access: SystemAccess::default(),
             queries: Vec::new(),
             cpu_resources_ref: Vec::new(),
             cpu_resources_mut: Vec::new(),
@@ -82,8 +126,11 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
             gpu_resources_mut: Vec::new(),
             event_read_buffers: Vec::new(),
             event_write_buffers: Vec::new(),
+            removed_component_read_buffers: Vec::new(),
+            optional_resources: Vec::new(),
             update: module.system_fn(system_index),
             update_data,
+            last_run_tick: 0,
             marker: PhantomData,
         };
 
@@ -120,13 +167,16 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
             let arg_type = module.system_arg_type(system_index, arg_index);
 
             match arg_type {
-                ArgType::DataAccessMut | ArgType::DataAccessRef => {
+                ArgType::DataAccessMut
+                | ArgType::DataAccessRef
+                | ArgType::DataAccessOptionalMut
+                | ArgType::DataAccessOptionalRef => {
                     // resource access
 
                     let string_id = module.system_arg_component(system_index, arg_index);
                     let string_id = string_id.as_ref();
 
-                    let (_, component_info) = component_registry
+                    let (component_id, component_info) = component_registry
                         .get_with_string_id(string_id)
                         .unwrap_or_else(|| {
                             panic!("could not find component for string {string_id:?}")
@@ -138,8 +188,20 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                         );
                     };
 
-                    if matches!(arg_type, ArgType::DataAccessRef) {
+                    if matches!(
+                        arg_type,
+                        ArgType::DataAccessOptionalRef | ArgType::DataAccessOptionalMut
+                    ) {
+                        system.optional_resources.push(arg_index);
+                    }
+
+                    if matches!(
+                        arg_type,
+                        ArgType::DataAccessRef | ArgType::DataAccessOptionalRef
+                    ) {
                         // resource ref
+                        system.access.resources_ref.push(component_id);
+
                         if component_info.gpu_compatible {
                             system.gpu_resources_ref.push(GpuBufferInputRef {
                                 buffer_index: resource_info.buffer_index,
@@ -163,6 +225,8 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                         }
                     } else {
                         // resource mut
+                        system.access.resources_mut.push(component_id);
+
                         if component_info.gpu_compatible {
                             system.gpu_resources_mut.push(GpuBufferInputMut {
                                 buffer_index: resource_info.buffer_index,
@@ -191,6 +255,11 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                 ArgType::EventReader => {
                     let event_type = module.system_arg_event(system_index, arg_index);
 
+                    system
+                        .access
+                        .events_read
+                        .push(event_type.clone().into_owned());
+
                     system.event_read_buffers.push(EventBufferReaderInfo {
                         update_data_index: arg_index,
                         event_type: event_type.into_owned(),
@@ -199,7 +268,11 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                 }
                 ArgType::EventWriter => {
                     let event_ident = module.system_arg_event(system_index, arg_index);
-                    event_manager.register_module_event_writer(event_ident.as_ref(), &system.name);
+
+                    system
+                        .access
+                        .events_write
+                        .push(event_ident.clone().into_owned());
 
                     system.event_write_buffers.push(EventBufferMut {
                         event_ident: event_ident.into_owned(),
@@ -209,6 +282,12 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                 }
                 ArgType::Query => {
                     let mut query_components = Vec::new();
+                    let mut query_with = Vec::new();
+                    let mut query_without = Vec::new();
+                    let mut query_changed = Vec::new();
+                    let mut query_added = Vec::new();
+                    let mut query_sparse_with = Vec::new();
+                    let mut query_sparse_without = Vec::new();
 
                     let query_arg_len = module.system_query_args_len(system_index, arg_index);
 
@@ -216,7 +295,17 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                         let arg_type =
                             module.system_query_arg_type(system_index, arg_index, query_arg_index);
 
-                        if !matches!(arg_type, ArgType::DataAccessRef | ArgType::DataAccessMut) {
+                        if !matches!(
+                            arg_type,
+                            ArgType::DataAccessRef
+                                | ArgType::DataAccessMut
+                                | ArgType::DataAccessOptionalRef
+                                | ArgType::DataAccessOptionalMut
+                                | ArgType::With
+                                | ArgType::Without
+                                | ArgType::Added
+                                | ArgType::Changed
+                        ) {
                             log::error!("queries may only access components");
                             abort();
                         };
@@ -239,24 +328,80 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                             abort();
                         };
 
-                        if !component_info.is_freely_mutable
-                            && matches!(arg_type, ArgType::DataAccessMut)
-                        {
+                        let mutable = matches!(
+                            arg_type,
+                            ArgType::DataAccessMut | ArgType::DataAccessOptionalMut
+                        );
+
+                        if !component_info.is_freely_mutable && mutable {
                             log::error!(
                                 "mutable read-only components not yet supported: {string_id:?}"
                             );
                             abort();
                         }
 
-                        query_components.push(SystemComponent {
+                        let component = SystemComponent {
                             id: component_id,
                             update_data_index: query_arg_index,
-                            mutable: matches!(arg_type, ArgType::DataAccessMut),
-                        });
+                            mutable,
+                            optional: matches!(
+                                arg_type,
+                                ArgType::DataAccessOptionalRef | ArgType::DataAccessOptionalMut
+                            ),
+                        };
+
+                        if component_registry.is_sparse(&component_id) {
+                            match arg_type {
+                                ArgType::With => query_sparse_with.push(component),
+                                ArgType::Without => query_sparse_without.push(component),
+                                _ => {
+                                    return Err(format!(
+                                        "sparse component {string_id:?} may only be used with `With` and `Without` query filters"
+                                    )
+                                    .into());
+                                }
+                            }
+                            continue;
+                        }
+
+                        match arg_type {
+                            ArgType::With => query_with.push(component),
+                            ArgType::Without => query_without.push(component),
+                            ArgType::Changed => query_changed.push(component),
+                            ArgType::Added => query_added.push(component),
+                            _ => query_components.push(component),
+                        }
                     }
 
                     query_components.sort_unstable();
 
+                    system.access.queries.push(QueryAccess {
+                        components_ref: query_components
+                            .iter()
+                            .filter(|component| !component.mutable)
+                            .map(|component| component.id)
+                            .collect(),
+                        components_mut: query_components
+                            .iter()
+                            .filter(|component| component.mutable)
+                            .map(|component| component.id)
+                            .collect(),
+                        required: query_components
+                            .iter()
+                            .filter(|component| !component.optional)
+                            .chain(&query_with)
+                            .chain(&query_changed)
+                            .chain(&query_added)
+                            .chain(&query_sparse_with)
+                            .map(|component| component.id)
+                            .collect(),
+                        excluded: query_without
+                            .iter()
+                            .chain(&query_sparse_without)
+                            .map(|component| component.id)
+                            .collect(),
+                    });
+
                     let update_data = UpdateData(
                         (0..P::Executor::available_parallelism().get())
                             .map(|_| UnsafeCell::new(vec![ptr::null(); query_arg_len]))
@@ -265,18 +410,60 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
 
                     system.queries.push(Query {
                         components: query_components,
+                        with: query_with,
+                        without: query_without,
+                        changed: query_changed,
+                        added: query_added,
+                        sparse_with: query_sparse_with,
+                        sparse_without: query_sparse_without,
+                        args_len: query_arg_len,
                         archetypes: Default::default(),
                         update_data_index: arg_index,
                         update_data,
+                        change_tick: 0,
+                        last_run_tick: 0,
+                        last_get_changed: Cell::new(false),
                     });
                 }
                 ArgType::Completion => {
                     // for now we do nothing (completion API functions take the completion id)
                 }
+                ArgType::RemovedComponents => {
+                    let string_id = module.system_arg_component(system_index, arg_index);
+                    let string_id = string_id.as_ref();
+
+                    let (component_id, _) = component_registry
+                        .get_with_string_id(string_id)
+                        .unwrap_or_else(|| {
+                            panic!("could not find component for string {string_id:?}")
+                        });
+
+                    system
+                        .removed_component_read_buffers
+                        .push(RemovedComponentsReaderInfo {
+                            update_data_index: arg_index,
+                            component_id,
+                            borrows: Vec::new(),
+                        });
+                }
+                ArgType::With | ArgType::Without | ArgType::Added | ArgType::Changed => {
+                    panic!(
+                        "invalid system parameter {arg_type:?}: filters may only be used in queries"
+                    );
+                }
+                ArgType::World => {
+                    panic!("systems taking World are executed by `ExclusiveSystem`");
+                }
             }
         }
 
-        system
+        // Event writers are registered once the system is known to be valid.
+        for event_write_buffer in &system.event_write_buffers {
+            event_manager
+                .register_module_event_writer(event_write_buffer.event_ident.clone(), &system.name);
+        }
+
+        Ok(system)
     }
 }
 
@@ -285,6 +472,10 @@ impl<P: Platform, G: Gpu> EcsSystem<P, G> for CpuSystem<P, G> {
         &self.name
     }
 
+    fn access(&self) -> &SystemAccess {
+        &self.access
+    }
+
     fn add_archetype_input(&mut self, archetype_key: &ArchetypeKey, storage: &ArchetypeStorage) {
         for query in &mut self.queries {
             query.add_archetype_input(archetype_key, storage);
@@ -297,10 +488,19 @@ impl<P: Platform, G: Gpu> EcsSystem<P, G> for CpuSystem<P, G> {
         }
     }
 
-    fn execute(&mut self) -> Result<(), Box<dyn Error + Send + Sync>> {
+    fn execute(&mut self, change_tick: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for query in &mut self.queries {
+            query.change_tick = change_tick;
+            query.last_run_tick = self.last_run_tick;
+        }
+
         self.lock_buffers();
 
-        self.assign_resource_ptrs();
+        // systems do not run while any of their required resources are removed
+        if !self.assign_resource_ptrs() {
+            self.unlock_buffers();
+            return Ok(());
+        }
 
         self.assign_event_ptrs();
 
@@ -319,6 +519,8 @@ impl<P: Platform, G: Gpu> EcsSystem<P, G> for CpuSystem<P, G> {
 
         self.unlock_buffers();
 
+        self.last_run_tick = change_tick;
+
         res
     }
 
@@ -344,7 +546,14 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
 
             for query in &mut self.queries {
                 for buffer in &mut query.archetypes {
-                    buffer.lock_buffers(cpu_data, gpu_data);
+                    buffer.lock_buffers(
+                        cpu_data,
+                        gpu_data,
+                        query.last_run_tick,
+                        resources.sparse_components,
+                        &query.sparse_with,
+                        &query.sparse_without,
+                    );
                 }
             }
 
@@ -411,9 +620,29 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
 
                 input.borrow = Some(borrow);
             }
+
+            for input in &mut self.removed_component_read_buffers {
+                if let Some(storage) = resources.removed_components.storage(&input.component_id) {
+                    // SAFETY: we drop the borrow at the end of the execute function
+                    let borrow = unsafe {
+                        transmute::<EventWriterStorageRef<'_>, EventWriterStorageRef<'static>>(
+                            storage.borrow(),
+                        )
+                    };
+
+                    input.borrows.push(borrow);
+                }
+            }
         });
     }
 
+    /// The component types read through `RemovedComponents` inputs.
+    pub fn removed_component_ids(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.removed_component_read_buffers
+            .iter()
+            .map(|input| input.component_id)
+    }
+
     fn unlock_buffers(&mut self) {
         for query in &mut self.queries {
             for buffer in &mut query.archetypes {
@@ -444,23 +673,45 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
         for input in &mut self.event_write_buffers {
             input.borrow = None;
         }
+
+        for input in &mut self.removed_component_read_buffers {
+            input.borrows.clear();
+        }
     }
 
-    fn assign_resource_ptrs(&mut self) {
+    /// Returns `false` if a required resource has been removed. Removed optional resources are
+    /// passed to the system as null pointers.
+    fn assign_resource_ptrs(&mut self) -> bool {
+        let mut resources_present = true;
+
         for input in &self.cpu_resources_ref {
-            let base_ptr = input.buffer.as_ref().unwrap().get_ptr(0);
+            let buffer = input.buffer.as_ref().unwrap();
 
             for component in &input.component_input_info {
-                let ptr = unsafe { base_ptr.add(component.input_buffer_offset) };
+                let ptr = if buffer.is_empty() {
+                    resources_present &= self
+                        .optional_resources
+                        .contains(&component.update_data_index);
+                    ptr::null()
+                } else {
+                    unsafe { buffer.get_ptr(0).add(component.input_buffer_offset) }
+                };
                 self.update_data.0[component.update_data_index] = ptr.cast();
             }
         }
 
         for input in &mut self.cpu_resources_mut {
-            let base_ptr = input.buffer.as_mut().unwrap().get_mut_ptr(0);
+            let buffer = input.buffer.as_mut().unwrap();
 
             for component in &input.component_input_info {
-                let ptr = unsafe { base_ptr.add(component.input_buffer_offset) };
+                let ptr = if buffer.is_empty() {
+                    resources_present &= self
+                        .optional_resources
+                        .contains(&component.update_data_index);
+                    ptr::null_mut()
+                } else {
+                    unsafe { buffer.get_mut_ptr(0).add(component.input_buffer_offset) }
+                };
                 self.update_data.0[component.update_data_index] = ptr.cast();
             }
         }
@@ -482,6 +733,8 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
                 self.update_data.0[component.update_data_index] = ptr.cast();
             }
         }
+
+        resources_present
     }
 
     fn assign_event_ptrs(&mut self) {
@@ -494,6 +747,12 @@ impl<P: Platform, G: Gpu> CpuSystem<P, G> {
             let ptr = input.borrow.as_ref().unwrap() as EventWriterHandle<P>;
             self.update_data.0[input.update_data_index] = ptr.cast();
         }
+
+        // `RemovedComponents` inputs are read through the event reader API
+        for input in &self.removed_component_read_buffers {
+            let ptr = &input.borrows as EventReaderHandle;
+            self.update_data.0[input.update_data_index] = ptr.cast();
+        }
     }
 }
 
@@ -502,16 +761,60 @@ impl<G: Gpu> Query<G> {
         use void_public::{EcsType, EntityId};
 
         // check if this archetype fits this system
-        if self.components.is_empty()
-            || !self
-                .components
-                .iter()
+        let mut required_components = self
+            .components
+            .iter()
+            .filter(|component| !component.optional)
+            .chain(&self.with)
+            .chain(&self.changed)
+            .chain(&self.added)
+            .peekable();
+
+        // queries made only of optional components, or filtered only by sparse
+        // components, match every archetype
+        if (required_components.peek().is_none()
+            && self.components.is_empty()
+            && self.sparse_with.is_empty())
+            || !required_components
                 .filter(|component| component.id != EntityId::id())
                 .all(|component| archetype_key.contains(&component.id))
+            || self
+                .without
+                .iter()
+                .any(|component| archetype_key.contains(&component.id))
         {
             return;
         }
 
+        let null_update_data_indices = self
+            .components
+            .iter()
+            .filter(|component| {
+                component.optional
+                    && component.id != EntityId::id()
+                    && !archetype_key.contains(&component.id)
+            })
+            .chain(&self.with)
+            .chain(&self.without)
+            .chain(&self.changed)
+            .chain(&self.added)
+            .chain(&self.sparse_with)
+            .chain(&self.sparse_without)
+            .map(|component| component.update_data_index)
+            .collect();
+
+        let ticks_offsets = |components: &mut dyn Iterator<Item = &SystemComponent>| {
+            components
+                .filter_map(|component| storage.get_component_ticks_offset(&component.id))
+                .collect::<Vec<_>>()
+        };
+
+        let accessed_ticks_offsets = ticks_offsets(&mut self.components.iter());
+        let mutable_ticks_offsets =
+            ticks_offsets(&mut self.components.iter().filter(|component| component.mutable));
+        let changed_ticks_offsets = ticks_offsets(&mut self.changed.iter());
+        let added_ticks_offsets = ticks_offsets(&mut self.added.iter());
+
         // CPU buffer
 
         let cpu_buffer_input = {
@@ -523,7 +826,8 @@ impl<G: Gpu> Query<G> {
                 .unwrap()
                 .offset;
 
-            let mut mutable = false;
+            // change ticks of mutable components are written to the CPU buffer
+            let mutable = self.components.iter().any(|component| component.mutable);
 
             let component_input_info: Vec<_> = self
                 .components
@@ -535,8 +839,6 @@ impl<G: Gpu> Query<G> {
                     })
                 })
                 .map(|component| {
-                    mutable |= component.mutable;
-
                     let input_buffer_offset = storage
                         .cpu
                         .components
@@ -635,34 +937,121 @@ impl<G: Gpu> Query<G> {
             cpu_buffer_input,
             gpu_buffer_inputs_ref,
             gpu_buffer_inputs_mut,
+            null_update_data_indices,
+            accessed_ticks_offsets,
+            mutable_ticks_offsets,
+            changed_ticks_offsets,
+            added_ticks_offsets,
+            filtered_rows: None,
         });
     }
 
     fn clear_archetype_inputs(&mut self) {
         self.archetypes.clear();
     }
+
+    /// Writes the component pointers of `entity_index` within `archetype`, and records whether
+    /// the entity had changed since the system last ran. Returns `true` on success, or `false` if
+    /// `entity_index` is out-of-bounds.
+    fn get(
+        &self,
+        archetype: &QueryArchetype<G>,
+        update_data: &mut [*const c_void],
+        entity_index: usize,
+    ) -> bool {
+        if entity_index >= archetype.entity_count() {
+            return false;
+        }
+
+        self.last_get_changed
+            .set(archetype.is_changed(entity_index, self.last_run_tick));
+
+        archetype.write_ptrs(update_data, entity_index, self.change_tick)
+    }
 }
 
 impl<G: Gpu> QueryArchetype<G> {
-    fn entity_count(&self) -> usize {
+    fn cpu_buffer(&self) -> &CpuDataBuffer {
         match &self.cpu_buffer_input.buffer {
-            CpuBufferInputBuffer::Ref(buffer) => buffer.as_ref().unwrap().len(),
-            CpuBufferInputBuffer::Mut(buffer) => buffer.as_ref().unwrap().len(),
+            CpuBufferInputBuffer::Ref(buffer) => buffer.as_ref().unwrap(),
+            CpuBufferInputBuffer::Mut(buffer) => buffer.as_ref().unwrap(),
         }
     }
 
-    /// Returns `true` on success, or `false` if `entity_index` is out-of-bounds.
-    fn write_ptrs(&self, update_data: &mut [*const c_void], entity_index: usize) -> bool {
-        let cpu_buffer = match &self.cpu_buffer_input.buffer {
-            CpuBufferInputBuffer::Ref(buffer) => &**buffer.as_ref().unwrap(),
-            CpuBufferInputBuffer::Mut(buffer) => &**buffer.as_ref().unwrap(),
-        };
+    /// The number of entities in the archetype buffers, regardless of filters.
+    fn row_count(&self) -> usize {
+        self.cpu_buffer().len()
+    }
+
+    /// The number of entities matching the query.
+    fn entity_count(&self) -> usize {
+        self.filtered_rows
+            .as_ref()
+            .map_or_else(|| self.row_count(), Vec::len)
+    }
+
+    /// Maps an entity index within the query to its archetype buffer row.
+    fn row(&self, entity_index: usize) -> usize {
+        self.filtered_rows
+            .as_ref()
+            .map_or(entity_index, |rows| rows[entity_index])
+    }
+
+    fn entity_id(&self, row: usize) -> EntityId {
+        unsafe {
+            *self
+                .cpu_buffer()
+                .get_with_offset_as::<EntityId>(row, self.cpu_buffer_input.entity_id_buffer_offset)
+                .unwrap()
+        }
+    }
+
+    fn component_ticks(&self, row: usize, ticks_offset: usize) -> ComponentTicks {
+        unsafe {
+            *self
+                .cpu_buffer()
+                .get_with_offset_as::<ComponentTicks>(row, ticks_offset)
+                .unwrap()
+        }
+    }
+
+    /// Returns whether any accessed component of `entity_index` has been added or changed since
+    /// `last_run_tick`.
+    fn is_changed(&self, entity_index: usize, last_run_tick: u64) -> bool {
+        let row = self.row(entity_index);
+
+        self.accessed_ticks_offsets
+            .iter()
+            .any(|offset| self.component_ticks(row, *offset).changed > last_run_tick)
+    }
 
-        if entity_index >= cpu_buffer.len() {
+    /// Returns `true` on success, or `false` if `entity_index` is out-of-bounds.
+    ///
+    /// Mutably accessed components are marked as changed at `change_tick`.
+    fn write_ptrs(
+        &self,
+        update_data: &mut [*const c_void],
+        entity_index: usize,
+        change_tick: u64,
+    ) -> bool {
+        if entity_index >= self.entity_count() {
             return false;
         }
 
-        let base_ptr = cpu_buffer.get_ptr(entity_index);
+        let entity_index = self.row(entity_index);
+        let base_ptr = self.cpu_buffer().get_ptr(entity_index);
+
+        for update_data_index in &self.null_update_data_indices {
+            update_data[*update_data_index] = ptr::null();
+        }
+
+        for ticks_offset in &self.mutable_ticks_offsets {
+            // SAFETY: the CPU buffer is borrowed mutably if any component is mutable
+            unsafe {
+                let ticks = base_ptr.add(*ticks_offset) as *mut ComponentTicks;
+                (*ticks).changed = change_tick;
+            }
+        }
 
         for component in &self.cpu_buffer_input.component_input_info {
             let ptr = unsafe { base_ptr.add(component.input_buffer_offset) };
@@ -703,6 +1092,8 @@ impl<G: Gpu> QueryArchetype<G> {
 
     /// Copies previous frame data to the current frame for the entire archetype. It may not occur
     /// naturally during frame processing, but we must copy previous frame changes in any case.
+    ///
+    /// `entity_range` refers to buffer rows, rather than entity indices within the query.
     fn copy_prev_frame_changes(&mut self, entity_range: Range<usize>) {
         if G::MULTI_BUFFERED {
             for input in &mut self.gpu_buffer_inputs_mut {
@@ -722,7 +1113,15 @@ impl<G: Gpu> QueryArchetype<G> {
         }
     }
 
-    fn lock_buffers(&mut self, cpu_data: &CpuFrameData, gpu_data: &G) {
+    fn lock_buffers(
+        &mut self,
+        cpu_data: &CpuFrameData,
+        gpu_data: &G,
+        last_run_tick: u64,
+        sparse_components: &SparseComponentStorage,
+        sparse_with: &[SystemComponent],
+        sparse_without: &[SystemComponent],
+    ) {
         match &mut self.cpu_buffer_input.buffer {
             CpuBufferInputBuffer::Ref(buffer) => {
                 *buffer = unsafe {
@@ -763,10 +1162,45 @@ impl<G: Gpu> QueryArchetype<G> {
 
             input.buffer = Some(buffer);
         }
+
+        if !self.changed_ticks_offsets.is_empty()
+            || !self.added_ticks_offsets.is_empty()
+            || !sparse_with.is_empty()
+            || !sparse_without.is_empty()
+        {
+            let filtered_rows = (0..self.row_count())
+                .filter(|row| {
+                    self.changed_ticks_offsets
+                        .iter()
+                        .all(|offset| self.component_ticks(*row, *offset).changed > last_run_tick)
+                        && self
+                            .added_ticks_offsets
+                            .iter()
+                            .all(|offset| self.component_ticks(*row, *offset).added > last_run_tick)
+                })
+                .filter(|row| {
+                    let entity_id = self.entity_id(*row);
+
+                    sparse_with
+                        .iter()
+                        .all(|component| sparse_components.contains(&component.id, entity_id))
+                        && !sparse_without
+                            .iter()
+                            .any(|component| sparse_components.contains(&component.id, entity_id))
+                })
+                .collect();
+
+            self.filtered_rows = Some(filtered_rows);
+
+            // not every row will be visited, so copy previous frame data up front
+            self.copy_prev_frame_changes(0..self.row_count());
+        }
     }
 
     fn unlock_buffers(&mut self) {
-        let entity_count = self.entity_count();
+        let row_count = self.row_count();
+
+        self.filtered_rows = None;
 
         match &mut self.cpu_buffer_input.buffer {
             CpuBufferInputBuffer::Ref(buffer) => {
@@ -782,7 +1216,7 @@ impl<G: Gpu> QueryArchetype<G> {
         }
 
         // it's possible that user code did not trigger multi-buffered data copying
-        self.copy_prev_frame_changes(0..entity_count);
+        self.copy_prev_frame_changes(0..row_count);
 
         for input in &mut self.gpu_buffer_inputs_mut {
             input.buffer = None;
@@ -812,6 +1246,7 @@ struct SystemComponent {
     id: ComponentId,
     update_data_index: usize,
     mutable: bool,
+    optional: bool,
 }
 
 impl PartialEq for SystemComponent {
@@ -878,6 +1313,13 @@ struct EventBufferReaderInfo {
     borrows: Vec<EventWriterStorageRef<'static>>,
 }
 
+struct RemovedComponentsReaderInfo {
+    update_data_index: usize,
+    component_id: ComponentId,
+    /// Holds a single borrow while the system executes.
+    borrows: Vec<EventWriterStorageRef<'static>>,
+}
+
 struct EventBufferMut<P: Platform> {
     event_ident: CString,
     update_data_index: usize,
@@ -896,12 +1338,46 @@ pub mod module_api {
     };
 
     use game_ecs::add_components_helper;
+    use void_public::{
+        PrefabOverride,
+        reflect::{ComponentField, FieldInfo},
+    };
 
     use super::*;
 
-    pub fn load_scene<P: Platform, G: GpuFrameData>(scene_str: &CStr) {
+    /// The reason `query_get_many()` failed.
+    #[derive(Debug)]
+    pub enum QueryGetManyError {
+        /// The entity is not matched by the query.
+        NotFound(EntityId),
+        /// The entity was passed more than once.
+        NotDisjoint(EntityId),
+    }
+
+    /// Loads a scene as a new instance. The scene's entities are spawned when
+    /// commands are applied, at the end of the frame.
+    pub fn load_scene<P: Platform, G: GpuFrameData>(scene_data: &[u8]) -> SceneHandle {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources.event_manager.command_load_scene(scene_data)
+        })
+    }
+
+    /// Starts loading a scene from an asset path as a new instance, when
+    /// commands are applied at the end of the frame. The scene's entities are
+    /// spawned once the scene is read and its assets are loaded.
+    pub fn load_scene_from_path<P: Platform, G: GpuFrameData>(asset_path: &CStr) -> SceneHandle {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .event_manager
+                .command_load_scene_from_path(asset_path)
+        })
+    }
+
+    /// Despawns the entities of a scene instance when commands are applied, at
+    /// the end of the frame.
+    pub fn unload_scene<P: Platform, G: GpuFrameData>(scene_handle: SceneHandle) {
         system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
-            resources.event_manager.command_load_scene(scene_str);
+            resources.event_manager.command_unload_scene(scene_handle);
         });
     }
 
@@ -933,9 +1409,164 @@ pub mod module_api {
         })
     }
 
+    /// Spawns `count` entities with the same `components`. Entity ids are
+    /// reserved at once, and the entities are spawned by a single command.
+    ///
+    /// # Safety
+    ///
+    /// The contents of `ComponentRef` objects are type-erased, and must be valid.
+    pub unsafe fn spawn_batch<P: Platform, G: GpuFrameData>(
+        components: &[ComponentRef<'_>],
+        count: usize,
+    ) -> Result<Vec<EntityId>, Box<dyn Error + Send + Sync>> {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            add_components_helper(
+                components,
+                resources.component_bundles,
+                |components_len, closure| {
+                    let entity_ids = resources.world_delegate.allocate_entity_ids(count);
+
+                    if !entity_ids.is_empty() {
+                        resources.event_manager.command_spawn_batch(
+                            &entity_ids,
+                            components_len,
+                            closure,
+                        );
+                    }
+
+                    entity_ids
+                },
+            )
+            .map_err(|err| {
+                let mut msg = err.to_string();
+                msg.insert_str(0, "spawn_batch(): ");
+                msg.into()
+            })
+        })
+    }
+
+    pub fn register_prefab<P: Platform, G: GpuFrameData>(name: &CStr, prefab_json: &CStr) {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .event_manager
+                .command_register_prefab(name, prefab_json);
+        });
+    }
+
+    /// Registers the prefab read from `asset_path` when commands are applied,
+    /// at the end of the frame. The prefab is registered under its asset path.
+    pub fn register_prefab_from_path<P: Platform, G: GpuFrameData>(asset_path: &CStr) {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .event_manager
+                .command_register_prefab_from_path(asset_path);
+        });
+    }
+
+    pub fn register_scene_migration<P: Platform, G: GpuFrameData>(
+        from_version: &CStr,
+        to_version: &CStr,
+        component_name: &CStr,
+        migrate_fn: SceneMigrationCallback,
+        user_data: *const c_void,
+    ) {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources.event_manager.command_register_scene_migration(
+                from_version,
+                to_version,
+                component_name,
+                migrate_fn,
+                user_data,
+            );
+        });
+    }
+
+    /// Spawns an instance of the prefab registered as `name`. `components`
+    /// are added to the root entity of the instance, and the components of
+    /// each of `overrides` to the prefab entity with the override's scene id,
+    /// replacing the prefab's components of the same type.
+    ///
+    /// # Safety
+    ///
+    /// The contents of `ComponentRef` objects are type-erased, and must be
+    /// valid. The pointers of `overrides` must be valid.
+    pub unsafe fn spawn_prefab<P: Platform, G: GpuFrameData>(
+        name: &CStr,
+        components: &[ComponentRef<'_>],
+        overrides: &[PrefabOverride<'_>],
+    ) -> Result<EntityId, Box<dyn Error + Send + Sync>> {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            if !resources.prefab_names.contains(name) {
+                return Err(format!(
+                    "spawn_prefab(): prefab {} is not registered",
+                    name.to_string_lossy()
+                )
+                .into());
+            }
+
+            let overrides = overrides
+                .iter()
+                .map(|prefab_override| {
+                    let scene_id = unsafe { CStr::from_ptr(prefab_override.scene_id) };
+                    let components = unsafe {
+                        slice::from_raw_parts(
+                            prefab_override.components,
+                            prefab_override.components_len,
+                        )
+                    };
+
+                    // required components are bundled when the instance is spawned
+                    add_components_helper(components, &[], |components_len, closure| {
+                        (
+                            scene_id,
+                            (0..components_len).map(closure).collect::<Vec<_>>(),
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| {
+                    let mut msg = err.to_string();
+                    msg.insert_str(0, "spawn_prefab(): ");
+                    msg
+                })?;
+
+            let overrides = overrides
+                .iter()
+                .map(|(scene_id, components)| (*scene_id, components.as_slice()))
+                .collect::<Vec<_>>();
+
+            // Required components are bundled when the instance is spawned, as
+            // the prefab may already provide them.
+            add_components_helper(components, &[], |components_len, closure| {
+                let entity_id = resources.world_delegate.allocate_entity_id();
+
+                resources.event_manager.command_spawn_prefab(
+                    name,
+                    entity_id,
+                    components_len,
+                    closure,
+                    &overrides,
+                );
+
+                entity_id
+            })
+            .map_err(|err| {
+                let mut msg = err.to_string();
+                msg.insert_str(0, "spawn_prefab(): ");
+                msg.into()
+            })
+        })
+    }
+
     pub fn despawn<P: Platform, G: GpuFrameData>(entity_id: EntityId) {
         system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
-            resources.event_manager.command_despawn(entity_id);
+            resources.event_manager.command_despawn(entity_id, true);
+        });
+    }
+
+    pub fn despawn_non_recursive<P: Platform, G: GpuFrameData>(entity_id: EntityId) {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources.event_manager.command_despawn(entity_id, false);
         });
     }
 
@@ -977,6 +1608,49 @@ pub mod module_api {
         });
     }
 
+    pub fn insert_resource<P: Platform, G: GpuFrameData>(
+        component_id: ComponentId,
+        data: &[MaybeUninit<u8>],
+    ) {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .event_manager
+                .command_insert_resource(component_id, data);
+        });
+    }
+
+    pub fn remove_resource<P: Platform, G: GpuFrameData>(component_id: ComponentId) {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .event_manager
+                .command_remove_resource(component_id);
+        });
+    }
+
+    pub fn component_fields_len<P: Platform, G: GpuFrameData>(component_id: ComponentId) -> usize {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .component_registry
+                .get(&component_id)
+                .map_or(0, |info| info.fields.len())
+        })
+    }
+
+    /// The returned `FieldInfo` borrows strings from the component registry,
+    /// which live as long as the engine.
+    pub fn component_field<P: Platform, G: GpuFrameData>(
+        component_id: ComponentId,
+        index: usize,
+    ) -> Option<FieldInfo> {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .component_registry
+                .get(&component_id)
+                .and_then(|info| info.fields.get(index))
+                .map(ComponentField::field_info)
+        })
+    }
+
     pub fn entity_label<P: Platform, G: GpuFrameData>(entity_id: EntityId) -> *const c_char {
         system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
             resources
@@ -1028,6 +1702,26 @@ pub mod module_api {
         )
     }
 
+    /// Calls `f` with the children of the entity. The children are only
+    /// borrowed, as they are owned by the `World`.
+    pub fn get_children<P: Platform, G: GpuFrameData, F, R>(
+        entity_id: EntityId,
+        f: F,
+    ) -> Result<R, Box<dyn Error + Send + Sync>>
+    where
+        F: FnOnce(&[EntityId]) -> R,
+    {
+        system_execute_resources(
+            |resources: &EcsSystemExecuteResources<'_, P, G>| match resources
+                .world_delegate
+                .children(entity_id)
+            {
+                Some(child_ids) => Ok(f(child_ids)),
+                None => Err("get_children: entity does not exist".into()),
+            },
+        )
+    }
+
     /// # Safety
     ///
     /// `query` must point to a valid `Query`.
@@ -1056,11 +1750,10 @@ pub mod module_api {
         component_ptrs: *mut *const c_void,
     ) -> bool {
         let query = unsafe { query.cast::<Query<G>>().as_ref().unwrap() };
-        let component_ptrs =
-            unsafe { slice::from_raw_parts_mut(component_ptrs, query.components.len()) };
+        let component_ptrs = unsafe { slice::from_raw_parts_mut(component_ptrs, query.args_len) };
 
         for archetype in &query.archetypes {
-            if archetype.write_ptrs(component_ptrs, index) {
+            if query.get(archetype, component_ptrs, index) {
                 return true;
             }
 
@@ -1089,27 +1782,23 @@ pub mod module_api {
         component_ptrs: *mut *const c_void,
     ) -> bool {
         let query = unsafe { query.cast::<Query<G>>().as_ref().unwrap() };
-        let component_ptrs =
-            unsafe { slice::from_raw_parts_mut(component_ptrs, query.components.len()) };
+        let component_ptrs = unsafe { slice::from_raw_parts_mut(component_ptrs, query.args_len) };
 
         for archetype in &query.archetypes {
-            let cpu_buffer = match &archetype.cpu_buffer_input.buffer {
-                CpuBufferInputBuffer::Ref(buffer) => &**buffer.as_ref().unwrap(),
-                CpuBufferInputBuffer::Mut(buffer) => &**buffer.as_ref().unwrap(),
-            };
+            let cpu_buffer = archetype.cpu_buffer();
 
             if let Some(entity_index) = (0..archetype.entity_count()).find(|i| {
                 entity_id
                     == unsafe {
                         *cpu_buffer
                             .get_with_offset_as::<EntityId>(
-                                *i,
+                                archetype.row(*i),
                                 archetype.cpu_buffer_input.entity_id_buffer_offset,
                             )
                             .unwrap()
                     }
             }) {
-                let res = archetype.write_ptrs(component_ptrs, entity_index);
+                let res = query.get(archetype, component_ptrs, entity_index);
                 assert!(res); // should be guaranteed by the index lookup
                 return true;
             };
@@ -1118,6 +1807,46 @@ pub mod module_api {
         false
     }
 
+    /// Gets the components of several distinct entities at once, so that they
+    /// may all be mutably accessed. The component pointers of each entity are
+    /// written one after the other to `component_ptrs`, and whether they were
+    /// changed since the current system last ran to `changed`.
+    ///
+    /// # Safety
+    ///
+    /// `query` must point to a valid `Query`.
+    ///
+    /// `component_ptrs` must be a pointer to an array of pointers, sized to the
+    /// number of components in the query times the number of entities, and
+    /// `changed` must have the same length as `entity_ids`. Both should be
+    /// assumed to be uninitialized if the function returns an error.
+    pub unsafe fn query_get_many<G: Gpu>(
+        query: *const c_void,
+        entity_ids: &[EntityId],
+        component_ptrs: *mut *const c_void,
+        changed: &mut [bool],
+    ) -> Result<(), QueryGetManyError> {
+        let args_len = unsafe { query.cast::<Query<G>>().as_ref().unwrap() }.args_len;
+
+        for (i, entity_id) in entity_ids.iter().enumerate() {
+            if entity_ids[..i].contains(entity_id) {
+                return Err(QueryGetManyError::NotDisjoint(*entity_id));
+            }
+        }
+
+        for (i, entity_id) in entity_ids.iter().enumerate() {
+            let entity_ptrs = unsafe { component_ptrs.add(i * args_len) };
+
+            if !unsafe { query_get_entity::<G>(query, *entity_id, entity_ptrs) } {
+                return Err(QueryGetManyError::NotFound(*entity_id));
+            }
+
+            changed[i] = unsafe { query_is_changed::<G>(query) };
+        }
+
+        Ok(())
+    }
+
     /// Returns `true` on success.
     ///
     ///  # Safety
@@ -1133,8 +1862,7 @@ pub mod module_api {
         component_ptrs: *mut *const c_void,
     ) -> bool {
         let query = unsafe { query.cast::<Query<G>>().as_ref().unwrap() };
-        let component_ptrs =
-            unsafe { slice::from_raw_parts_mut(component_ptrs, query.components.len()) };
+        let component_ptrs = unsafe { slice::from_raw_parts_mut(component_ptrs, query.args_len) };
 
         let Some(entity_id) =
             system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
@@ -1145,23 +1873,20 @@ pub mod module_api {
         };
 
         for archetype in &query.archetypes {
-            let cpu_buffer = match &archetype.cpu_buffer_input.buffer {
-                CpuBufferInputBuffer::Ref(buffer) => &**buffer.as_ref().unwrap(),
-                CpuBufferInputBuffer::Mut(buffer) => &**buffer.as_ref().unwrap(),
-            };
+            let cpu_buffer = archetype.cpu_buffer();
 
             if let Some(entity_index) = (0..archetype.entity_count()).find(|i| {
                 entity_id
                     == unsafe {
                         *cpu_buffer
                             .get_with_offset_as::<EntityId>(
-                                *i,
+                                archetype.row(*i),
                                 archetype.cpu_buffer_input.entity_id_buffer_offset,
                             )
                             .unwrap()
                     }
             }) {
-                let res = archetype.write_ptrs(component_ptrs, entity_index);
+                let res = query.get(archetype, component_ptrs, entity_index);
                 assert!(res); // should be guaranteed by the index lookup
                 return true;
             };
@@ -1170,6 +1895,18 @@ pub mod module_api {
         false
     }
 
+    /// Returns whether the entity most recently returned by one of the `query_get` functions had
+    /// any of its accessed components added or changed since the system last ran.
+    ///
+    /// # Safety
+    ///
+    /// `query` must point to a valid `Query`.
+    pub unsafe fn query_is_changed<G: Gpu>(query: *const c_void) -> bool {
+        let query = unsafe { query.cast::<Query<G>>().as_ref().unwrap() };
+
+        query.last_get_changed.get()
+    }
+
     /// # Safety
     ///
     /// `query` must point to a valid `Query`. It must not be aliased or be in use by any other thread.
@@ -1179,10 +1916,11 @@ pub mod module_api {
     {
         let query = unsafe { query.cast::<Query<G>>().as_mut().unwrap() };
         let update_data = unsafe { &mut *query.update_data.borrow_mut(0) };
+        let change_tick = query.change_tick;
 
         for archetype in &mut query.archetypes {
             for i in 0..archetype.entity_count() {
-                archetype.write_ptrs(update_data, i);
+                archetype.write_ptrs(update_data, i, change_tick);
 
                 match closure(update_data, &mut user_data) {
                     ForEachResult::Continue => {
@@ -1217,6 +1955,7 @@ pub mod module_api {
         U: Sync,
     {
         let query = unsafe { query.cast::<Query<G>>().as_mut().unwrap() };
+        let change_tick = query.change_tick;
 
         for archetype in &mut query.archetypes {
             const BLOCK_SIZE: usize = 256;
@@ -1230,7 +1969,7 @@ pub mod module_api {
                 let update_data = unsafe { &mut *query.update_data.borrow_mut(thread_index) };
 
                 for entity_index in start_index..end_index {
-                    archetype.write_ptrs(update_data, entity_index);
+                    archetype.write_ptrs(update_data, entity_index, change_tick);
 
                     let res = closure(update_data, &user_data);
                     assert_eq!(
@@ -1360,4 +2099,439 @@ pub mod module_api {
                 .command_set_system_enabled(system_name, enabled);
         });
     }
+
+    pub fn state_get<P: Platform, G: GpuFrameData>(state_id: &CStr) -> u32 {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources.states.get(state_id)
+        })
+    }
+
+    pub fn set_next_state<P: Platform, G: GpuFrameData>(state_id: &CStr, state: u32) {
+        system_execute_resources(|resources: &EcsSystemExecuteResources<'_, P, G>| {
+            resources
+                .event_manager
+                .command_set_next_state(state_id, state);
+        });
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    unsafe fn exclusive_world<'a, P: Platform, G: Gpu>(
+        world: *mut c_void,
+    ) -> &'a mut ExclusiveWorld<'a, P, G> {
+        unsafe { world.cast::<ExclusiveWorld<'a, P, G>>().as_mut().unwrap() }
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    ///
+    /// The contents of `ComponentRef` objects are type-erased, and must be valid.
+    pub unsafe fn world_spawn<P: Platform, G: Gpu>(
+        world: *mut c_void,
+        components: &[ComponentRef<'_>],
+    ) -> Result<EntityId, Box<dyn Error + Send + Sync>> {
+        unsafe { exclusive_world::<P, G>(world).spawn(components) }.map_err(|err| {
+            let mut msg = err.to_string();
+            msg.insert_str(0, "world_spawn(): ");
+            msg.into()
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_despawn<P: Platform, G: Gpu>(
+        world: *mut c_void,
+        entity_id: EntityId,
+        recursive: bool,
+    ) -> bool {
+        unsafe { exclusive_world::<P, G>(world).despawn(entity_id, recursive) }
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    ///
+    /// The contents of `ComponentRef` objects are type-erased, and must be valid.
+    pub unsafe fn world_add_components<P: Platform, G: Gpu>(
+        world: *mut c_void,
+        entity_id: EntityId,
+        components: &[ComponentRef<'_>],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        unsafe { exclusive_world::<P, G>(world).add_components(entity_id, components) }.map_err(
+            |err| {
+                let mut msg = err.to_string();
+                msg.insert_str(0, "world_add_components(): ");
+                msg.into()
+            },
+        )
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_remove_components<P: Platform, G: Gpu>(
+        world: *mut c_void,
+        entity_id: EntityId,
+        component_ids: &[ComponentId],
+    ) -> bool {
+        unsafe { exclusive_world::<P, G>(world).remove_components(entity_id, component_ids) }
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_component<P: Platform, G: Gpu>(
+        world: *mut c_void,
+        entity_id: EntityId,
+        component_id: ComponentId,
+        mutable: bool,
+    ) -> *mut MaybeUninit<u8> {
+        unsafe { exclusive_world::<P, G>(world).component(entity_id, component_id, mutable) }
+    }
+
+    /// Calls `f` with all entities in the world.
+    ///
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_entities<P: Platform, G: Gpu, F, R>(world: *mut c_void, f: F) -> R
+    where
+        F: FnOnce(&mut dyn Iterator<Item = EntityId>) -> R,
+    {
+        f(&mut unsafe { exclusive_world::<P, G>(world) }.entities())
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_entity_components<P: Platform, G: Gpu>(
+        world: *mut c_void,
+        entity_id: EntityId,
+    ) -> Option<Vec<ComponentId>> {
+        unsafe { exclusive_world::<P, G>(world).entity_components(entity_id) }
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_archetypes_len<P: Platform, G: Gpu>(world: *mut c_void) -> usize {
+        unsafe { exclusive_world::<P, G>(world).archetypes_len() }
+    }
+
+    /// Calls `f` with the components of an archetype, or returns `None` if the
+    /// index is out of bounds.
+    ///
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_archetype_components<P: Platform, G: Gpu, F, R>(
+        world: *mut c_void,
+        archetype_index: usize,
+        f: F,
+    ) -> Option<R>
+    where
+        F: FnOnce(&[ComponentId]) -> R,
+    {
+        unsafe { exclusive_world::<P, G>(world).archetype_components(archetype_index) }.map(f)
+    }
+
+    /// # Safety
+    ///
+    /// `world` must point to a valid `World`, provided as a system input.
+    pub unsafe fn world_archetype_entities<P: Platform, G: Gpu>(
+        world: *mut c_void,
+        archetype_index: usize,
+    ) -> Option<Vec<EntityId>> {
+        unsafe { exclusive_world::<P, G>(world).archetype_entities(archetype_index) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicU32, Ordering},
+    };
+
+    use game_module_macro::Component;
+    use void_public::{
+        Added, ArgType, Changed, Component, ComponentId, EcsType, Engine, EntityId, With, Without,
+        bundle,
+    };
+
+    use super::module_api::{self, QueryGetManyError};
+    use crate::test_engine::{SystemInputs, TestArg, TestEngine, TestGpu, TestModule, TestSystem};
+
+    #[derive(Component, Debug)]
+    #[repr(C)]
+    struct Health {
+        value: u32,
+    }
+
+    #[derive(Component, Debug)]
+    #[repr(C)]
+    struct Armor {
+        value: u32,
+    }
+
+    #[derive(Component, Debug)]
+    #[repr(C)]
+    struct Tag {
+        value: u32,
+    }
+
+    fn spawn_entities(_: SystemInputs) {
+        Engine::spawn(bundle!(&Health { value: 1 }));
+        Engine::spawn(bundle!(&Armor { value: 2 }));
+        Engine::spawn(bundle!(&Health { value: 3 }, &Armor { value: 4 }));
+        Engine::spawn(bundle!(&Tag { value: 5 }));
+    }
+
+    /// Runs `system` on the entities of `spawn_entities()`, and returns the
+    /// values it recorded in `matched`, sorted.
+    fn run_query<T: Ord + Clone>(system: TestSystem, matched: &Mutex<Vec<T>>) -> Vec<T> {
+        let module = TestModule::new(c"query_test")
+            .component::<Health>()
+            .component::<Armor>()
+            .component::<Tag>()
+            .system(TestSystem::new(c"spawn_entities", spawn_entities).once())
+            .system(system);
+
+        let mut engine = TestEngine::new([module]);
+        engine.frames(2);
+
+        let mut matched = matched.lock().unwrap().clone();
+        matched.sort();
+        matched
+    }
+
+    #[test]
+    fn query_optional_components_only() {
+        static MATCHED: Mutex<Vec<(Option<u32>, Option<u32>)>> = Mutex::new(Vec::new());
+
+        let system = TestSystem::new(c"optional_query", |inputs| {
+            let mut matched = MATCHED.lock().unwrap();
+            matched.clear();
+
+            inputs
+                .query::<(Option<&Health>, Option<&Armor>)>(0)
+                .for_each(|(health, armor)| {
+                    matched.push((
+                        health.map(|health| health.value),
+                        armor.map(|armor| armor.value),
+                    ));
+                });
+        })
+        .arg(TestArg::query(&[
+            (ArgType::DataAccessOptionalRef, Health::string_id()),
+            (ArgType::DataAccessOptionalRef, Armor::string_id()),
+        ]));
+
+        assert_eq!(
+            run_query(system, &MATCHED),
+            [
+                (None, None),
+                (None, Some(2)),
+                (Some(1), None),
+                (Some(3), Some(4))
+            ]
+        );
+    }
+
+    #[test]
+    fn query_required_and_optional_components() {
+        static MATCHED: Mutex<Vec<(u32, Option<u32>)>> = Mutex::new(Vec::new());
+
+        let system = TestSystem::new(c"mixed_query", |inputs| {
+            let mut matched = MATCHED.lock().unwrap();
+            matched.clear();
+
+            inputs
+                .query::<(&Health, Option<&Armor>)>(0)
+                .for_each(|(health, armor)| {
+                    matched.push((health.value, armor.map(|armor| armor.value)));
+                });
+        })
+        .arg(TestArg::query(&[
+            (ArgType::DataAccessRef, Health::string_id()),
+            (ArgType::DataAccessOptionalRef, Armor::string_id()),
+        ]));
+
+        assert_eq!(run_query(system, &MATCHED), [(1, None), (3, Some(4))]);
+    }
+
+    #[test]
+    fn query_optional_components_with_filter() {
+        static MATCHED: Mutex<Vec<Option<u32>>> = Mutex::new(Vec::new());
+
+        let system = TestSystem::new(c"filtered_query", |inputs| {
+            let mut matched = MATCHED.lock().unwrap();
+            matched.clear();
+
+            inputs
+                .query::<(Option<&Health>, Without<Armor>)>(0)
+                .for_each(|(health, _)| {
+                    matched.push(health.map(|health| health.value));
+                });
+        })
+        .arg(TestArg::query(&[
+            (ArgType::DataAccessOptionalRef, Health::string_id()),
+            (ArgType::Without, Armor::string_id()),
+        ]));
+
+        assert_eq!(run_query(system, &MATCHED), [None, Some(1)]);
+    }
+
+    /// Records the values of the `Health` components matched by a query of
+    /// `(&Health, F)`, once per frame.
+    fn record_health<F>(inputs: SystemInputs, records: &Mutex<Vec<Vec<u32>>>) {
+        let mut values = Vec::new();
+        inputs
+            .query::<(&Health, F)>(0)
+            .for_each(|(health, _)| values.push(health.value));
+
+        values.sort_unstable();
+        records.lock().unwrap().push(values);
+    }
+
+    #[test]
+    fn changed_and_added_filters() {
+        static FRAME: AtomicU32 = AtomicU32::new(0);
+        static CHANGED: Mutex<Vec<Vec<u32>>> = Mutex::new(Vec::new());
+        static ADDED: Mutex<Vec<Vec<u32>>> = Mutex::new(Vec::new());
+
+        let module = TestModule::new(c"change_test")
+            .component::<Health>()
+            .component::<Armor>()
+            .system(TestSystem::new(c"spawn_entities", |_| {
+                match FRAME.fetch_add(1, Ordering::Relaxed) + 1 {
+                    1 => {
+                        Engine::spawn(bundle!(&Health { value: 1 }));
+                        Engine::spawn(bundle!(&Health { value: 2 }, &Armor { value: 0 }));
+                    }
+                    3 => {
+                        Engine::spawn(bundle!(&Health { value: 3 }));
+                    }
+                    _ => {}
+                }
+            }))
+            // accesses `Health` mutably without writing to it
+            .system(
+                TestSystem::new(c"touch_armored", |inputs| {
+                    inputs
+                        .query::<(&mut Health, With<Armor>)>(0)
+                        .for_each(|_| {});
+                })
+                .arg(TestArg::query(&[
+                    (ArgType::DataAccessMut, Health::string_id()),
+                    (ArgType::With, Armor::string_id()),
+                ])),
+            )
+            .system(
+                TestSystem::new(c"record_changed", |inputs| {
+                    record_health::<Changed<Health>>(inputs, &CHANGED);
+                })
+                .arg(TestArg::query(&[
+                    (ArgType::DataAccessRef, Health::string_id()),
+                    (ArgType::Changed, Health::string_id()),
+                ]))
+                .after(c"touch_armored"),
+            )
+            .system(
+                TestSystem::new(c"record_added", |inputs| {
+                    record_health::<Added<Health>>(inputs, &ADDED);
+                })
+                .arg(TestArg::query(&[
+                    (ArgType::DataAccessRef, Health::string_id()),
+                    (ArgType::Added, Health::string_id()),
+                ])),
+            );
+
+        let mut engine = TestEngine::new([module]);
+        engine.frames(4);
+
+        // entities spawned during a frame are matched from the next frame
+        assert_eq!(
+            *CHANGED.lock().unwrap(),
+            [vec![], vec![1, 2], vec![2], vec![2, 3]]
+        );
+        assert_eq!(
+            *ADDED.lock().unwrap(),
+            [vec![], vec![1, 2], vec![], vec![3]]
+        );
+    }
+
+    #[test]
+    fn query_get_many_mut() {
+        static SPAWNED: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+        static RESULTS: Mutex<Vec<(bool, bool)>> = Mutex::new(Vec::new());
+
+        let module = TestModule::new(c"get_many_test")
+            .component::<Health>()
+            .component::<Armor>()
+            .system(
+                TestSystem::new(c"spawn_entities", |_| {
+                    *SPAWNED.lock().unwrap() = vec![
+                        Engine::spawn(bundle!(&Health { value: 1 })),
+                        Engine::spawn(bundle!(&Health { value: 2 }, &Armor { value: 0 })),
+                        Engine::spawn(bundle!(&Armor { value: 3 })),
+                    ];
+                })
+                .once(),
+            )
+            .system(
+                TestSystem::new(c"swap_health", |inputs| {
+                    let spawned = SPAWNED.lock().unwrap().clone();
+                    let mut query = inputs.query::<&mut Health>(0);
+
+                    let swapped = if let Some([mut a, mut b]) =
+                        query.get_many_mut([spawned[0], spawned[1]])
+                    {
+                        std::mem::swap(&mut a.unpack().value, &mut b.unpack().value);
+                        true
+                    } else {
+                        false
+                    };
+
+                    // an entity which is not matched by the query
+                    let missing = query.get_many_mut([spawned[0], spawned[2]]).is_none();
+                    RESULTS.lock().unwrap().push((swapped, missing));
+
+                    // the same entity may not be accessed twice
+                    let duplicate = spawned[1].into();
+                    let mut component_ptrs = [std::ptr::null(); 2];
+                    let mut changed = [false; 2];
+                    let res = unsafe {
+                        module_api::query_get_many::<TestGpu>(
+                            inputs.input(0),
+                            &[duplicate, duplicate],
+                            component_ptrs.as_mut_ptr(),
+                            &mut changed,
+                        )
+                    };
+                    assert!(matches!(
+                        res,
+                        Err(QueryGetManyError::NotDisjoint(entity_id)) if entity_id == duplicate
+                    ));
+                })
+                .arg(TestArg::query(&[(
+                    ArgType::DataAccessMut,
+                    Health::string_id(),
+                )]))
+                .after(c"spawn_entities"),
+            );
+
+        let mut engine = TestEngine::new([module]);
+        engine.frames(2);
+
+        // entities spawned during the first frame are matched from the next frame
+        assert_eq!(*RESULTS.lock().unwrap(), [(false, true), (true, true)]);
+
+        let spawned = SPAWNED.lock().unwrap().clone();
+        assert_eq!(engine.component::<Health>(spawned[0]).unwrap().value, 2);
+        assert_eq!(engine.component::<Health>(spawned[1]).unwrap().value, 1);
+    }
 }
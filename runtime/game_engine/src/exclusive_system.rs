@@ -0,0 +1,88 @@
+use std::{error::Error, ffi::c_void, marker::PhantomData, ptr};
+
+use game_ecs::{ArchetypeKey, ArchetypeStorage, EcsSystem, SystemAccess};
+use gpu_common::Gpu;
+use platform::{EcsModule, EcsSystemFn, Platform};
+use void_public::system::system_name_generator;
+
+/// A system taking a `World` input, which gives it exclusive access to the
+/// whole ECS world. See `FrameUpdate::execute_exclusive_systems()`.
+pub struct ExclusiveSystem<P: Platform, G: Gpu> {
+    /// The system name, namespaced by the module name. For example: `MyModule::my_system`.
+    name: String,
+    /// Exclusive systems never execute concurrently, so they declare no access.
+    access: SystemAccess,
+    update: Box<dyn EcsSystemFn>,
+    marker: PhantomData<(P, G)>,
+}
+
+/// Points to the `ExclusiveWorld` passed to the exclusive system currently
+/// executing, and is null otherwise.
+///
+/// # Safety
+///
+/// As with `SYSTEM_EXECUTE_RESOURCES`, this is a type-erased raw pointer to a
+/// stack-allocated value, because generic-parameterized statics are not
+/// supported.
+static mut EXCLUSIVE_WORLD: *mut c_void = ptr::null_mut();
+
+impl<P: Platform, G: Gpu> ExclusiveSystem<P, G> {
+    pub fn new(system_index: usize, module: &dyn EcsModule) -> Self {
+        let name = system_name_generator(
+            &module.module_name(),
+            &module.system_name(system_index).as_ref().to_string_lossy(),
+        );
+
+        Self {
+            name,
+            access: SystemAccess::default(),
+            update: module.system_fn(system_index),
+            marker: PhantomData,
+        }
+    }
+
+    /// Calls `f`, during which exclusive systems are passed `world` as their
+    /// `World` input.
+    pub fn with_world<T>(world: *mut c_void, f: impl FnOnce() -> T) -> T {
+        unsafe {
+            EXCLUSIVE_WORLD = world;
+        }
+
+        let res = f();
+
+        unsafe {
+            EXCLUSIVE_WORLD = ptr::null_mut();
+        }
+
+        res
+    }
+}
+
+impl<P: Platform, G: Gpu> EcsSystem<P, G> for ExclusiveSystem<P, G> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn access(&self) -> &SystemAccess {
+        &self.access
+    }
+
+    fn add_archetype_input(&mut self, _archetype_key: &ArchetypeKey, _storage: &ArchetypeStorage) {}
+
+    fn clear_archetype_inputs(&mut self) {}
+
+    fn execute(&mut self, _change_tick: u64) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let world = unsafe { EXCLUSIVE_WORLD };
+        assert!(
+            !world.is_null(),
+            "exclusive system `{}` executed without a World",
+            self.name
+        );
+
+        let update_data = [world.cast_const()];
+
+        unsafe { self.update.call(update_data.as_ptr()) }
+    }
+
+    fn clear_event_writer_buffers(&mut self) {}
+}
@@ -1,44 +1,66 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
-    ffi::CString,
+    ffi::{CStr, CString, c_char, c_void},
+    io,
     mem::{MaybeUninit, size_of},
     num::NonZero,
+    ptr::{self, null_mut},
     slice::from_raw_parts,
+    sync::{Arc, Mutex},
 };
 
 use event::{
     AddComponents, CommandRef, EventManager, SpawnComponentData,
-    events_generated::{RemoveComponents, SetEntityLabel},
+    events_generated::{RemoveComponents, SetEntityLabel, SpawnBatch, SpawnPrefab},
 };
 use game_asset::{
     ecs_module::GpuInterface, resource_managers::texture_asset_manager::PendingTexture,
 };
 use game_ecs::{
     ArchetypeKey, ArchetypeStorage, ArchetypeStorageMap, AsyncCompletionInfo, Callables,
-    ComponentBundle, ComponentInfo, ComponentRegistry, CpuFrameData, EcsSystemExecuteResources,
-    EcsTypeInfo, EntityComponentInfo, FrameDataBufferBorrowRef, FrameDataBufferBorrowRefMut,
-    FrameDataBufferRefMut, ResourceInfo, SystemGraph, bundle_required_components,
+    ComponentBundle, ComponentInfo, ComponentOffsetInfo, ComponentRegistry, ComponentTicks,
+    CpuFrameData, EcsSystem, EcsSystemExecuteResources, EcsTypeInfo, EntityComponentInfo,
+    FrameDataBufferBorrowRef, FrameDataBufferBorrowRefMut, FrameDataBufferRefMut,
+    RemovedComponentStorage, ResourceInfo, ScopeId, SparseComponentStorage, StateRegistry,
+    SystemConstraint, SystemGraph, SystemStateCondition, bundle_required_components,
     cpu_frame_data::CpuDataBufferRefMut,
 };
 use game_entity::EntityId;
 use game_input_manager::InputManager;
-use game_scene::SceneEntityComponents;
+use game_scene::{
+    SceneEntities, SceneEntityComponents, SceneEntityInfo, SceneEntityJson, SceneFile,
+    SceneMigrations, SceneReference, SceneTable, SceneTableRow,
+};
 use game_world::{EntityData, World};
 use gpu_common::Gpu;
-use platform::{EcsModule, Platform};
+use json::JsonValue;
+use platform::{Clock, EcsModule, Filesystem, Platform};
 use void_public::{
-    Component, ComponentData, ComponentId, ComponentRef, ComponentType, ENGINE_VERSION, EcsType,
-    FrameConstants, LocalToWorld, Mat4, Quat, Transform, api_version_compatible, api_version_major,
+    ArgType, AssetId, AssetPath, Component, ComponentData, ComponentId, ComponentRef,
+    ComponentType, ENGINE_VERSION, EcsType, FrameConfig, FrameConstants, LocalToWorld, Mat4, Quat,
+    SceneHandle, SceneMigrationCallback, Transform, api_version_compatible, api_version_major,
     api_version_minor, api_version_patch,
-    graphics::{TextureId, TextureRender},
+    event::engine::SceneLoadStage,
+    graphics::TextureId,
+    reflect::ComponentField,
+    state::StateCondition,
+    system::{SystemOrdering, system_name_generator},
 };
 
-use crate::{cpu_system::CpuSystem, transforms_update::update_world_transforms};
+use crate::{
+    cpu_system::CpuSystem, exclusive_system::ExclusiveSystem,
+    transforms_update::update_world_transforms,
+};
 
+mod exclusive_world;
+mod scene_assets;
 #[cfg(feature = "state_snapshots")]
 mod serialize;
 
+pub use exclusive_world::ExclusiveWorld;
+use scene_assets::{PendingParticleDescriptor, SceneAsset};
+
 pub struct FrameUpdate<P: Platform, G: Gpu> {
     pub archetypes: ArchetypeStorageMap,
     pub system_graph: SystemGraph<P, G>,
@@ -47,7 +69,154 @@ pub struct FrameUpdate<P: Platform, G: Gpu> {
     /// (component id, buffer index)
     gpu_single_buffer_components: Vec<(ComponentId, Option<usize>)>,
     pub world: World,
+    states: StateRegistry,
+    removed_components: RemovedComponentStorage<P>,
+    sparse_components: SparseComponentStorage,
+    /// Registered prefabs, by name. The root entity of each prefab is stored
+    /// first.
+    prefabs: HashMap<String, Arc<SceneEntities>>,
+    /// The names of the registered prefabs, and of the prefabs being read from
+    /// their asset paths. Modules may only spawn these prefabs.
+    prefab_names: HashSet<CString>,
+    /// Prefabs being read from their asset paths.
+    pending_prefab_loads: Vec<PendingPrefabLoad>,
+    /// The resources of the engine's own modules, which the engine expects to
+    /// be present, so they may not be removed.
+    engine_resources: HashSet<ComponentId>,
+    /// The entities spawned from each loaded scene instance, which were not
+    /// despawned since.
+    scene_instances: HashMap<SceneHandle, HashSet<EntityId>>,
+    /// Scene instances being loaded from asset paths.
+    pending_scene_loads: Vec<PendingSceneLoad>,
+    /// Migrations applied to scenes and prefabs of older versions when they
+    /// are loaded.
+    scene_migrations: SceneMigrations,
+    /// The ids of the particle effect descriptors loaded by scenes, by asset
+    /// path.
+    particle_descriptors: HashMap<AssetPath, AssetId>,
+    /// Particle effect descriptors loaded by scenes, whose JSON is being read.
+    pending_particle_descriptors: Vec<PendingParticleDescriptor>,
+    /// Scene instances loaded or unloaded since the last call to
+    /// `take_scene_events()`.
+    scene_events: Vec<SceneEvent>,
     frame_timer: FrameTimer,
+    fixed_timestep: FixedTimestep,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum SceneEvent {
+    Loaded {
+        scene_handle: SceneHandle,
+        entity_count: usize,
+    },
+    Unloaded {
+        scene_handle: SceneHandle,
+    },
+    /// A scene instance is being loaded from an asset path.
+    Progress {
+        scene_handle: SceneHandle,
+        stage: SceneLoadStage,
+        assets_loaded: usize,
+        assets_total: usize,
+    },
+}
+
+/// A scene instance being loaded from an asset path.
+struct PendingSceneLoad {
+    scene_handle: SceneHandle,
+    asset_path: AssetPath,
+    state: PendingSceneState,
+}
+
+enum PendingSceneState {
+    /// The scene file is being read and parsed, possibly on another thread.
+    Reading(Arc<SceneRead>),
+    /// The scene's entities are waiting for their assets to load.
+    LoadingAssets {
+        scene_entities: SceneEntities,
+        assets: Vec<SceneAsset>,
+    },
+}
+
+type SceneReadResult = Result<SceneFile, Box<dyn Error + Send + Sync>>;
+
+/// A scene file being read by `read_scene_file()`.
+struct SceneRead {
+    /// The migrations with which JSON scenes are upgraded once parsed.
+    migrations: SceneMigrations,
+    /// The parsed scene file, once it is read.
+    result: Mutex<Option<SceneReadResult>>,
+}
+
+/// Type-erased component data, by component id.
+type ComponentOverrides = BTreeMap<ComponentId, Box<[MaybeUninit<u8>]>>;
+
+/// An instance of a prefab, spawned with a preallocated root entity.
+struct PrefabInstance {
+    root_entity_id: EntityId,
+    /// The components replacing those of the prefab's root entity.
+    root_overrides: ComponentOverrides,
+    /// The components replacing those of the prefab's other entities, by scene
+    /// id.
+    overrides: HashMap<String, ComponentOverrides>,
+}
+
+impl PrefabInstance {
+    fn new(
+        prefab: &SpawnPrefab<'_>,
+        root_overrides: &AddComponents<'_>,
+        overrides: &[AddComponents<'_>],
+    ) -> Self {
+        fn component_overrides(components: &AddComponents<'_>) -> ComponentOverrides {
+            components
+                .sorted_component_ids()
+                .iter()
+                .map(|&component_id| {
+                    let component_data = components.component_data(component_id).unwrap();
+                    (component_id, component_data.into())
+                })
+                .collect()
+        }
+
+        let overrides = prefab
+            .override_scene_ids()
+            .into_iter()
+            .flatten()
+            .zip(overrides)
+            .map(|(scene_id, components)| (scene_id.to_owned(), component_overrides(components)))
+            .collect();
+
+        Self {
+            root_entity_id: *root_overrides.entity_id,
+            root_overrides: component_overrides(root_overrides),
+            overrides,
+        }
+    }
+
+    /// The components replacing those of the scene entity at `index` of the
+    /// prefab's entities, if any.
+    fn overrides(
+        &self,
+        index: usize,
+        scene_entity: &SceneEntityInfo,
+    ) -> Option<&ComponentOverrides> {
+        if index == 0 {
+            Some(&self.root_overrides)
+        } else {
+            scene_entity
+                .scene_id
+                .as_ref()
+                .and_then(|scene_id| self.overrides.get(scene_id))
+        }
+    }
+}
+
+/// A prefab being read from its asset path, which is also its name.
+struct PendingPrefabLoad {
+    asset_path: AssetPath,
+    scene_read: Arc<SceneRead>,
+    /// The instances spawned before the prefab was read.
+    instances: Vec<PrefabInstance>,
 }
 
 impl<P: Platform, G: Gpu> Default for FrameUpdate<P, G> {
@@ -59,7 +228,21 @@ impl<P: Platform, G: Gpu> Default for FrameUpdate<P, G> {
             gpu_component_bundles: Default::default(),
             gpu_single_buffer_components: Default::default(),
             world: Default::default(),
+            states: Default::default(),
+            removed_components: Default::default(),
+            sparse_components: Default::default(),
+            prefabs: Default::default(),
+            prefab_names: Default::default(),
+            pending_prefab_loads: Default::default(),
+            engine_resources: Default::default(),
+            scene_instances: Default::default(),
+            pending_scene_loads: Default::default(),
+            scene_migrations: Default::default(),
+            particle_descriptors: Default::default(),
+            pending_particle_descriptors: Default::default(),
+            scene_events: Default::default(),
             frame_timer: FrameTimer::default(),
+            fixed_timestep: FixedTimestep::default(),
         }
     }
 }
@@ -130,9 +313,12 @@ impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
 
                     EcsTypeInfo::AsyncCompletion(AsyncCompletionInfo { callable_id })
                 }
-                ComponentType::Component => EcsTypeInfo::Component(EntityComponentInfo {
-                    declaring_module_name: module_name.to_string(),
-                }),
+                ComponentType::Component | ComponentType::SparseComponent => {
+                    EcsTypeInfo::Component(EntityComponentInfo {
+                        declaring_module_name: module_name.to_string(),
+                        sparse: matches!(component_type, ComponentType::SparseComponent),
+                    })
+                }
                 ComponentType::Resource => {
                     let buffer_index = cpu_data.new_buffer(size, align);
                     let mut buffer = cpu_data.get_buffer_mut(buffer_index);
@@ -151,10 +337,17 @@ impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
                 }
             };
 
+            let fields = if matches!(ecs_type_info, EcsTypeInfo::Component(_)) {
+                ecs_module.component_fields(string_id.as_ref())
+            } else {
+                Vec::new()
+            };
+
             component_registry.register(ComponentInfo {
                 name: string_id.into_owned(),
                 size,
                 align,
+                fields,
                 gpu_compatible: false,
                 is_freely_mutable: true,
                 ecs_type_info,
@@ -165,19 +358,112 @@ impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
 
         // Register systems.
         for system_index in 0..ecs_module.systems_len() {
-            let system = Box::new(CpuSystem::<P, G>::new(
-                system_index,
-                component_registry,
-                event_manager,
-                ecs_module,
-            ));
-
             let is_once = ecs_module.system_is_once(system_index);
+            let is_fixed = ecs_module.system_is_fixed(system_index);
+
+            let is_exclusive = (0..ecs_module.system_args_len(system_index)).any(|arg_index| {
+                matches!(
+                    ecs_module.system_arg_type(system_index, arg_index),
+                    ArgType::World
+                )
+            });
+
+            let system: Box<dyn EcsSystem<P, G>> = if is_exclusive {
+                Box::new(ExclusiveSystem::<P, G>::new(system_index, ecs_module))
+            } else {
+                let system = match CpuSystem::<P, G>::new(
+                    system_index,
+                    component_registry,
+                    event_manager,
+                    ecs_module,
+                ) {
+                    Ok(system) => system,
+                    Err(err) => {
+                        log::error!("failed to register system from module {module_name}: {err}");
+                        continue;
+                    }
+                };
+
+                for component_id in system.removed_component_ids() {
+                    self.removed_components.register(component_id);
+                }
+
+                Box::new(system)
+            };
+
+            let constraints = (0..ecs_module.system_orderings_len(system_index))
+                .map(|ordering_index| {
+                    let ordering = ecs_module.system_ordering_type(system_index, ordering_index);
+                    let target = ecs_module.system_ordering_target(system_index, ordering_index);
+                    let target = target.to_string_lossy();
+
+                    let target = match ordering {
+                        SystemOrdering::Before | SystemOrdering::After => {
+                            system_name_generator(&module_name, &target)
+                        }
+                        _ => target.into_owned(),
+                    };
+
+                    SystemConstraint { ordering, target }
+                })
+                .collect();
+
+            let state_conditions = (0..ecs_module.system_state_conditions_len(system_index))
+                .map(|condition_index| {
+                    let state_id =
+                        ecs_module.system_state_condition_state(system_index, condition_index);
+                    self.states.register(&state_id);
+
+                    SystemStateCondition {
+                        condition: ecs_module
+                            .system_state_condition_type(system_index, condition_index),
+                        state_id: state_id.into_owned(),
+                        state: ecs_module
+                            .system_state_condition_value(system_index, condition_index),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let res = if is_exclusive {
+                let is_transition = state_conditions
+                    .iter()
+                    .any(|state_condition| state_condition.condition != StateCondition::InState);
+
+                if is_gpu || is_fixed || is_transition {
+                    log::error!(
+                        "{module_name}: system {} takes World, and may not be a GPU, fixed, enter or exit system",
+                        system.name()
+                    );
+                    continue;
+                }
 
-            if is_gpu {
-                self.system_graph.add_gpu_system(system, is_once);
+                self.system_graph.add_exclusive_system(
+                    system,
+                    is_once,
+                    constraints,
+                    state_conditions,
+                )
+            } else if is_gpu {
+                if is_fixed {
+                    log::warn!(
+                        "{module_name}: fixed systems are not supported in GPU modules, system {system_index} will run every frame"
+                    );
+                }
+
+                self.system_graph
+                    .add_gpu_system(system, is_once, constraints, state_conditions)
             } else {
-                self.system_graph.add_cpu_system(system, is_once);
+                self.system_graph.add_cpu_system(
+                    system,
+                    is_once,
+                    is_fixed,
+                    constraints,
+                    state_conditions,
+                )
+            };
+
+            if let Err(err) = res {
+                log::error!("failed to register system from module {module_name}: {err}");
             }
         }
 
@@ -189,9 +475,13 @@ impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
         }
     }
 
+    /// Loads a JSON or binary scene as a new instance, identified by
+    /// `scene_handle`. The scene is added to the world, alongside previously
+    /// loaded scenes.
     pub fn load_scene(
         &mut self,
-        scene_file: &str,
+        scene_handle: SceneHandle,
+        scene_data: &[u8],
         cpu_data: &mut CpuFrameData,
         gpu_data: &mut G,
         component_registry: &ComponentRegistry,
@@ -199,378 +489,761 @@ impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
         self.apply_prev_frame_changes(cpu_data, gpu_data);
 
-        let all_scene_entities = game_scene::parse_scene(scene_file, |name, text| {
-            Self::deserialize_component_json(name, text, component_registry, modules)
-        })?;
+        let change_tick = self.system_graph.increment_change_tick();
 
-        // map the JSON "id" field to the engine-generated `EntityId`
-        let mut json_id_map: HashMap<&str, EntityId> = HashMap::new();
+        let scene_file = game_scene::parse_scene_file(scene_data.to_vec())?;
+        let mut all_scene_entities =
+            self.scene_file_entities(scene_file, component_registry, modules)?;
 
-        let mut process_batched_textures = false;
+        self.load_scene_assets(&mut all_scene_entities, cpu_data, component_registry);
 
-        let texture_render_str_id = TextureRender::string_id();
+        self.spawn_scene_instance(
+            scene_handle,
+            &all_scene_entities,
+            cpu_data,
+            gpu_data,
+            component_registry,
+            change_tick,
+        );
 
-        for (component_ref, asset_path) in all_scene_entities.iter().map(|scene_entity| {
-            (
-                scene_entity.components.iter().find_map(|component| {
-                    component_registry
-                        .get(&component.component_id().unwrap())
-                        .filter(|comp_info| comp_info.name.as_c_str() == texture_render_str_id)
-                        .map(|_| ComponentRef::from(component))
-                }),
-                scene_entity.texture_asset_path.as_ref(),
-            )
-        }) {
-            if let Some(texture_asset_path) = asset_path {
-                // reinterpret as TextureRender component
-                let texture_render = unsafe {
-                    component_ref
-                        .unwrap()
-                        .component_val
-                        .cast_mut()
-                        .cast::<TextureRender>()
-                        .as_mut()
-                        .unwrap()
-                };
+        Ok(())
+    }
 
-                // assign the texture id, based on the asset_path from json
-                texture_render.texture_id = Self::load_texture_from_path(
-                    texture_asset_path.as_str(),
-                    cpu_data,
-                    component_registry,
-                );
-                process_batched_textures = true;
-            }
-        }
+    /// Starts loading a scene from an asset path, as a new instance identified
+    /// by `scene_handle`. The scene file is read and parsed asynchronously, and
+    /// the scene's entities are spawned at the start of the first frame in
+    /// which all of its assets are loaded, or failed to load.
+    pub fn load_scene_from_path(&mut self, scene_handle: SceneHandle, asset_path: &AssetPath) {
+        let scene_read = self.read_scene_file(asset_path);
+
+        self.pending_scene_loads.push(PendingSceneLoad {
+            scene_handle,
+            asset_path: asset_path.clone(),
+            state: PendingSceneState::Reading(scene_read),
+        });
+    }
 
-        if process_batched_textures {
-            cpu_data.get_resource_mut(component_registry, |gpu_interface: &mut GpuInterface| {
-                gpu_interface
-                    .texture_asset_manager
-                    .trigger_batched_textures();
-            });
-        }
+    /// Starts reading a scene file. The file is parsed on the thread which
+    /// read it, and JSON scenes are upgraded to the current version there too,
+    /// so only the components are deserialized on the main thread.
+    fn read_scene_file(&self, asset_path: &AssetPath) -> Arc<SceneRead> {
+        let scene_read = Arc::new(SceneRead {
+            migrations: self.scene_migrations.clone(),
+            result: Mutex::new(None),
+        });
 
-        for scene_entity in &all_scene_entities {
-            // type-erase the component data
-            let mut components: BTreeMap<ComponentId, Box<[MaybeUninit<u8>]>> = scene_entity
-                .components
-                .iter()
-                .map(|component| {
-                    let component_ref: ComponentRef<'_> = component.into();
-                    let component_id = component_ref.component_id.unwrap();
-                    let component_data = unsafe {
-                        from_raw_parts(
-                            component_ref.component_val.cast(),
-                            component_ref.component_size,
-                        )
+        P::Filesystem::read_async(&**asset_path, scene_read.clone(), |scene_read, bytes| {
+            let result = parse_scene_bytes(bytes, &scene_read.migrations);
+            *scene_read.result.lock().unwrap() = Some(result);
+        });
+
+        scene_read
+    }
+
+    /// Advances the scenes being loaded from asset paths, and spawns the
+    /// entities of each scene whose assets are all loaded, or failed to load.
+    fn update_scene_loads(
+        &mut self,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+    ) {
+        self.update_particle_descriptors(cpu_data, component_registry);
+        self.update_prefab_loads(cpu_data, gpu_data, component_registry, modules);
+
+        for pending in std::mem::take(&mut self.pending_scene_loads) {
+            let PendingSceneLoad {
+                scene_handle,
+                asset_path,
+                state,
+            } = pending;
+
+            let (mut scene_entities, assets) = match state {
+                PendingSceneState::Reading(scene_read) => {
+                    let Some(scene_file) = scene_read.result.lock().unwrap().take() else {
+                        self.scene_events.push(SceneEvent::Progress {
+                            scene_handle,
+                            stage: SceneLoadStage::Reading,
+                            assets_loaded: 0,
+                            assets_total: 0,
+                        });
+                        self.pending_scene_loads.push(PendingSceneLoad {
+                            scene_handle,
+                            asset_path,
+                            state: PendingSceneState::Reading(scene_read),
+                        });
+                        continue;
                     };
-                    (component_id, component_data.into())
-                })
-                .collect();
 
-            // look up any required bundled components
-            let required_components = bundle_required_components(
-                &components.keys().copied(),
-                &self.gpu_component_bundles,
-            );
+                    let scene_entities = scene_file.and_then(|scene_file| {
+                        self.scene_file_entities(scene_file, component_registry, modules)
+                    });
+
+                    let mut scene_entities = match scene_entities {
+                        Ok(scene_entities) => scene_entities,
+                        Err(e) => {
+                            log::warn!("Unable to load scene {asset_path}: {e}");
+                            self.scene_events.push(SceneEvent::Progress {
+                                scene_handle,
+                                stage: SceneLoadStage::Failed,
+                                assets_loaded: 0,
+                                assets_total: 0,
+                            });
+                            continue;
+                        }
+                    };
 
-            for component in required_components {
-                components.insert(component.id, component.default_value.clone());
-            }
+                    let assets =
+                        self.load_scene_assets(&mut scene_entities, cpu_data, component_registry);
 
-            let components = &SceneEntityComponents {
-                component_ids: components.keys().copied().collect(),
-                components: &components,
+                    (scene_entities, assets)
+                }
+                PendingSceneState::LoadingAssets {
+                    scene_entities,
+                    assets,
+                } => (scene_entities, assets),
             };
 
-            let archetype_key = ArchetypeKey {
-                component_ids: components.sorted_component_ids().into(),
-            };
+            let assets_loaded = assets
+                .iter()
+                .filter(|asset| self.is_scene_asset_resolved(asset, cpu_data, component_registry))
+                .count();
+
+            if assets_loaded < assets.len() {
+                self.scene_events.push(SceneEvent::Progress {
+                    scene_handle,
+                    stage: SceneLoadStage::LoadingAssets,
+                    assets_loaded,
+                    assets_total: assets.len(),
+                });
+                self.pending_scene_loads.push(PendingSceneLoad {
+                    scene_handle,
+                    asset_path,
+                    state: PendingSceneState::LoadingAssets {
+                        scene_entities,
+                        assets,
+                    },
+                });
+                continue;
+            }
+
+            // materials which were being read are registered by now
+            if assets
+                .iter()
+                .any(|asset| matches!(asset, SceneAsset::Material(_)))
+            {
+                Self::assign_scene_materials(&mut scene_entities, cpu_data, component_registry);
+            }
 
-            self.allocate_archetype_storage_if_needed(
-                &archetype_key,
+            let change_tick = self.system_graph.increment_change_tick();
+
+            self.spawn_scene_instance(
+                scene_handle,
+                &scene_entities,
                 cpu_data,
                 gpu_data,
                 component_registry,
+                change_tick,
             );
+        }
+    }
 
-            let storage = &self.archetypes[&archetype_key];
-            let buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
-            let entity_index = buffer.len();
+    fn spawn_scene_instance(
+        &mut self,
+        scene_handle: SceneHandle,
+        scene_entities: &SceneEntities,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        change_tick: u64,
+    ) {
+        let entity_ids = self.spawn_scene_entities(
+            scene_entities,
+            None,
+            cpu_data,
+            gpu_data,
+            component_registry,
+            change_tick,
+        );
 
-            // spawn the entity
-            let entity_id = self
-                .world
-                .spawn(EntityData::new(archetype_key, entity_index));
+        self.scene_events.push(SceneEvent::Loaded {
+            scene_handle,
+            entity_count: entity_ids.len(),
+        });
+        self.scene_instances
+            .insert(scene_handle, entity_ids.into_iter().collect());
+    }
 
-            write_spawn_component_data(
+    /// Despawns the entities of a scene instance, including any children added
+    /// to them since the scene was loaded. Scenes which are still being loaded
+    /// from an asset path are cancelled. Returns `false` if the instance is not
+    /// loaded.
+    pub fn unload_scene(
+        &mut self,
+        scene_handle: SceneHandle,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+    ) -> bool {
+        if let Some(index) = self
+            .pending_scene_loads
+            .iter()
+            .position(|pending| pending.scene_handle == scene_handle)
+        {
+            // the scene has no entities yet
+            self.pending_scene_loads.remove(index);
+            self.scene_events
+                .push(SceneEvent::Unloaded { scene_handle });
+            return true;
+        }
+
+        let Some(entity_ids) = self.scene_instances.remove(&scene_handle) else {
+            return false;
+        };
+
+        self.apply_prev_frame_changes(cpu_data, gpu_data);
+
+        let change_tick = self.system_graph.increment_change_tick();
+
+        // entities which are despawned as children of other scene entities
+        // are skipped
+        for entity_id in entity_ids {
+            self.handle_despawn(
                 entity_id,
-                entity_index,
-                components,
-                storage,
+                true,
                 cpu_data,
                 gpu_data,
                 component_registry,
+                change_tick,
             );
+        }
 
-            if let Some(label) = scene_entity.label.as_ref() {
-                self.world
-                    .set_entity_label(entity_id, CString::new(label.as_bytes()).unwrap());
-            }
+        self.scene_events
+            .push(SceneEvent::Unloaded { scene_handle });
 
-            if let Some(scene_id) = &scene_entity.scene_id {
-                json_id_map.insert(scene_id, entity_id);
-            } else {
-                log::warn!(
-                    "Detected entity without a scene id. Other entities will not be able to reference it"
-                );
-            }
-        }
+        true
+    }
 
-        // reconstruct the entity <-> entity relationships
-        for scene_entity in &all_scene_entities {
-            if let Some(parent_entity_id) = scene_entity
-                .parent_scene_id
-                .as_ref()
-                .and_then(|id| json_id_map.get(id.as_str()))
-            {
-                if let Some(entity_id) = scene_entity
-                    .scene_id
-                    .as_ref()
-                    .and_then(|id| json_id_map.get(id.as_str()))
-                {
-                    let entity = self.world.get_mut(*entity_id).unwrap();
-                    entity.parent_id = Some(*parent_entity_id);
+    /// Marks all registered resources as resources of the engine, which may not
+    /// be removed. Called once the engine's own modules are registered.
+    pub fn register_engine_resources(&mut self, component_registry: &ComponentRegistry) {
+        self.engine_resources = component_registry
+            .iter()
+            .filter(|(_, component_info)| {
+                matches!(component_info.ecs_type_info, EcsTypeInfo::Resource(_))
+            })
+            .map(|(component_id, _)| component_id)
+            .collect();
+    }
 
-                    let parent_entity = self.world.get_mut(*parent_entity_id).unwrap();
-                    parent_entity.child_ids.push(*entity_id);
-                }
-            }
-        }
+    /// The migrations applied to scenes and prefabs of older versions when
+    /// they are loaded.
+    pub fn scene_migrations_mut(&mut self) -> &mut SceneMigrations {
+        &mut self.scene_migrations
+    }
 
-        Ok(())
+    /// Returns the scene instances loaded or unloaded since the last call, in
+    /// order.
+    pub fn take_scene_events(&mut self) -> Vec<SceneEvent> {
+        std::mem::take(&mut self.scene_events)
     }
 
-    fn deserialize_component_json(
-        component_name: &str,
-        text: &str,
+    /// Registers a prefab, which may then be spawned with
+    /// `module_api::spawn_prefab()`. The prefab is a scene with a single root
+    /// entity, i.e. a single entity without a `parent_id`. Registering a prefab
+    /// under an existing name replaces the previous prefab.
+    pub fn register_prefab(
+        &mut self,
+        name: &str,
+        prefab_json: &str,
+        cpu_data: &mut CpuFrameData,
         component_registry: &ComponentRegistry,
         modules: &HashMap<String, Box<dyn EcsModule>>,
-    ) -> Result<ComponentData, Box<dyn Error + Send + Sync>> {
-        let c_str = CString::new(component_name)?;
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let prefab_entities = game_scene::parse_scene(
+            prefab_json,
+            &self.scene_migrations,
+            |name| Self::component_fields(name, component_registry),
+            |name, text| Self::deserialize_component_json(name, text, component_registry, modules),
+        )?;
+
+        self.insert_prefab(name, prefab_entities, cpu_data, component_registry)
+    }
 
-        let result = component_registry
-            .iter()
-            .find(|component| component.1.name == c_str);
+    /// Starts reading a prefab from an asset path, under which it is
+    /// registered once read. Instances of the prefab spawned in the meantime
+    /// are spawned at the start of the frame in which it is registered.
+    pub fn register_prefab_from_path(&mut self, asset_path: &AssetPath) {
+        let scene_read = self.read_scene_file(asset_path);
+
+        self.prefab_names.insert(asset_path.as_c_string());
+        self.pending_prefab_loads.push(PendingPrefabLoad {
+            asset_path: asset_path.clone(),
+            scene_read,
+            instances: Vec::new(),
+        });
+    }
 
-        if let Some((component_id, component_info)) = result {
-            if let EcsTypeInfo::Component(entity_component_info) = &component_info.ecs_type_info {
-                // create a buffer big enough for the data
-                let mut dest_vec = Vec::<MaybeUninit<u8>>::with_capacity(component_info.size);
+    /// Registers the prefabs which were read from their asset paths, and spawns
+    /// their pending instances. The instances of prefabs which failed to load
+    /// are discarded.
+    fn update_prefab_loads(
+        &mut self,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+    ) {
+        for pending in std::mem::take(&mut self.pending_prefab_loads) {
+            let Some(scene_file) = pending.scene_read.result.lock().unwrap().take() else {
+                self.pending_prefab_loads.push(pending);
+                continue;
+            };
 
-                // deserialize via the owning module
-                if let Err(error) = modules[entity_component_info.declaring_module_name.as_str()]
-                    .as_ref()
-                    .component_deserialize_json(
-                        component_info.name.as_c_str(),
-                        dest_vec.as_mut_slice(),
-                        text,
-                    )
-                {
-                    return Err(format!(
-                        "Component {} could not be deserialized: {:?}",
-                        component_name, error
+            let name = pending.asset_path.to_string_lossy().into_owned();
+
+            let result = scene_file
+                .and_then(|scene_file| {
+                    self.scene_file_entities(scene_file, component_registry, modules)
+                })
+                .and_then(|prefab_entities| {
+                    // prefab instances are spawned entity by entity, as their components may
+                    // be overridden
+                    self.insert_prefab(
+                        &name,
+                        prefab_entities.into_vec(),
+                        cpu_data,
+                        component_registry,
                     )
-                    .into());
+                });
+
+            if let Err(e) = result {
+                log::warn!("Unable to register prefab {name}: {e}");
+
+                if !self.prefabs.contains_key(&name) {
+                    self.prefab_names.remove(&pending.asset_path.as_c_string());
                 }
 
-                unsafe {
-                    // since we're writing directly to dest_vec's buffer, set the size manually
-                    dest_vec.set_len(component_info.size);
-                };
+                // release the preallocated entity ids
+                for instance in pending.instances {
+                    self.world.despawn(instance.root_entity_id);
+                }
 
-                Ok(ComponentData::new(component_id, dest_vec))
-            } else {
-                Err(format!("Trying to deserialize non-component {}", component_name).into())
+                continue;
+            }
+
+            let prefab_entities = self.prefabs[&name].clone();
+            let change_tick = self.system_graph.increment_change_tick();
+
+            for instance in pending.instances {
+                self.spawn_scene_entities(
+                    &prefab_entities,
+                    Some(&instance),
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    change_tick,
+                );
             }
-        } else {
-            Err(format!("Component {} not found", component_name).into())
         }
     }
 
-    fn load_texture_from_path(
-        asset_path: &str,
+    /// Registers the entities of a prefab under `name`.
+    fn insert_prefab(
+        &mut self,
+        name: &str,
+        mut prefab_entities: Vec<SceneEntityInfo>,
         cpu_data: &mut CpuFrameData,
         component_registry: &ComponentRegistry,
-    ) -> TextureId {
-        cpu_data.get_resource_mut(component_registry, |gpu_interface: &mut GpuInterface| {
-            if let Some(texture) = gpu_interface
-                .texture_asset_manager
-                .get_texture_by_path(&asset_path.into())
-            {
-                texture.id()
-            } else {
-                let texture_id = gpu_interface
-                    .texture_asset_manager
-                    .register_next_texture_id();
-                let pending_texture = PendingTexture::new(texture_id, &asset_path.into(), false);
-                gpu_interface
-                    .texture_asset_manager
-                    .add_to_batched_textures(pending_texture);
-                texture_id
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let root_index = {
+            let mut roots = prefab_entities
+                .iter()
+                .enumerate()
+                .filter(|(_, scene_entity)| scene_entity.parent_scene_id.is_none())
+                .map(|(index, _)| index);
+
+            match (roots.next(), roots.next()) {
+                (Some(root_index), None) => root_index,
+                _ => return Err("prefab must contain exactly one root entity".into()),
             }
-        })
+        };
+
+        // the root entity is always spawned first
+        prefab_entities.swap(0, root_index);
+
+        let mut prefab_entities = SceneEntities::from(prefab_entities);
+        self.load_scene_assets(&mut prefab_entities, cpu_data, component_registry);
+
+        if self
+            .prefabs
+            .insert(name.to_owned(), prefab_entities.into())
+            .is_some()
+        {
+            log::info!("Replacing prefab {name}");
+        }
+
+        self.prefab_names
+            .insert(CString::new(name).unwrap_or_default());
+
+        Ok(())
     }
 
-    /// Update systems which may update asynchronously, in parallel with frame rendering
-    // We need more arguments for this function
-    #[allow(clippy::too_many_arguments)]
-    pub async fn update_async(
-        &mut self,
-        event_manager: &mut EventManager<P>,
-        input_manager: &InputManager,
+    /// Serializes all entities into a scene, in the format read by
+    /// `load_scene()`. Components are serialized by their declaring modules.
+    pub fn save_scene(
+        &self,
         cpu_data: &mut CpuFrameData,
         gpu_data: &mut G,
         component_registry: &ComponentRegistry,
         modules: &HashMap<String, Box<dyn EcsModule>>,
-        callables: &Callables,
-        delta_time: f32,
-    ) {
-        self.frame_timer.update_frame(delta_time);
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let mut scene_entities = Vec::new();
 
-        self.apply_prev_frame_changes(cpu_data, gpu_data);
+        // the asset and entity reference fields of each component
+        let mut reference_fields = HashMap::new();
 
-        self.update_frame_constants(cpu_data, component_registry, delta_time);
+        for entity_id in self.world.entities() {
+            let entity_data = &self.world[entity_id];
 
-        // process cpu systems
-        {
-            let resources = &EcsSystemExecuteResources {
-                cpu_data,
-                gpu_data,
-                event_manager,
-                input_buffer: input_manager.binary_buffer(),
-                world_delegate: &self.world.sync_delegate(),
-                component_bundles: &self.gpu_component_bundles,
-                component_registry,
-                callables,
-            };
+            let component_ids = entity_data
+                .archetype_key
+                .component_ids
+                .iter()
+                .copied()
+                .chain(self.sparse_components.components(entity_id));
 
-            self.system_graph.execute_cpu(resources).await;
-        }
+            let mut components = Vec::new();
+            let mut references = Vec::new();
 
-        // Apply changes before running GPU systems, so that we don't change the number
-        // of objects by spawning/despawning after the GPU has already issued draw calls
-        self.apply_current_frame_changes(
-            event_manager,
-            cpu_data,
-            gpu_data,
-            component_registry,
-            modules,
-        );
+            for component_id in component_ids {
+                let component_info = &component_registry[&component_id];
 
-        // process gpu systems
+                let EcsTypeInfo::Component(entity_component_info) = &component_info.ecs_type_info
+                else {
+                    continue;
+                };
 
-        let resources = &EcsSystemExecuteResources {
-            cpu_data,
-            gpu_data,
-            event_manager,
-            input_buffer: input_manager.binary_buffer(),
-            world_delegate: &self.world.sync_delegate(),
-            component_bundles: &self.gpu_component_bundles,
-            component_registry,
-            callables,
-        };
+                // `LocalToWorld` is bundled with `Transform`, and recomputed from it
+                if component_id == LocalToWorld::id() {
+                    continue;
+                }
 
-        self.system_graph.execute_gpu(resources).await;
-    }
+                // sparse components have no data
+                let val = if component_registry.is_sparse(&component_id) {
+                    &[]
+                } else {
+                    let ptr =
+                        self.entity_component_data_mut(entity_id, component_id, cpu_data, gpu_data);
+                    unsafe { from_raw_parts(ptr, component_info.size) }
+                };
 
-    fn update_frame_constants(
-        &self,
-        cpu_data: &mut CpuFrameData,
-        component_registry: &ComponentRegistry,
-        delta_time: f32,
-    ) {
-        cpu_data.get_resource_mut(component_registry, |constants: &mut FrameConstants| {
-            let prev_tick_count = constants.tick_count;
+                let component_name = component_info.name.to_string_lossy();
 
-            *constants = FrameConstants {
-                delta_time,
-                frame_rate: self.frame_timer.get_average_fps(),
-                tick_count: prev_tick_count + 1,
-            };
-        });
-    }
+                let (asset_fields, entity_fields) =
+                    reference_fields.entry(component_id).or_insert_with(|| {
+                        (
+                            game_scene::asset_fields(&component_name, &component_info.fields),
+                            game_scene::entity_fields(&component_info.fields),
+                        )
+                    });
+
+                for asset_field in &*asset_fields {
+                    let offset = asset_field.field.offset;
+                    if asset_field.field.size != size_of::<u32>()
+                        || offset + size_of::<u32>() > val.len()
+                    {
+                        continue;
+                    }
 
-    #[allow(clippy::unused_self)]
-    fn apply_prev_frame_changes(&mut self, _cpu_data: &mut CpuFrameData, _gpu_data: &mut G) {
-        assert!(
-            !G::MULTI_BUFFERED,
-            "multi-buffered support temporarily removed, pending readdition of `entity_changes`"
-        );
+                    let id = unsafe { val.as_ptr().add(offset).cast::<u32>().read_unaligned() };
+
+                    // asset ids are only valid for the current session, so assets without a
+                    // path are saved as null, and loaded as the fallback asset
+                    references.push(SceneReference {
+                        component_name: component_name.clone().into_owned(),
+                        json_path: asset_field
+                            .json_path
+                            .iter()
+                            .map(|key| (*key).to_owned())
+                            .collect(),
+                        value: self.scene_asset_path(
+                            asset_field.field.field_type,
+                            id,
+                            cpu_data,
+                            component_registry,
+                        ),
+                    });
+                }
+
+                for entity_field in &*entity_fields {
+                    let offset = entity_field.field.offset;
+                    if entity_field.field.size != size_of::<u64>()
+                        || offset + size_of::<u64>() > val.len()
+                    {
+                        continue;
+                    }
+
+                    let id = unsafe { val.as_ptr().add(offset).cast::<u64>().read_unaligned() };
+
+                    let Some(referenced_id) = NonZero::new(id).map(EntityId::from) else {
+                        continue;
+                    };
+
+                    // references to entities which no longer exist are saved as null
+                    references.push(SceneReference {
+                        component_name: component_name.clone().into_owned(),
+                        json_path: entity_field
+                            .json_path
+                            .iter()
+                            .map(|key| (*key).to_owned())
+                            .collect(),
+                        value: self
+                            .world
+                            .get(referenced_id)
+                            .is_some()
+                            .then(|| referenced_id.id.to_string()),
+                    });
+                }
+
+                // serialize via the owning module
+                let text = match modules[entity_component_info.declaring_module_name.as_str()]
+                    .component_serialize_json(component_info.name.as_c_str(), val)
+                {
+                    Ok(text) => text,
+                    Err(error) => {
+                        log::warn!(
+                            "Skipping component {component_name} of entity {}: {error}",
+                            entity_id.id
+                        );
+                        continue;
+                    }
+                };
+
+                components.push((component_info.name.to_string_lossy().into_owned(), text));
+            }
+
+            scene_entities.push(SceneEntityJson {
+                scene_id: entity_id.id.to_string(),
+                parent_scene_id: entity_data
+                    .parent_id
+                    .map(|parent_id| parent_id.id.to_string()),
+                label: self
+                    .world
+                    .entity_label(entity_id)
+                    .map(|label| label.to_string_lossy().into_owned()),
+                components,
+                references,
+            });
+        }
+
+        game_scene::write_scene(&scene_entities)
     }
 
-    fn apply_current_frame_changes(
+    /// Spawns scene entities, and reconstructs their hierarchy. If
+    /// `prefab_instance` is provided, the first scene entity is spawned as the
+    /// preallocated root entity of the instance, the instance's component
+    /// overrides replace the components of the scene entities, and labels are
+    /// scoped to the instance.
+    ///
+    /// Returns the spawned entities.
+    fn spawn_scene_entities(
         &mut self,
-        event_manager: &mut EventManager<P>,
+        scene_entities: &SceneEntities,
+        prefab_instance: Option<&PrefabInstance>,
         cpu_data: &mut CpuFrameData,
         gpu_data: &mut G,
         component_registry: &ComponentRegistry,
-        modules: &HashMap<String, Box<dyn EcsModule>>,
-    ) {
-        event_manager.drain_commands(|command| match command {
-            CommandRef::AddComponents(command) => {
-                self.handle_add_components(&command, cpu_data, gpu_data, component_registry);
+        change_tick: u64,
+    ) -> Vec<EntityId> {
+        let mut entity_ids = Vec::with_capacity(scene_entities.entities.len());
+        let mut tables = scene_entities.tables.iter().peekable();
+
+        // map the JSON "id" field to the engine-generated `EntityId`
+        let mut json_id_map: HashMap<&str, EntityId> = HashMap::new();
+
+        for (index, scene_entity) in scene_entities.entities.iter().enumerate() {
+            // the entities of a table are spawned with the first entity of the table
+            if index == entity_ids.len() {
+                if let Some(table) = tables.next_if(|table| table.entities.start == index) {
+                    self.spawn_scene_table(
+                        table,
+                        &mut entity_ids,
+                        cpu_data,
+                        gpu_data,
+                        component_registry,
+                        change_tick,
+                    );
+                } else {
+                    let overrides = prefab_instance
+                        .and_then(|instance| instance.overrides(index, scene_entity));
+                    let preallocated_entity_id = prefab_instance
+                        .filter(|_| index == 0)
+                        .map(|instance| instance.root_entity_id);
+
+                    let Some(entity_id) = self.spawn_scene_entity(
+                        scene_entity,
+                        overrides,
+                        preallocated_entity_id,
+                        cpu_data,
+                        gpu_data,
+                        component_registry,
+                        change_tick,
+                    ) else {
+                        // the instance was despawned before it was spawned
+                        return entity_ids;
+                    };
+
+                    entity_ids.push(entity_id);
+                }
             }
-            CommandRef::Despawn(command) => {
-                let entity_id = NonZero::new(command.entity_id()).unwrap().into();
-                self.handle_despawn(entity_id, cpu_data, gpu_data);
+
+            let entity_id = entity_ids[index];
+
+            if let Some(label) = scene_entity.label.as_ref() {
+                // instances of the same prefab must not share labels
+                let label = match prefab_instance {
+                    Some(instance) => {
+                        format!("{}/{label}", NonZero::<u64>::from(instance.root_entity_id))
+                    }
+                    None => label.clone(),
+                };
+                self.world
+                    .set_entity_label(entity_id, CString::new(label).unwrap());
             }
-            CommandRef::LoadScene(command) => {
-                let scene_str = command.scene_json().unwrap();
-                if let Err(e) =
-                    self.load_scene(scene_str, cpu_data, gpu_data, component_registry, modules)
+
+            if let Some(scene_id) = &scene_entity.scene_id {
+                json_id_map.insert(scene_id, entity_id);
+            } else {
+                log::warn!(
+                    "Detected entity without a scene id. Other entities will not be able to reference it"
+                );
+            }
+        }
+
+        // reconstruct the entity <-> entity relationships
+        for (index, scene_entity) in scene_entities.entities.iter().enumerate() {
+            if let Some(parent_entity_id) = scene_entity
+                .parent_scene_id
+                .as_ref()
+                .and_then(|id| json_id_map.get(id.as_str()))
+            {
+                if let Some(entity_id) = scene_entity
+                    .scene_id
+                    .as_ref()
+                    .and_then(|id| json_id_map.get(id.as_str()))
                 {
-                    log::warn!("Unable to load scene JSON: {:?}", e);
+                    let entity = self.world.get_mut(*entity_id).unwrap();
+                    entity.parent_id = Some(*parent_entity_id);
+
+                    let parent_entity = self.world.get_mut(*parent_entity_id).unwrap();
+                    parent_entity.child_ids.push(*entity_id);
                 }
             }
-            CommandRef::RemoveComponents(command) => {
-                self.handle_remove_components(&command, cpu_data, gpu_data, component_registry);
-            }
-            CommandRef::SetEntityLabel(command) => {
-                self.handle_set_entity_label(&command);
-            }
-            CommandRef::SetParent(command) => {
-                let entity_id = NonZero::new(command.entity_id()).unwrap().into();
-                let parent_id = NonZero::new(command.parent_id()).map(|id| id.into());
-                self.handle_set_parent(
+
+            let Some(&entity_id) = entity_ids.get(index) else {
+                continue;
+            };
+
+            let overrides =
+                prefab_instance.and_then(|instance| instance.overrides(index, scene_entity));
+
+            for entity_ref in &scene_entity.entity_refs {
+                // the components of prefab overrides keep their entity references
+                if overrides
+                    .is_some_and(|overrides| overrides.contains_key(&entity_ref.component_id))
+                {
+                    continue;
+                }
+
+                // references to entities which are not in the scene are cleared
+                let referenced_id =
+                    if let Some(referenced_id) = json_id_map.get(entity_ref.scene_id.as_str()) {
+                        NonZero::<u64>::from(*referenced_id).get()
+                    } else {
+                        log::warn!(
+                            "Scene entity references unknown scene id {}",
+                            entity_ref.scene_id
+                        );
+                        0
+                    };
+
+                let ptr = self.entity_component_data_mut(
                     entity_id,
-                    parent_id,
-                    command.keep_world_space_transform(),
+                    entity_ref.component_id,
                     cpu_data,
+                    gpu_data,
                 );
+                if ptr.is_null() {
+                    continue;
+                }
+
+                unsafe {
+                    ptr.add(entity_ref.offset)
+                        .cast::<u64>()
+                        .write_unaligned(referenced_id);
+                }
             }
-            CommandRef::SetSystemEnabled(command) => {
-                self.system_graph
-                    .set_system_enabled(command.system_name().unwrap(), command.enabled());
-            }
-            CommandRef::Spawn(command) => {
-                self.handle_spawn(&command, cpu_data, gpu_data, component_registry);
-            }
-        });
+        }
 
-        update_world_transforms(&self.world, &self.archetypes, cpu_data);
+        entity_ids
     }
 
-    fn handle_spawn(
+    /// Spawns a scene entity. If `preallocated_entity_id` is provided, the
+    /// entity is spawned with that id, unless it was despawned already, in
+    /// which case `None` is returned.
+    // We need more arguments for this function
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_scene_entity(
         &mut self,
-        command: &AddComponents<'_>,
+        scene_entity: &SceneEntityInfo,
+        overrides: Option<&ComponentOverrides>,
+        preallocated_entity_id: Option<EntityId>,
         cpu_data: &mut CpuFrameData,
         gpu_data: &mut G,
         component_registry: &ComponentRegistry,
-    ) {
+        change_tick: u64,
+    ) -> Option<EntityId> {
+        // type-erase the component data
+        let mut components: BTreeMap<ComponentId, Box<[MaybeUninit<u8>]>> = scene_entity
+            .components
+            .iter()
+            .map(|component| {
+                let component_ref: ComponentRef<'_> = component.into();
+                let component_id = component_ref.component_id.unwrap();
+                let component_data = unsafe {
+                    from_raw_parts(
+                        component_ref.component_val.cast(),
+                        component_ref.component_size,
+                    )
+                };
+                (component_id, component_data.into())
+            })
+            .collect();
+
+        if let Some(overrides) = overrides {
+            components.extend(overrides.clone());
+        }
+
+        // look up any required bundled components
+        let required_components =
+            bundle_required_components(&components.keys().copied(), &self.gpu_component_bundles);
+
+        for component in required_components {
+            components.insert(component.id, component.default_value.clone());
+        }
+
+        let (component_ids, sparse_component_ids) =
+            partition_sparse_components(components.keys(), component_registry);
+
+        let components = &SceneEntityComponents {
+            component_ids,
+            components: &components,
+        };
+
         let archetype_key = ArchetypeKey {
-            component_ids: command.sorted_component_ids().into(),
+            component_ids: components.sorted_component_ids().into(),
         };
 
         self.allocate_archetype_storage_if_needed(
@@ -584,575 +1257,3055 @@ impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
         let buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
         let entity_index = buffer.len();
 
-        let despawned = !self.world.spawn_preallocated(
-            *command.entity_id,
-            EntityData::new(archetype_key, entity_index),
-        );
+        // spawn the entity
+        let entity_id = if let Some(entity_id) = preallocated_entity_id {
+            let despawned = !self
+                .world
+                .spawn_preallocated(entity_id, EntityData::new(archetype_key, entity_index));
 
-        if despawned {
-            return;
-        }
+            if despawned {
+                return None;
+            }
 
-        log::info!("Spawning entity {}", command.entity_id.id);
+            entity_id
+        } else {
+            self.world
+                .spawn(EntityData::new(archetype_key, entity_index))
+        };
 
         write_spawn_component_data(
-            *command.entity_id,
+            entity_id,
             entity_index,
-            command,
+            components,
             storage,
             cpu_data,
             gpu_data,
             component_registry,
+            change_tick,
         );
+
+        for component_id in sparse_component_ids {
+            self.sparse_components.insert(component_id, entity_id);
+        }
+
+        Some(entity_id)
     }
 
-    fn handle_despawn(
+    /// Spawns the entities of a table of a binary scene in one step, as they
+    /// have the same components. The ids of the entities are appended to
+    /// `entity_ids`.
+    fn spawn_scene_table(
         &mut self,
-        entity_id: EntityId,
+        table: &SceneTable,
+        entity_ids: &mut Vec<EntityId>,
         cpu_data: &mut CpuFrameData,
         gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        change_tick: u64,
     ) {
-        let Some(mut entity_data) = self.world.despawn(entity_id) else {
-            return;
-        };
+        // look up any required bundled components
+        let bundled_components: BTreeMap<ComponentId, Box<[MaybeUninit<u8>]>> =
+            bundle_required_components(
+                &table.columns.iter().map(|column| column.component_id),
+                &self.gpu_component_bundles,
+            )
+            .into_iter()
+            .map(|component| (component.id, component.default_value.clone()))
+            .collect();
 
-        let storage = self.archetypes.get(&entity_data.archetype_key).unwrap();
+        let mut all_component_ids = table
+            .columns
+            .iter()
+            .map(|column| column.component_id)
+            .chain(bundled_components.keys().copied())
+            .collect::<Vec<_>>();
+        all_component_ids.sort_unstable();
 
-        log::info!("Despawning entity {}", entity_id.id);
+        let (component_ids, sparse_component_ids) =
+            partition_sparse_components(&all_component_ids, component_registry);
 
-        // cpu
+        let archetype_key = ArchetypeKey {
+            component_ids: component_ids.clone(),
+        };
+
+        self.allocate_archetype_storage_if_needed(
+            &archetype_key,
+            cpu_data,
+            gpu_data,
+            component_registry,
+        );
+
+        let storage = &self.archetypes[&archetype_key];
+        cpu_data
+            .get_buffer_mut(storage.cpu.buffer_index)
+            .reserve(table.entities.len());
+
+        for row in 0..table.entities.len() {
+            let entity_index = cpu_data.get_buffer_mut(storage.cpu.buffer_index).len();
+            let entity_id = self
+                .world
+                .spawn(EntityData::new(archetype_key.clone(), entity_index));
+
+            write_spawn_component_data(
+                entity_id,
+                entity_index,
+                &SceneTableRow {
+                    component_ids: &component_ids,
+                    table,
+                    row,
+                    bundled_components: &bundled_components,
+                },
+                storage,
+                cpu_data,
+                gpu_data,
+                component_registry,
+                change_tick,
+            );
+
+            for component_id in &sparse_component_ids {
+                self.sparse_components.insert(*component_id, entity_id);
+            }
+
+            entity_ids.push(entity_id);
+        }
+    }
+
+    /// Deserializes the components of the entities of a scene file.
+    fn scene_file_entities(
+        &self,
+        scene_file: SceneFile,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+    ) -> Result<SceneEntities, Box<dyn Error + Send + Sync>> {
+        match scene_file {
+            SceneFile::Json(scene_json) => scene_json
+                .into_entities(
+                    &self.scene_migrations,
+                    |name| Self::component_fields(name, component_registry),
+                    |name, text| {
+                        Self::deserialize_component_json(name, text, component_registry, modules)
+                    },
+                )
+                .map(SceneEntities::from),
+            SceneFile::Binary(binary_scene) => binary_scene.into_entities(|name| {
+                let component_name = CString::new(name).ok()?;
+
+                component_registry
+                    .get_with_string_id(&component_name)
+                    .map(|(component_id, component_info)| (component_id, component_info.size))
+            }),
+        }
+    }
+
+    /// Converts a JSON scene to the binary scene format, which may be loaded
+    /// like JSON scenes without deserializing its components. The scene is
+    /// upgraded to the current version first.
+    pub fn convert_scene_to_binary(
+        &self,
+        scene_file: &str,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+    ) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let scene_entities = game_scene::parse_scene(
+            scene_file,
+            &self.scene_migrations,
+            |name| Self::component_fields(name, component_registry),
+            |name, text| Self::deserialize_component_json(name, text, component_registry, modules),
+        )?;
+
+        game_scene::write_binary_scene(&scene_entities, |component_id| {
+            let component_info = component_registry.get(&component_id)?;
+
+            Some((
+                component_info.name.to_str().ok()?,
+                component_info.fields.as_slice(),
+            ))
+        })
+    }
+
+    /// Returns the reflected fields of a component, by name.
+    fn component_fields<'a>(
+        component_name: &str,
+        component_registry: &'a ComponentRegistry,
+    ) -> Option<&'a [ComponentField]> {
+        let component_name = CString::new(component_name).ok()?;
+
+        component_registry
+            .get_with_string_id(&component_name)
+            .map(|(_, component_info)| component_info.fields.as_slice())
+    }
+
+    fn deserialize_component_json(
+        component_name: &str,
+        text: &str,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+    ) -> Result<ComponentData, Box<dyn Error + Send + Sync>> {
+        let c_str = CString::new(component_name)?;
+
+        let result = component_registry.get_with_string_id(&c_str);
+
+        if let Some((component_id, component_info)) = result {
+            if let EcsTypeInfo::Component(entity_component_info) = &component_info.ecs_type_info {
+                // create a buffer big enough for the data, zeroed so that padding is initialized
+                let mut dest_vec = vec![MaybeUninit::new(0); component_info.size];
+
+                // deserialize via the owning module
+                if let Err(error) = modules[entity_component_info.declaring_module_name.as_str()]
+                    .as_ref()
+                    .component_deserialize_json(
+                        component_info.name.as_c_str(),
+                        dest_vec.as_mut_slice(),
+                        text,
+                    )
+                {
+                    return Err(format!(
+                        "Component {} could not be deserialized: {:?}",
+                        component_name, error
+                    )
+                    .into());
+                }
+
+                Ok(ComponentData::new(component_id, dest_vec))
+            } else {
+                Err(format!("Trying to deserialize non-component {}", component_name).into())
+            }
+        } else {
+            Err(format!("Component {} not found", component_name).into())
+        }
+    }
+
+    fn load_texture_from_path(
+        asset_path: &str,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) -> TextureId {
+        cpu_data.get_resource_mut(component_registry, |gpu_interface: &mut GpuInterface| {
+            if let Some(texture) = gpu_interface
+                .texture_asset_manager
+                .get_texture_by_path(&asset_path.into())
+            {
+                texture.id()
+            } else {
+                let texture_id = gpu_interface
+                    .texture_asset_manager
+                    .register_next_texture_id();
+                let pending_texture = PendingTexture::new(texture_id, &asset_path.into(), false);
+                gpu_interface
+                    .texture_asset_manager
+                    .add_to_batched_textures(pending_texture);
+                texture_id
+            }
+        })
+    }
+
+    /// Update systems which may update asynchronously, in parallel with frame rendering
+    // We need more arguments for this function
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_async(
+        &mut self,
+        event_manager: &mut EventManager<P>,
+        input_manager: &InputManager,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+        callables: &Callables,
+        delta_time: f32,
+    ) {
+        self.frame_timer.update_frame(delta_time);
+
+        self.apply_prev_frame_changes(cpu_data, gpu_data);
+
+        let (tick_count, fixed_steps) =
+            self.update_frame_constants(cpu_data, component_registry, delta_time);
+        self.system_graph.begin_frame(tick_count);
+        self.removed_components.swap_buffers();
+
+        self.update_scene_loads(cpu_data, gpu_data, component_registry, modules);
+
+        // Apply state transitions requested during the previous frame, and run
+        // their exit and enter systems before any other systems.
+        let transitions = self.states.apply_transitions();
+
+        if !transitions.is_empty() {
+            {
+                let resources = &EcsSystemExecuteResources {
+                    cpu_data,
+                    gpu_data,
+                    event_manager,
+                    input_buffer: input_manager.binary_buffer(),
+                    world_delegate: &self.world.sync_delegate(),
+                    component_bundles: &self.gpu_component_bundles,
+                    component_registry,
+                    callables,
+                    states: &self.states,
+                    removed_components: &self.removed_components,
+                    sparse_components: &self.sparse_components,
+                    prefab_names: &self.prefab_names,
+                };
+
+                self.system_graph
+                    .execute_transitions(resources, &transitions)
+                    .await;
+            }
+
+            self.apply_current_frame_changes(
+                event_manager,
+                cpu_data,
+                gpu_data,
+                component_registry,
+                modules,
+            );
+        }
+
+        // process fixed cpu systems, applying changes after each timestep
+        for _ in 0..fixed_steps {
+            {
+                let resources = &EcsSystemExecuteResources {
+                    cpu_data,
+                    gpu_data,
+                    event_manager,
+                    input_buffer: input_manager.binary_buffer(),
+                    world_delegate: &self.world.sync_delegate(),
+                    component_bundles: &self.gpu_component_bundles,
+                    component_registry,
+                    callables,
+                    states: &self.states,
+                    removed_components: &self.removed_components,
+                    sparse_components: &self.sparse_components,
+                    prefab_names: &self.prefab_names,
+                };
+
+                self.system_graph.execute_fixed(resources).await;
+            }
+
+            self.apply_current_frame_changes(
+                event_manager,
+                cpu_data,
+                gpu_data,
+                component_registry,
+                modules,
+            );
+        }
+
+        // process cpu systems
+        {
+            let resources = &EcsSystemExecuteResources {
+                cpu_data,
+                gpu_data,
+                event_manager,
+                input_buffer: input_manager.binary_buffer(),
+                world_delegate: &self.world.sync_delegate(),
+                component_bundles: &self.gpu_component_bundles,
+                component_registry,
+                callables,
+                states: &self.states,
+                removed_components: &self.removed_components,
+                sparse_components: &self.sparse_components,
+                prefab_names: &self.prefab_names,
+            };
+
+            self.system_graph.execute_cpu(resources).await;
+        }
+
+        // Apply changes before running GPU systems, so that we don't change the number
+        // of objects by spawning/despawning after the GPU has already issued draw calls
+        self.apply_current_frame_changes(
+            event_manager,
+            cpu_data,
+            gpu_data,
+            component_registry,
+            modules,
+        );
+
+        // process exclusive systems, which apply their changes immediately
+        self.execute_exclusive_systems(
+            event_manager,
+            cpu_data,
+            gpu_data,
+            component_registry,
+            modules,
+            input_manager.binary_buffer(),
+            callables,
+        );
+
+        // process gpu systems
+
+        let resources = &EcsSystemExecuteResources {
+            cpu_data,
+            gpu_data,
+            event_manager,
+            input_buffer: input_manager.binary_buffer(),
+            world_delegate: &self.world.sync_delegate(),
+            component_bundles: &self.gpu_component_bundles,
+            component_registry,
+            callables,
+            states: &self.states,
+            removed_components: &self.removed_components,
+            sparse_components: &self.sparse_components,
+            prefab_names: &self.prefab_names,
+        };
+
+        self.system_graph.execute_gpu(resources).await;
+    }
+
+    /// Returns the new `tick_count`, and the number of fixed timesteps to
+    /// execute this frame.
+    fn update_frame_constants(
+        &mut self,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+        delta_time: f32,
+    ) -> (u64, u32) {
+        let fixed_delta_time = cpu_data
+            .get_resource(component_registry, |frame_config: &FrameConfig| {
+                frame_config.fixed_delta_time
+            });
+
+        let fixed_steps = self
+            .fixed_timestep
+            .update_frame(delta_time, fixed_delta_time);
+
+        cpu_data.get_resource_mut(component_registry, |constants: &mut FrameConstants| {
+            let prev_tick_count = constants.tick_count;
+
+            *constants = FrameConstants {
+                delta_time,
+                frame_rate: self.frame_timer.get_average_fps(),
+                tick_count: prev_tick_count + 1,
+                fixed_delta_time,
+                fixed_alpha: self.fixed_timestep.alpha(fixed_delta_time),
+            };
+
+            (constants.tick_count, fixed_steps)
+        })
+    }
+
+    #[allow(clippy::unused_self)]
+    fn apply_prev_frame_changes(&mut self, _cpu_data: &mut CpuFrameData, _gpu_data: &mut G) {
+        assert!(
+            !G::MULTI_BUFFERED,
+            "multi-buffered support temporarily removed, pending readdition of `entity_changes`"
+        );
+    }
+
+    fn apply_current_frame_changes(
+        &mut self,
+        event_manager: &mut EventManager<P>,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+    ) {
+        let change_tick = self.system_graph.increment_change_tick();
+
+        let start = P::Clock::now();
+
+        event_manager.drain_commands(|command| match command {
+            CommandRef::AddComponents(command) => {
+                self.handle_add_components(
+                    &command,
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    change_tick,
+                );
+            }
+            CommandRef::Despawn(command) => {
+                let entity_id = NonZero::new(command.entity_id()).unwrap().into();
+                self.handle_despawn(
+                    entity_id,
+                    command.recursive(),
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    change_tick,
+                );
+            }
+            CommandRef::InsertResource(command, data) => {
+                let component_id = ComponentId::new(command.component_id()).unwrap();
+                handle_insert_resource(component_id, data, cpu_data, component_registry);
+            }
+            CommandRef::LoadScene(command) => {
+                let scene_handle = SceneHandle(NonZero::new(command.handle()).unwrap());
+                let scene_data = command.scene_data().unwrap().bytes();
+                if let Err(e) = self.load_scene(
+                    scene_handle,
+                    scene_data,
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    modules,
+                ) {
+                    log::warn!("Unable to load scene: {:?}", e);
+                }
+            }
+            CommandRef::LoadSceneFromPath(command) => {
+                let scene_handle = SceneHandle(NonZero::new(command.handle()).unwrap());
+                self.load_scene_from_path(scene_handle, &command.asset_path().unwrap().into());
+            }
+            CommandRef::RegisterPrefab(command) => {
+                let name = command.name().unwrap();
+                if let Err(e) = self.register_prefab(
+                    name,
+                    command.prefab_json().unwrap(),
+                    cpu_data,
+                    component_registry,
+                    modules,
+                ) {
+                    log::warn!("Unable to register prefab {name}: {e:?}");
+                }
+            }
+            CommandRef::RegisterPrefabFromPath(command) => {
+                self.register_prefab_from_path(&command.asset_path().unwrap().into());
+            }
+            CommandRef::RegisterSceneMigration(command) => {
+                let from_version = command.from_version().unwrap();
+                let to_version = command.to_version().unwrap();
+                let component_name = command.component_name().unwrap();
+
+                // SAFETY: the command was written from a `SceneMigrationCallback`.
+                let migrate_fn = unsafe {
+                    std::mem::transmute::<usize, SceneMigrationCallback>(
+                        command.migrate_fn() as usize
+                    )
+                };
+                let user_data = command.user_data() as usize;
+
+                if let Err(e) = self.scene_migrations.register_component(
+                    from_version,
+                    to_version,
+                    component_name,
+                    move |component| migrate_component_json(component, migrate_fn, user_data),
+                ) {
+                    log::warn!(
+                        "Unable to register scene migration of {component_name} from {from_version}: {e}"
+                    );
+                }
+            }
+            CommandRef::RemoveComponents(command) => {
+                self.handle_remove_components(&command, cpu_data, gpu_data, component_registry);
+            }
+            CommandRef::RemoveResource(command) => {
+                let component_id = ComponentId::new(command.component_id()).unwrap();
+                if self.engine_resources.contains(&component_id) {
+                    log::warn!(
+                        "{:?}: engine resources may not be removed",
+                        component_registry[&component_id].name
+                    );
+                } else {
+                    handle_remove_resource(component_id, cpu_data, component_registry);
+                }
+            }
+            CommandRef::SetEntityLabel(command) => {
+                self.handle_set_entity_label(&command);
+            }
+            CommandRef::SetNextState(command) => {
+                let state_id = CString::new(command.state_id().unwrap()).unwrap();
+                self.states.set_next(&state_id, command.state());
+            }
+            CommandRef::SetParent(command) => {
+                let entity_id = NonZero::new(command.entity_id()).unwrap().into();
+                let parent_id = NonZero::new(command.parent_id()).map(|id| id.into());
+                self.handle_set_parent(
+                    entity_id,
+                    parent_id,
+                    command.keep_world_space_transform(),
+                    cpu_data,
+                    change_tick,
+                );
+            }
+            CommandRef::SetSystemEnabled(command) => {
+                self.system_graph
+                    .set_system_enabled(command.system_name().unwrap(), command.enabled());
+            }
+            CommandRef::Spawn(command) => {
+                self.handle_spawn(
+                    &command,
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    change_tick,
+                );
+            }
+            CommandRef::SpawnBatch(batch, command) => {
+                self.handle_spawn_batch(
+                    &batch,
+                    &command,
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    change_tick,
+                );
+            }
+            CommandRef::SpawnPrefab(prefab, command, overrides) => {
+                self.handle_spawn_prefab(
+                    prefab.name().unwrap(),
+                    PrefabInstance::new(&prefab, &command, &overrides),
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    change_tick,
+                );
+            }
+            CommandRef::UnloadScene(command) => {
+                let scene_handle = SceneHandle(NonZero::new(command.handle()).unwrap());
+                if !self.unload_scene(scene_handle, cpu_data, gpu_data, component_registry) {
+                    log::warn!("Unable to unload scene {scene_handle:?}, scene is not loaded");
+                }
+            }
+        });
+
+        let profiler = self.system_graph.profiler();
+        profiler.record::<P>(ScopeId::APPLY_COMMANDS, start);
+
+        profiler.scope::<P, _>(ScopeId::UPDATE_TRANSFORMS, || {
+            update_world_transforms(&self.world, &self.archetypes, cpu_data);
+        });
+    }
+
+    fn handle_spawn(
+        &mut self,
+        command: &AddComponents<'_>,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        change_tick: u64,
+    ) {
+        let (component_ids, sparse_component_ids) =
+            partition_sparse_components(command.sorted_component_ids(), component_registry);
+
+        let archetype_key = ArchetypeKey { component_ids };
+
+        self.allocate_archetype_storage_if_needed(
+            &archetype_key,
+            cpu_data,
+            gpu_data,
+            component_registry,
+        );
+
+        let storage = &self.archetypes[&archetype_key];
+        let buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
+        let entity_index = buffer.len();
+
+        let despawned = !self.world.spawn_preallocated(
+            *command.entity_id,
+            EntityData::new(archetype_key, entity_index),
+        );
+
+        if despawned {
+            return;
+        }
+
+        log::info!("Spawning entity {}", command.entity_id.id);
+
+        write_spawn_component_data(
+            *command.entity_id,
+            entity_index,
+            command,
+            storage,
+            cpu_data,
+            gpu_data,
+            component_registry,
+            change_tick,
+        );
+
+        for component_id in sparse_component_ids {
+            self.sparse_components
+                .insert(component_id, *command.entity_id);
+        }
+    }
+
+    /// Spawns all entities of a batch into the same archetype. The row of the
+    /// first spawned entity is written from the command data, and copied to the
+    /// following rows.
+    fn handle_spawn_batch(
+        &mut self,
+        batch: &SpawnBatch<'_>,
+        command: &AddComponents<'_>,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        change_tick: u64,
+    ) {
+        let entity_ids = batch.entity_ids().unwrap();
+
+        let (component_ids, sparse_component_ids) =
+            partition_sparse_components(command.sorted_component_ids(), component_registry);
+
+        let archetype_key = ArchetypeKey { component_ids };
+
+        self.allocate_archetype_storage_if_needed(
+            &archetype_key,
+            cpu_data,
+            gpu_data,
+            component_registry,
+        );
+
+        let storage = &self.archetypes[&archetype_key];
+        cpu_data
+            .get_buffer_mut(storage.cpu.buffer_index)
+            .reserve(entity_ids.len());
+
+        log::info!("Spawning batch of {} entities", entity_ids.len());
+
+        let mut template_index = None;
+
+        for entity_id in entity_ids {
+            let entity_id: EntityId = NonZero::new(entity_id).unwrap().into();
+            let entity_index = cpu_data.get_buffer_mut(storage.cpu.buffer_index).len();
+
+            let despawned = !self.world.spawn_preallocated(
+                entity_id,
+                EntityData::new(archetype_key.clone(), entity_index),
+            );
+
+            if despawned {
+                continue;
+            }
+
+            if let Some(template_index) = template_index {
+                copy_spawned_row(entity_id, template_index, storage, cpu_data, gpu_data);
+            } else {
+                write_spawn_component_data(
+                    entity_id,
+                    entity_index,
+                    command,
+                    storage,
+                    cpu_data,
+                    gpu_data,
+                    component_registry,
+                    change_tick,
+                );
+                template_index = Some(entity_index);
+            }
+
+            for component_id in &sparse_component_ids {
+                self.sparse_components.insert(*component_id, entity_id);
+            }
+        }
+    }
+
+    fn handle_spawn_prefab(
+        &mut self,
+        name: &str,
+        instance: PrefabInstance,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        change_tick: u64,
+    ) {
+        let Some(prefab_entities) = self.prefabs.get(name).cloned() else {
+            if let Some(pending) = self
+                .pending_prefab_loads
+                .iter_mut()
+                .find(|pending| pending.asset_path.to_string_lossy() == name)
+            {
+                // spawned once the prefab is read
+                pending.instances.push(instance);
+                return;
+            }
+
+            log::warn!("Unable to spawn prefab {name}, prefab is not registered");
+
+            // release the preallocated entity id
+            self.world.despawn(instance.root_entity_id);
+            return;
+        };
+
+        log::info!(
+            "Spawning prefab {name} with root entity {}",
+            instance.root_entity_id.id
+        );
+
+        self.spawn_scene_entities(
+            &prefab_entities,
+            Some(&instance),
+            cpu_data,
+            gpu_data,
+            component_registry,
+            change_tick,
+        );
+    }
+
+    /// Despawns an entity. If `recursive` is `false`, its children are
+    /// detached, becoming root entities which keep their world space transform.
+    fn handle_despawn(
+        &mut self,
+        entity_id: EntityId,
+        recursive: bool,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        change_tick: u64,
+    ) {
+        if !recursive {
+            let child_ids = self
+                .world
+                .get(entity_id)
+                .map(|entity_data| entity_data.child_ids.clone())
+                .unwrap_or_default();
+
+            for child_id in child_ids {
+                let Some(child_data) = self.world.get(child_id) else {
+                    continue;
+                };
+
+                let keep_world_transform = child_data
+                    .archetype_key
+                    .component_ids
+                    .contains(&Transform::id());
+
+                self.handle_set_parent(child_id, None, keep_world_transform, cpu_data, change_tick);
+            }
+        }
+
+        let Some(mut entity_data) = self.world.despawn(entity_id) else {
+            return;
+        };
+
+        for component_id in self.sparse_components.remove_entity(entity_id) {
+            self.removed_components.write(&component_id, entity_id, &[]);
+        }
+
+        // remove the entity from its scene instance (if applicable)
+        for scene_entity_ids in self.scene_instances.values_mut() {
+            if scene_entity_ids.remove(&entity_id) {
+                break;
+            }
+        }
+
+        // remove the entity from its parent's child list (if applicable)
+        if let Some(parent_id) = entity_data.parent_id {
+            if let Some(parent_data) = self.world.get_mut(parent_id) {
+                parent_data.child_ids.retain(|id| *id != entity_id);
+            }
+        }
+
+        let storage = self.archetypes.get(&entity_data.archetype_key).unwrap();
+
+        log::info!("Despawning entity {}", entity_id.id);
+
+        // cpu
 
         let mut buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
+
+        write_removed_components(
+            &mut self.removed_components,
+            entity_id,
+            buffer.get_ptr(entity_data.archetype_index),
+            &storage.cpu.components,
+            component_registry,
+        );
+
         buffer.swap_remove(entity_data.archetype_index);
 
-        // reassign world entity index for swapped entity data
-        if let Some(entity_id) = unsafe {
-            buffer.get_mut_with_offset_as::<EntityId>(
-                entity_data.archetype_index,
-                storage.cpu.entity_id_offset.unwrap(),
+        // reassign world entity index for swapped entity data
+        if let Some(entity_id) = unsafe {
+            buffer.get_mut_with_offset_as::<EntityId>(
+                entity_data.archetype_index,
+                storage.cpu.entity_id_offset.unwrap(),
+            )
+        } {
+            self.world[*entity_id].archetype_index = entity_data.archetype_index;
+        }
+
+        // gpu
+
+        for storage_gpu in &storage.gpu {
+            let mut buffer =
+                gpu_data.get_buffer_mut(cpu_data, storage_gpu.buffer_index, storage_gpu.partition);
+
+            write_removed_components(
+                &mut self.removed_components,
+                entity_id,
+                buffer.get_ptr(entity_data.archetype_index),
+                &storage_gpu.components,
+                component_registry,
+            );
+
+            buffer.swap_remove(entity_data.archetype_index);
+        }
+
+        for child_id in entity_data.child_ids.drain(..) {
+            self.handle_despawn(
+                child_id,
+                true,
+                cpu_data,
+                gpu_data,
+                component_registry,
+                change_tick,
+            );
+        }
+    }
+
+    fn handle_add_components(
+        &mut self,
+        command: &AddComponents<'_>,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        change_tick: u64,
+    ) {
+        log::info!("Adding components to entity {}", command.entity_id.id);
+
+        let Some(entity_data) = self.world.get(*command.entity_id) else {
+            log::warn!(
+                "... entity id {} with lifecycle {} does not exist",
+                command.entity_id.id,
+                command.entity_id.lifecycle
+            );
+            return;
+        };
+
+        let (component_ids, sparse_component_ids) =
+            partition_sparse_components(command.sorted_component_ids(), component_registry);
+
+        // Add new component ids to the existing archetype key.
+        let mut archetype_key = entity_data.archetype_key.clone();
+        archetype_key.component_ids.extend(component_ids);
+        archetype_key.component_ids.sort_unstable();
+        archetype_key.component_ids.dedup();
+
+        // Sparse components do not change the archetype.
+        for &component_id in &sparse_component_ids {
+            self.sparse_components
+                .insert(component_id, *command.entity_id);
+        }
+
+        if archetype_key == entity_data.archetype_key {
+            if sparse_component_ids.is_empty() {
+                log::warn!("... archetypes do not differ, likely no new components were added");
+            }
+            return;
+        }
+
+        self.allocate_archetype_storage_if_needed(
+            &archetype_key,
+            cpu_data,
+            gpu_data,
+            component_registry,
+        );
+
+        let entity_data = &mut self.world[*command.entity_id];
+
+        let prev_storage = &self.archetypes[&entity_data.archetype_key];
+        let storage = &self.archetypes[&archetype_key];
+        let (mut prev_buffer, mut buffer) =
+            cpu_data.get_buffer_pair_mut((prev_storage.cpu.buffer_index, storage.cpu.buffer_index));
+
+        let prev_entry_ptr = prev_buffer.get_ptr(entity_data.archetype_index);
+        let entry = buffer.grow();
+
+        // Copy change ticks, newly added components are marked as added.
+        storage.write_component_ticks(entry, |component_id| {
+            prev_storage
+                .get_component_ticks_offset(&component_id)
+                .map_or(ComponentTicks::new(change_tick), |offset| unsafe {
+                    prev_entry_ptr.add(offset).cast::<ComponentTicks>().read()
+                })
+        });
+
+        // Copy data from old archetype to new archetype.
+        write_cpu_component_data(
+            *command.entity_id,
+            storage,
+            component_registry,
+            entry,
+            |component_id| {
+                // Prefer checking previous storage first, so that we don't
+                // overwrite existing component data.
+                if let Some(component_info) = prev_storage
+                    .cpu
+                    .components
+                    .iter()
+                    .find(|c| c.component_id == component_id)
+                {
+                    unsafe {
+                        let ptr = prev_entry_ptr.add(component_info.offset);
+                        let size = component_registry[&component_id].size;
+
+                        from_raw_parts(ptr, size)
+                    }
+                } else {
+                    command.component_data(component_id).unwrap()
+                }
+            },
+        );
+
+        assert!(storage.gpu.is_empty(), "gpu data currently unsupported");
+
+        // Remove old archetype buffer entry.
+        let prev_archetype_index = entity_data.archetype_index;
+        prev_buffer.swap_remove(prev_archetype_index);
+        let swapped_entity_id = unsafe {
+            prev_buffer
+                .get_mut_with_offset_as::<EntityId>(
+                    prev_archetype_index,
+                    prev_storage.cpu.entity_id_offset.unwrap(),
+                )
+                .copied()
+        };
+
+        // Update entity data stored on the `World`.
+        entity_data.archetype_key = archetype_key;
+        entity_data.archetype_index = buffer.len() - 1;
+
+        initialize_local_to_world_if_needed(cpu_data, entity_data.archetype_index, storage);
+
+        // reassign world entity index for swapped entity data
+        if let Some(swapped_entity_id) = swapped_entity_id {
+            self.world[swapped_entity_id].archetype_index = prev_archetype_index;
+        }
+    }
+
+    fn handle_remove_components(
+        &mut self,
+        command: &RemoveComponents<'_>,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+    ) {
+        let entity_id: EntityId = NonZero::new(command.entity_id()).unwrap().into();
+
+        log::info!("Removing components from entity {}", entity_id.id);
+
+        let Some(entity_data) = self.world.get(entity_id) else {
+            log::warn!(
+                "... entity id {} with lifecycle {} does not exist",
+                entity_id.id,
+                entity_id.lifecycle
+            );
+            return;
+        };
+
+        // Sparse components do not change the archetype.
+        let mut sparse_removed = false;
+        for component_id in command
+            .component_ids()
+            .unwrap()
+            .iter()
+            .filter_map(ComponentId::new)
+        {
+            if self.sparse_components.remove(&component_id, entity_id) {
+                self.removed_components.write(&component_id, entity_id, &[]);
+                sparse_removed = true;
+            }
+        }
+
+        // Remove component ids from the existing archetype key.
+        let mut archetype_key = entity_data.archetype_key.clone();
+        archetype_key.component_ids.retain(|cid| {
+            command
+                .component_ids()
+                .unwrap()
+                .iter()
+                .all(|removed_id| cid.get() != removed_id)
+        });
+
+        if archetype_key == entity_data.archetype_key {
+            if !sparse_removed {
+                log::warn!("... archetypes do not differ, likely no components were removed");
+            }
+            return;
+        }
+
+        self.allocate_archetype_storage_if_needed(
+            &archetype_key,
+            cpu_data,
+            gpu_data,
+            component_registry,
+        );
+
+        let entity_data = &mut self.world[entity_id];
+
+        let prev_storage = &self.archetypes[&entity_data.archetype_key];
+        let storage = &self.archetypes[&archetype_key];
+        let (mut prev_buffer, mut buffer) =
+            cpu_data.get_buffer_pair_mut((prev_storage.cpu.buffer_index, storage.cpu.buffer_index));
+
+        let prev_entry_ptr = prev_buffer.get_ptr(entity_data.archetype_index);
+        let entry = buffer.grow();
+
+        // Copy change ticks of the remaining components.
+        storage.write_component_ticks(entry, |component_id| {
+            let prev_offset = prev_storage
+                .get_component_ticks_offset(&component_id)
+                .unwrap();
+
+            unsafe {
+                prev_entry_ptr
+                    .add(prev_offset)
+                    .cast::<ComponentTicks>()
+                    .read()
+            }
+        });
+
+        // Copy data from old archetype to new archetype.
+        write_cpu_component_data(
+            entity_id,
+            storage,
+            component_registry,
+            entry,
+            |component_id| {
+                let prev_offset = prev_storage
+                    .cpu
+                    .components
+                    .iter()
+                    .find(|c| c.component_id == component_id)
+                    .unwrap()
+                    .offset;
+
+                unsafe {
+                    let ptr = prev_entry_ptr.add(prev_offset);
+                    let size = component_registry[&component_id].size;
+
+                    from_raw_parts(ptr, size)
+                }
+            },
+        );
+
+        assert!(storage.gpu.is_empty(), "gpu data currently unsupported");
+
+        // Record the data of removed components.
+        write_removed_components(
+            &mut self.removed_components,
+            entity_id,
+            prev_entry_ptr,
+            prev_storage.cpu.components.iter().filter(|component| {
+                !archetype_key
+                    .component_ids
+                    .contains(&component.component_id)
+            }),
+            component_registry,
+        );
+
+        // Remove old archetype buffer entry.
+        let prev_archetype_index = entity_data.archetype_index;
+        prev_buffer.swap_remove(prev_archetype_index);
+        let swapped_entity_id = unsafe {
+            prev_buffer
+                .get_mut_with_offset_as::<EntityId>(
+                    prev_archetype_index,
+                    prev_storage.cpu.entity_id_offset.unwrap(),
+                )
+                .copied()
+        };
+
+        // Update entity data stored on the `World`.
+        entity_data.archetype_key = archetype_key;
+        entity_data.archetype_index = buffer.len() - 1;
+
+        // reassign world entity index for swapped entity data
+        if let Some(swapped_entity_id) = swapped_entity_id {
+            self.world[swapped_entity_id].archetype_index = prev_archetype_index;
+        }
+
+        // The new archetype has no GPU components, so the entity's GPU entries
+        // are removed.
+        for storage_gpu in &prev_storage.gpu {
+            let mut buffer =
+                gpu_data.get_buffer_mut(cpu_data, storage_gpu.buffer_index, storage_gpu.partition);
+
+            write_removed_components(
+                &mut self.removed_components,
+                entity_id,
+                buffer.get_ptr(prev_archetype_index),
+                &storage_gpu.components,
+                component_registry,
+            );
+
+            buffer.swap_remove(prev_archetype_index);
+        }
+    }
+
+    /// Allocates a new storage for the archetype, if none exists.
+    fn allocate_archetype_storage_if_needed(
+        &mut self,
+        archetype_key: &ArchetypeKey,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+    ) {
+        if self.archetypes.contains_key(archetype_key) {
+            return;
+        }
+
+        let storage = ArchetypeStorage::new(
+            &archetype_key.component_ids,
+            component_registry,
+            &self.gpu_component_groupings,
+            &mut self.gpu_single_buffer_components,
+            cpu_data,
+            gpu_data,
+        );
+
+        // add archetype as input to relevant systems
+        self.system_graph
+            .add_archetype_input(archetype_key, &storage);
+
+        self.archetypes.insert(archetype_key.clone(), storage);
+    }
+
+    fn handle_set_entity_label(&mut self, command: &SetEntityLabel<'_>) {
+        let entity_id = NonZero::new(command.entity_id()).unwrap().into();
+
+        if let Some(label) = command.label() {
+            let label = CString::new(label).unwrap();
+            self.world.set_entity_label(entity_id, label);
+        } else {
+            self.world.remove_entity_label(entity_id);
+        }
+    }
+
+    fn handle_set_parent(
+        &mut self,
+        entity_id: EntityId,
+        parent_id: Option<EntityId>,
+        keep_world_transform: bool,
+        cpu_data: &mut CpuFrameData,
+        change_tick: u64,
+    ) {
+        let Some(entity_data) = self.world.get(entity_id) else {
+            // unable to find the entity to update
+            return;
+        };
+
+        if entity_data.parent_id == parent_id {
+            // parentage isn't actually changing, ignore.
+            return;
+        }
+
+        if let Some(parent_id) = parent_id {
+            if self.world.get(parent_id).is_none() {
+                // unable to find the parent entity
+                log::warn!("Unable to set parent. Parent Id {parent_id:?} not found");
+                return;
+            }
+        }
+
+        log::info!("Setting parent of {entity_id:?} to {parent_id:?}...");
+
+        // remove the entity from its current parent's child list (if applicable)
+        if let Some(prev_parent_id) = entity_data.parent_id {
+            if let Some(parent_data) = self.world.get_mut(prev_parent_id) {
+                parent_data.child_ids.retain(|id| *id != entity_id);
+            }
+        }
+
+        // add to new parent's child list (if not root)
+        if let Some(parent_id) = parent_id {
+            if let Some(parent_data) = self.world.get_mut(parent_id) {
+                if parent_data.child_ids.contains(&entity_id) {
+                    log::warn!("Child cannot be added more than once");
+                    return;
+                }
+                parent_data.child_ids.push(entity_id);
+            }
+        }
+
+        // update the parent field
+        self.world.get_mut(entity_id).unwrap().parent_id = parent_id;
+
+        // if the re-parent is in-place, we need to update the Transform component based on the new parentage
+        if keep_world_transform {
+            // calculate world->new_parent matrix
+            let mut mat_parent_world_to_local = Mat4::IDENTITY;
+            if let Some(new_parent_id) = parent_id {
+                let (parent_entity_data, parent_archetype_storage, parent_data_buffer) =
+                    self.get_buffer_for_entity(new_parent_id, cpu_data);
+                if let Some(data_parent_local_to_world) = unsafe {
+                    get_data_from_buffer::<LocalToWorld>(
+                        &parent_data_buffer,
+                        parent_entity_data.archetype_index,
+                        parent_archetype_storage,
+                    )
+                } {
+                    mat_parent_world_to_local = data_parent_local_to_world.inverse();
+                }
+            }
+
+            // get entity->world matrix
+            let mut entity_buffer = self.get_buffer_for_entity(entity_id, cpu_data);
+
+            let data_entity_local_to_world = unsafe {
+                get_data_from_buffer::<LocalToWorld>(
+                    &entity_buffer.2,
+                    entity_buffer.0.archetype_index,
+                    entity_buffer.1,
+                )
+            }
+            .unwrap();
+
+            // calculate entity->new_parent matrix
+            let new_local_matrix = mat_parent_world_to_local.mul_mat4(data_entity_local_to_world);
+            let (scale, rotation, translation) = new_local_matrix.to_scale_rotation_translation();
+
+            // mark the Transform as changed
+            let ticks_offset = entity_buffer
+                .1
+                .get_component_ticks_offset(&Transform::id())
+                .unwrap();
+            let ticks = unsafe {
+                entity_buffer.2.get_mut_with_offset_as::<ComponentTicks>(
+                    entity_buffer.0.archetype_index,
+                    ticks_offset,
+                )
+            }
+            .unwrap();
+            ticks.changed = change_tick;
+
+            // write back to the Transform component
+            let transform_to_update = unsafe {
+                get_data_from_buffer_mut::<Transform>(
+                    &mut entity_buffer.2,
+                    entity_buffer.0.archetype_index,
+                    entity_buffer.1,
+                )
+            }
+            .unwrap();
+            let axis_angle = rotation.to_axis_angle();
+            transform_to_update.position = translation.into();
+            transform_to_update.scale = scale.truncate().into();
+            transform_to_update.rotation = axis_angle.0.z.signum() * axis_angle.1;
+        }
+    }
+
+    /// Returns a pointer to the specified component data for the specified entity.
+    /// If the entity + component pair does not exist, returns a null pointer.
+    pub fn entity_component_data_mut(
+        &self,
+        entity_id: EntityId,
+        component_id: ComponentId,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+    ) -> *mut MaybeUninit<u8> {
+        let Some(entity_data) = self.world.get(entity_id) else {
+            return null_mut();
+        };
+
+        let Some(storage) = self.archetypes.get(&entity_data.archetype_key) else {
+            return null_mut();
+        };
+
+        if let Some(found_component) = storage
+            .cpu
+            .components
+            .iter()
+            .find(|component_info| component_info.component_id == component_id)
+        {
+            return unsafe {
+                cpu_data
+                    .get_buffer_mut(storage.cpu.buffer_index)
+                    .get_mut_ptr(entity_data.archetype_index)
+                    .add(found_component.offset)
+            };
+        }
+
+        if let Some((gpu_storage, found_component)) = storage.gpu.iter().find_map(|gpu_storage| {
+            gpu_storage
+                .components
+                .iter()
+                .find(|component_info| component_info.component_id == component_id)
+                .map(|info| (gpu_storage, info))
+        }) {
+            return unsafe {
+                gpu_data
+                    .get_buffer_mut(cpu_data, gpu_storage.buffer_index, gpu_storage.partition)
+                    .get_mut_ptr(entity_data.archetype_index)
+                    .add(found_component.offset)
+            };
+        }
+
+        null_mut()
+    }
+
+    /// Returns a tuple containing the buffer for an entity along with the structs to lookup
+    /// component specific data
+    /// (`entity_data`, `archetype_storage`, `AtomicRef<Buffer>`)
+    fn get_buffer_for_entity<'a>(
+        &self,
+        entity_id: EntityId,
+        cpu_data: &'a mut CpuFrameData,
+    ) -> (&EntityData, &ArchetypeStorage, CpuDataBufferRefMut<'a>) {
+        let entity_data = self.world.get(entity_id).unwrap();
+        let storage_map_parent = self.archetypes.get(&entity_data.archetype_key).unwrap();
+
+        (
+            entity_data,
+            storage_map_parent,
+            cpu_data.get_buffer_mut(storage_map_parent.cpu.buffer_index),
+        )
+    }
+}
+
+/// Returns the buffer index of a resource which may be inserted or removed at
+/// runtime, or `None` if the component is not such a resource.
+fn removable_resource_buffer_index(
+    component_id: ComponentId,
+    component_registry: &ComponentRegistry,
+) -> Option<usize> {
+    let component_info = component_registry.get(&component_id)?;
+
+    let EcsTypeInfo::Resource(resource_info) = &component_info.ecs_type_info else {
+        log::warn!("{:?} is not a resource", component_info.name);
+        return None;
+    };
+
+    if component_info.gpu_compatible {
+        log::warn!(
+            "{:?}: GPU resources may not be inserted or removed",
+            component_info.name
+        );
+        return None;
+    }
+
+    Some(resource_info.buffer_index)
+}
+
+/// Inserts a resource, replacing its value if it is already present.
+fn handle_insert_resource(
+    component_id: ComponentId,
+    data: &[MaybeUninit<u8>],
+    cpu_data: &mut CpuFrameData,
+    component_registry: &ComponentRegistry,
+) {
+    let Some(buffer_index) = removable_resource_buffer_index(component_id, component_registry)
+    else {
+        return;
+    };
+
+    let size = component_registry[&component_id].size;
+    if data.len() != size {
+        log::warn!(
+            "{:?}: inserted resource is {} bytes, expected {size}",
+            component_registry[&component_id].name,
+            data.len(),
+        );
+        return;
+    }
+
+    let mut buffer = cpu_data.get_buffer_mut(buffer_index);
+
+    // resource buffers hold a single entry while the resource is present
+    if !buffer.is_empty() {
+        buffer.swap_remove(0);
+    }
+
+    buffer.grow()[..size].copy_from_slice(data);
+}
+
+/// Removes a resource. The resource's buffer is left empty until the resource
+/// is inserted again.
+fn handle_remove_resource(
+    component_id: ComponentId,
+    cpu_data: &mut CpuFrameData,
+    component_registry: &ComponentRegistry,
+) {
+    let Some(buffer_index) = removable_resource_buffer_index(component_id, component_registry)
+    else {
+        return;
+    };
+
+    let mut buffer = cpu_data.get_buffer_mut(buffer_index);
+
+    if !buffer.is_empty() {
+        buffer.swap_remove(0);
+    }
+}
+
+/// Migrates the JSON of a component with a migration registered by a module.
+fn migrate_component_json(
+    component: &mut JsonValue,
+    migrate_fn: SceneMigrationCallback,
+    user_data: usize,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    unsafe extern "C" fn write_output(output: *mut c_void, text: *const c_char) {
+        unsafe {
+            *output.cast::<String>() = CStr::from_ptr(text).to_string_lossy().into_owned();
+        }
+    }
+
+    let component_json = CString::new(component.dump())?;
+    let mut output = String::new();
+
+    let success = unsafe {
+        migrate_fn(
+            user_data as *const c_void,
+            component_json.as_ptr(),
+            (&raw mut output).cast(),
+            write_output,
+        )
+    };
+
+    if !success {
+        return Err(output.into());
+    }
+
+    *component = json::parse(&output)?;
+
+    Ok(())
+}
+
+/// Parses the bytes of a scene file read by `read_scene_file()`, and upgrades
+/// JSON scenes to the current version. Called on the thread which read the
+/// file.
+fn parse_scene_bytes(bytes: io::Result<Vec<u8>>, migrations: &SceneMigrations) -> SceneReadResult {
+    let mut scene_file = game_scene::parse_scene_file(bytes?)?;
+
+    if let SceneFile::Json(scene_json) = &mut scene_file {
+        scene_json.upgrade(migrations)?;
+    }
+
+    Ok(scene_file)
+}
+
+/// Splits component ids into those stored in archetypes, and sparse component
+/// ids.
+fn partition_sparse_components<'a>(
+    component_ids: impl IntoIterator<Item = &'a ComponentId>,
+    component_registry: &ComponentRegistry,
+) -> (Vec<ComponentId>, Vec<ComponentId>) {
+    component_ids
+        .into_iter()
+        .copied()
+        .partition(|component_id| !component_registry.is_sparse(component_id))
+}
+
+/// Writes component data from a new spawn event into archetype storage buffers.
+/// All components are marked as added at `change_tick`.
+// We need more arguments for this function
+#[allow(clippy::too_many_arguments)]
+fn write_spawn_component_data<G: Gpu, T: SpawnComponentData>(
+    entity_id: EntityId,
+    entity_index: usize,
+    components: &T,
+    storage: &ArchetypeStorage,
+    cpu_data: &mut CpuFrameData,
+    gpu_data: &mut G,
+    component_registry: &ComponentRegistry,
+    change_tick: u64,
+) {
+    let mut buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
+    let component_entry_bytes = buffer.grow();
+
+    assert_eq!(
+        component_entry_bytes
+            .as_ptr()
+            .align_offset(storage.cpu.align),
+        0
+    );
+
+    // write cpu component data
+
+    storage.write_component_ticks(component_entry_bytes, |_| ComponentTicks::new(change_tick));
+
+    write_cpu_component_data(
+        entity_id,
+        storage,
+        component_registry,
+        component_entry_bytes,
+        |component_id| components.component_data(component_id).unwrap(),
+    );
+
+    // initialize/sync component data if required
+
+    initialize_local_to_world_if_needed(cpu_data, entity_index, storage);
+
+    // write gpu component data
+
+    for storage_gpu in &storage.gpu {
+        let mut buffer =
+            gpu_data.get_buffer_mut(cpu_data, storage_gpu.buffer_index, storage_gpu.partition);
+        let buffer = buffer.grow();
+
+        for component_offset_info in &storage_gpu.components {
+            let data = components
+                .component_data(component_offset_info.component_id)
+                .unwrap();
+            buffer[component_offset_info.offset..component_offset_info.offset + data.len()]
+                .copy_from_slice(data);
+        }
+    }
+}
+
+/// Appends a copy of the row at `template_index` for `entity_id`, including
+/// the GPU rows of the entity.
+fn copy_spawned_row<G: Gpu>(
+    entity_id: EntityId,
+    template_index: usize,
+    storage: &ArchetypeStorage,
+    cpu_data: &mut CpuFrameData,
+    gpu_data: &mut G,
+) {
+    let mut buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
+    let entity_index = buffer.len();
+    buffer.grow();
+
+    unsafe {
+        ptr::copy_nonoverlapping(
+            buffer.get_ptr(template_index),
+            buffer.get_mut_ptr(entity_index),
+            storage.cpu.stride,
+        );
+
+        if let Some(entity_id_offset) = storage.cpu.entity_id_offset {
+            *buffer
+                .get_mut_with_offset_as::<u64>(entity_index, entity_id_offset)
+                .unwrap() = NonZero::from(entity_id).get();
+        }
+    }
+
+    for storage_gpu in &storage.gpu {
+        let mut buffer =
+            gpu_data.get_buffer_mut(cpu_data, storage_gpu.buffer_index, storage_gpu.partition);
+        let entity_index = buffer.len();
+        buffer.grow();
+
+        unsafe {
+            ptr::copy_nonoverlapping(
+                buffer.get_ptr(template_index),
+                buffer.get_mut_ptr(entity_index),
+                storage_gpu.stride,
+            );
+        }
+    }
+}
+
+/// Records the data of `components` of a buffer entry, for the component types
+/// registered in `removed_components`.
+fn write_removed_components<'a, P: Platform>(
+    removed_components: &mut RemovedComponentStorage<P>,
+    entity_id: EntityId,
+    entry_ptr: *const MaybeUninit<u8>,
+    components: impl IntoIterator<Item = &'a ComponentOffsetInfo>,
+    component_registry: &ComponentRegistry,
+) {
+    for component in components {
+        if removed_components.is_registered(&component.component_id) {
+            let data = unsafe {
+                from_raw_parts(
+                    entry_ptr.add(component.offset).cast::<u8>(),
+                    component_registry[&component.component_id].size,
+                )
+            };
+
+            removed_components.write(&component.component_id, entity_id, data);
+        }
+    }
+}
+
+fn write_cpu_component_data<'a, F>(
+    entity_id: EntityId,
+    storage: &ArchetypeStorage,
+    component_registry: &ComponentRegistry,
+    mut buffer_data: &mut [MaybeUninit<u8>],
+    mut component_data: F,
+) where
+    F: FnMut(ComponentId) -> &'a [MaybeUninit<u8>],
+{
+    let mut write_offset_check = 0;
+    for component_offset_info in &storage.cpu.components {
+        debug_assert_eq!(write_offset_check, component_offset_info.offset);
+
+        if component_offset_info.component_id == void_public::EntityId::id() {
+            // EntityId
+            let size = size_of::<void_public::EntityId>();
+            buffer_data[..size].copy_from_slice(
+                &NonZero::<u64>::from(entity_id)
+                    .get()
+                    .to_ne_bytes()
+                    .map(MaybeUninit::new),
+            );
+            buffer_data = &mut buffer_data[size..];
+            write_offset_check += size;
+        } else {
+            let data = component_data(component_offset_info.component_id);
+            let component_info = component_registry
+                .get(&component_offset_info.component_id)
+                .unwrap();
+
+            if data.len() != component_info.size {
+                log::error!(
+                    "spawn: data for component {:?} has size {}, does not match expected size {}",
+                    component_info.name,
+                    data.len(),
+                    component_info.size
+                );
+                panic!();
+            }
+
+            buffer_data[..data.len()].copy_from_slice(data);
+            buffer_data = &mut buffer_data[data.len()..];
+            write_offset_check += data.len();
+        }
+    }
+}
+
+/// Initialize the `LocalToWorld` component based on `Transform` data, if
+/// needed.
+fn initialize_local_to_world_if_needed(
+    cpu_data: &mut CpuFrameData,
+    entity_index: usize,
+    storage: &ArchetypeStorage,
+) {
+    let mut buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
+
+    let Some(transform_state) =
+        (unsafe { get_data_from_buffer::<Transform>(&buffer, entity_index, storage) })
+    else {
+        return;
+    };
+
+    // create the initial matrix
+    let initial_matrix = Mat4::from_scale_rotation_translation(
+        transform_state.scale.extend(1.),
+        Quat::from_rotation_z(transform_state.rotation),
+        *transform_state.position,
+    );
+
+    // write it back to the LocalToWorld component
+    let local_to_world_state =
+        unsafe { get_data_from_buffer_mut::<LocalToWorld>(&mut buffer, entity_index, storage) }
+            .expect("LocalToWorld is expected if Transform is present");
+    *local_to_world_state = initial_matrix.into();
+}
+
+unsafe fn get_data_from_buffer<'a, T>(
+    data_buffer: &'a CpuDataBufferRefMut<'a>,
+    entity_index: usize,
+    archetype_storage: &ArchetypeStorage,
+) -> Option<&'a T>
+where
+    T: Component,
+{
+    let data_offset = archetype_storage.get_component_byte_offset(&T::id())?;
+    unsafe { data_buffer.get_with_offset_as::<T>(entity_index, data_offset) }
+}
+
+unsafe fn get_data_from_buffer_mut<'a, T>(
+    data_buffer: &'a mut CpuDataBufferRefMut<'a>,
+    entity_index: usize,
+    archetype_storage: &ArchetypeStorage,
+) -> Option<&'a mut T>
+where
+    T: Component,
+{
+    let data_offset = archetype_storage.get_component_byte_offset(&T::id())?;
+    unsafe { data_buffer.get_mut_with_offset_as::<T>(entity_index, data_offset) }
+}
+
+#[derive(Debug, Default)]
+struct FrameTimer {
+    accumulated_time: f32,
+    num_frames: u32,
+    average_frame_time: f32,
+}
+
+impl FrameTimer {
+    pub fn update_frame(&mut self, delta_time: f32) {
+        self.accumulated_time += delta_time;
+        self.num_frames += 1;
+        if self.accumulated_time >= 1.0 && self.num_frames >= 120 {
+            self.average_frame_time = self.accumulated_time / self.num_frames as f32;
+            self.accumulated_time = 0.;
+            self.num_frames = 0;
+        }
+    }
+
+    pub fn get_average_frame_time(&self) -> f32 {
+        self.average_frame_time
+    }
+
+    pub fn get_average_fps(&self) -> f32 {
+        if self.get_average_frame_time() == 0.0 {
+            0.
+        } else {
+            1. / self.get_average_frame_time()
+        }
+    }
+}
+
+/// Accumulates frame time, which is consumed in fixed timesteps.
+#[derive(Debug, Default)]
+struct FixedTimestep {
+    accumulated_time: f32,
+    /// Set once an invalid `fixed_delta_time` has been reported, until it is
+    /// valid again.
+    invalid_reported: bool,
+}
+
+impl FixedTimestep {
+    /// Returns the number of fixed timesteps to execute this frame. No fixed
+    /// timesteps execute while `fixed_delta_time` is not positive.
+    pub fn update_frame(&mut self, delta_time: f32, fixed_delta_time: f32) -> u32 {
+        if fixed_delta_time > 0. {
+            self.invalid_reported = false;
+        } else {
+            if !self.invalid_reported {
+                log::warn!("fixed_delta_time is set to {fixed_delta_time}, skipping fixed systems");
+                self.invalid_reported = true;
+            }
+
+            return 0;
+        }
+
+        self.accumulated_time += delta_time;
+
+        let steps = (self.accumulated_time / fixed_delta_time).floor();
+        self.accumulated_time = (self.accumulated_time - steps * fixed_delta_time).max(0.);
+
+        steps as u32
+    }
+
+    /// Returns the fraction of a fixed timestep which has accumulated since the
+    /// last fixed timestep.
+    pub fn alpha(&self, fixed_delta_time: f32) -> f32 {
+        if fixed_delta_time > 0. {
+            (self.accumulated_time / fixed_delta_time).min(1.)
+        } else {
+            0.
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        ffi::{CStr, CString},
+        num::NonZeroUsize,
+        sync::{
+            Condvar, Mutex,
+            atomic::{AtomicBool, AtomicU32, Ordering},
+        },
+        thread::{self, ThreadId},
+        time::Duration,
+    };
+
+    use game_ecs::SystemErrorPolicy;
+    use game_module_macro::{Component, ResourceWithoutSerialize};
+    use platform::test::TestFilesystem;
+    use void_public::{
+        ArgType, Component, ComponentId, EcsType, Engine, EntityId, FrameConfig, FrameConstants,
+        PrefabOverride, Resource, SceneHandle, With, Without, bundle,
+        event::{
+            Vec2,
+            engine::{SceneLoadProgress, SceneLoadStage, SceneLoaded},
+        },
+        event_name,
+        state::StateCondition,
+    };
+
+    use super::FixedTimestep;
+    use crate::test_engine::{GpuColor, SystemInputs, TestArg, TestEngine, TestModule, TestSystem};
+
+    #[derive(Component, Debug)]
+    #[repr(C)]
+    struct Health {
+        value: u32,
+    }
+
+    #[derive(Component, Debug)]
+    #[repr(C)]
+    struct Armor {
+        value: u32,
+    }
+
+    #[derive(Component, Debug)]
+    #[repr(C)]
+    struct Link {
+        other: Option<EntityId>,
+    }
+
+    #[derive(Component, Debug)]
+    #[component(sparse)]
+    #[repr(C)]
+    struct Tagged;
+
+    #[derive(Debug, Default, ResourceWithoutSerialize)]
+    #[repr(C)]
+    struct Score {
+        value: u32,
+    }
+
+    #[test]
+    fn transition_system_events_are_readable() {
+        static RECEIVED: AtomicU32 = AtomicU32::new(0);
+
+        fn write_on_enter(inputs: SystemInputs) {
+            inputs.event_writer::<Vec2>(0).write(Vec2::new(1., 2.));
+        }
+
+        fn read_events(inputs: SystemInputs) {
+            let count = inputs.event_reader::<Vec2>(0).iter().count();
+            RECEIVED.fetch_add(count as u32, Ordering::Relaxed);
+        }
+
+        let mut engine = TestEngine::new([TestModule::new(c"transition_events")
+            .system(
+                TestSystem::new(c"write_on_enter", write_on_enter)
+                    .arg(TestArg::EventWriter(event_name!(Vec2)))
+                    .run_if(StateCondition::OnEnter, c"test_state", 0),
             )
-        } {
-            self.world[*entity_id].archetype_index = entity_data.archetype_index;
+            .system(
+                TestSystem::new(c"read_events", read_events)
+                    .arg(TestArg::EventReader(event_name!(Vec2))),
+            )]);
+
+        // the initial state is entered in the first frame, and its events are
+        // cleared in the next frame
+        engine.frames(3);
+        assert_eq!(RECEIVED.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn non_conflicting_systems_execute_on_different_threads() {
+        static THREADS: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+        static STARTED: Mutex<usize> = Mutex::new(0);
+        static STARTED_CONDVAR: Condvar = Condvar::new();
+
+        // Each system waits for the other to start, so that a single thread
+        // can not execute both. Their queries can not match a common
+        // archetype, so they do not conflict.
+        fn wait_for_other_system(_: SystemInputs) {
+            THREADS.lock().unwrap().push(thread::current().id());
+
+            let mut started = STARTED.lock().unwrap();
+            *started += 1;
+            STARTED_CONDVAR.notify_all();
+
+            let _started = STARTED_CONDVAR
+                .wait_timeout_while(started, Duration::from_secs(5), |started| *started < 2)
+                .unwrap();
+        }
+
+        let mut engine = TestEngine::threaded(
+            NonZeroUsize::new(2).unwrap(),
+            [TestModule::new(c"threaded")
+                .component::<Health>()
+                .component::<Armor>()
+                .system(
+                    TestSystem::new(c"update_health", wait_for_other_system).arg(TestArg::query(
+                        &[
+                            (ArgType::DataAccessMut, Health::string_id()),
+                            (ArgType::Without, Armor::string_id()),
+                        ],
+                    )),
+                )
+                .system(TestSystem::new(c"update_armor", wait_for_other_system).arg(
+                    TestArg::query(&[(ArgType::DataAccessMut, Armor::string_id())]),
+                ))],
+        );
+
+        engine.frames(1);
+
+        let threads = THREADS.lock().unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_ne!(threads[0], threads[1]);
+    }
+
+    #[test]
+    fn fixed_timestep_accumulates_frame_time() {
+        let mut fixed_timestep = FixedTimestep::default();
+
+        assert_eq!(fixed_timestep.update_frame(0.25, 0.1), 2);
+        assert_eq!(fixed_timestep.update_frame(0.04, 0.1), 0);
+        assert_eq!(fixed_timestep.update_frame(0.02, 0.1), 1);
+        assert!((fixed_timestep.alpha(0.1) - 0.1).abs() < 1e-4);
+
+        assert_eq!(fixed_timestep.update_frame(1., 0.), 0);
+        assert_eq!(fixed_timestep.update_frame(1., -0.1), 0);
+        assert!(fixed_timestep.invalid_reported);
+        assert_eq!(fixed_timestep.update_frame(0.1, 0.1), 1);
+        assert!(!fixed_timestep.invalid_reported);
+    }
+
+    #[test]
+    fn fixed_systems_execute_per_timestep() {
+        static STEPS: AtomicU32 = AtomicU32::new(0);
+
+        fn count_steps(_: SystemInputs) {
+            STEPS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut engine = TestEngine::new([TestModule::new(c"fixed_steps")
+            .system(TestSystem::new(c"count_steps", count_steps).fixed())]);
+
+        let steps = |engine: &mut TestEngine, delta_time: f32| {
+            STEPS.store(0, Ordering::Relaxed);
+            engine.frame(delta_time);
+            STEPS.load(Ordering::Relaxed)
+        };
+
+        // the default fixed timestep is 1/60 s
+        assert_eq!(steps(&mut engine, 3.5 / 60.), 3);
+        assert_eq!(steps(&mut engine, 0.6 / 60.), 1);
+
+        // frame time is capped by `max_delta_time`, which is 0.4 s by default
+        assert_eq!(steps(&mut engine, 10.), 24);
+
+        let game_engine = &mut *engine;
+        game_engine.cpu_data.get_resource_mut(
+            &game_engine.component_registry,
+            |frame_config: &mut FrameConfig| frame_config.fixed_delta_time = 0.,
+        );
+        assert_eq!(steps(&mut engine, 1. / 60.), 0);
+    }
+
+    /// Runs a system which fails on every run for three frames, and returns
+    /// how many times it ran.
+    fn run_failing_system(
+        runs: &'static AtomicU32,
+        fail: fn(SystemInputs),
+        set_policy: impl FnOnce(&mut TestEngine),
+    ) -> (TestEngine, u32) {
+        let mut engine =
+            TestEngine::new([TestModule::new(c"errors").system(TestSystem::new(c"fail", fail))]);
+        set_policy(&mut engine);
+
+        engine.frames(3);
+
+        (engine, runs.load(Ordering::Relaxed))
+    }
+
+    #[test]
+    fn failing_systems_are_disabled_by_default() {
+        static RUNS: AtomicU32 = AtomicU32::new(0);
+
+        let (engine, runs) = run_failing_system(
+            &RUNS,
+            |_| {
+                RUNS.fetch_add(1, Ordering::Relaxed);
+                panic!("failed");
+            },
+            |_| {},
+        );
+
+        assert_eq!(runs, 1);
+        assert!(engine.engine_errors().is_empty());
+        assert!(!engine.is_aborted());
+    }
+
+    #[test]
+    fn failing_systems_are_retried() {
+        static RUNS: AtomicU32 = AtomicU32::new(0);
+
+        let (engine, runs) = run_failing_system(
+            &RUNS,
+            |_| {
+                RUNS.fetch_add(1, Ordering::Relaxed);
+                panic!("failed");
+            },
+            |engine| engine.set_system_error_policy(SystemErrorPolicy::Retry),
+        );
+
+        assert_eq!(runs, 3);
+        assert!(!engine.is_aborted());
+
+        let errors = engine.engine_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].system_name.as_deref(), Some("errors::fail"));
+        assert_eq!(errors[0].module_name.as_deref(), Some("errors"));
+        assert_eq!(errors[0].frame, 3);
+    }
+
+    #[test]
+    fn failing_systems_abort_the_engine() {
+        static RUNS: AtomicU32 = AtomicU32::new(0);
+
+        let (engine, runs) = run_failing_system(
+            &RUNS,
+            |_| {
+                RUNS.fetch_add(1, Ordering::Relaxed);
+                panic!("failed");
+            },
+            |engine| {
+                engine.set_system_error_policy(SystemErrorPolicy::Retry);
+                engine.set_system_error_policy_override(
+                    "errors::fail",
+                    Some(SystemErrorPolicy::Abort),
+                );
+            },
+        );
+
+        // no frames are executed after aborting, and the error is kept
+        assert_eq!(runs, 1);
+        assert!(engine.is_aborted());
+
+        let errors = engine.engine_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].frame, 1);
+    }
+
+    #[test]
+    fn system_errors_are_capped() {
+        static RUNS: AtomicU32 = AtomicU32::new(0);
+
+        let mut engine = TestEngine::new([TestModule::new(c"errors").system(
+            TestSystem::new(c"fail", |_| {
+                RUNS.fetch_add(1, Ordering::Relaxed);
+                panic!("failed");
+            })
+            .fixed(),
+        )]);
+        engine.set_system_error_policy(SystemErrorPolicy::Retry);
+
+        // frame time is capped to 0.4 s, so this runs 24 fixed steps
+        engine.frame(10.);
+
+        assert_eq!(RUNS.load(Ordering::Relaxed), 24);
+        assert_eq!(engine.engine_errors().len(), 16);
+    }
+
+    #[test]
+    fn systems_are_profiled_when_enabled() {
+        let mut engine =
+            TestEngine::new(
+                [TestModule::new(c"profiled").system(TestSystem::new(c"work", |_| {}))],
+            );
+
+        engine.frames(2);
+        assert!(engine.scope_stats().is_empty());
+
+        engine.set_profiling_enabled(true);
+        engine.frames(2);
+
+        let scope_stats = engine.scope_stats();
+        let names = scope_stats
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert!(names.contains(&"profiled::work"));
+        assert!(names.contains(&"engine::execute_cpu"));
+        assert!(names.contains(&"engine::apply_commands"));
+    }
+
+    #[test]
+    fn removed_components_are_reported() {
+        static SPAWNED: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+        static REMOVE: AtomicBool = AtomicBool::new(false);
+        static REMOVED: Mutex<Vec<(&str, EntityId, u32)>> = Mutex::new(Vec::new());
+
+        fn spawn_entities(_: SystemInputs) {
+            *SPAWNED.lock().unwrap() = vec![
+                Engine::spawn(bundle!(&Health { value: 1 })),
+                Engine::spawn(bundle!(&Health { value: 2 }, &GpuColor { value: 3 })),
+                Engine::spawn(bundle!(&Health { value: 4 }, &GpuColor { value: 5 })),
+            ];
+        }
+
+        fn remove_components(_: SystemInputs) {
+            if REMOVE.swap(false, Ordering::Relaxed) {
+                let spawned = SPAWNED.lock().unwrap();
+                Engine::despawn(spawned[0]);
+                Engine::remove_components(spawned[1], &[GpuColor::id()]);
+                Engine::despawn(spawned[2]);
+            }
+        }
+
+        fn record_removed(inputs: SystemInputs) {
+            let mut removed = REMOVED.lock().unwrap();
+
+            for (entity_id, health) in inputs.removed_components::<Health>(0).iter() {
+                removed.push(("health", entity_id, health.value));
+            }
+
+            for (entity_id, color) in inputs.removed_components::<GpuColor>(1).iter() {
+                removed.push(("color", entity_id, color.value));
+            }
+        }
+
+        let mut engine = TestEngine::new([TestModule::new(c"removed_components")
+            .component::<Health>()
+            .system(TestSystem::new(c"spawn_entities", spawn_entities).once())
+            .system(TestSystem::new(c"remove_components", remove_components))
+            .system(
+                TestSystem::new(c"record_removed", record_removed)
+                    .arg(TestArg::RemovedComponents(Health::string_id()))
+                    .arg(TestArg::RemovedComponents(GpuColor::string_id())),
+            )]);
+
+        engine.frames(1);
+        REMOVE.store(true, Ordering::Relaxed);
+        engine.frames(3);
+
+        let spawned = SPAWNED.lock().unwrap().clone();
+        let mut removed = REMOVED.lock().unwrap().clone();
+        removed.sort_by_key(|(name, _, value)| (*name, *value));
+        assert_eq!(
+            removed,
+            [
+                ("color", spawned[1], 3),
+                ("color", spawned[2], 5),
+                ("health", spawned[0], 1),
+                ("health", spawned[2], 4),
+            ]
+        );
+
+        assert_eq!(
+            engine
+                .component::<Health>(spawned[1])
+                .map(|health| health.value),
+            Some(2)
+        );
+        assert!(engine.component::<GpuColor>(spawned[1]).is_none());
+    }
+
+    #[test]
+    fn despawn_removes_or_keeps_children() {
+        static SPAWNED: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+        static DESPAWN: AtomicBool = AtomicBool::new(false);
+
+        // spawns a parent with a child, and a grandchild, for each despawn call
+        fn spawn_entities(_: SystemInputs) {
+            let mut spawned = SPAWNED.lock().unwrap();
+
+            for _ in 0..3 {
+                let parent = Engine::spawn(bundle!(&Health { value: 1 }));
+                let child = Engine::spawn(bundle!(&Health { value: 2 }));
+                let grandchild = Engine::spawn(bundle!(&Health { value: 3 }));
+                Engine::set_parent_deferred(child, Some(parent), false);
+                Engine::set_parent_deferred(grandchild, Some(child), false);
+                spawned.extend([parent, child, grandchild]);
+            }
+        }
+
+        fn despawn_parents(_: SystemInputs) {
+            if DESPAWN.swap(false, Ordering::Relaxed) {
+                let spawned = SPAWNED.lock().unwrap();
+                Engine::despawn(spawned[0]);
+                Engine::despawn_recursive(spawned[3]);
+                Engine::despawn_non_recursive(spawned[6]);
+            }
         }
 
-        // gpu
+        let mut engine = TestEngine::new([TestModule::new(c"despawn")
+            .component::<Health>()
+            .system(TestSystem::new(c"spawn_entities", spawn_entities).once())
+            .system(TestSystem::new(c"despawn_parents", despawn_parents))]);
 
-        for storage_gpu in &storage.gpu {
-            let mut buffer =
-                gpu_data.get_buffer_mut(cpu_data, storage_gpu.buffer_index, storage_gpu.partition);
-            buffer.swap_remove(entity_data.archetype_index);
-        }
+        engine.frames(1);
+        let spawned = SPAWNED.lock().unwrap().clone();
+        assert!(spawned.iter().all(|entity_id| engine.contains(*entity_id)));
 
-        for child_id in entity_data.child_ids.drain(..) {
-            self.handle_despawn(child_id, cpu_data, gpu_data);
-        }
+        DESPAWN.store(true, Ordering::Relaxed);
+        engine.frames(1);
+
+        let exists: Vec<_> = spawned
+            .iter()
+            .map(|entity_id| engine.contains(*entity_id))
+            .collect();
+        assert_eq!(
+            exists,
+            [false, false, false, false, false, false, false, true, true]
+        );
+
+        // the child of the non-recursively despawned parent is now a root
+        // entity, which keeps its own child
+        let child = engine.frame_update.world.get(spawned[7].into()).unwrap();
+        assert_eq!(child.parent_id, None);
+        assert_eq!(child.child_ids, [spawned[8].into()]);
     }
 
-    fn handle_add_components(
-        &mut self,
-        command: &AddComponents<'_>,
-        cpu_data: &mut CpuFrameData,
-        gpu_data: &mut G,
-        component_registry: &ComponentRegistry,
-    ) {
-        log::info!("Adding components to entity {}", command.entity_id.id);
+    /// A prefab with a root entity and a child entity, labeled `root` and
+    /// `weapon`.
+    fn prefab_json() -> String {
+        let health = Health::string_id().to_str().unwrap();
+        let version = game_scene::VERSION;
+
+        format!(
+            r#"{{
+                "version": "{version}",
+                "entities": [
+                    {{ "id": "root", "label": "root", "components": {{ "{health}": {{ "value": 1 }} }} }},
+                    {{ "id": "weapon", "parent_id": "root", "label": "weapon", "components": {{ "{health}": {{ "value": 2 }} }} }}
+                ]
+            }}"#
+        )
+    }
 
-        let Some(entity_data) = self.world.get(*command.entity_id) else {
-            log::warn!(
-                "... entity id {} with lifecycle {} does not exist",
-                command.entity_id.id,
-                command.entity_id.lifecycle
-            );
-            return;
-        };
+    /// Returns the value of the `Health` of the entity labeled `label` in the
+    /// prefab instance of `root_entity_id`.
+    fn instance_health(engine: &mut TestEngine, root_entity_id: EntityId, label: &str) -> u32 {
+        let label = Engine::prefab_instance_label(root_entity_id, label);
+        let entity_id = engine.get_entity_from_label(&label).unwrap();
+        engine.component::<Health>(entity_id.into()).unwrap().value
+    }
 
-        // Add new component ids to the existing archetype key.
-        let mut archetype_key = entity_data.archetype_key.clone();
-        archetype_key
-            .component_ids
-            .extend(command.sorted_component_ids());
-        archetype_key.component_ids.sort_unstable();
-        archetype_key.component_ids.dedup();
+    #[test]
+    fn prefab_instances_are_spawned() {
+        static ROOTS: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+        static FRAME: AtomicU32 = AtomicU32::new(0);
 
-        if archetype_key == entity_data.archetype_key {
-            log::warn!("... archetypes do not differ, likely no new components were added");
-            return;
+        fn spawn_instances(_: SystemInputs) {
+            match FRAME.fetch_add(1, Ordering::Relaxed) {
+                0 => {
+                    let prefab_json = CString::new(prefab_json()).unwrap();
+                    Engine::register_prefab(c"enemy", &prefab_json);
+
+                    // the prefab is registered at the end of the frame
+                    assert_eq!(Engine::spawn_prefab(c"enemy", &[]), None);
+                }
+                1 => {
+                    let root_health = Health { value: 10 };
+                    let weapon_health = Health { value: 20 };
+
+                    *ROOTS.lock().unwrap() = vec![
+                        Engine::spawn_prefab(c"enemy", &[]).unwrap(),
+                        Engine::spawn_prefab_with_overrides(
+                            c"enemy",
+                            bundle!(&root_health),
+                            &[PrefabOverride::new(c"weapon", bundle!(&weapon_health))],
+                        )
+                        .unwrap(),
+                    ];
+
+                    assert_eq!(Engine::spawn_prefab(c"unknown", &[]), None);
+                }
+                _ => {}
+            }
         }
 
-        self.allocate_archetype_storage_if_needed(
-            &archetype_key,
-            cpu_data,
-            gpu_data,
-            component_registry,
-        );
+        let mut engine = TestEngine::new([TestModule::new(c"prefab_instances")
+            .component::<Health>()
+            .system(TestSystem::new(c"spawn_instances", spawn_instances))]);
 
-        let entity_data = &mut self.world[*command.entity_id];
+        engine.frames(2);
 
-        let prev_storage = &self.archetypes[&entity_data.archetype_key];
-        let storage = &self.archetypes[&archetype_key];
-        let (mut prev_buffer, mut buffer) =
-            cpu_data.get_buffer_pair_mut((prev_storage.cpu.buffer_index, storage.cpu.buffer_index));
+        let roots = ROOTS.lock().unwrap().clone();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(engine.entities().count(), 4);
 
-        let prev_entry_ptr = prev_buffer.get_ptr(entity_data.archetype_index);
+        // each instance has its own labels
+        assert_eq!(instance_health(&mut engine, roots[0], "root"), 1);
+        assert_eq!(instance_health(&mut engine, roots[0], "weapon"), 2);
+        assert_eq!(instance_health(&mut engine, roots[1], "root"), 10);
+        assert_eq!(instance_health(&mut engine, roots[1], "weapon"), 20);
 
-        // Copy data from old archetype to new archetype.
-        write_cpu_component_data(
-            *command.entity_id,
-            storage,
-            component_registry,
-            buffer.grow(),
-            |component_id| {
-                // Prefer checking previous storage first, so that we don't
-                // overwrite existing component data.
-                if let Some(component_info) = prev_storage
-                    .cpu
-                    .components
-                    .iter()
-                    .find(|c| c.component_id == component_id)
-                {
-                    unsafe {
-                        let ptr = prev_entry_ptr.add(component_info.offset);
-                        let size = component_registry[&component_id].size;
+        let label = Engine::prefab_instance_label(roots[1], "root");
+        assert_eq!(engine.get_entity_from_label(&label), Some(roots[1].into()));
+    }
 
-                        from_raw_parts(ptr, size)
+    #[test]
+    fn prefabs_are_registered_from_path() {
+        static ROOTS: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+        static STEP: AtomicU32 = AtomicU32::new(0);
+        static PATHS: Mutex<Vec<CString>> = Mutex::new(Vec::new());
+
+        // registers the prefabs on the first fixed step, and spawns them on the
+        // second, before they are read at the start of the next frame
+        fn spawn_instances(_: SystemInputs) {
+            let paths = PATHS.lock().unwrap();
+
+            match STEP.fetch_add(1, Ordering::Relaxed) {
+                0 => {
+                    for path in paths.iter() {
+                        Engine::register_prefab_from_path(path);
                     }
-                } else {
-                    command.component_data(component_id).unwrap()
                 }
-            },
-        );
+                1 => {
+                    *ROOTS.lock().unwrap() = paths
+                        .iter()
+                        .map(|path| Engine::spawn_prefab(path, &[]).unwrap())
+                        .collect();
+                }
+                _ => {}
+            }
+        }
 
-        assert!(storage.gpu.is_empty(), "gpu data currently unsupported");
+        let prefab_path = std::env::temp_dir().join(format!(
+            "prefabs_are_registered_from_path_{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&prefab_path, prefab_json()).unwrap();
+        let missing_path = prefab_path.with_extension("missing");
 
-        // Remove old archetype buffer entry.
-        prev_buffer.swap_remove(entity_data.archetype_index);
+        *PATHS.lock().unwrap() = [&prefab_path, &missing_path]
+            .map(|path| CString::new(path.to_str().unwrap()).unwrap())
+            .into();
 
-        // Update entity data stored on the `World`.
-        entity_data.archetype_key = archetype_key;
-        entity_data.archetype_index = buffer.len() - 1;
+        let mut engine = TestEngine::new([TestModule::new(c"prefab_paths")
+            .component::<Health>()
+            .system(TestSystem::new(c"spawn_instances", spawn_instances).fixed())]);
 
-        initialize_local_to_world_if_needed(cpu_data, entity_data.archetype_index, storage);
-    }
+        engine.frame(2. / 60.);
+        assert_eq!(engine.entities().count(), 0);
 
-    fn handle_remove_components(
-        &mut self,
-        command: &RemoveComponents<'_>,
-        cpu_data: &mut CpuFrameData,
-        gpu_data: &mut G,
-        component_registry: &ComponentRegistry,
-    ) {
-        let entity_id: EntityId = NonZero::new(command.entity_id()).unwrap().into();
+        TestFilesystem::complete_reads();
+        engine.frame(0.);
+        std::fs::remove_file(&prefab_path).unwrap();
 
-        log::info!("Removing components from entity {}", entity_id.id);
+        let roots = ROOTS.lock().unwrap().clone();
+        assert_eq!(instance_health(&mut engine, roots[0], "weapon"), 2);
 
-        let Some(entity_data) = self.world.get(entity_id) else {
-            log::warn!(
-                "... entity id {} with lifecycle {} does not exist",
-                entity_id.id,
-                entity_id.lifecycle
-            );
-            return;
-        };
+        // the instance of the prefab which failed to load is discarded
+        assert!(!engine.contains(roots[1]));
+        assert_eq!(engine.entities().count(), 2);
 
-        // Remove component ids from the existing archetype key.
-        let mut archetype_key = entity_data.archetype_key.clone();
-        archetype_key.component_ids.retain(|cid| {
-            command
-                .component_ids()
-                .unwrap()
-                .iter()
-                .all(|removed_id| cid.get() != removed_id)
-        });
+        let paths = PATHS.lock().unwrap();
+        assert!(engine.frame_update.prefab_names.contains(&paths[0]));
+        assert!(!engine.frame_update.prefab_names.contains(&paths[1]));
+    }
 
-        if archetype_key == entity_data.archetype_key {
-            log::warn!("... archetypes do not differ, likely no components were removed");
-            return;
+    #[test]
+    fn scene_entities_are_unloaded() {
+        static STEP: AtomicU32 = AtomicU32::new(0);
+        static SCENE_HANDLE: Mutex<Option<SceneHandle>> = Mutex::new(None);
+        static DESPAWNED: Mutex<Option<EntityId>> = Mutex::new(None);
+
+        fn load_and_unload(_: SystemInputs) {
+            match STEP.fetch_add(1, Ordering::Relaxed) {
+                0 => {
+                    *SCENE_HANDLE.lock().unwrap() =
+                        Some(Engine::load_scene(prefab_json().as_bytes()));
+                    Engine::spawn(bundle!(&Health { value: 3 }));
+                }
+                1 => Engine::despawn(DESPAWNED.lock().unwrap().unwrap()),
+                2 => Engine::unload_scene(SCENE_HANDLE.lock().unwrap().unwrap()),
+                _ => {}
+            }
         }
 
-        self.allocate_archetype_storage_if_needed(
-            &archetype_key,
-            cpu_data,
-            gpu_data,
-            component_registry,
-        );
+        let mut engine = TestEngine::new([TestModule::new(c"scene_unload")
+            .component::<Health>()
+            .system(TestSystem::new(c"load_and_unload", load_and_unload))]);
 
-        let entity_data = &mut self.world[entity_id];
+        engine.frames(1);
+        assert_eq!(engine.entities().count(), 3);
 
-        let prev_storage = &self.archetypes[&entity_data.archetype_key];
-        let storage = &self.archetypes[&archetype_key];
-        let (mut prev_buffer, mut buffer) =
-            cpu_data.get_buffer_pair_mut((prev_storage.cpu.buffer_index, storage.cpu.buffer_index));
+        let scene_handle = SCENE_HANDLE.lock().unwrap().unwrap();
+        assert_eq!(engine.frame_update.scene_instances[&scene_handle].len(), 2);
 
-        let prev_entry_ptr = prev_buffer.get_ptr(entity_data.archetype_index);
+        // despawned scene entities are removed from their scene instance
+        let weapon = engine.get_entity_from_label(c"weapon").unwrap();
+        *DESPAWNED.lock().unwrap() = Some(weapon.into());
+        engine.frames(1);
 
-        // Copy data from old archetype to new archetype.
-        write_cpu_component_data(
-            entity_id,
-            storage,
-            component_registry,
-            buffer.grow(),
-            |component_id| {
-                let prev_offset = prev_storage
-                    .cpu
-                    .components
-                    .iter()
-                    .find(|c| c.component_id == component_id)
-                    .unwrap()
-                    .offset;
+        assert_eq!(engine.entities().count(), 2);
+        assert!(!engine.frame_update.scene_instances[&scene_handle].contains(&weapon));
 
-                unsafe {
-                    let ptr = prev_entry_ptr.add(prev_offset);
-                    let size = component_registry[&component_id].size;
+        // only the entities of the scene are unloaded
+        engine.frames(1);
 
-                    from_raw_parts(ptr, size)
-                }
-            },
+        assert_eq!(engine.entities().count(), 1);
+        assert!(engine.frame_update.scene_instances.is_empty());
+        let remaining = engine.entities().next().unwrap();
+        assert_eq!(
+            engine.component::<Health>(remaining.into()).unwrap().value,
+            3
         );
+    }
 
-        assert!(storage.gpu.is_empty(), "gpu data currently unsupported");
-
-        // Remove old archetype buffer entry.
-        prev_buffer.swap_remove(entity_data.archetype_index);
+    #[test]
+    fn scenes_are_loaded_from_path() {
+        static PROGRESS: Mutex<Vec<(u64, SceneLoadStage)>> = Mutex::new(Vec::new());
+        static LOADED: Mutex<Vec<(u64, u32)>> = Mutex::new(Vec::new());
 
-        // Update entity data stored on the `World`.
-        entity_data.archetype_key = archetype_key;
-        entity_data.archetype_index = buffer.len() - 1;
-    }
+        fn record_scene_events(inputs: SystemInputs) {
+            for event in inputs.event_reader::<SceneLoadProgress>(0).iter() {
+                PROGRESS
+                    .lock()
+                    .unwrap()
+                    .push((event.handle(), event.stage()));
+            }
 
-    /// Allocates a new storage for the archetype, if none exists.
-    fn allocate_archetype_storage_if_needed(
-        &mut self,
-        archetype_key: &ArchetypeKey,
-        cpu_data: &mut CpuFrameData,
-        gpu_data: &mut G,
-        component_registry: &ComponentRegistry,
-    ) {
-        if self.archetypes.contains_key(archetype_key) {
-            return;
+            for event in inputs.event_reader::<SceneLoaded>(1).iter() {
+                LOADED
+                    .lock()
+                    .unwrap()
+                    .push((event.handle(), event.entity_count()));
+            }
         }
 
-        let storage = ArchetypeStorage::new(
-            &archetype_key.component_ids,
-            component_registry,
-            &self.gpu_component_groupings,
-            &mut self.gpu_single_buffer_components,
-            cpu_data,
-            gpu_data,
-        );
+        // the scene is upgraded from an older version when it is read
+        let scene_json = prefab_json().replace(game_scene::VERSION, "0.0.1");
+        let scene_path = std::env::temp_dir().join(format!(
+            "scenes_are_loaded_from_path_{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&scene_path, scene_json).unwrap();
+        let missing_path = scene_path.with_extension("missing");
+
+        let mut engine = TestEngine::new([TestModule::new(c"scene_paths")
+            .component::<Health>()
+            .system(
+                TestSystem::new(c"record_scene_events", record_scene_events)
+                    .arg(TestArg::EventReader(event_name!(SceneLoadProgress)))
+                    .arg(TestArg::EventReader(event_name!(SceneLoaded))),
+            )]);
+
+        engine
+            .register_component_scene_migration(
+                "0.0.1",
+                game_scene::VERSION,
+                Health::string_id().to_str().unwrap(),
+                |health| {
+                    health["value"] = (health["value"].as_u32().unwrap() * 10).into();
+                    Ok(())
+                },
+            )
+            .unwrap();
 
-        // add archetype as input to relevant systems
-        self.system_graph
-            .add_archetype_input(archetype_key, &storage);
+        let scene_handle = engine.load_scene_from_path(&scene_path.to_str().unwrap().into());
+        let missing_handle = engine.load_scene_from_path(&missing_path.to_str().unwrap().into());
 
-        self.archetypes.insert(archetype_key.clone(), storage);
-    }
+        // the scenes are not spawned until their files are read
+        engine.frames(2);
+        assert_eq!(engine.entities().count(), 0);
 
-    fn handle_set_entity_label(&mut self, command: &SetEntityLabel<'_>) {
-        let entity_id = NonZero::new(command.entity_id()).unwrap().into();
+        TestFilesystem::complete_reads();
+        engine.frames(2);
+        std::fs::remove_file(&scene_path).unwrap();
 
-        if let Some(label) = command.label() {
-            let label = CString::new(label).unwrap();
-            self.world.set_entity_label(entity_id, label);
-        } else {
-            self.world.remove_entity_label(entity_id);
-        }
-    }
+        let weapon = engine.get_entity_from_label(c"weapon").unwrap();
+        assert_eq!(engine.component::<Health>(weapon.into()).unwrap().value, 20);
+        assert_eq!(engine.entities().count(), 2);
 
-    fn handle_set_parent(
-        &mut self,
-        entity_id: EntityId,
-        parent_id: Option<EntityId>,
-        keep_world_transform: bool,
-        cpu_data: &mut CpuFrameData,
-    ) {
-        let Some(entity_data) = self.world.get(entity_id) else {
-            // unable to find the entity to update
-            return;
-        };
+        let (scene_handle, missing_handle) = (scene_handle.0.get(), missing_handle.0.get());
+        let progress = PROGRESS.lock().unwrap();
 
-        if entity_data.parent_id == parent_id {
-            // parentage isn't actually changing, ignore.
-            return;
+        for handle in [scene_handle, missing_handle] {
+            assert_eq!(
+                progress
+                    .iter()
+                    .filter(|&&progress| progress == (handle, SceneLoadStage::Reading))
+                    .count(),
+                2
+            );
         }
 
-        if let Some(parent_id) = parent_id {
-            if self.world.get(parent_id).is_none() {
-                // unable to find the parent entity
-                log::warn!("Unable to set parent. Parent Id {parent_id:?} not found");
-                return;
-            }
-        }
+        assert!(progress.contains(&(missing_handle, SceneLoadStage::Failed)));
+        assert!(!progress.contains(&(scene_handle, SceneLoadStage::Failed)));
+        assert_eq!(*LOADED.lock().unwrap(), [(scene_handle, 2)]);
+    }
 
-        log::info!("Setting parent of {entity_id:?} to {parent_id:?}...");
+    /// Returns the entity referenced by the `Link` of the entity labeled `label`.
+    fn linked_entity(engine: &mut TestEngine, label: &CStr) -> Option<EntityId> {
+        let entity_id = engine.get_entity_from_label(label).unwrap();
+        engine.component::<Link>(entity_id.into()).unwrap().other
+    }
 
-        // remove the entity from its current parent's child list (if applicable)
-        if let Some(prev_parent_id) = entity_data.parent_id {
-            if let Some(parent_data) = self.world.get_mut(prev_parent_id) {
-                parent_data.child_ids.retain(|id| *id != entity_id);
-            }
-        }
+    #[test]
+    fn scene_entity_references_are_remapped() {
+        static SCENE: Mutex<String> = Mutex::new(String::new());
+        static STEP: AtomicU32 = AtomicU32::new(0);
 
-        // add to new parent's child list (if not root)
-        if let Some(parent_id) = parent_id {
-            if let Some(parent_data) = self.world.get_mut(parent_id) {
-                if parent_data.child_ids.contains(&entity_id) {
-                    log::warn!("Child cannot be added more than once");
-                    return;
-                }
-                parent_data.child_ids.push(entity_id);
+        fn load_scene(_: SystemInputs) {
+            if STEP.fetch_add(1, Ordering::Relaxed) == 0 {
+                Engine::load_scene(SCENE.lock().unwrap().as_bytes());
             }
         }
 
-        // update the parent field
-        self.world.get_mut(entity_id).unwrap().parent_id = parent_id;
-
-        // if the re-parent is in-place, we need to update the Transform component based on the new parentage
-        if keep_world_transform {
-            // calculate world->new_parent matrix
-            let mut mat_parent_world_to_local = Mat4::IDENTITY;
-            if let Some(new_parent_id) = parent_id {
-                let (parent_entity_data, parent_archetype_storage, parent_data_buffer) =
-                    self.get_buffer_for_entity(new_parent_id, cpu_data);
-                if let Some(data_parent_local_to_world) = unsafe {
-                    get_data_from_buffer::<LocalToWorld>(
-                        &parent_data_buffer,
-                        parent_entity_data.archetype_index,
-                        parent_archetype_storage,
-                    )
-                } {
-                    mat_parent_world_to_local = data_parent_local_to_world.inverse();
+        let link = Link::string_id().to_str().unwrap();
+        let version = game_scene::VERSION;
+
+        // the parent and child reference each other, and the stray entity
+        // references an entity which is not in the scene
+        *SCENE.lock().unwrap() = format!(
+            r#"{{
+                "version": "{version}",
+                "entities": [
+                    {{ "id": "child", "parent_id": "parent", "label": "child", "components": {{ "{link}": {{ "other": "parent" }} }} }},
+                    {{ "id": "parent", "label": "parent", "components": {{ "{link}": {{ "other": "child" }} }} }},
+                    {{ "id": "stray", "label": "stray", "components": {{ "{link}": {{ "other": "missing" }} }} }}
+                ]
+            }}"#
+        );
+
+        let mut engine = TestEngine::new([TestModule::new(c"scene_references")
+            .component::<Health>()
+            .component::<Link>()
+            .system(TestSystem::new(c"load_scene", load_scene))]);
+
+        engine.frames(1);
+        assert_eq!(engine.entities().count(), 3);
+
+        let parent = engine.get_entity_from_label(c"parent").unwrap();
+        let child = engine.get_entity_from_label(c"child").unwrap();
+        assert_eq!(linked_entity(&mut engine, c"parent"), Some(child.into()));
+        assert_eq!(linked_entity(&mut engine, c"child"), Some(parent.into()));
+        assert_eq!(linked_entity(&mut engine, c"stray"), None);
+
+        // the saved scene references the spawned entities by their new ids
+        *SCENE.lock().unwrap() = engine.save_scene().unwrap();
+        drop(engine);
+
+        STEP.store(0, Ordering::Relaxed);
+        let mut engine = TestEngine::new([TestModule::new(c"scene_references")
+            .component::<Health>()
+            .component::<Link>()
+            .system(TestSystem::new(c"load_scene", load_scene))]);
+
+        engine.frames(1);
+        assert_eq!(engine.entities().count(), 3);
+
+        let parent = engine.get_entity_from_label(c"parent").unwrap();
+        let child = engine.get_entity_from_label(c"child").unwrap();
+        assert_eq!(linked_entity(&mut engine, c"parent"), Some(child.into()));
+        assert_eq!(linked_entity(&mut engine, c"child"), Some(parent.into()));
+        assert_eq!(linked_entity(&mut engine, c"stray"), None);
+    }
+
+    #[test]
+    fn dangling_entity_references_are_saved_as_null() {
+        static STEP: AtomicU32 = AtomicU32::new(0);
+        static TARGET: Mutex<Option<EntityId>> = Mutex::new(None);
+
+        fn spawn_dangling_link(_: SystemInputs) {
+            match STEP.fetch_add(1, Ordering::Relaxed) {
+                0 => {
+                    let target = Engine::spawn(bundle!(&Health { value: 3 }));
+                    Engine::spawn(bundle!(&Link {
+                        other: Some(target)
+                    }));
+                    *TARGET.lock().unwrap() = Some(target);
                 }
+                1 => Engine::despawn(TARGET.lock().unwrap().unwrap()),
+                _ => {}
             }
+        }
 
-            // get entity->world matrix
-            let mut entity_buffer = self.get_buffer_for_entity(entity_id, cpu_data);
+        let mut engine = TestEngine::new([TestModule::new(c"dangling_references")
+            .component::<Health>()
+            .component::<Link>()
+            .system(TestSystem::new(c"spawn_dangling_link", spawn_dangling_link))]);
 
-            let data_entity_local_to_world = unsafe {
-                get_data_from_buffer::<LocalToWorld>(
-                    &entity_buffer.2,
-                    entity_buffer.0.archetype_index,
-                    entity_buffer.1,
-                )
-            }
-            .unwrap();
+        engine.frames(2);
+        assert_eq!(engine.entities().count(), 1);
 
-            // calculate entity->new_parent matrix
-            let new_local_matrix = mat_parent_world_to_local.mul_mat4(data_entity_local_to_world);
-            let (scale, rotation, translation) = new_local_matrix.to_scale_rotation_translation();
+        let scene = json::parse(&engine.save_scene().unwrap()).unwrap();
+        let link = Link::string_id().to_str().unwrap();
 
-            // write back to the Transform component
-            let transform_to_update = unsafe {
-                get_data_from_buffer_mut::<Transform>(
-                    &mut entity_buffer.2,
-                    entity_buffer.0.archetype_index,
-                    entity_buffer.1,
+        assert_eq!(scene["entities"].len(), 1);
+        assert!(scene["entities"][0]["components"][link]["other"].is_null());
+    }
+
+    /// The label, parent label, `Health` and linked entity label of an entity
+    /// of a saved scene.
+    type SavedEntity = (String, Option<String>, Option<u32>, Option<String>);
+
+    /// Returns the entities of a saved scene, sorted by label.
+    fn saved_entities(scene: &str) -> Vec<SavedEntity> {
+        let scene = json::parse(scene).unwrap();
+        let health = Health::string_id().to_str().unwrap();
+        let link = Link::string_id().to_str().unwrap();
+
+        let label = |id: &json::JsonValue| {
+            scene["entities"]
+                .members()
+                .find(|entity| id.is_string() && entity["id"] == *id)
+                .map(|entity| entity["label"].to_string())
+        };
+
+        let mut entities = scene["entities"]
+            .members()
+            .map(|entity| {
+                let components = &entity["components"];
+                (
+                    entity["label"].to_string(),
+                    label(&entity["parent_id"]),
+                    components[health]["value"].as_u32(),
+                    label(&components[link]["other"]),
                 )
+            })
+            .collect::<Vec<_>>();
+
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn binary_scenes_are_loaded_like_json_scenes() {
+        static SCENE: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+        static STEP: AtomicU32 = AtomicU32::new(0);
+
+        fn load_scene(_: SystemInputs) {
+            if STEP.fetch_add(1, Ordering::Relaxed) == 0 {
+                Engine::load_scene(&SCENE.lock().unwrap());
             }
-            .unwrap();
-            let axis_angle = rotation.to_axis_angle();
-            transform_to_update.position = translation.into();
-            transform_to_update.scale = scale.truncate().into();
-            transform_to_update.rotation = axis_angle.0.z.signum() * axis_angle.1;
         }
+
+        let new_engine = || {
+            STEP.store(0, Ordering::Relaxed);
+            TestEngine::new([TestModule::new(c"binary_scenes")
+                .component::<Health>()
+                .component::<Link>()
+                .system(TestSystem::new(c"load_scene", load_scene))])
+        };
+
+        let health = Health::string_id().to_str().unwrap();
+        let link = Link::string_id().to_str().unwrap();
+        let version = game_scene::VERSION;
+
+        let json_scene = format!(
+            r#"{{
+                "version": "{version}",
+                "entities": [
+                    {{ "id": "parent", "label": "parent", "components": {{ "{health}": {{ "value": 1 }}, "{link}": {{ "other": "child" }} }} }},
+                    {{ "id": "child", "parent_id": "parent", "label": "child", "components": {{ "{health}": {{ "value": 2 }}, "{link}": {{ "other": "parent" }} }} }},
+                    {{ "id": "stray", "label": "stray", "components": {{ "{link}": {{ "other": "missing" }} }} }},
+                    {{ "id": "plain", "label": "plain", "components": {{ "{health}": {{ "value": 3 }} }} }}
+                ]
+            }}"#
+        );
+
+        *SCENE.lock().unwrap() = json_scene.clone().into_bytes();
+        let mut engine = new_engine();
+        engine.frames(1);
+
+        let saved_json_scene = engine.save_scene().unwrap();
+        let binary_scene = engine.convert_scene_to_binary(&json_scene).unwrap();
+        assert!(game_scene::is_binary_scene(&binary_scene));
+        drop(engine);
+
+        *SCENE.lock().unwrap() = binary_scene;
+        let mut engine = new_engine();
+        engine.frames(1);
+        assert_eq!(engine.entities().count(), 4);
+
+        let saved_binary_scene = engine.save_scene().unwrap();
+
+        let entity = |label: &str, parent: Option<&str>, health, other: Option<&str>| {
+            (
+                label.to_owned(),
+                parent.map(str::to_owned),
+                health,
+                other.map(str::to_owned),
+            )
+        };
+
+        assert_eq!(
+            saved_entities(&saved_binary_scene),
+            [
+                entity("child", Some("parent"), Some(2), Some("parent")),
+                entity("parent", None, Some(1), Some("child")),
+                entity("plain", None, Some(3), None),
+                entity("stray", None, None, None),
+            ]
+        );
+        assert_eq!(
+            saved_entities(&saved_binary_scene),
+            saved_entities(&saved_json_scene)
+        );
     }
 
-    /// Returns a tuple containing the buffer for an entity along with the structs to lookup
-    /// component specific data
-    /// (`entity_data`, `archetype_storage`, `AtomicRef<Buffer>`)
-    fn get_buffer_for_entity<'a>(
-        &self,
-        entity_id: EntityId,
-        cpu_data: &'a mut CpuFrameData,
-    ) -> (&EntityData, &ArchetypeStorage, CpuDataBufferRefMut<'a>) {
-        let entity_data = self.world.get(entity_id).unwrap();
-        let storage_map_parent = self.archetypes.get(&entity_data.archetype_key).unwrap();
+    #[test]
+    fn batches_are_spawned() {
+        static SPAWNED: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+
+        fn spawn_batches(_: SystemInputs) {
+            let mut spawned = SPAWNED.lock().unwrap();
+            spawned.push(Engine::spawn(bundle!(&Health { value: 1 })));
+            spawned.extend(Engine::spawn_batch(bundle!(&Health { value: 2 }), 100));
+            spawned.extend(Engine::spawn_batch(
+                bundle!(&Health { value: 3 }, &GpuColor { value: 4 }),
+                50,
+            ));
+            spawned.push(Engine::spawn(bundle!(&Health { value: 5 })));
 
-        (
-            entity_data,
-            storage_map_parent,
-            cpu_data.get_buffer_mut(storage_map_parent.cpu.buffer_index),
-        )
+            // entities despawned in the same frame are skipped
+            Engine::despawn(spawned[1]);
+        }
+
+        let mut engine = TestEngine::new([TestModule::new(c"spawn_batch")
+            .component::<Health>()
+            .system(TestSystem::new(c"spawn_batches", spawn_batches).once())]);
+
+        engine.frames(1);
+
+        let spawned = SPAWNED.lock().unwrap().clone();
+        assert_eq!(spawned.len(), 152);
+        assert_eq!(spawned.iter().collect::<HashSet<_>>().len(), 152);
+        assert_eq!(engine.entities().count(), 151);
+
+        assert!(!engine.contains(spawned[1]));
+        assert_eq!(engine.component::<Health>(spawned[0]).unwrap().value, 1);
+        assert_eq!(engine.component::<Health>(spawned[151]).unwrap().value, 5);
+
+        for &entity_id in &spawned[2..101] {
+            assert_eq!(engine.component::<Health>(entity_id).unwrap().value, 2);
+            assert!(engine.component::<GpuColor>(entity_id).is_none());
+        }
+
+        for &entity_id in &spawned[101..151] {
+            assert_eq!(engine.component::<Health>(entity_id).unwrap().value, 3);
+            assert_eq!(engine.component::<GpuColor>(entity_id).unwrap().value, 4);
+        }
     }
-}
 
-/// Writes component data from a new spawn event into archetype storage buffers.
-fn write_spawn_component_data<G: Gpu, T: SpawnComponentData>(
-    entity_id: EntityId,
-    entity_index: usize,
-    components: &T,
-    storage: &ArchetypeStorage,
-    cpu_data: &mut CpuFrameData,
-    gpu_data: &mut G,
-    component_registry: &ComponentRegistry,
-) {
-    let mut buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
-    let component_entry_bytes = buffer.grow();
+    #[test]
+    fn exclusive_systems_change_the_world_and_call_the_engine() {
+        static SPAWNED: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+        static FIELDS: Mutex<Vec<CString>> = Mutex::new(Vec::new());
 
-    assert_eq!(
-        component_entry_bytes
-            .as_ptr()
-            .align_offset(storage.cpu.align),
-        0
-    );
+        fn edit_world(inputs: SystemInputs) {
+            let mut world = inputs.world(0);
 
-    // write cpu component data
+            let first = world.spawn(bundle!(&Health { value: 1 }));
+            assert_eq!(world.get::<Health>(first).unwrap().value, 1);
+            assert!(world.add_components(first, bundle!(&Armor { value: 2 })));
+            assert!(world.remove_components(first, &[Health::id()]));
 
-    write_cpu_component_data(
-        entity_id,
-        storage,
-        component_registry,
-        component_entry_bytes,
-        |component_id| components.component_data(component_id).unwrap(),
-    );
+            // engine commands are applied along with the next world change
+            let second = Engine::spawn(bundle!(&Health { value: 3 }));
+            let third = world.spawn(bundle!(&Health { value: 4 }));
+            assert_ne!(second, third);
+            assert_eq!(world.get::<Health>(second).unwrap().value, 3);
 
-    // initialize/sync component data if required
+            // or when the system returns
+            Engine::add_components(third, bundle!(&Armor { value: 5 }));
+            Engine::remove_components(third, &[Health::id()]);
 
-    initialize_local_to_world_if_needed(cpu_data, entity_index, storage);
+            *FIELDS.lock().unwrap() = Engine::component_fields(Health::id())
+                .into_iter()
+                .map(|field| field.name)
+                .collect();
+            *SPAWNED.lock().unwrap() = vec![first, second, third];
+        }
 
-    // write gpu component data
+        let mut engine = TestEngine::new([TestModule::new(c"exclusive")
+            .component::<Health>()
+            .component::<Armor>()
+            .system(
+                TestSystem::new(c"edit_world", edit_world)
+                    .arg(TestArg::World)
+                    .once(),
+            )]);
+
+        engine.frames(2);
+
+        let spawned = SPAWNED.lock().unwrap().clone();
+        assert_eq!(*FIELDS.lock().unwrap(), [c"value".to_owned()]);
+        assert!(engine.component::<Health>(spawned[0]).is_none());
+        assert_eq!(engine.component::<Armor>(spawned[0]).unwrap().value, 2);
+        assert_eq!(engine.component::<Health>(spawned[1]).unwrap().value, 3);
+        assert!(engine.component::<Health>(spawned[2]).is_none());
+        assert_eq!(engine.component::<Armor>(spawned[2]).unwrap().value, 5);
+        assert_eq!(engine.entities().count(), 3);
+    }
 
-    for storage_gpu in &storage.gpu {
-        let mut buffer =
-            gpu_data.get_buffer_mut(cpu_data, storage_gpu.buffer_index, storage_gpu.partition);
-        let buffer = buffer.grow();
+    #[test]
+    fn resources_are_removed_and_inserted() {
+        static FRAME: AtomicU32 = AtomicU32::new(0);
+        static SCORES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
 
-        for component_offset_info in &storage_gpu.components {
-            let data = components
-                .component_data(component_offset_info.component_id)
-                .unwrap();
-            buffer[component_offset_info.offset..component_offset_info.offset + data.len()]
-                .copy_from_slice(data);
+        fn change_resources(_: SystemInputs) {
+            match FRAME.fetch_add(1, Ordering::Relaxed) {
+                0 => {
+                    Engine::remove_resource::<Score>();
+                    Engine::remove_resource::<FrameConstants>();
+                }
+                2 => Engine::insert_resource(Score { value: 5 }),
+                3 => Engine::remove_resource::<Score>(),
+                _ => {}
+            }
+        }
+
+        fn read_score(inputs: SystemInputs) {
+            SCORES
+                .lock()
+                .unwrap()
+                .push(inputs.resource::<Score>(0).value);
         }
+
+        let mut engine = TestEngine::new([TestModule::new(c"resources")
+            .resource(|| Score { value: 1 })
+            .system(TestSystem::new(c"change_resources", change_resources))
+            .system(
+                TestSystem::new(c"read_score", read_score)
+                    .arg(TestArg::Resource(
+                        ArgType::DataAccessRef,
+                        Score::string_id(),
+                    ))
+                    .after(c"change_resources"),
+            )]);
+
+        // the system reading the resource is not run while it is removed
+        engine.frames(5);
+        assert_eq!(*SCORES.lock().unwrap(), [1, 5]);
+        assert!(engine.resource_mut(Score::id()).is_null());
+
+        // engine resources are kept
+        assert!(!engine.resource_mut(FrameConstants::id()).is_null());
     }
-}
 
-fn write_cpu_component_data<'a, F>(
-    entity_id: EntityId,
-    storage: &ArchetypeStorage,
-    component_registry: &ComponentRegistry,
-    mut buffer_data: &mut [MaybeUninit<u8>],
-    mut component_data: F,
-) where
-    F: FnMut(ComponentId) -> &'a [MaybeUninit<u8>],
-{
-    let mut write_offset_check = 0;
-    for component_offset_info in &storage.cpu.components {
-        debug_assert_eq!(write_offset_check, component_offset_info.offset);
+    #[test]
+    fn sparse_components_filter_queries() {
+        static STEP: AtomicU32 = AtomicU32::new(0);
+        static SPAWNED: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
+        static MATCHED: Mutex<Vec<(Vec<u32>, Vec<u32>)>> = Mutex::new(Vec::new());
+        static REMOVED: Mutex<Vec<EntityId>> = Mutex::new(Vec::new());
 
-        if component_offset_info.component_id == void_public::EntityId::id() {
-            // EntityId
-            let size = size_of::<void_public::EntityId>();
-            buffer_data[..size].copy_from_slice(
-                &NonZero::<u64>::from(entity_id)
-                    .get()
-                    .to_ne_bytes()
-                    .map(MaybeUninit::new),
-            );
-            buffer_data = &mut buffer_data[size..];
-            write_offset_check += size;
-        } else {
-            let data = component_data(component_offset_info.component_id);
-            let component_info = component_registry
-                .get(&component_offset_info.component_id)
-                .unwrap();
+        fn toggle_tags(inputs: SystemInputs) {
+            let mut tagged = Vec::new();
+            inputs
+                .query::<(&Health, With<Tagged>)>(0)
+                .for_each(|(health, _)| tagged.push(health.value));
 
-            if data.len() != component_info.size {
-                log::error!(
-                    "spawn: data for component {:?} has size {}, does not match expected size {}",
-                    component_info.name,
-                    data.len(),
-                    component_info.size
-                );
-                panic!();
+            let mut untagged = Vec::new();
+            inputs
+                .query::<(&Health, Without<Tagged>)>(1)
+                .for_each(|(health, _)| untagged.push(health.value));
+
+            MATCHED.lock().unwrap().push((tagged, untagged));
+
+            for (entity_id, _) in inputs.removed_components::<Tagged>(2).iter() {
+                REMOVED.lock().unwrap().push(entity_id);
             }
 
-            buffer_data[..data.len()].copy_from_slice(data);
-            buffer_data = &mut buffer_data[data.len()..];
-            write_offset_check += data.len();
+            let mut spawned = SPAWNED.lock().unwrap();
+            match STEP.fetch_add(1, Ordering::Relaxed) {
+                0 => {
+                    *spawned = vec![
+                        Engine::spawn(bundle!(&Health { value: 1 }, &Tagged)),
+                        Engine::spawn(bundle!(&Health { value: 2 })),
+                    ];
+                }
+                1 => {
+                    Engine::remove_components(spawned[0], &[Tagged::id()]);
+                    Engine::add_components(spawned[1], bundle!(&Tagged));
+                }
+                2 => Engine::despawn(spawned[1]),
+                _ => {}
+            }
         }
-    }
-}
 
-/// Initialize the `LocalToWorld` component based on `Transform` data, if
-/// needed.
-fn initialize_local_to_world_if_needed(
-    cpu_data: &mut CpuFrameData,
-    entity_index: usize,
-    storage: &ArchetypeStorage,
-) {
-    let mut buffer = cpu_data.get_buffer_mut(storage.cpu.buffer_index);
+        let mut engine = TestEngine::new([TestModule::new(c"sparse_queries")
+            .component::<Health>()
+            .sparse_component::<Tagged>()
+            .system(
+                TestSystem::new(c"toggle_tags", toggle_tags)
+                    .arg(TestArg::query(&[
+                        (ArgType::DataAccessRef, Health::string_id()),
+                        (ArgType::With, Tagged::string_id()),
+                    ]))
+                    .arg(TestArg::query(&[
+                        (ArgType::DataAccessRef, Health::string_id()),
+                        (ArgType::Without, Tagged::string_id()),
+                    ]))
+                    .arg(TestArg::RemovedComponents(Tagged::string_id())),
+            )]);
+
+        engine.frames(5);
+
+        assert_eq!(
+            MATCHED.lock().unwrap()[..4],
+            [
+                (vec![], vec![]),
+                (vec![1], vec![2]),
+                (vec![2], vec![1]),
+                (vec![], vec![1]),
+            ]
+        );
 
-    let Some(transform_state) =
-        (unsafe { get_data_from_buffer::<Transform>(&buffer, entity_index, storage) })
-    else {
-        return;
-    };
+        // the tag is reported as removed when it is removed, and when its
+        // entity is despawned
+        let spawned = SPAWNED.lock().unwrap().clone();
+        assert_eq!(*REMOVED.lock().unwrap(), spawned);
+        assert!(
+            engine
+                .frame_update
+                .sparse_components
+                .components(spawned[1].into())
+                .next()
+                .is_none()
+        );
+    }
 
-    // create the initial matrix
-    let initial_matrix = Mat4::from_scale_rotation_translation(
-        transform_state.scale.extend(1.),
-        Quat::from_rotation_z(transform_state.rotation),
-        *transform_state.position,
-    );
+    #[test]
+    fn sparse_components_are_spawned_from_scenes() {
+        static SCENE: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+        static STEP: AtomicU32 = AtomicU32::new(0);
 
-    // write it back to the LocalToWorld component
-    let local_to_world_state =
-        unsafe { get_data_from_buffer_mut::<LocalToWorld>(&mut buffer, entity_index, storage) }
-            .expect("LocalToWorld is expected if Transform is present");
-    *local_to_world_state = initial_matrix.into();
-}
+        fn load_scene(_: SystemInputs) {
+            if STEP.fetch_add(1, Ordering::Relaxed) == 0 {
+                Engine::load_scene(&SCENE.lock().unwrap());
+            }
+        }
 
-unsafe fn get_data_from_buffer<'a, T>(
-    data_buffer: &'a CpuDataBufferRefMut<'a>,
-    entity_index: usize,
-    archetype_storage: &ArchetypeStorage,
-) -> Option<&'a T>
-where
-    T: Component,
-{
-    let data_offset = archetype_storage.get_component_byte_offset(&T::id())?;
-    unsafe { data_buffer.get_with_offset_as::<T>(entity_index, data_offset) }
-}
+        let new_engine = || {
+            STEP.store(0, Ordering::Relaxed);
+            TestEngine::new([TestModule::new(c"sparse_scenes")
+                .component::<Health>()
+                .sparse_component::<Tagged>()
+                .system(TestSystem::new(c"load_scene", load_scene))])
+        };
 
-unsafe fn get_data_from_buffer_mut<'a, T>(
-    data_buffer: &'a mut CpuDataBufferRefMut<'a>,
-    entity_index: usize,
-    archetype_storage: &ArchetypeStorage,
-) -> Option<&'a mut T>
-where
-    T: Component,
-{
-    let data_offset = archetype_storage.get_component_byte_offset(&T::id())?;
-    unsafe { data_buffer.get_mut_with_offset_as::<T>(entity_index, data_offset) }
-}
+        let health = Health::string_id().to_str().unwrap();
+        let tagged = Tagged::string_id().to_str().unwrap();
+        let version = game_scene::VERSION;
+
+        let json_scene = format!(
+            r#"{{
+                "version": "{version}",
+                "entities": [
+                    {{ "id": "tagged", "label": "tagged", "components": {{ "{health}": {{ "value": 1 }}, "{tagged}": {{}} }} }},
+                    {{ "id": "untagged", "label": "untagged", "components": {{ "{health}": {{ "value": 2 }} }} }}
+                ]
+            }}"#
+        );
 
-#[derive(Debug, Default)]
-struct FrameTimer {
-    accumulated_time: f32,
-    num_frames: u32,
-    average_frame_time: f32,
-}
+        *SCENE.lock().unwrap() = json_scene.clone().into_bytes();
+        let engine = new_engine();
+        let binary_scene = engine.convert_scene_to_binary(&json_scene).unwrap();
+        drop(engine);
+
+        for scene in [json_scene.into_bytes(), binary_scene] {
+            *SCENE.lock().unwrap() = scene;
+            let mut engine = new_engine();
+            engine.frames(1);
+
+            let tagged = engine.get_entity_from_label(c"tagged").unwrap();
+            let untagged = engine.get_entity_from_label(c"untagged").unwrap();
+
+            // the tag is not part of the entity's archetype
+            assert_eq!(engine.component::<Health>(tagged.into()).unwrap().value, 1);
+            assert!(
+                !engine.frame_update.world[tagged]
+                    .archetype_key
+                    .component_ids
+                    .contains(&Tagged::id())
+            );
 
-impl FrameTimer {
-    pub fn update_frame(&mut self, delta_time: f32) {
-        self.accumulated_time += delta_time;
-        self.num_frames += 1;
-        if self.accumulated_time >= 1.0 && self.num_frames >= 120 {
-            self.average_frame_time = self.accumulated_time / self.num_frames as f32;
-            self.accumulated_time = 0.;
-            self.num_frames = 0;
+            let sparse_components = &engine.frame_update.sparse_components;
+            assert!(sparse_components.contains(&Tagged::id(), tagged));
+            assert!(!sparse_components.contains(&Tagged::id(), untagged));
         }
     }
 
-    pub fn get_average_frame_time(&self) -> f32 {
-        self.average_frame_time
-    }
+    #[test]
+    fn systems_accessing_sparse_component_data_are_not_registered() {
+        static EXECUTED: Mutex<Vec<&str>> = Mutex::new(Vec::new());
 
-    pub fn get_average_fps(&self) -> f32 {
-        if self.get_average_frame_time() == 0.0 {
-            0.
-        } else {
-            1. / self.get_average_frame_time()
+        fn read_tags(_: SystemInputs) {
+            EXECUTED.lock().unwrap().push("read_tags");
+        }
+
+        fn filter_tags(_: SystemInputs) {
+            EXECUTED.lock().unwrap().push("filter_tags");
         }
+
+        let mut engine = TestEngine::new([TestModule::new(c"sparse_data")
+            .component::<Health>()
+            .sparse_component::<Tagged>()
+            .system(
+                TestSystem::new(c"read_tags", read_tags).arg(TestArg::query(&[
+                    (ArgType::DataAccessRef, Health::string_id()),
+                    (ArgType::DataAccessRef, Tagged::string_id()),
+                ])),
+            )
+            .system(
+                TestSystem::new(c"filter_tags", filter_tags).arg(TestArg::query(&[
+                    (ArgType::DataAccessRef, Health::string_id()),
+                    (ArgType::With, Tagged::string_id()),
+                ])),
+            )]);
+
+        engine.frames(1);
+
+        assert_eq!(*EXECUTED.lock().unwrap(), ["filter_tags"]);
+        assert_eq!(engine.system_enabled("sparse_data::read_tags"), None);
     }
 }
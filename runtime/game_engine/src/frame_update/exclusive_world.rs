@@ -0,0 +1,369 @@
+use std::ptr::{NonNull, null_mut};
+
+use game_ecs::{
+    SystemErrorPolicy, SystemInfo, WorldDelegate, add_components_helper,
+    cpu_frame_data::CpuDataBuffer, with_system_execute_resources,
+};
+use game_entity::ParentType;
+use game_world::World;
+
+use super::*;
+use crate::exclusive_system::ExclusiveSystem;
+
+/// The `World` input of exclusive systems, giving them mutable access to the
+/// whole engine state. Structural changes are applied immediately, by issuing
+/// the same commands as other systems and applying them right away.
+pub struct ExclusiveWorld<'a, P: Platform, G: Gpu> {
+    frame_update: &'a mut FrameUpdate<P, G>,
+    event_manager: &'a mut EventManager<P>,
+    cpu_data: &'a mut CpuFrameData,
+    gpu_data: &'a mut G,
+    component_registry: &'a ComponentRegistry,
+    modules: &'a HashMap<String, Box<dyn EcsModule>>,
+    /// The change tick of the executing system.
+    change_tick: u64,
+}
+
+impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
+    /// Executes the exclusive systems which should run, one at a time. Pending
+    /// commands must already be applied.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn execute_exclusive_systems(
+        &mut self,
+        event_manager: &mut EventManager<P>,
+        cpu_data: &mut CpuFrameData,
+        gpu_data: &mut G,
+        component_registry: &ComponentRegistry,
+        modules: &HashMap<String, Box<dyn EcsModule>>,
+        input_buffer: &[u8],
+        callables: &Callables,
+    ) {
+        let mut exclusive_systems = self.system_graph.take_exclusive_systems();
+
+        for system_info in &mut exclusive_systems {
+            if !system_info.should_run(&self.states) {
+                continue;
+            }
+
+            let change_tick = self.system_graph.increment_change_tick();
+            let error_policy = self.system_graph.error_policy();
+
+            let mut world = ExclusiveWorld {
+                frame_update: self,
+                event_manager,
+                cpu_data,
+                gpu_data,
+                component_registry,
+                modules,
+                change_tick,
+            };
+
+            let start = P::Clock::now();
+            execute_exclusive_system(
+                system_info,
+                &mut world,
+                input_buffer,
+                callables,
+                change_tick,
+                error_policy,
+            );
+            self.system_graph
+                .profiler()
+                .record::<P>(system_info.profiler_scope(), start);
+        }
+
+        self.system_graph
+            .restore_exclusive_systems(exclusive_systems);
+    }
+}
+
+/// Executes an exclusive system, which may also call `Engine` functions. Their
+/// commands are applied along with the next change made through the `World`,
+/// or when the system returns.
+fn execute_exclusive_system<P: Platform, G: Gpu>(
+    system_info: &mut SystemInfo<P, G>,
+    world: &mut ExclusiveWorld<'_, P, G>,
+    input_buffer: &[u8],
+    callables: &Callables,
+    change_tick: u64,
+    error_policy: SystemErrorPolicy,
+) {
+    let world_ptr: *mut ExclusiveWorld<'_, P, G> = world;
+
+    {
+        // SAFETY: exclusive systems execute on this thread, and `Engine`
+        // functions never execute concurrently with `World` functions, so the
+        // resources are not accessed while the `World` mutates the engine.
+        let world = unsafe { &*world_ptr };
+        let frame_update = &*world.frame_update;
+
+        let resources = EcsSystemExecuteResources {
+            cpu_data: world.cpu_data,
+            gpu_data: world.gpu_data,
+            event_manager: world.event_manager,
+            input_buffer,
+            world_delegate: &ExclusiveWorldDelegate {
+                world: unsafe { &raw mut (*world_ptr).frame_update.world },
+            },
+            component_bundles: &frame_update.gpu_component_bundles,
+            component_registry: world.component_registry,
+            callables,
+            states: &frame_update.states,
+            removed_components: &frame_update.removed_components,
+            sparse_components: &frame_update.sparse_components,
+            prefab_names: &frame_update.prefab_names,
+        };
+
+        with_system_execute_resources(&resources, || {
+            ExclusiveSystem::<P, G>::with_world(world_ptr.cast(), || {
+                system_info.execute(change_tick, error_policy);
+            });
+        });
+    }
+
+    world.apply_changes();
+}
+
+/// The `WorldDelegate` of `Engine` functions called from exclusive systems.
+///
+/// Unlike `SyncWorldDelegate`, which reserves entity ids when it is dropped,
+/// this reserves them as they are allocated, since the `World` input may spawn
+/// entities before the `Engine` commands are applied.
+struct ExclusiveWorldDelegate {
+    world: *mut World,
+}
+
+// SAFETY: see `execute_exclusive_system()`.
+unsafe impl Sync for ExclusiveWorldDelegate {}
+
+impl ExclusiveWorldDelegate {
+    fn world(&self) -> &World {
+        unsafe { &*self.world }
+    }
+}
+
+impl WorldDelegate for ExclusiveWorldDelegate {
+    fn allocate_entity_id(&self) -> EntityId {
+        unsafe { (*self.world).sync_delegate().allocate_entity_id() }
+    }
+
+    fn allocate_entity_ids(&self, count: usize) -> Vec<EntityId> {
+        unsafe { (*self.world).sync_delegate().allocate_entity_ids(count) }
+    }
+
+    fn label_entity(&self, label: &CStr) -> Option<EntityId> {
+        self.world().label_entity(label)
+    }
+
+    fn entity_label(&self, entity_id: EntityId) -> Option<&CStr> {
+        self.world().entity_label(entity_id)
+    }
+
+    fn get_parent_type(&self, entity_id: EntityId) -> Option<ParentType> {
+        let entity_data = self.world().get(entity_id)?;
+        Some(
+            entity_data
+                .parent_id
+                .map_or(ParentType::Root, ParentType::Parent),
+        )
+    }
+
+    fn children(&self, entity_id: EntityId) -> Option<&[EntityId]> {
+        self.world()
+            .get(entity_id)
+            .map(|entity_data| entity_data.child_ids.as_slice())
+    }
+}
+
+impl<P: Platform, G: Gpu> ExclusiveWorld<'_, P, G> {
+    /// # Safety
+    ///
+    /// The contents of `ComponentRef` objects are type-erased, and must be valid.
+    pub unsafe fn spawn(
+        &mut self,
+        components: &[ComponentRef<'_>],
+    ) -> Result<EntityId, Box<dyn Error + Send + Sync>> {
+        let entity_id = {
+            let frame_update = &mut *self.frame_update;
+            let event_manager = &*self.event_manager;
+            let world_delegate = frame_update.world.sync_delegate();
+
+            add_components_helper(
+                components,
+                &frame_update.gpu_component_bundles,
+                |components_len, closure| {
+                    let entity_id = world_delegate.allocate_entity_id();
+                    event_manager.command_spawn(entity_id, components_len, closure);
+                    entity_id
+                },
+            )?
+        };
+
+        self.apply_changes();
+
+        Ok(entity_id)
+    }
+
+    /// Returns `false` if the entity does not exist.
+    pub fn despawn(&mut self, entity_id: EntityId, recursive: bool) -> bool {
+        if self.frame_update.world.get(entity_id).is_none() {
+            return false;
+        }
+
+        self.event_manager.command_despawn(entity_id, recursive);
+        self.apply_changes();
+
+        true
+    }
+
+    /// # Safety
+    ///
+    /// The contents of `ComponentRef` objects are type-erased, and must be valid.
+    pub unsafe fn add_components(
+        &mut self,
+        entity_id: EntityId,
+        components: &[ComponentRef<'_>],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if self.frame_update.world.get(entity_id).is_none() {
+            return Err("entity does not exist".into());
+        }
+
+        let event_manager = &*self.event_manager;
+
+        add_components_helper(
+            components,
+            &self.frame_update.gpu_component_bundles,
+            |components_len, closure| {
+                event_manager.command_add_components(entity_id, components_len, closure);
+            },
+        )?;
+
+        self.apply_changes();
+
+        Ok(())
+    }
+
+    /// Returns `false` if the entity does not exist.
+    pub fn remove_components(
+        &mut self,
+        entity_id: EntityId,
+        component_ids: &[ComponentId],
+    ) -> bool {
+        if self.frame_update.world.get(entity_id).is_none() {
+            return false;
+        }
+
+        self.event_manager
+            .command_remove_components(entity_id, component_ids);
+        self.apply_changes();
+
+        true
+    }
+
+    /// Returns a pointer to a component of an entity, or null if the entity
+    /// does not have the component. If `mutable` is `true`, the component is
+    /// marked as changed.
+    ///
+    /// Sparse components have no data, so a dangling pointer is returned for
+    /// them.
+    pub fn component(
+        &mut self,
+        entity_id: EntityId,
+        component_id: ComponentId,
+        mutable: bool,
+    ) -> *mut MaybeUninit<u8> {
+        if self.component_registry.is_sparse(&component_id) {
+            return if self
+                .frame_update
+                .sparse_components
+                .contains(&component_id, entity_id)
+            {
+                NonNull::dangling().as_ptr()
+            } else {
+                null_mut()
+            };
+        }
+
+        let ptr = self.frame_update.entity_component_data_mut(
+            entity_id,
+            component_id,
+            self.cpu_data,
+            self.gpu_data,
+        );
+
+        if mutable && !ptr.is_null() {
+            let entity_data = &self.frame_update.world[entity_id];
+            let storage = &self.frame_update.archetypes[&entity_data.archetype_key];
+            let ticks_offset = storage.get_component_ticks_offset(&component_id).unwrap();
+
+            let mut buffer = self.cpu_data.get_buffer_mut(storage.cpu.buffer_index);
+            let ticks = unsafe {
+                buffer.get_mut_with_offset_as::<ComponentTicks>(
+                    entity_data.archetype_index,
+                    ticks_offset,
+                )
+            }
+            .unwrap();
+            ticks.changed = self.change_tick;
+        }
+
+        ptr
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.frame_update.world.entities()
+    }
+
+    /// Returns all components of an entity, including sparse components, or
+    /// `None` if the entity does not exist.
+    pub fn entity_components(&self, entity_id: EntityId) -> Option<Vec<ComponentId>> {
+        let entity_data = self.frame_update.world.get(entity_id)?;
+
+        let mut component_ids = entity_data.archetype_key.component_ids.clone();
+        component_ids.extend(self.frame_update.sparse_components.components(entity_id));
+
+        Some(component_ids)
+    }
+
+    /// Returns the number of archetypes. Archetypes are indexed in an
+    /// unspecified order, which may change after any structural change.
+    pub fn archetypes_len(&self) -> usize {
+        self.frame_update.archetypes.values().count()
+    }
+
+    pub fn archetype_components(&self, archetype_index: usize) -> Option<&[ComponentId]> {
+        (&self.frame_update.archetypes)
+            .into_iter()
+            .nth(archetype_index)
+            .map(|(archetype_key, _)| archetype_key.component_ids.as_slice())
+    }
+
+    pub fn archetype_entities(&self, archetype_index: usize) -> Option<Vec<EntityId>> {
+        let storage = self.frame_update.archetypes.values().nth(archetype_index)?;
+        let entity_id_offset = storage.cpu.entity_id_offset.unwrap();
+        let buffer = self.cpu_data.borrow_buffer(storage.cpu.buffer_index);
+
+        let entities = (0..buffer.len())
+            .map(|index| unsafe {
+                *<CpuDataBuffer as FrameDataBufferBorrowRef>::get_with_offset_as::<EntityId>(
+                    &buffer,
+                    index,
+                    entity_id_offset,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        Some(entities)
+    }
+
+    fn apply_changes(&mut self) {
+        self.frame_update.apply_current_frame_changes(
+            self.event_manager,
+            self.cpu_data,
+            self.gpu_data,
+            self.component_registry,
+            self.modules,
+        );
+    }
+}
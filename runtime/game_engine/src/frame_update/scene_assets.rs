@@ -0,0 +1,370 @@
+//! Assets referenced by the components of scene entities, by their asset path
+//! or name. See `void_public::reflect` for how asset reference fields are
+//! declared.
+
+use game_asset::{
+    ecs_module::TextAssetManager,
+    particles::ParticleEffectDescriptor,
+    resource_managers::text_asset_manager::{PendingText, Text},
+};
+use game_scene::{SceneAssetRef, SceneTable};
+use void_public::{AssetId, material::MaterialId, reflect::FieldType, text::TextId};
+
+use super::*;
+
+/// An asset referenced by a scene. Scenes loaded from an asset path are
+/// spawned once all of their assets are resolved.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum SceneAsset {
+    Texture(TextureId),
+    Text(TextId),
+    /// A material whose definition is being read, by name.
+    Material(String),
+    /// A particle effect descriptor, whose JSON is being read.
+    ParticleEffect(AssetId),
+}
+
+/// A particle effect descriptor, whose JSON is being read as a text asset.
+pub(super) struct PendingParticleDescriptor {
+    asset_id: AssetId,
+    text_id: TextId,
+}
+
+impl<P: Platform, G: Gpu> FrameUpdate<P, G> {
+    /// Assigns the ids of the assets referenced by scene entities, loading any
+    /// textures, texts and particle effect descriptors which are not loaded
+    /// yet. Returns the scene's assets, without duplicates.
+    ///
+    /// Materials are referenced by name, and only assigned if they are
+    /// registered. Materials which are still being read may be assigned once
+    /// they are resolved, with [`Self::assign_scene_materials()`].
+    pub(super) fn load_scene_assets(
+        &mut self,
+        scene_entities: &mut SceneEntities,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) -> Vec<SceneAsset> {
+        let mut assets = Vec::new();
+        let mut process_batched_textures = false;
+
+        for (index, scene_entity) in scene_entities.entities.iter_mut().enumerate() {
+            for asset_ref in &scene_entity.asset_refs {
+                let asset = match asset_ref.field_type {
+                    FieldType::TextureId => {
+                        process_batched_textures = true;
+                        SceneAsset::Texture(Self::load_texture_from_path(
+                            &asset_ref.asset_path,
+                            cpu_data,
+                            component_registry,
+                        ))
+                    }
+                    FieldType::TextId => SceneAsset::Text(Self::load_text_from_path(
+                        &asset_ref.asset_path,
+                        cpu_data,
+                        component_registry,
+                    )),
+                    FieldType::ParticleEffectId => {
+                        SceneAsset::ParticleEffect(self.load_particle_descriptor(
+                            &asset_ref.asset_path,
+                            cpu_data,
+                            component_registry,
+                        ))
+                    }
+                    // assigned below
+                    _ => continue,
+                };
+
+                let id = match &asset {
+                    SceneAsset::Texture(texture_id) => texture_id.0,
+                    SceneAsset::Text(text_id) => text_id.0.get(),
+                    SceneAsset::ParticleEffect(asset_id) => asset_id.0,
+                    SceneAsset::Material(_) => unreachable!(),
+                };
+
+                write_asset_id(
+                    &mut scene_entity.components,
+                    &mut scene_entities.tables,
+                    index,
+                    asset_ref,
+                    id,
+                );
+                assets.push(asset);
+            }
+        }
+
+        if process_batched_textures {
+            cpu_data.get_resource_mut(component_registry, |gpu_interface: &mut GpuInterface| {
+                gpu_interface
+                    .texture_asset_manager
+                    .trigger_batched_textures();
+            });
+        }
+
+        assets.extend(
+            Self::assign_scene_materials(scene_entities, cpu_data, component_registry)
+                .into_iter()
+                .map(SceneAsset::Material),
+        );
+
+        assets.sort_unstable();
+        assets.dedup();
+        assets
+    }
+
+    /// Assigns the ids of the materials referenced by scene entities, by name.
+    /// Returns the names of the materials which are still being read.
+    pub(super) fn assign_scene_materials(
+        scene_entities: &mut SceneEntities,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) -> Vec<String> {
+        cpu_data.get_resource_mut(component_registry, |gpu_interface: &mut GpuInterface| {
+            let material_manager = &gpu_interface.material_manager;
+            let mut pending_materials = Vec::new();
+
+            for (index, scene_entity) in scene_entities.entities.iter_mut().enumerate() {
+                for asset_ref in &scene_entity.asset_refs {
+                    if asset_ref.field_type != FieldType::MaterialId {
+                        continue;
+                    }
+
+                    let material_name = asset_ref.asset_path.as_str();
+
+                    if let Some(material_id) =
+                        material_manager.get_material_id_by_name(material_name)
+                    {
+                        write_asset_id(
+                            &mut scene_entity.components,
+                            &mut scene_entities.tables,
+                            index,
+                            asset_ref,
+                            material_id.0,
+                        );
+                    } else if material_manager.is_material_pending(material_name) {
+                        pending_materials.push(material_name.to_owned());
+                    } else {
+                        log::warn!("Scene references unknown material {material_name}");
+                    }
+                }
+            }
+
+            pending_materials
+        })
+    }
+
+    /// Returns whether a scene asset finished loading, successfully or not.
+    pub(super) fn is_scene_asset_resolved(
+        &self,
+        asset: &SceneAsset,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) -> bool {
+        match asset {
+            SceneAsset::Texture(texture_id) => {
+                cpu_data.get_resource(component_registry, |gpu_interface: &GpuInterface| {
+                    gpu_interface
+                        .texture_asset_manager
+                        .is_texture_resolved(*texture_id)
+                })
+            }
+            SceneAsset::Text(text_id) => cpu_data.get_resource(
+                component_registry,
+                |text_asset_manager: &TextAssetManager| {
+                    text_asset_manager.is_text_resolved(*text_id)
+                },
+            ),
+            SceneAsset::Material(material_name) => {
+                cpu_data.get_resource(component_registry, |gpu_interface: &GpuInterface| {
+                    !gpu_interface
+                        .material_manager
+                        .is_material_pending(material_name)
+                })
+            }
+            SceneAsset::ParticleEffect(asset_id) => !self
+                .pending_particle_descriptors
+                .iter()
+                .any(|pending| pending.asset_id == *asset_id),
+        }
+    }
+
+    /// Parses the particle effect descriptors whose JSON finished loading.
+    pub(super) fn update_particle_descriptors(
+        &mut self,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) {
+        if self.pending_particle_descriptors.is_empty() {
+            return;
+        }
+
+        let mut descriptors = Vec::new();
+
+        cpu_data.get_resource(
+            component_registry,
+            |text_asset_manager: &TextAssetManager| {
+                self.pending_particle_descriptors.retain(|pending| {
+                    match text_asset_manager.get_text_by_id(pending.text_id) {
+                        Some(Text::Loaded(loaded_text)) => {
+                            match ParticleEffectDescriptor::from_json(loaded_text.raw_text()) {
+                                Ok(descriptor) => descriptors.push((pending.asset_id, descriptor)),
+                                Err(e) => log::warn!(
+                                    "Invalid particle effect descriptor {}: {e}",
+                                    loaded_text.text_path()
+                                ),
+                            }
+                            false
+                        }
+                        Some(Text::Failed(failed_text)) => {
+                            log::warn!(
+                                "Unable to load particle effect descriptor {}: {}",
+                                failed_text.text_path(),
+                                failed_text.failure_reason()
+                            );
+                            false
+                        }
+                        _ => true,
+                    }
+                });
+            },
+        );
+
+        cpu_data.get_resource_mut(component_registry, |gpu_interface: &mut GpuInterface| {
+            gpu_interface
+                .particle_effect_descriptors
+                .extend(descriptors);
+        });
+    }
+
+    /// Returns the asset path of the asset referenced by an asset reference
+    /// field, or the name of a material, if the asset is known.
+    pub(super) fn scene_asset_path(
+        &self,
+        field_type: FieldType,
+        id: u32,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) -> Option<String> {
+        match field_type {
+            FieldType::TextureId => {
+                cpu_data.get_resource(component_registry, |gpu_interface: &GpuInterface| {
+                    gpu_interface
+                        .texture_asset_manager
+                        .get_texture_by_id(TextureId(id))
+                        .map(|texture| texture.path().to_string())
+                })
+            }
+            FieldType::TextId => cpu_data.get_resource(
+                component_registry,
+                |text_asset_manager: &TextAssetManager| {
+                    NonZero::new(id)
+                        .and_then(|id| text_asset_manager.get_text_by_id(TextId(id)))
+                        .map(|text| text.path().to_string())
+                },
+            ),
+            FieldType::MaterialId => {
+                cpu_data.get_resource(component_registry, |gpu_interface: &GpuInterface| {
+                    gpu_interface
+                        .material_manager
+                        .get_material(MaterialId(id))
+                        .map(|material| material.name().to_owned())
+                })
+            }
+            FieldType::ParticleEffectId => self
+                .particle_descriptors
+                .iter()
+                .find(|(_, asset_id)| asset_id.0 == id)
+                .map(|(asset_path, _)| asset_path.to_string()),
+            _ => None,
+        }
+    }
+
+    fn load_text_from_path(
+        asset_path: &str,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) -> TextId {
+        cpu_data.get_resource_mut(
+            component_registry,
+            |text_asset_manager: &mut TextAssetManager| {
+                if let Some(text) = text_asset_manager.get_text_by_path(&asset_path.into()) {
+                    text.id()
+                } else {
+                    let text_id = text_asset_manager.register_next_text_id();
+                    let pending_text = PendingText::new(text_id, &asset_path.into(), false);
+                    text_asset_manager.add_to_batched_text(pending_text);
+                    text_id
+                }
+            },
+        )
+    }
+
+    /// Returns the id of a particle effect descriptor, and starts reading it
+    /// if it was not read yet.
+    fn load_particle_descriptor(
+        &mut self,
+        asset_path: &str,
+        cpu_data: &mut CpuFrameData,
+        component_registry: &ComponentRegistry,
+    ) -> AssetId {
+        if let Some(asset_id) = self.particle_descriptors.get(&asset_path.into()) {
+            return *asset_id;
+        }
+
+        let asset_id = cpu_data.get_resource(component_registry, |gpu_interface: &GpuInterface| {
+            (1..)
+                .map(AssetId)
+                .find(|asset_id| {
+                    !gpu_interface
+                        .particle_effect_descriptors
+                        .contains_key(asset_id)
+                        && !self.particle_descriptors.values().any(|id| id == asset_id)
+                })
+                .unwrap()
+        });
+
+        let text_id = Self::load_text_from_path(asset_path, cpu_data, component_registry);
+
+        self.particle_descriptors
+            .insert(asset_path.into(), asset_id);
+        self.pending_particle_descriptors
+            .push(PendingParticleDescriptor { asset_id, text_id });
+
+        asset_id
+    }
+}
+
+/// Writes the id of an asset into the field of the component of the scene
+/// entity at `index`, which is stored with the entity, or in its table.
+fn write_asset_id(
+    components: &mut [ComponentData],
+    tables: &mut [SceneTable],
+    index: usize,
+    asset_ref: &SceneAssetRef,
+    id: u32,
+) {
+    let data = if let Some(component) = components
+        .iter_mut()
+        .find(|component| component.component_id() == Some(asset_ref.component_id))
+    {
+        component.component_data_mut()
+    } else if let Some(data) = tables
+        .iter_mut()
+        .find_map(|table| table.component_data_mut(index, asset_ref.component_id))
+    {
+        data
+    } else {
+        return;
+    };
+
+    if asset_ref.offset + size_of::<u32>() > data.len() {
+        log::warn!("Asset reference field is out of bounds of its component");
+        return;
+    }
+
+    unsafe {
+        data.as_mut_ptr()
+            .add(asset_ref.offset)
+            .cast::<u32>()
+            .write_unaligned(id);
+    }
+}
@@ -9,7 +9,9 @@ impl<P: Platform, G: Gpu> Serialize for FrameUpdate<P, G> {
     {
         self.archetypes.serialize(serializer)?;
         self.system_graph.serialize(serializer)?;
-        self.world.serialize(serializer)
+        self.world.serialize(serializer)?;
+        self.sparse_components.serialize(serializer)?;
+        self.scene_instances.serialize(serializer)
     }
 }
 
@@ -28,7 +30,9 @@ impl<P: Platform, G: Gpu> Deserialize for FrameUpdate<P, G> {
         unsafe {
             self.archetypes.deserialize_in_place(deserializer)?;
             self.system_graph.deserialize_in_place(deserializer)?;
-            self.world.deserialize_in_place(deserializer)
+            self.world.deserialize_in_place(deserializer)?;
+            self.sparse_components.deserialize_in_place(deserializer)?;
+            self.scene_instances.deserialize_in_place(deserializer)
         }
     }
 }
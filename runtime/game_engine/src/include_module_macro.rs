@@ -29,23 +29,11 @@ macro_rules! include_module {
                 }
 
                 fn init(&self) -> Result<(), Box<dyn ::std::error::Error + Send + Sync>> {
-                    let res = $($module_path ::)*init();
-
-                    if res == 0 {
-                        Ok(())
-                    } else {
-                        Err(format!("error code ({res})").into())
-                    }
+                    $crate::platform::module_fn_result($($module_path ::)*init())
                 }
 
                 fn deinit(&self) -> Result<(), Box<dyn ::std::error::Error + Send + Sync>> {
-                    let res = $($module_path ::)*deinit();
-
-                    if res == 0 {
-                        Ok(())
-                    } else {
-                        Err(format!("error code ({res})").into())
-                    }
+                    $crate::platform::module_fn_result($($module_path ::)*deinit())
                 }
 
                 fn module_name(&self) -> ::std::borrow::Cow<'_, str> {
@@ -104,6 +92,18 @@ macro_rules! include_module {
                     )
                 }
 
+                fn component_serialize_json(
+                    &self,
+                    string_id: &::std::ffi::CStr,
+                    val: &[::std::mem::MaybeUninit<u8>],
+                ) -> Result<String, Box<dyn ::std::error::Error + Send + Sync>> {
+                    $crate::void_public_module::component_serialize_json_ffi(
+                        $($module_path ::)*component_serialize_json,
+                        string_id,
+                        val,
+                    )
+                }
+
                 fn component_string_id(&self, index: usize) -> Option<::std::borrow::Cow<'_, ::std::ffi::CStr>> {
                     unsafe {
                         let ptr = $($module_path ::)*component_string_id(index);
@@ -127,6 +127,17 @@ macro_rules! include_module {
                     unsafe { $($module_path ::)*component_type(string_id.as_ptr()) }
                 }
 
+                fn component_fields(&self, string_id: &::std::ffi::CStr) -> Vec<$crate::void_public::reflect::ComponentField> {
+                    unsafe {
+                        let mut fields_len = 0;
+                        let fields = $($module_path ::)*component_fields(string_id.as_ptr(), &mut fields_len);
+                        ::std::slice::from_raw_parts(fields, fields_len)
+                            .iter()
+                            .map(|field| $crate::void_public::reflect::ComponentField::from_field_info(field))
+                            .collect()
+                    }
+                }
+
                 fn component_async_completion_callable(&self, string_id: &::std::ffi::CStr) -> ::std::borrow::Cow<'_, ::std::ffi::CStr> {
                     unsafe {
                         let ptr = $($module_path ::)*component_async_completion_callable(string_id.as_ptr());
@@ -150,6 +161,38 @@ macro_rules! include_module {
                     $($module_path ::)*system_is_once(system_index)
                 }
 
+                fn system_is_fixed(&self, system_index: usize) -> bool {
+                    $($module_path ::)*system_is_fixed(system_index)
+                }
+
+                fn system_orderings_len(&self, system_index: usize) -> usize {
+                    $($module_path ::)*system_orderings_len(system_index)
+                }
+
+                fn system_ordering_type(&self, system_index: usize, ordering_index: usize) -> $crate::void_public::system::SystemOrdering {
+                    $($module_path ::)*system_ordering_type(system_index, ordering_index)
+                }
+
+                fn system_ordering_target(&self, system_index: usize, ordering_index: usize) -> ::std::borrow::Cow<'_, ::std::ffi::CStr> {
+                    unsafe { ::std::ffi::CStr::from_ptr($($module_path ::)*system_ordering_target(system_index, ordering_index)).into() }
+                }
+
+                fn system_state_conditions_len(&self, system_index: usize) -> usize {
+                    $($module_path ::)*system_state_conditions_len(system_index)
+                }
+
+                fn system_state_condition_type(&self, system_index: usize, condition_index: usize) -> $crate::void_public::state::StateCondition {
+                    $($module_path ::)*system_state_condition_type(system_index, condition_index)
+                }
+
+                fn system_state_condition_state(&self, system_index: usize, condition_index: usize) -> ::std::borrow::Cow<'_, ::std::ffi::CStr> {
+                    unsafe { ::std::ffi::CStr::from_ptr($($module_path ::)*system_state_condition_state(system_index, condition_index)).into() }
+                }
+
+                fn system_state_condition_value(&self, system_index: usize, condition_index: usize) -> u32 {
+                    $($module_path ::)*system_state_condition_value(system_index, condition_index)
+                }
+
                 fn system_fn(&self, system_index: usize) -> Box<dyn $crate::platform::EcsSystemFn> {
                     Box::new($crate::include_module_macro::EcsSystemFnC($($module_path ::)*system_fn(system_index)))
                 }
@@ -210,12 +253,6 @@ impl EcsSystemFn for EcsSystemFnC {
         &mut self,
         ptr: *const *const c_void,
     ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let res = unsafe { (self.0)(ptr) };
-
-        if res == 0 {
-            Ok(())
-        } else {
-            Err(format!("error code ({res})").into())
-        }
+        platform::module_fn_result(unsafe { (self.0)(ptr) })
     }
 }
@@ -10,7 +10,8 @@ use std::{
 pub use cpu_system::module_api;
 pub use event;
 use event::{EventManager, PlatformEventDelegate, platform_event_iter};
-use frame_update::FrameUpdate;
+use flatbuffers::FlatBufferBuilder;
+use frame_update::{FrameUpdate, SceneEvent};
 use game_asset::{
     ecs_module::GpuInterface,
     resource_managers::texture_asset_manager::{FormatType, LoadedTexture, TextureAssetManager},
@@ -18,27 +19,38 @@ use game_asset::{
 pub use game_ecs;
 use game_ecs::{
     CallableInfo, Callables, ComponentInfo, ComponentRegistry, CpuFrameData, EcsTypeInfo,
-    FrameDataBufferBorrowRefMut,
+    FrameDataBufferBorrowRef, FrameDataBufferBorrowRefMut, ScopeStats, SystemErrorPolicy,
 };
 use game_entity::EntityId;
 pub use game_input_manager;
 use game_input_manager::InputManager;
 use gpu_common::Gpu;
 use gpu_web::ecs_module::CameraRenderResource;
+use json::JsonValue;
 pub use platform;
 use platform::{EcsModule, Executor, Platform, PlatformLibrary};
 pub use void_public;
 use void_public::{
-    Aspect, ComponentId, FrameConfig, api_version_compatible, api_version_major, api_version_minor,
-    api_version_patch, callable::TaskId, event::input::WindowResized, graphics::TextureId,
+    Aspect, AssetPath, ComponentId, FrameConfig, FrameConstants, SceneHandle,
+    api_version_compatible, api_version_major, api_version_minor, api_version_patch,
+    callable::TaskId,
+    event::{
+        engine::{EngineError, EngineErrorT, SceneLoadProgress, SceneLoaded, SceneUnloaded},
+        input::WindowResized,
+    },
+    event_name,
+    graphics::TextureId,
     input::InputState,
 };
 pub use void_public_module;
 
 pub mod c_api;
 mod cpu_system;
+mod exclusive_system;
 mod frame_update;
 pub mod include_module_macro;
+#[cfg(test)]
+mod test_engine;
 mod transforms_update;
 
 pub struct GameEngine<P: Platform, G: Gpu> {
@@ -51,6 +63,12 @@ pub struct GameEngine<P: Platform, G: Gpu> {
     ecs_modules: HashMap<String, Box<dyn EcsModule>>,
     platform_libraries: Vec<Box<dyn PlatformLibrary>>,
     callables: Callables,
+    /// Errors returned by systems during the last frame. They are sent to
+    /// systems as `EngineError` events during the next frame.
+    engine_errors: Vec<EngineErrorT>,
+    /// Set when a system with the `Abort` error policy returns an error. No
+    /// further frames are executed.
+    aborted: bool,
     gpu: ManuallyDrop<G>,
 }
 
@@ -66,6 +84,8 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
             ecs_modules: Default::default(),
             platform_libraries: Default::default(),
             callables: Default::default(),
+            engine_errors: Default::default(),
+            aborted: false,
             gpu: ManuallyDrop::new(gpu),
         };
 
@@ -136,6 +156,10 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
             );
         }
 
+        engine
+            .frame_update
+            .register_engine_resources(&engine.component_registry);
+
         engine
     }
 }
@@ -164,6 +188,15 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
             return;
         }
 
+        let res = lib.init();
+        if res != 0 {
+            log::error!(
+                "{:?} not loaded: init() returned with code {res}",
+                lib.name()
+            );
+            return;
+        }
+
         let platform_lib_name = lib.name();
         let platform_lib_name = platform_lib_name.to_string_lossy();
 
@@ -183,6 +216,7 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
                 name: name.clone(),
                 size: size_of_val(&function),
                 align: align_of_val(&function),
+                fields: Vec::new(),
                 gpu_compatible: false,
                 is_freely_mutable: false,
                 ecs_type_info: EcsTypeInfo::Callable(CallableInfo { is_sync }),
@@ -197,9 +231,6 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
                 .add_platform_function(function_id, function, is_sync);
         }
 
-        let res = lib.init();
-        assert_eq!(res, 0, "{:?} init() returned with code {res}", lib.name());
-
         // save library
         self.platform_libraries.push(lib);
     }
@@ -238,9 +269,9 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
         unsafe { self.callables.complete_task(task_id, return_value) };
     }
 
-    // The following few ECS functions were written to unblock Editor and give it simple
-    // access to the ECS world. They will likely be removed in the future, in favor of
-    // a `World` query, which gives unrestricted, exclusive access to the world.
+    // The following few ECS functions give the host (e.g. Editor) simple access to the ECS
+    // world between frames. Systems which need unrestricted access to the world take a
+    // `World` input instead, see `void_public::World`.
 
     /// Returns all registered components + resources.
     pub fn component_ids(&self) -> impl Iterator<Item = ComponentId> + '_ {
@@ -259,7 +290,8 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
         self.frame_update.world.entities()
     }
 
-    /// Returns all the components attached to an entity.
+    /// Returns all the components attached to an entity, excluding sparse
+    /// components, which are not part of the entity's archetype.
     pub fn entity_components(&self, entity_id: EntityId) -> Option<&[ComponentId]> {
         self.frame_update
             .world
@@ -274,48 +306,12 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
         entity_id: EntityId,
         component_id: ComponentId,
     ) -> *mut MaybeUninit<u8> {
-        let Some(entity_data) = self.frame_update.world.get(entity_id) else {
-            return null_mut();
-        };
-
-        let Some(storage) = self.frame_update.archetypes.get(&entity_data.archetype_key) else {
-            return null_mut();
-        };
-
-        if let Some(found_component) = storage
-            .cpu
-            .components
-            .iter()
-            .find(|component_info| component_info.component_id == component_id)
-        {
-            return unsafe {
-                self.cpu_data
-                    .get_buffer_mut(storage.cpu.buffer_index)
-                    .get_mut_ptr(entity_data.archetype_index)
-                    .add(found_component.offset)
-            };
-        }
-
-        if let Some((gpu_storage, found_component)) = storage.gpu.iter().find_map(|gpu_storage| {
-            gpu_storage
-                .components
-                .iter()
-                .find(|component_info| component_info.component_id == component_id)
-                .map(|info| (gpu_storage, info))
-        }) {
-            return unsafe {
-                self.gpu
-                    .get_buffer_mut(
-                        &mut self.cpu_data,
-                        gpu_storage.buffer_index,
-                        gpu_storage.partition,
-                    )
-                    .get_mut_ptr(entity_data.archetype_index)
-                    .add(found_component.offset)
-            };
-        }
-
-        null_mut()
+        self.frame_update.entity_component_data_mut(
+            entity_id,
+            component_id,
+            &mut self.cpu_data,
+            &mut self.gpu,
+        )
     }
 
     /// Returns the `EntityId` associated with the given `label`. Returns `None` if no entity
@@ -336,7 +332,8 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
     }
 
     /// Returns a pointer to the specified resource data.
-    /// If the resource does not exist, returns a null pointer.
+    /// If the resource does not exist or has been removed, returns a null
+    /// pointer.
     pub fn resource_mut(&mut self, component_id: ComponentId) -> *mut MaybeUninit<u8> {
         let Some(component_info) = self.component_registry.get(&component_id) else {
             return null_mut();
@@ -350,7 +347,13 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
         };
 
         if !component_info.gpu_compatible {
-            self.cpu_data.get_buffer_mut(buffer_index).get_mut_ptr(0)
+            let mut buffer = self.cpu_data.get_buffer_mut(buffer_index);
+
+            if buffer.is_empty() {
+                null_mut()
+            } else {
+                buffer.get_mut_ptr(0)
+            }
         } else {
             self.gpu
                 .get_buffer_mut(&mut self.cpu_data, buffer_index, 0)
@@ -358,6 +361,8 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
         }
     }
 
+    /// Returns the names of all systems, in the order they will execute after
+    /// resolving `before`, `after` and `set` ordering constraints.
     pub fn system_names(&self) -> impl Iterator<Item = &str> {
         self.frame_update.system_graph.system_names()
     }
@@ -373,6 +378,75 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
             .set_system_enabled(system_name, enabled);
     }
 
+    /// Debug flag which forces CPU systems to execute one at a time, rather
+    /// than concurrently executing systems with non-conflicting data access.
+    ///
+    /// Systems only execute concurrently if the platform's `Executor` is
+    /// multi-threaded, as `executor_native::TaskExecutor` is.
+    pub fn set_sequential_system_execution(&mut self, sequential_execution: bool) {
+        self.frame_update
+            .system_graph
+            .set_sequential_execution(sequential_execution);
+    }
+
+    /// Sets how errors returned by systems are handled, for systems which do
+    /// not have their own policy. Systems are disabled by default.
+    pub fn set_system_error_policy(&mut self, error_policy: SystemErrorPolicy) {
+        self.frame_update
+            .system_graph
+            .set_error_policy(error_policy);
+    }
+
+    /// Overrides how errors returned by a single system are handled. `None`
+    /// restores the policy set with [`GameEngine::set_system_error_policy()`].
+    pub fn set_system_error_policy_override(
+        &mut self,
+        system_name: &str,
+        error_policy: Option<SystemErrorPolicy>,
+    ) {
+        self.frame_update
+            .system_graph
+            .set_system_error_policy(system_name, error_policy);
+    }
+
+    /// Returns the errors returned by systems during the last frame.
+    pub fn engine_errors(&self) -> &[EngineErrorT] {
+        &self.engine_errors
+    }
+
+    /// Returns `true` if a system with the `Abort` error policy returned an
+    /// error. The engine no longer executes frames, and the platform should
+    /// shut down.
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    pub fn profiling_enabled(&self) -> bool {
+        self.frame_update.system_graph.profiler().is_enabled()
+    }
+
+    /// Enables timing of systems and engine work, which is disabled by
+    /// default. See [`GameEngine::scope_stats()`] and
+    /// [`GameEngine::chrome_trace()`].
+    pub fn set_profiling_enabled(&mut self, enabled: bool) {
+        self.frame_update
+            .system_graph
+            .profiler_mut()
+            .set_enabled(enabled);
+    }
+
+    /// Returns the time spent in each system and in engine work, such as
+    /// command application, during the last frames. Sorted by scope name.
+    pub fn scope_stats(&self) -> Vec<(String, ScopeStats)> {
+        self.frame_update.system_graph.profiler().scope_stats()
+    }
+
+    /// Returns a trace of the last frames in the Chrome `trace_event` JSON
+    /// format, which may be viewed in `chrome://tracing` or Perfetto.
+    pub fn chrome_trace(&self) -> String {
+        self.frame_update.system_graph.profiler().chrome_trace()
+    }
+
     // End Editor-facing ECS functions
 
     pub fn register_preloaded_texture(
@@ -426,13 +500,115 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
         (texture_id, width, height)
     }
 
-    pub fn load_scene(&mut self, scene_file: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+    /// Loads a JSON or binary scene as a new instance, alongside previously
+    /// loaded scenes. The returned handle may be used to unload the scene's
+    /// entities.
+    pub fn load_scene(
+        &mut self,
+        scene_data: &[u8],
+    ) -> Result<SceneHandle, Box<dyn Error + Send + Sync>> {
+        let scene_handle = self.event_manager.reserve_scene_handle();
+
         self.frame_update.load_scene(
-            scene_file,
+            scene_handle,
+            scene_data,
             &mut self.cpu_data,
             &mut self.gpu,
             &self.component_registry,
             &self.ecs_modules,
+        )?;
+
+        Ok(scene_handle)
+    }
+
+    /// Starts loading a scene from an asset path as a new instance. The scene
+    /// is read and parsed asynchronously, and its entities are spawned once
+    /// its assets are loaded. `SceneLoadProgress` events are sent while the
+    /// scene is loading.
+    pub fn load_scene_from_path(&mut self, asset_path: &AssetPath) -> SceneHandle {
+        let scene_handle = self.event_manager.reserve_scene_handle();
+
+        self.frame_update
+            .load_scene_from_path(scene_handle, asset_path);
+
+        scene_handle
+    }
+
+    /// Despawns the entities of a scene instance. Returns `false` if the
+    /// instance is not loaded.
+    pub fn unload_scene(&mut self, scene_handle: SceneHandle) -> bool {
+        self.frame_update.unload_scene(
+            scene_handle,
+            &mut self.cpu_data,
+            &mut self.gpu,
+            &self.component_registry,
+        )
+    }
+
+    /// Registers a migration of whole scenes from `from_version` to
+    /// `to_version`, applied to scenes and prefabs of older versions when they
+    /// are loaded.
+    pub fn register_scene_migration<F>(
+        &mut self,
+        from_version: &str,
+        to_version: &str,
+        migration: F,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>
+    where
+        F: Fn(&mut JsonValue) -> Result<(), Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        self.frame_update
+            .scene_migrations_mut()
+            .register(from_version, to_version, migration)
+    }
+
+    /// Registers a migration of a component from `from_version` to
+    /// `to_version`, applied to the component's JSON in scenes and prefabs of
+    /// older versions when they are loaded.
+    pub fn register_component_scene_migration<F>(
+        &mut self,
+        from_version: &str,
+        to_version: &str,
+        component_name: &str,
+        migration: F,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>
+    where
+        F: Fn(&mut JsonValue) -> Result<(), Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        self.frame_update.scene_migrations_mut().register_component(
+            from_version,
+            to_version,
+            component_name,
+            migration,
+        )
+    }
+
+    /// Serializes all entities into a scene JSON string, in the format read by
+    /// `load_scene()`. Asset and entity reference fields are saved with the
+    /// asset path of their asset, and the scene id of their entity. References
+    /// to entities which no longer exist are saved as null.
+    pub fn save_scene(&mut self) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self.frame_update.save_scene(
+            &mut self.cpu_data,
+            &mut self.gpu,
+            &self.component_registry,
+            &self.ecs_modules,
+        )
+    }
+
+    /// Converts a JSON scene to the binary scene format, which loads faster.
+    /// Binary scenes contain the raw data of components, so they must be
+    /// converted again when components change. Components are written from
+    /// their reflected fields, so components with opaque fields cannot be
+    /// converted.
+    pub fn convert_scene_to_binary(
+        &self,
+        scene_file: &str,
+    ) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        self.frame_update.convert_scene_to_binary(
+            scene_file,
+            &self.component_registry,
+            &self.ecs_modules,
         )
     }
 
@@ -488,16 +664,26 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
     /// frame that is 0.004 seconds versus a frame that is 0.008 seconds (twice
     /// as much time).
     pub fn frame(&mut self, delta_time: f32) {
+        if self.aborted {
+            return;
+        }
+
         let delta_time = self.clamp_delta_time(delta_time);
 
         self.check_window_resize();
 
         self.update_input_state();
 
+        self.send_engine_errors();
+
+        self.send_scene_events();
+
         self.gpu.begin_frame(&mut self.cpu_data);
 
         self.update_and_record_frame(delta_time);
 
+        self.collect_engine_errors();
+
         self.gpu.submit_frame(&mut self.cpu_data);
 
         self.event_manager.platform_event_delegate().clear();
@@ -552,6 +738,117 @@ impl<P: Platform, G: Gpu> GameEngine<P, G> {
         });
     }
 
+    fn send_engine_errors(&mut self) {
+        let mut delegate = self.event_manager.platform_event_delegate();
+
+        for engine_error in &self.engine_errors {
+            let mut builder = FlatBufferBuilder::new();
+            let event = engine_error.pack(&mut builder);
+            builder.finish_minimal(event);
+
+            unsafe {
+                delegate.send(event_name!(EngineError), builder.finished_data());
+            }
+        }
+    }
+
+    /// Sends the scene instances loaded or unloaded since the last frame as
+    /// `SceneLoaded` and `SceneUnloaded` events, and the progress of scenes
+    /// being loaded from asset paths as `SceneLoadProgress` events.
+    fn send_scene_events(&mut self) {
+        let mut delegate = self.event_manager.platform_event_delegate();
+
+        for scene_event in self.frame_update.take_scene_events() {
+            let mut builder = FlatBufferBuilder::new();
+
+            match scene_event {
+                SceneEvent::Loaded {
+                    scene_handle,
+                    entity_count,
+                } => {
+                    let event = SceneLoaded::new(scene_handle.0.get(), entity_count as u32);
+                    let offset = builder.push(event);
+                    builder.finish_minimal(offset);
+
+                    unsafe {
+                        delegate.send(event_name!(SceneLoaded), builder.finished_data());
+                    }
+                }
+                SceneEvent::Unloaded { scene_handle } => {
+                    let event = SceneUnloaded::new(scene_handle.0.get());
+                    let offset = builder.push(event);
+                    builder.finish_minimal(offset);
+
+                    unsafe {
+                        delegate.send(event_name!(SceneUnloaded), builder.finished_data());
+                    }
+                }
+                SceneEvent::Progress {
+                    scene_handle,
+                    stage,
+                    assets_loaded,
+                    assets_total,
+                } => {
+                    let event = SceneLoadProgress::new(
+                        scene_handle.0.get(),
+                        stage,
+                        assets_loaded as u32,
+                        assets_total as u32,
+                    );
+                    let offset = builder.push(event);
+                    builder.finish_minimal(offset);
+
+                    unsafe {
+                        delegate.send(event_name!(SceneLoadProgress), builder.finished_data());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Collects the errors returned by systems during the frame, and aborts if
+    /// any system has the `Abort` error policy. The errors are kept, so that
+    /// they may still be reported after aborting.
+    fn collect_engine_errors(&mut self) {
+        let frame = self
+            .cpu_data
+            .get_resource(&self.component_registry, |constants: &FrameConstants| {
+                constants.tick_count
+            });
+
+        let system_errors = self.frame_update.system_graph.take_errors();
+
+        if let Some(error) = system_errors
+            .iter()
+            .find(|error| error.policy == SystemErrorPolicy::Abort)
+        {
+            log::error!(
+                "system `{}` returned error: {}, aborting",
+                error.system_name,
+                error.message
+            );
+
+            self.aborted = true;
+        }
+
+        self.engine_errors = system_errors
+            .into_iter()
+            .map(|error| {
+                let module_name = error
+                    .system_name
+                    .rsplit_once("::")
+                    .map(|(module_name, _)| module_name.to_owned());
+
+                let mut engine_error = EngineErrorT::default();
+                engine_error.system_name = Some(error.system_name);
+                engine_error.module_name = module_name;
+                engine_error.message = Some(error.message);
+                engine_error.frame = frame;
+                engine_error
+            })
+            .collect();
+    }
+
     fn update_input_state(&mut self) {
         self.cpu_data
             .get_resource_mut(&self.component_registry, |input_state: &mut InputState| {
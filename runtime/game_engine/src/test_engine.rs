@@ -0,0 +1,820 @@
+//! Engine-level tests, which run systems inside a [`GameEngine`].
+//!
+//! Systems are declared by a [`TestModule`], an `EcsModule` implemented in
+//! Rust, and call the engine through `void_public` like any module. The engine
+//! runs on a [`TestGpu`], which does not render, so only CPU systems may be
+//! used. Its only GPU component is [`GpuColor`].
+
+use std::{
+    borrow::Cow,
+    error::Error,
+    ffi::{CStr, c_void},
+    fmt,
+    mem::MaybeUninit,
+    num::NonZeroUsize,
+    ops::{Deref, DerefMut},
+    sync::{
+        Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use executor_native::TaskExecutor;
+use flatbuffers::Follow;
+use game_ecs::{
+    ComponentBundle, ComponentInfo, ComponentRegistry, CpuFrameData, EcsTypeInfo,
+    EntityComponentInfo, FrameDataBufferBorrowRef, GpuFrameData, PartitionIndex,
+    cpu_frame_data::CpuDataBufferRefMut,
+};
+use game_module_macro::Component;
+use gpu_common::Gpu;
+use gpu_web::{DataBufferBorrowRef, DataBufferBorrowRefMut, DataBufferRef};
+use json::JsonValue;
+use platform::{
+    DeserializeReadFn, EcsModule, EcsSystemFn, Platform, SerializeWriteFn,
+    test::{TestClock, TestExecutor, TestFilesystem, TestPlatform},
+};
+use void_public::{
+    ArgType, Component, ComponentId, ComponentType, ENGINE_VERSION, EcsType, EntityId, EventReader,
+    EventWriter, Query, RemovedComponents, World,
+    graphics::TextureId,
+    reflect::{ComponentField, FieldType, Reflect},
+    state::StateCondition,
+    system::SystemOrdering,
+};
+
+use crate::GameEngine;
+
+/// The engine uses global state while executing systems, so engines must not
+/// run concurrently.
+static ENGINE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A `GameEngine` with the modules of a test registered.
+pub struct TestEngine<P: Platform = TestPlatform> {
+    engine: GameEngine<P, TestGpu>,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TestEngine {
+    pub fn new(modules: impl IntoIterator<Item = TestModule>) -> Self {
+        Self::with_executor(TestExecutor, modules)
+    }
+}
+
+impl TestEngine<ThreadedTestPlatform> {
+    /// Creates an engine which executes systems on `thread_count` threads.
+    /// The executor is global, so only one test may create such an engine.
+    pub fn threaded(
+        thread_count: NonZeroUsize,
+        modules: impl IntoIterator<Item = TestModule>,
+    ) -> Self {
+        static EXECUTOR_CREATED: AtomicBool = AtomicBool::new(false);
+
+        assert!(
+            !EXECUTOR_CREATED.swap(true, Ordering::Relaxed),
+            "only one threaded test engine may be created"
+        );
+
+        // SAFETY: the flag above ensures the executor is only created once.
+        let executor = unsafe { TaskExecutor::with_thread_count(thread_count) };
+        Self::with_executor(executor, modules)
+    }
+}
+
+impl<P: Platform> TestEngine<P> {
+    fn with_executor(executor: P::Executor, modules: impl IntoIterator<Item = TestModule>) -> Self {
+        let lock = ENGINE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut engine = GameEngine::new(executor, 800, 600, TestGpu::default());
+
+        for module in modules {
+            engine.register_ecs_module(Box::new(module));
+        }
+
+        Self {
+            engine,
+            _lock: lock,
+        }
+    }
+
+    /// Runs `count` frames of 1/60 s.
+    pub fn frames(&mut self, count: usize) {
+        for _ in 0..count {
+            self.engine.frame(1. / 60.);
+        }
+    }
+
+    /// Returns a copy of a component of an entity.
+    pub fn component<T: Component>(&mut self, entity_id: EntityId) -> Option<T> {
+        let ptr = self
+            .engine
+            .entity_component_data_mut(entity_id.into(), T::id());
+        (!ptr.is_null()).then(|| unsafe { ptr.cast::<T>().read_unaligned() })
+    }
+
+    /// Returns whether an entity exists in the world.
+    pub fn contains(&self, entity_id: EntityId) -> bool {
+        self.engine
+            .entities()
+            .any(|entity| entity == entity_id.into())
+    }
+}
+
+impl<P: Platform> Deref for TestEngine<P> {
+    type Target = GameEngine<P, TestGpu>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.engine
+    }
+}
+
+impl<P: Platform> DerefMut for TestEngine<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.engine
+    }
+}
+
+/// A [`TestPlatform`] whose executor is multi-threaded, so that systems with
+/// non-conflicting data access execute concurrently.
+pub struct ThreadedTestPlatform;
+
+impl Platform for ThreadedTestPlatform {
+    type Clock = TestClock;
+    type Executor = TaskExecutor;
+    type Filesystem = TestFilesystem;
+}
+
+/// A GPU without rendering. Its buffers are CPU buffers, which systems may not
+/// borrow, so systems may not query GPU components.
+#[derive(Default)]
+pub struct TestGpu {
+    buffers: CpuFrameData,
+}
+
+impl fmt::Debug for TestGpu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestGpu")
+            .field("buffers_len", &self.buffers.buffers_len())
+            .finish()
+    }
+}
+
+/// A component stored in a GPU buffer of [`TestGpu`].
+#[derive(Component, Debug)]
+#[repr(C)]
+pub struct GpuColor {
+    pub value: u32,
+}
+
+impl Gpu for TestGpu {
+    type Error = ();
+
+    const MULTI_BUFFERED: bool = false;
+
+    fn multi_buffer_count(&self) -> NonZeroUsize {
+        NonZeroUsize::new(1).unwrap()
+    }
+
+    fn window_resized(
+        &mut self,
+        _width: u32,
+        _height: u32,
+        _component_registry: &ComponentRegistry,
+        _cpu_data: &mut CpuFrameData,
+    ) {
+    }
+
+    fn register_preloaded_texture(
+        &mut self,
+        _cpu_data: &mut CpuFrameData,
+        _component_registry: &ComponentRegistry,
+        _texture_id: TextureId,
+        _path: String,
+        _data: Vec<u8>,
+        width_and_height: (u32, u32),
+        _use_atlas: bool,
+    ) -> (u32, u32) {
+        width_and_height
+    }
+
+    fn register_components(&mut self, component_registry: &mut ComponentRegistry) {
+        let component_id = component_registry.register(ComponentInfo {
+            name: GpuColor::string_id().to_owned(),
+            size: size_of::<GpuColor>(),
+            align: align_of::<GpuColor>(),
+            fields: Vec::new(),
+            gpu_compatible: true,
+            is_freely_mutable: true,
+            ecs_type_info: EcsTypeInfo::Component(EntityComponentInfo {
+                declaring_module_name: "gpu".to_string(),
+                sparse: false,
+            }),
+        });
+
+        unsafe {
+            GpuColor::set_id(component_id);
+        }
+    }
+
+    fn register_resources(
+        &mut self,
+        _cpu_data: &mut CpuFrameData,
+        _component_registry: &mut ComponentRegistry,
+    ) {
+    }
+
+    fn component_groupings(&mut self) -> Vec<Vec<ComponentId>> {
+        vec![vec![GpuColor::id()]]
+    }
+
+    fn component_bundles(&mut self) -> Vec<ComponentBundle> {
+        Vec::new()
+    }
+
+    fn single_buffer_components(&mut self) -> Vec<ComponentId> {
+        Vec::new()
+    }
+
+    fn component_archetype_keys(&mut self) -> Vec<ComponentId> {
+        Vec::new()
+    }
+
+    fn ecs_module<P: Platform>(&self) -> Box<dyn EcsModule> {
+        Box::new(TestModule::new(c"gpu"))
+    }
+
+    fn begin_frame(&mut self, _cpu_data: &mut CpuFrameData) {}
+
+    fn submit_frame(&mut self, _cpu_data: &mut CpuFrameData) {}
+
+    fn destroy(self, _cpu_data: &mut CpuFrameData) {}
+}
+
+impl GpuFrameData for TestGpu {
+    type FrameDataBufferBorrowRef = DataBufferBorrowRef;
+
+    type FrameDataBufferBorrowRefMut = DataBufferBorrowRefMut;
+
+    type FrameDataBufferRef<'a> = DataBufferRef<'a>;
+
+    type FrameDataBufferRefMut<'a> = CpuDataBufferRefMut<'a>;
+
+    fn new_buffer(&mut self, _cpu_data: &mut CpuFrameData, stride: usize) -> usize {
+        self.buffers.new_buffer(stride, align_of::<GpuColor>())
+    }
+
+    fn allocate_buffer_partition(&mut self, _index: usize) -> PartitionIndex {
+        unreachable!()
+    }
+
+    fn buffers_len(&self) -> usize {
+        self.buffers.buffers_len()
+    }
+
+    fn buffer_total_len(&self, index: usize) -> usize {
+        self.buffers.borrow_buffer(index).len()
+    }
+
+    fn borrow_buffer(
+        &self,
+        _index: usize,
+        _partition: PartitionIndex,
+    ) -> Self::FrameDataBufferBorrowRef {
+        unreachable!()
+    }
+
+    fn borrow_buffer_prev(
+        &self,
+        _index: usize,
+        _partition: PartitionIndex,
+    ) -> Self::FrameDataBufferBorrowRef {
+        unreachable!()
+    }
+
+    fn borrow_buffer_mut(
+        &self,
+        _index: usize,
+        _partition: PartitionIndex,
+    ) -> Self::FrameDataBufferBorrowRefMut {
+        unreachable!()
+    }
+
+    fn get_buffer_mut(
+        &mut self,
+        _cpu_data: &mut CpuFrameData,
+        index: usize,
+        partition: PartitionIndex,
+    ) -> Self::FrameDataBufferRefMut<'_> {
+        assert_eq!(partition, 0, "buffers are not partitioned");
+        self.buffers.get_buffer_mut(index)
+    }
+
+    fn get_buffer_prev(
+        &mut self,
+        _index: usize,
+        _partition: PartitionIndex,
+        _frames_behind: usize,
+    ) -> Self::FrameDataBufferRef<'_> {
+        unreachable!()
+    }
+}
+
+/// A module declared in Rust.
+pub struct TestModule {
+    name: &'static CStr,
+    components: Vec<TestComponent>,
+    systems: Vec<TestSystem>,
+}
+
+impl TestModule {
+    pub fn new(name: &'static CStr) -> Self {
+        Self {
+            name,
+            components: Vec::new(),
+            systems: Vec::new(),
+        }
+    }
+
+    /// Declares a component. Scenes describe it by its reflected fields.
+    pub fn component<T: Component + Reflect>(self) -> Self {
+        self.ecs_type::<T>(ComponentType::Component, reflected_fields::<T>(), None)
+    }
+
+    /// Declares a zero-sized tag component, stored outside of archetypes.
+    pub fn sparse_component<T: Component + Reflect>(self) -> Self {
+        self.ecs_type::<T>(ComponentType::SparseComponent, Vec::new(), None)
+    }
+
+    /// Declares a resource, initialized with `init`.
+    pub fn resource<T: EcsType + 'static>(self, init: fn() -> T) -> Self {
+        let resource_init = Box::new(move |val: &mut [MaybeUninit<u8>]| unsafe {
+            val.as_mut_ptr().cast::<T>().write(init());
+        });
+
+        self.ecs_type::<T>(ComponentType::Resource, Vec::new(), Some(resource_init))
+    }
+
+    pub fn system(mut self, system: TestSystem) -> Self {
+        self.systems.push(system);
+        self
+    }
+
+    fn ecs_type<T: EcsType>(
+        mut self,
+        component_type: ComponentType,
+        fields: Vec<ComponentField>,
+        resource_init: Option<ResourceInitFn>,
+    ) -> Self {
+        self.components.push(TestComponent {
+            string_id: T::string_id(),
+            size: size_of::<T>(),
+            align: align_of::<T>(),
+            component_type,
+            fields,
+            set_id: |id| unsafe { T::set_id(id) },
+            resource_init,
+        });
+        self
+    }
+
+    fn find_component(&self, string_id: &CStr) -> &TestComponent {
+        self.components
+            .iter()
+            .find(|component| component.string_id == string_id)
+            .unwrap_or_else(|| panic!("unknown test component {string_id:?}"))
+    }
+}
+
+fn reflected_fields<T: Reflect>() -> Vec<ComponentField> {
+    T::fields()
+        .iter()
+        .map(|field| unsafe { ComponentField::from_field_info(field) })
+        .collect()
+}
+
+type ResourceInitFn = Box<dyn Fn(&mut [MaybeUninit<u8>]) + Send + Sync>;
+
+struct TestComponent {
+    string_id: &'static CStr,
+    size: usize,
+    align: usize,
+    component_type: ComponentType,
+    fields: Vec<ComponentField>,
+    set_id: fn(ComponentId),
+    resource_init: Option<ResourceInitFn>,
+}
+
+impl TestComponent {
+    /// The top-level numeric fields of the component, which are read from and
+    /// written to scene JSON.
+    fn json_fields(&self) -> impl Iterator<Item = &ComponentField> {
+        self.fields
+            .iter()
+            .filter(|field| field.parent.is_none())
+            .filter(|field| {
+                matches!(
+                    field.field_type,
+                    FieldType::U32
+                        | FieldType::U64
+                        | FieldType::F32
+                        | FieldType::EntityId
+                        | FieldType::TextureId
+                        | FieldType::TextId
+                        | FieldType::MaterialId
+                        | FieldType::AssetId
+                        | FieldType::ParticleEffectId
+                )
+            })
+    }
+}
+
+/// A system of a [`TestModule`]. `run` is called with the system's inputs, in
+/// the order of its arguments.
+pub struct TestSystem {
+    name: &'static CStr,
+    args: Vec<TestArg>,
+    run: fn(SystemInputs),
+    is_once: bool,
+    is_fixed: bool,
+    orderings: Vec<(SystemOrdering, &'static CStr)>,
+    state_conditions: Vec<(StateCondition, &'static CStr, u32)>,
+}
+
+impl TestSystem {
+    pub fn new(name: &'static CStr, run: fn(SystemInputs)) -> Self {
+        Self {
+            name,
+            args: Vec::new(),
+            run,
+            is_once: false,
+            is_fixed: false,
+            orderings: Vec::new(),
+            state_conditions: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, arg: TestArg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    pub fn once(mut self) -> Self {
+        self.is_once = true;
+        self
+    }
+
+    pub fn fixed(mut self) -> Self {
+        self.is_fixed = true;
+        self
+    }
+
+    pub fn after(mut self, system_name: &'static CStr) -> Self {
+        self.orderings.push((SystemOrdering::After, system_name));
+        self
+    }
+
+    pub fn run_if(mut self, condition: StateCondition, state: &'static CStr, value: u32) -> Self {
+        self.state_conditions.push((condition, state, value));
+        self
+    }
+}
+
+pub enum TestArg {
+    /// A query, given as the type and component of each of its terms.
+    Query(Vec<(ArgType, &'static CStr)>),
+    /// A resource accessed by `DataAccessRef` or `DataAccessMut`.
+    Resource(ArgType, &'static CStr),
+    EventReader(&'static CStr),
+    EventWriter(&'static CStr),
+    RemovedComponents(&'static CStr),
+    World,
+}
+
+impl TestArg {
+    pub fn query(terms: &[(ArgType, &'static CStr)]) -> Self {
+        Self::Query(terms.to_vec())
+    }
+
+    fn arg_type(&self) -> ArgType {
+        match self {
+            Self::Query(_) => ArgType::Query,
+            Self::Resource(arg_type, _) => *arg_type,
+            Self::EventReader(_) => ArgType::EventReader,
+            Self::EventWriter(_) => ArgType::EventWriter,
+            Self::RemovedComponents(_) => ArgType::RemovedComponents,
+            Self::World => ArgType::World,
+        }
+    }
+}
+
+/// The inputs passed to a [`TestSystem`].
+#[derive(Clone, Copy)]
+pub struct SystemInputs(*const *const c_void);
+
+impl SystemInputs {
+    pub fn input(self, index: usize) -> *const c_void {
+        unsafe { self.0.add(index).read() }
+    }
+
+    pub fn query<Q>(self, index: usize) -> Query<Q> {
+        unsafe { Query::new(self.input(index).cast_mut()) }
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn resource<T>(self, index: usize) -> &'static mut T {
+        unsafe { &mut *self.input(index).cast::<T>().cast_mut() }
+    }
+
+    pub fn event_reader<'a, T: Follow<'a> + 'a>(self, index: usize) -> EventReader<T> {
+        unsafe { EventReader::new(self.input(index)) }
+    }
+
+    pub fn event_writer<T>(self, index: usize) -> EventWriter<T> {
+        unsafe { EventWriter::new(self.input(index)) }
+    }
+
+    pub fn removed_components<T: Component>(self, index: usize) -> RemovedComponents<T> {
+        unsafe { RemovedComponents::new(self.input(index)) }
+    }
+
+    pub fn world(self, index: usize) -> World {
+        unsafe { World::new(self.input(index).cast_mut()) }
+    }
+}
+
+struct TestSystemFn(fn(SystemInputs));
+
+impl EcsSystemFn for TestSystemFn {
+    unsafe fn call(
+        &mut self,
+        ptr: *const *const c_void,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let run = self.0;
+        void_public::panic::catch_unwind(|| run(SystemInputs(ptr))).map_err(Into::into)
+    }
+}
+
+impl EcsModule for TestModule {
+    fn void_target_version(&self) -> u32 {
+        ENGINE_VERSION
+    }
+
+    fn module_name(&self) -> Cow<'_, str> {
+        self.name.to_string_lossy()
+    }
+
+    fn set_component_id(&mut self, string_id: &CStr, component_id: ComponentId) {
+        if let Some(component) = self
+            .components
+            .iter()
+            .find(|component| component.string_id == string_id)
+        {
+            (component.set_id)(component_id);
+        }
+    }
+
+    fn init(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn deinit(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn resource_init(
+        &self,
+        string_id: &CStr,
+        val: &mut [MaybeUninit<u8>],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let init = self.find_component(string_id).resource_init.as_ref();
+        init.ok_or("not a resource")?(val);
+        Ok(())
+    }
+
+    fn resource_deserialize(
+        &self,
+        _string_id: &CStr,
+        _val: &mut [MaybeUninit<u8>],
+        _read: DeserializeReadFn<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err("resource serialization is not supported by test modules".into())
+    }
+
+    fn resource_serialize(
+        &self,
+        _string_id: &CStr,
+        _val: &[MaybeUninit<u8>],
+        _write: SerializeWriteFn<'_>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err("resource serialization is not supported by test modules".into())
+    }
+
+    fn component_deserialize_json(
+        &self,
+        string_id: &CStr,
+        dest_buffer: &mut [MaybeUninit<u8>],
+        json_string: &str,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let component = self.find_component(string_id);
+        let value = json::parse(json_string)?;
+
+        dest_buffer.fill(MaybeUninit::new(0));
+
+        for field in component.json_fields() {
+            let field_value = &value[field.name.to_str()?];
+            let dest = &mut dest_buffer[field.offset..field.offset + field.size];
+
+            let bytes = match field.field_type {
+                FieldType::F32 => field_value
+                    .as_f32()
+                    .unwrap_or_default()
+                    .to_ne_bytes()
+                    .to_vec(),
+                FieldType::U64 | FieldType::EntityId => field_value
+                    .as_u64()
+                    .unwrap_or_default()
+                    .to_ne_bytes()
+                    .to_vec(),
+                _ => field_value
+                    .as_u32()
+                    .unwrap_or_default()
+                    .to_ne_bytes()
+                    .to_vec(),
+            };
+
+            for (dest, byte) in dest.iter_mut().zip(bytes) {
+                *dest = MaybeUninit::new(byte);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn component_serialize_json(
+        &self,
+        string_id: &CStr,
+        val: &[MaybeUninit<u8>],
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let component = self.find_component(string_id);
+        let mut value = JsonValue::new_object();
+
+        for field in component.json_fields() {
+            let ptr = val[field.offset..].as_ptr();
+
+            let field_value: JsonValue = unsafe {
+                match field.field_type {
+                    FieldType::F32 => ptr.cast::<f32>().read_unaligned().into(),
+                    FieldType::U64 | FieldType::EntityId => {
+                        ptr.cast::<u64>().read_unaligned().into()
+                    }
+                    _ => ptr.cast::<u32>().read_unaligned().into(),
+                }
+            };
+
+            value[field.name.to_str()?] = field_value;
+        }
+
+        Ok(value.dump())
+    }
+
+    fn component_string_id(&self, index: usize) -> Option<Cow<'_, CStr>> {
+        self.components
+            .get(index)
+            .map(|component| component.string_id.into())
+    }
+
+    fn component_size(&self, string_id: &CStr) -> usize {
+        self.find_component(string_id).size
+    }
+
+    fn component_align(&self, string_id: &CStr) -> usize {
+        self.find_component(string_id).align
+    }
+
+    fn component_type(&self, string_id: &CStr) -> ComponentType {
+        self.find_component(string_id).component_type
+    }
+
+    fn component_fields(&self, string_id: &CStr) -> Vec<ComponentField> {
+        self.find_component(string_id).fields.clone()
+    }
+
+    fn component_async_completion_callable(&self, _string_id: &CStr) -> Cow<'_, CStr> {
+        unreachable!()
+    }
+
+    fn systems_len(&self) -> usize {
+        self.systems.len()
+    }
+
+    fn system_name(&self, system_index: usize) -> Cow<'_, CStr> {
+        self.systems[system_index].name.into()
+    }
+
+    fn system_is_once(&self, system_index: usize) -> bool {
+        self.systems[system_index].is_once
+    }
+
+    fn system_is_fixed(&self, system_index: usize) -> bool {
+        self.systems[system_index].is_fixed
+    }
+
+    fn system_orderings_len(&self, system_index: usize) -> usize {
+        self.systems[system_index].orderings.len()
+    }
+
+    fn system_ordering_type(&self, system_index: usize, ordering_index: usize) -> SystemOrdering {
+        self.systems[system_index].orderings[ordering_index].0
+    }
+
+    fn system_ordering_target(&self, system_index: usize, ordering_index: usize) -> Cow<'_, CStr> {
+        self.systems[system_index].orderings[ordering_index]
+            .1
+            .into()
+    }
+
+    fn system_state_conditions_len(&self, system_index: usize) -> usize {
+        self.systems[system_index].state_conditions.len()
+    }
+
+    fn system_state_condition_type(
+        &self,
+        system_index: usize,
+        condition_index: usize,
+    ) -> StateCondition {
+        self.systems[system_index].state_conditions[condition_index].0
+    }
+
+    fn system_state_condition_state(
+        &self,
+        system_index: usize,
+        condition_index: usize,
+    ) -> Cow<'_, CStr> {
+        self.systems[system_index].state_conditions[condition_index]
+            .1
+            .into()
+    }
+
+    fn system_state_condition_value(&self, system_index: usize, condition_index: usize) -> u32 {
+        self.systems[system_index].state_conditions[condition_index].2
+    }
+
+    fn system_fn(&self, system_index: usize) -> Box<dyn EcsSystemFn> {
+        Box::new(TestSystemFn(self.systems[system_index].run))
+    }
+
+    fn system_args_len(&self, system_index: usize) -> usize {
+        self.systems[system_index].args.len()
+    }
+
+    fn system_arg_type(&self, system_index: usize, arg_index: usize) -> ArgType {
+        self.systems[system_index].args[arg_index].arg_type()
+    }
+
+    fn system_arg_component(&self, system_index: usize, arg_index: usize) -> Cow<'_, CStr> {
+        match &self.systems[system_index].args[arg_index] {
+            TestArg::Resource(_, string_id) | TestArg::RemovedComponents(string_id) => {
+                (*string_id).into()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn system_arg_event(&self, system_index: usize, arg_index: usize) -> Cow<'_, CStr> {
+        match &self.systems[system_index].args[arg_index] {
+            TestArg::EventReader(event) | TestArg::EventWriter(event) => (*event).into(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn system_query_args_len(&self, system_index: usize, arg_index: usize) -> usize {
+        match &self.systems[system_index].args[arg_index] {
+            TestArg::Query(terms) => terms.len(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn system_query_arg_type(
+        &self,
+        system_index: usize,
+        system_arg_index: usize,
+        query_arg_index: usize,
+    ) -> ArgType {
+        match &self.systems[system_index].args[system_arg_index] {
+            TestArg::Query(terms) => terms[query_arg_index].0,
+            _ => unreachable!(),
+        }
+    }
+
+    fn system_query_arg_component(
+        &self,
+        system_index: usize,
+        system_arg_index: usize,
+        query_arg_index: usize,
+    ) -> Cow<'_, CStr> {
+        match &self.systems[system_index].args[system_arg_index] {
+            TestArg::Query(terms) => terms[query_arg_index].1.into(),
+            _ => unreachable!(),
+        }
+    }
+}
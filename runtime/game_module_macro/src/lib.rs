@@ -1,9 +1,11 @@
+use std::ffi::CString;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::{quote, quote_spanned};
 use snapshot_derive_impl::{derive_deserialize_impl, derive_serialize_impl};
 use syn::{
-    DeriveInput, ExprLit, FnArg, Ident, ItemFn, Lit, LitStr, Token, Type,
+    Data, DeriveInput, ExprLit, Fields, FnArg, Ident, ItemFn, Lit, LitStr, Token, Type,
     parse::{Parse, ParseStream},
     parse_macro_input,
 };
@@ -41,9 +43,11 @@ pub fn derive_ecs_type(input: TokenStream) -> TokenStream {
     .into()
 }
 
-#[proc_macro_derive(Component)]
+#[proc_macro_derive(Component, attributes(component, reflect))]
 pub fn derive_component(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, attrs, .. } = parse_macro_input!(input);
+    let DeriveInput {
+        ident, attrs, data, ..
+    } = parse_macro_input!(input);
 
     let cid = Ident::new(
         &format!("_{}_CID", ident.to_string().to_uppercase()),
@@ -53,17 +57,32 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
     let sid = LitStr::new(&ident.to_string(), Span::call_site());
 
     let mut proper_repr_found = false;
+    let mut sparse = false;
+    let mut custom_reflect = false;
     for attr in attrs {
-        if attr.path().is_ident("repr") {
+        if attr.path().is_ident("component") {
+            let res = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("sparse") {
+                    sparse = true;
+                    Ok(())
+                } else if meta.path.is_ident("custom_reflect") {
+                    // `Reflect` is implemented manually
+                    custom_reflect = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported component attribute"))
+                }
+            });
+            if let Err(err) = res {
+                return err.to_compile_error().into();
+            }
+        } else if attr.path().is_ident("repr") {
             let _ = attr.parse_nested_meta(|meta| {
                 if meta.path.is_ident("C") || meta.path.is_ident("transparent") {
                     proper_repr_found = true;
                 }
                 Ok(())
             });
-            if proper_repr_found {
-                break;
-            }
         }
     }
 
@@ -77,11 +96,27 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
         }
     }
 
+    // Sparse components are stored as a set of entities, without data.
+    let sparse_assert = sparse.then(|| {
+        quote! {
+            const _: () = assert!(
+                ::std::mem::size_of::<#ident>() == 0,
+                "#[component(sparse)] is only supported for zero-sized tag components",
+            );
+        }
+    });
+
+    let reflect_impl = (!custom_reflect).then(|| reflect_impl(&ident, &data));
+
     quote!(
+        #sparse_assert
+
         static mut #cid: Option<ComponentId> = None;
 
         impl Component for #ident {}
 
+        #reflect_impl
+
         impl EcsType for #ident {
             fn id() -> ComponentId {
                 unsafe { #cid.expect("ComponentId unassigned") }
@@ -112,6 +147,90 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Describes the fields of a struct nested in a component, see
+/// `void_public::reflect`.
+#[proc_macro_derive(Reflect, attributes(reflect))]
+pub fn derive_reflect(input: TokenStream) -> TokenStream {
+    let DeriveInput { ident, data, .. } = parse_macro_input!(input);
+
+    reflect_impl(&ident, &data).into()
+}
+
+/// Implements `Reflect` for a struct. Enums and unions are described without
+/// fields.
+///
+/// The kind of asset of an `AssetId` field is declared with a field attribute,
+/// e.g. `#[reflect(particle_effect)]`.
+fn reflect_impl(ident: &Ident, data: &Data) -> proc_macro2::TokenStream {
+    let fields = match data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| -> syn::Result<_> {
+                let member = field.ident.as_ref().map_or_else(
+                    || {
+                        let index = syn::Index::from(index);
+                        quote!(#index)
+                    },
+                    |ident| quote!(#ident),
+                );
+                let name = CString::new(member.to_string()).unwrap();
+                let ty = &field.ty;
+                let type_name = CString::new(quote!(#ty).to_string().replace(' ', "")).unwrap();
+
+                let mut field_type = quote!(
+                    (&::void_public::reflect::ReflectProbe::<#ty>::new()).field_type()
+                );
+
+                for attr in &field.attrs {
+                    if attr.path().is_ident("reflect") {
+                        attr.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("particle_effect") {
+                                field_type =
+                                    quote!(::void_public::reflect::FieldType::ParticleEffectId);
+                                Ok(())
+                            } else {
+                                Err(meta.error("unsupported reflect attribute"))
+                            }
+                        })?;
+                    }
+                }
+
+                Ok(quote! {
+                    ::void_public::reflect::FieldDescriptor {
+                        name: #name,
+                        type_name: #type_name,
+                        field_type: #field_type,
+                        offset: ::std::mem::offset_of!(Self, #member),
+                        size: ::std::mem::size_of::<#ty>(),
+                        fields: (&::void_public::reflect::ReflectProbe::<#ty>::new()).fields(),
+                    }
+                })
+            })
+            .collect::<syn::Result<Vec<_>>>(),
+        Data::Enum(_) | Data::Union(_) => Ok(Vec::new()),
+    };
+
+    let fields = match fields {
+        Ok(fields) => fields,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    quote! {
+        impl ::void_public::reflect::Reflect for #ident {
+            fn fields() -> &'static [::void_public::reflect::FieldInfo] {
+                use ::void_public::reflect::{ReflectProbeOpaque as _, ReflectProbeReflect as _};
+
+                static FIELDS: ::std::sync::OnceLock<Vec<::void_public::reflect::FieldInfo>> =
+                    ::std::sync::OnceLock::new();
+
+                FIELDS.get_or_init(|| ::void_public::reflect::flatten_fields(&[#(#fields),*]))
+            }
+        }
+    }
+}
+
 #[proc_macro_derive(Resource)]
 pub fn derive_resource(input: TokenStream) -> TokenStream {
     let deserialize_impl = derive_deserialize_impl(input.clone().into());
@@ -157,6 +276,62 @@ pub fn derive_resource(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Implements `void_public::state::States` for a fieldless enum. The initial
+/// state is the first variant.
+#[proc_macro_derive(States)]
+pub fn derive_states(input: TokenStream) -> TokenStream {
+    let DeriveInput { ident, data, .. } = parse_macro_input!(input);
+
+    let Data::Enum(data) = data else {
+        return syn::Error::new_spanned(ident, "States may only be derived for enums")
+            .to_compile_error()
+            .into();
+    };
+
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return syn::Error::new_spanned(variant, "States variants must not have fields")
+            .to_compile_error()
+            .into();
+    }
+
+    if data.variants.is_empty() {
+        return syn::Error::new_spanned(ident, "States enums must have at least one variant")
+            .to_compile_error()
+            .into();
+    }
+
+    let sid = LitStr::new(&ident.to_string(), Span::call_site());
+    let variants = data.variants.iter().map(|variant| &variant.ident);
+    let variants2 = variants.clone();
+    let index = (0..data.variants.len() as u32).collect::<Vec<_>>();
+
+    quote! {
+        impl ::void_public::state::States for #ident {
+            fn string_id() -> &'static ::std::ffi::CStr {
+                unsafe { ::std::ffi::CStr::from_bytes_with_nul_unchecked(concat!(module_path!(), "::", #sid, "\0").as_bytes()) }
+            }
+
+            fn index(self) -> u32 {
+                match self {
+                    #(Self::#variants => #index,)*
+                }
+            }
+
+            fn from_index(index: u32) -> Self {
+                match index {
+                    #(#index => Self::#variants2,)*
+                    _ => panic!("invalid {} state index: {index}", #sid),
+                }
+            }
+        }
+    }
+    .into()
+}
+
 /// This version of `Resource` is used internally to allow resources to opt out
 /// of state snapshot serialization.
 ///
@@ -531,18 +706,120 @@ pub fn derive_matrix_impl(input: TokenStream) -> TokenStream {
     .into()
 }
 
-/// `system_once` is a marker attribute for FFI codegen.
+/// `system_once` is a marker attribute for FFI codegen. It accepts the same
+/// ordering constraints as [`macro@system`].
 #[proc_macro_attribute]
-pub fn system_once(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn system_once(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let parser = system_attr_parser();
+    parse_macro_input!(attr with parser);
     item
 }
 
 /// `system` is a marker attribute for FFI codegen.
+///
+/// Systems may optionally declare ordering constraints:
+///
+/// * `before = other_system` / `after = other_system` orders the system
+///   relative to another system in the same module.
+/// * `before = "label"` / `after = "label"` orders the system relative to all
+///   systems in the named set, which may span modules.
+/// * `set = "label"` adds the system to the named set.
+///
+/// For example, `#[system(after = apply_input, set = "physics")]`.
+///
+/// Systems may also be limited to a game state, with
+/// `run_if = in_state(GameState::Playing)`. See `void_public::state`.
+///
+/// `fixed` systems run at a fixed rate, zero or more times per frame, rather
+/// than once per frame. See `void_public::FrameConfig::fixed_delta_time`.
+#[proc_macro_attribute]
+pub fn system(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let parser = system_attr_parser();
+    parse_macro_input!(attr with parser);
+    item
+}
+
+/// `system_on_enter` is a marker attribute for FFI codegen. The system runs
+/// once each time the given state is entered, i.e.
+/// `#[system_on_enter(GameState::Playing)]`, rather than every frame. It
+/// accepts the same ordering constraints as [`macro@system`], following the
+/// state.
+#[proc_macro_attribute]
+pub fn system_on_enter(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let parser = state_system_attr_parser();
+    parse_macro_input!(attr with parser);
+    item
+}
+
+/// `system_on_exit` is a marker attribute for FFI codegen. The system runs
+/// once each time the given state is exited, i.e.
+/// `#[system_on_exit(GameState::Playing)]`, rather than every frame. It
+/// accepts the same ordering constraints as [`macro@system`], following the
+/// state.
 #[proc_macro_attribute]
-pub fn system(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn system_on_exit(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let parser = state_system_attr_parser();
+    parse_macro_input!(attr with parser);
     item
 }
 
+/// Validates the attributes of `#[system_on_enter]` and `#[system_on_exit]`.
+fn state_system_attr_parser() -> impl syn::parse::Parser<Output = ()> {
+    |input: ParseStream<'_>| {
+        input.parse::<syn::Path>()?;
+
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            syn::parse::Parser::parse2(system_attr_parser(), input.parse()?)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates the attributes of `#[system]` and `#[system_once]`. The attributes
+/// themselves are read by FFI codegen.
+fn system_attr_parser() -> impl syn::parse::Parser<Output = ()> {
+    syn::meta::parser(|meta| {
+        if meta.path.is_ident("before") || meta.path.is_ident("after") {
+            let value = meta.value()?;
+
+            if value.peek(LitStr) {
+                value.parse::<LitStr>()?;
+            } else {
+                value.parse::<syn::Path>()?;
+            }
+
+            Ok(())
+        } else if meta.path.is_ident("set") {
+            meta.value()?.parse::<LitStr>()?;
+            Ok(())
+        } else if meta.path.is_ident("run_if") {
+            let condition = meta.value()?.parse::<syn::ExprCall>()?;
+
+            let is_in_state = matches!(
+                condition.func.as_ref(),
+                syn::Expr::Path(path) if path.path.is_ident("in_state")
+            );
+
+            if !is_in_state || condition.args.len() != 1 {
+                return Err(syn::Error::new_spanned(
+                    condition,
+                    "unsupported run condition, expected `in_state(State::Variant)`",
+                ));
+            }
+
+            Ok(())
+        } else if meta.path.is_ident("fixed") {
+            Ok(())
+        } else {
+            Err(meta.error(
+                "unsupported system attribute, expected `before`, `after`, `set`, `run_if` or `fixed`",
+            ))
+        }
+    })
+}
+
 /// `init` is a marker attribute to run the function when the module starts.
 /// `init` functions should take no parameters and not have a return type.
 #[proc_macro_attribute]
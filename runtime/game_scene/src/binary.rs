@@ -0,0 +1,1021 @@
+//! The binary scene format, which is loaded without parsing JSON or
+//! deserializing components. Binary scenes are converted from JSON scenes, by
+//! [`write_binary_scene`].
+//!
+//! Components are stored as their raw bytes, keyed by their string id, so a
+//! binary scene must be converted again when the layout of any of its
+//! components changes. Entities are grouped by archetype, and the components of
+//! an archetype are stored in columns, which are copied as a whole into a
+//! [`SceneTable`], so that the entities of the archetype are spawned in one
+//! step.
+//!
+//! Components are written field by field from their reflected fields, and
+//! their padding is written as zeros. Components whose data is not fully
+//! described by their fields, e.g. components with opaque fields, cannot be
+//! stored in binary scenes. Array fields are written as is, so their elements
+//! must not contain padding.
+//!
+//! All integers are little-endian `u32`s, and strings are prefixed by their
+//! length in bytes:
+//!
+//! ```text
+//! magic               b"VSCN"
+//! binary version      u32
+//! scene version       string, see `VERSION`
+//! components          u32 count, then per component: string id, u32 size
+//! archetypes          u32 count, then per archetype:
+//!     components      u32 count, then u32 component indices
+//!     entities        u32 count, then per entity:
+//!         parent      u32 entity index, or u32::MAX
+//!         label       u8 flag, then a string if the flag is 1
+//!     columns         per component, the data of each entity
+//! asset references    u32 count, then per reference: u32 entity index,
+//!                     u32 component index, u32 offset, u8 asset type, string
+//!                     asset path
+//! entity references   u32 count, then per reference: u32 entity index,
+//!                     u32 component index, u32 offset, u32 entity index
+//! ```
+//!
+//! Entities are indexed in the order they are stored, across archetypes.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    mem::MaybeUninit,
+    ops::Range,
+};
+
+use void_public::{
+    ComponentId, ComponentRef,
+    reflect::{ComponentField, FieldType},
+};
+
+use crate::{
+    SceneAssetRef, SceneColumn, SceneEntities, SceneEntityInfo, SceneEntityRef, SceneTable, VERSION,
+};
+
+const MAGIC: &[u8; 4] = b"VSCN";
+
+/// The version of the binary layout, which is incremented whenever it changes.
+const BINARY_VERSION: u32 = 1;
+
+const NO_PARENT: u32 = u32::MAX;
+
+/// Returns whether `scene_data` is a binary scene, rather than a JSON scene.
+pub fn is_binary_scene(scene_data: &[u8]) -> bool {
+    scene_data.starts_with(MAGIC)
+}
+
+/// A binary scene whose layout was read, but whose components are not
+/// matched with the registered components yet. It may be read on any thread.
+pub struct BinaryScene {
+    data: Vec<u8>,
+    /// The string id and size of each component.
+    components: Vec<(String, usize)>,
+    entities: Vec<BinaryEntity>,
+    archetypes: Vec<BinaryArchetype>,
+    asset_refs: Vec<BinaryAssetRef>,
+    entity_refs: Vec<BinaryEntityRef>,
+}
+
+struct BinaryEntity {
+    parent: Option<usize>,
+    label: Option<String>,
+}
+
+struct BinaryArchetype {
+    entities: Range<usize>,
+    /// The index and data range of each component's column.
+    columns: Vec<(usize, Range<usize>)>,
+}
+
+struct BinaryAssetRef {
+    entity: usize,
+    component: usize,
+    offset: usize,
+    field_type: FieldType,
+    asset_path: String,
+}
+
+struct BinaryEntityRef {
+    entity: usize,
+    component: usize,
+    offset: usize,
+    referenced_entity: usize,
+}
+
+/// Reads the layout of a binary scene.
+///
+/// Binary scenes are not upgraded by migrations, so this fails if the scene
+/// was converted from an older version of the scene format.
+pub fn parse_binary_scene(
+    scene_data: Vec<u8>,
+) -> Result<BinaryScene, Box<dyn Error + Send + Sync>> {
+    let mut reader = Reader {
+        data: &scene_data,
+        position: 0,
+    };
+
+    if reader.bytes(MAGIC.len())? != MAGIC {
+        return Err("not a binary scene".into());
+    }
+
+    let binary_version = reader.u32()?;
+    if binary_version != BINARY_VERSION {
+        return Err(format!(
+            "unexpected binary scene version: '{binary_version}', expected: '{BINARY_VERSION}', the scene must be converted again"
+        )
+        .into());
+    }
+
+    let version = reader.string()?;
+    if version != VERSION {
+        return Err(format!(
+            "binary scene was converted from scene version '{version}', expected: '{VERSION}', the scene must be converted again"
+        )
+        .into());
+    }
+
+    let components = (0..reader.u32()?)
+        .map(|_| Ok((reader.string()?, reader.u32()? as usize)))
+        .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()?;
+
+    let mut entities = Vec::new();
+    let mut archetypes = Vec::new();
+
+    for _ in 0..reader.u32()? {
+        let component_indices = (0..reader.u32()?)
+            .map(|_| reader.index(components.len()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let first_entity = entities.len();
+
+        for _ in 0..reader.u32()? {
+            let parent = match reader.u32()? {
+                NO_PARENT => None,
+                parent => Some(parent as usize),
+            };
+
+            let label = match reader.u8()? {
+                0 => None,
+                _ => Some(reader.string()?),
+            };
+
+            entities.push(BinaryEntity { parent, label });
+        }
+
+        let entity_count = entities.len() - first_entity;
+
+        let columns = component_indices
+            .into_iter()
+            .map(|component| {
+                let column_size = components[component]
+                    .1
+                    .checked_mul(entity_count)
+                    .ok_or("binary scene is truncated")?;
+
+                let start = reader.position;
+                reader.bytes(column_size)?;
+                Ok((component, start..reader.position))
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()?;
+
+        archetypes.push(BinaryArchetype {
+            entities: first_entity..entities.len(),
+            columns,
+        });
+    }
+
+    // parents may be stored after their children, so they are validated once all entities are read
+    if let Some(parent) = entities
+        .iter()
+        .filter_map(|entity| entity.parent)
+        .find(|parent| *parent >= entities.len())
+    {
+        return Err(format!("invalid index {parent} in binary scene").into());
+    }
+
+    let asset_refs = (0..reader.u32()?)
+        .map(|_| {
+            Ok(BinaryAssetRef {
+                entity: reader.index(entities.len())?,
+                component: reader.index(components.len())?,
+                offset: reader.u32()? as usize,
+                field_type: asset_field_type(reader.u8()?)?,
+                asset_path: reader.string()?,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()?;
+
+    let entity_refs = (0..reader.u32()?)
+        .map(|_| {
+            Ok(BinaryEntityRef {
+                entity: reader.index(entities.len())?,
+                component: reader.index(components.len())?,
+                offset: reader.u32()? as usize,
+                referenced_entity: reader.index(entities.len())?,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()?;
+
+    Ok(BinaryScene {
+        data: scene_data,
+        components,
+        entities,
+        archetypes,
+        asset_refs,
+        entity_refs,
+    })
+}
+
+impl BinaryScene {
+    /// Copies the components of each archetype into a [`SceneTable`], one
+    /// column at a time. `component_func` returns the id and size of a
+    /// registered component by name.
+    ///
+    /// Components which are not registered, or whose size differs from their
+    /// size when the scene was converted, are skipped.
+    pub fn into_entities<F>(
+        self,
+        component_func: F,
+    ) -> Result<SceneEntities, Box<dyn Error + Send + Sync>>
+    where
+        F: Fn(&str) -> Option<(ComponentId, usize)>,
+    {
+        let component_ids = self
+            .components
+            .iter()
+            .map(|(name, size)| match component_func(name) {
+                Some((component_id, registered_size)) if registered_size == *size => {
+                    Some(component_id)
+                }
+                Some((_, registered_size)) => {
+                    log::error!(
+                        "Component {name} is {registered_size} bytes, but was {size} bytes when the scene was converted, the scene must be converted again"
+                    );
+                    None
+                }
+                None => {
+                    log::error!("Unknown component {name} in binary scene");
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut scene_entities = self
+            .entities
+            .into_iter()
+            .enumerate()
+            .map(|(index, entity)| SceneEntityInfo {
+                scene_id: Some(index.to_string()),
+                parent_scene_id: entity.parent.map(|parent| parent.to_string()),
+                label: entity.label,
+                components: Vec::new(),
+                asset_refs: Vec::new(),
+                entity_refs: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        let tables = self
+            .archetypes
+            .into_iter()
+            .filter(|archetype| !archetype.entities.is_empty())
+            .map(|archetype| SceneTable {
+                entities: archetype.entities,
+                columns: archetype
+                    .columns
+                    .into_iter()
+                    .filter_map(|(component, range)| {
+                        Some(SceneColumn {
+                            component_id: component_ids[component]?,
+                            size: self.components[component].1,
+                            data: self.data[range]
+                                .iter()
+                                .map(|byte| MaybeUninit::new(*byte))
+                                .collect(),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        // references must be within their components, as they are written at their offsets
+        let in_bounds = |component: usize, offset: usize, size: usize| {
+            if offset
+                .checked_add(size)
+                .is_some_and(|end| end <= self.components[component].1)
+            {
+                true
+            } else {
+                log::error!("Invalid reference field offset {offset} in binary scene");
+                false
+            }
+        };
+
+        for asset_ref in self.asset_refs {
+            if !in_bounds(asset_ref.component, asset_ref.offset, size_of::<u32>()) {
+                continue;
+            }
+
+            if let Some(component_id) = component_ids[asset_ref.component] {
+                scene_entities[asset_ref.entity]
+                    .asset_refs
+                    .push(SceneAssetRef {
+                        component_id,
+                        offset: asset_ref.offset,
+                        field_type: asset_ref.field_type,
+                        asset_path: asset_ref.asset_path,
+                    });
+            }
+        }
+
+        for entity_ref in self.entity_refs {
+            if !in_bounds(entity_ref.component, entity_ref.offset, size_of::<u64>()) {
+                continue;
+            }
+
+            if let Some(component_id) = component_ids[entity_ref.component] {
+                scene_entities[entity_ref.entity]
+                    .entity_refs
+                    .push(SceneEntityRef {
+                        component_id,
+                        offset: entity_ref.offset,
+                        scene_id: entity_ref.referenced_entity.to_string(),
+                    });
+            }
+        }
+
+        Ok(SceneEntities {
+            entities: scene_entities,
+            tables,
+        })
+    }
+}
+
+/// Writes scene entities, as parsed from a JSON scene, in the binary scene
+/// format. `component_func` returns the string id and the reflected fields of
+/// a component.
+///
+/// Components are written field by field, see the module documentation.
+pub fn write_binary_scene<'a, F>(
+    scene_entities: &[SceneEntityInfo],
+    component_func: F,
+) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>
+where
+    F: Fn(ComponentId) -> Option<(&'a str, &'a [ComponentField])>,
+{
+    let mut writer = Writer::default();
+
+    writer.bytes(MAGIC);
+    writer.u32(BINARY_VERSION);
+    writer.string(VERSION)?;
+
+    // index the components, and group the entities by archetype
+    let mut component_indices: HashMap<ComponentId, usize> = HashMap::new();
+    let mut components: Vec<(&str, usize)> = Vec::new();
+    // the byte ranges of the fields of each component, which exclude its padding
+    let mut component_field_ranges: Vec<Vec<Range<usize>>> = Vec::new();
+    let mut archetypes: BTreeMap<Vec<usize>, Vec<usize>> = BTreeMap::new();
+
+    for (entity_index, scene_entity) in scene_entities.iter().enumerate() {
+        let mut archetype = Vec::with_capacity(scene_entity.components.len());
+
+        for component in &scene_entity.components {
+            let component_ref = ComponentRef::from(component);
+            let component_id = component_ref.component_id.unwrap();
+
+            let component_index =
+                if let Some(component_index) = component_indices.get(&component_id) {
+                    *component_index
+                } else {
+                    let (name, fields) = component_func(component_id)
+                        .ok_or_else(|| format!("unknown component id {component_id}"))?;
+                    component_field_ranges.push(field_ranges(
+                        name,
+                        component_ref.component_size,
+                        fields,
+                    )?);
+                    components.push((name, component_ref.component_size));
+                    component_indices.insert(component_id, components.len() - 1);
+                    components.len() - 1
+                };
+
+            archetype.push(component_index);
+        }
+
+        archetype.sort_unstable();
+        archetypes.entry(archetype).or_default().push(entity_index);
+    }
+
+    // entities are indexed in the order they are written
+    let entity_order = archetypes.values().flatten().copied().collect::<Vec<_>>();
+
+    let mut binary_indices = vec![0; scene_entities.len()];
+    for (binary_index, entity_index) in entity_order.iter().enumerate() {
+        binary_indices[*entity_index] = binary_index;
+    }
+    let binary_indices = &binary_indices;
+
+    let scene_id_indices = scene_entities
+        .iter()
+        .enumerate()
+        .filter_map(|(entity_index, scene_entity)| {
+            Some((
+                scene_entity.scene_id.as_deref()?,
+                binary_indices[entity_index] as u32,
+            ))
+        })
+        .collect::<HashMap<_, _>>();
+
+    writer.u32(components.len() as u32);
+    for (name, size) in &components {
+        writer.string(name)?;
+        writer.u32(*size as u32);
+    }
+
+    writer.u32(archetypes.len() as u32);
+    for (archetype, entity_indices) in &archetypes {
+        writer.u32(archetype.len() as u32);
+        for component_index in archetype {
+            writer.u32(*component_index as u32);
+        }
+
+        writer.u32(entity_indices.len() as u32);
+        for entity_index in entity_indices {
+            let scene_entity = &scene_entities[*entity_index];
+
+            let parent = scene_entity
+                .parent_scene_id
+                .as_deref()
+                .and_then(|parent_scene_id| {
+                    let parent = scene_id_indices.get(parent_scene_id).copied();
+                    if parent.is_none() {
+                        log::warn!("Skipping unknown parent scene id {parent_scene_id}");
+                    }
+                    parent
+                });
+            writer.u32(parent.unwrap_or(NO_PARENT));
+
+            if let Some(label) = &scene_entity.label {
+                writer.u8(1);
+                writer.string(label)?;
+            } else {
+                writer.u8(0);
+            }
+        }
+
+        for component_index in archetype {
+            for entity_index in entity_indices {
+                let component = scene_entities[*entity_index]
+                    .components
+                    .iter()
+                    .map(ComponentRef::from)
+                    .find(|component_ref| {
+                        component_indices[&component_ref.component_id.unwrap()] == *component_index
+                    })
+                    .unwrap();
+
+                let data = unsafe {
+                    std::slice::from_raw_parts(
+                        component.component_val.cast::<MaybeUninit<u8>>(),
+                        component.component_size,
+                    )
+                };
+
+                // only the bytes of the fields are initialized, the padding is written as zeros
+                let start = writer.data.len();
+                writer.data.resize(start + data.len(), 0);
+                for range in &component_field_ranges[*component_index] {
+                    for (byte, field_byte) in writer.data[start + range.start..start + range.end]
+                        .iter_mut()
+                        .zip(&data[range.clone()])
+                    {
+                        *byte = unsafe { field_byte.assume_init() };
+                    }
+                }
+
+                // references to entities which are not in the scene are cleared
+                for entity_ref in &scene_entities[*entity_index].entity_refs {
+                    if entity_ref.component_id == component.component_id.unwrap()
+                        && !scene_id_indices.contains_key(entity_ref.scene_id.as_str())
+                        && entity_ref.offset + size_of::<u64>() <= data.len()
+                    {
+                        let field = start + entity_ref.offset;
+                        writer.data[field..field + size_of::<u64>()].fill(0);
+                    }
+                }
+            }
+        }
+    }
+
+    let asset_refs = entity_order
+        .iter()
+        .flat_map(|entity_index| {
+            scene_entities[*entity_index]
+                .asset_refs
+                .iter()
+                .map(move |asset_ref| (binary_indices[*entity_index], asset_ref))
+        })
+        .collect::<Vec<_>>();
+
+    writer.u32(asset_refs.len() as u32);
+    for (binary_index, asset_ref) in asset_refs {
+        writer.u32(binary_index as u32);
+        writer.u32(component_indices[&asset_ref.component_id] as u32);
+        writer.u32(asset_ref.offset as u32);
+        writer.u8(asset_type(asset_ref.field_type)?);
+        writer.string(&asset_ref.asset_path)?;
+    }
+
+    let entity_refs = entity_order
+        .iter()
+        .flat_map(|entity_index| {
+            scene_entities[*entity_index]
+                .entity_refs
+                .iter()
+                .map(move |entity_ref| (binary_indices[*entity_index], entity_ref))
+        })
+        .filter_map(|(binary_index, entity_ref)| {
+            let referenced_entity = scene_id_indices.get(entity_ref.scene_id.as_str());
+            if referenced_entity.is_none() {
+                log::warn!(
+                    "Clearing reference to unknown scene id {}",
+                    entity_ref.scene_id
+                );
+            }
+            Some((binary_index, entity_ref, *referenced_entity?))
+        })
+        .collect::<Vec<_>>();
+
+    writer.u32(entity_refs.len() as u32);
+    for (binary_index, entity_ref, referenced_entity) in entity_refs {
+        writer.u32(binary_index as u32);
+        writer.u32(component_indices[&entity_ref.component_id] as u32);
+        writer.u32(entity_ref.offset as u32);
+        writer.u32(referenced_entity);
+    }
+
+    Ok(writer.data)
+}
+
+/// Returns the byte ranges of a component's data which hold the values of its
+/// fields. Structs are described by their own fields, and the bytes which are
+/// not part of any field are padding.
+fn field_ranges(
+    component_name: &str,
+    size: usize,
+    fields: &[ComponentField],
+) -> Result<Vec<Range<usize>>, Box<dyn Error + Send + Sync>> {
+    if size > 0 && fields.is_empty() {
+        return Err(format!(
+            "component {component_name} has no reflected fields, so it cannot be stored in binary scenes"
+        )
+        .into());
+    }
+
+    let mut ranges = Vec::new();
+
+    for (index, field) in fields.iter().enumerate() {
+        if fields.iter().any(|nested| nested.parent == Some(index)) {
+            continue;
+        }
+
+        let name = field.name.to_string_lossy();
+
+        // the bytes of opaque values, like those of enums, may be uninitialized
+        if matches!(field.field_type, FieldType::Opaque | FieldType::Struct) && field.size > 0 {
+            return Err(format!(
+                "field {name} of component {component_name} is opaque, so the component cannot be stored in binary scenes"
+            )
+            .into());
+        }
+
+        if field.offset + field.size > size {
+            return Err(
+                format!("field {name} is out of bounds of component {component_name}").into(),
+            );
+        }
+
+        ranges.push(field.offset..field.offset + field.size);
+    }
+
+    Ok(ranges)
+}
+
+/// The tag of an asset reference field's type in binary scenes.
+fn asset_type(field_type: FieldType) -> Result<u8, Box<dyn Error + Send + Sync>> {
+    match field_type {
+        FieldType::TextureId => Ok(0),
+        FieldType::TextId => Ok(1),
+        FieldType::MaterialId => Ok(2),
+        FieldType::ParticleEffectId => Ok(3),
+        _ => Err(format!("{field_type:?} is not an asset reference field").into()),
+    }
+}
+
+fn asset_field_type(asset_type: u8) -> Result<FieldType, Box<dyn Error + Send + Sync>> {
+    match asset_type {
+        0 => Ok(FieldType::TextureId),
+        1 => Ok(FieldType::TextId),
+        2 => Ok(FieldType::MaterialId),
+        3 => Ok(FieldType::ParticleEffectId),
+        _ => Err(format!("invalid asset type {asset_type} in binary scene").into()),
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    data: Vec<u8>,
+}
+
+impl Writer {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.data.extend_from_slice(bytes);
+    }
+
+    fn u8(&mut self, val: u8) {
+        self.bytes(&[val]);
+    }
+
+    fn u32(&mut self, val: u32) {
+        self.bytes(&val.to_le_bytes());
+    }
+
+    fn string(&mut self, val: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let len = u32::try_from(val.len()).map_err(|err| format!("string is too long: {err}"))?;
+        self.u32(len);
+        self.bytes(val.as_bytes());
+        Ok(())
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Box<dyn Error + Send + Sync>> {
+        let bytes = self
+            .position
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.position..end))
+            .ok_or("binary scene is truncated")?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, Box<dyn Error + Send + Sync>> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn Error + Send + Sync>> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    /// Reads an index, which must be less than `len`.
+    fn index(&mut self, len: usize) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let index = self.u32()? as usize;
+        if index >= len {
+            return Err(format!("invalid index {index} in binary scene").into());
+        }
+        Ok(index)
+    }
+
+    fn string(&mut self) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let len = self.u32()? as usize;
+        Ok(String::from_utf8(self.bytes(len)?.to_vec())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use void_public::ComponentData;
+
+    use super::*;
+    use crate::{PLACEHOLDER_ENTITY_ID, SceneMigrations, parse_scene};
+
+    const FOLLOW_ID: ComponentId = ComponentId::new(1).unwrap();
+    const FLAG_ID: ComponentId = ComponentId::new(2).unwrap();
+    const NAME_ID: ComponentId = ComponentId::new(3).unwrap();
+
+    fn field(name: &str, field_type: FieldType, offset: usize, size: usize) -> ComponentField {
+        ComponentField {
+            name: CString::new(name).unwrap(),
+            type_name: CString::new(name).unwrap(),
+            field_type,
+            offset,
+            size,
+            parent: None,
+        }
+    }
+
+    /// The components of the test scenes: `follow`, which follows `target`
+    /// and is displayed with the `icon` texture, `flag`, whose `enabled` field
+    /// is followed by padding, and `name`, which holds an opaque `String`.
+    struct TestComponents {
+        follow: Vec<ComponentField>,
+        flag: Vec<ComponentField>,
+        name: Vec<ComponentField>,
+    }
+
+    impl TestComponents {
+        fn new() -> Self {
+            Self {
+                follow: vec![
+                    field("target", FieldType::EntityId, 0, 8),
+                    field("icon", FieldType::TextureId, 8, 4),
+                    field("speed", FieldType::F32, 12, 4),
+                ],
+                flag: vec![
+                    field("enabled", FieldType::Bool, 0, 1),
+                    field("value", FieldType::U32, 4, 4),
+                ],
+                name: vec![field("name", FieldType::Opaque, 0, 24)],
+            }
+        }
+
+        fn by_name(&self, name: &str) -> Option<(ComponentId, &[ComponentField])> {
+            match name {
+                "follow" => Some((FOLLOW_ID, &self.follow)),
+                "flag" => Some((FLAG_ID, &self.flag)),
+                "name" => Some((NAME_ID, &self.name)),
+                _ => None,
+            }
+        }
+
+        fn by_id(&self, component_id: ComponentId) -> Option<(&'static str, &[ComponentField])> {
+            ["follow", "flag", "name"].into_iter().find_map(|name| {
+                let (id, fields) = self.by_name(name)?;
+                (id == component_id).then_some((name, fields))
+            })
+        }
+    }
+
+    fn parse_component(
+        name: &str,
+        text: &str,
+    ) -> Result<ComponentData, Box<dyn Error + Send + Sync>> {
+        let json = json::parse(text)?;
+        let mut data = Vec::new();
+
+        match name {
+            "follow" => {
+                data.extend(json["target"].as_u64().ok_or("target")?.to_ne_bytes());
+                data.extend(json["icon"].as_u32().ok_or("icon")?.to_ne_bytes());
+                data.extend(json["speed"].as_f32().ok_or("speed")?.to_ne_bytes());
+            }
+            "flag" => {
+                data.push(u8::from(json["enabled"].as_bool().ok_or("enabled")?));
+                data.extend(json["value"].as_u32().ok_or("value")?.to_ne_bytes());
+            }
+            _ => return Err(format!("unknown component {name}").into()),
+        }
+
+        let mut data = data.into_iter().map(MaybeUninit::new).collect::<Vec<_>>();
+
+        // the padding of `flag` is uninitialized
+        if name == "flag" {
+            for _ in 0..3 {
+                data.insert(1, MaybeUninit::uninit());
+            }
+        }
+
+        Ok(ComponentData::new(
+            TestComponents::new().by_name(name).unwrap().0,
+            data,
+        ))
+    }
+
+    fn json_scene() -> String {
+        format!(
+            r#"{{
+                "version": "{VERSION}",
+                "entities": [
+                    {{
+                        "id": "leader",
+                        "label": "leader",
+                        "components": {{
+                            "follow": {{ "target": 0, "icon": "textures/flag.png", "speed": 1.5 }},
+                            "flag": {{ "enabled": true, "value": 7 }}
+                        }}
+                    }},
+                    {{
+                        "id": "follower",
+                        "parent_id": "leader",
+                        "components": {{ "follow": {{ "target": "leader", "icon": null, "speed": 2.5 }} }}
+                    }},
+                    {{
+                        "id": "stray",
+                        "components": {{ "follow": {{ "target": "missing", "icon": null, "speed": 0 }} }}
+                    }}
+                ]
+            }}"#
+        )
+    }
+
+    fn binary_scene() -> Vec<u8> {
+        let components = TestComponents::new();
+
+        let scene_entities = parse_scene(
+            &json_scene(),
+            &SceneMigrations::default(),
+            |name| Some(components.by_name(name)?.1),
+            parse_component,
+        )
+        .unwrap();
+
+        write_binary_scene(&scene_entities, |component_id| {
+            components.by_id(component_id)
+        })
+        .unwrap()
+    }
+
+    fn bytes(data: &[MaybeUninit<u8>]) -> Vec<u8> {
+        data.iter()
+            .map(|byte| unsafe { byte.assume_init() })
+            .collect()
+    }
+
+    #[test]
+    fn json_scenes_are_converted() {
+        let components = TestComponents::new();
+
+        let scene_entities = parse_binary_scene(binary_scene())
+            .unwrap()
+            .into_entities(|name| {
+                let size = match name {
+                    "follow" => 16,
+                    _ => 8,
+                };
+                Some((components.by_name(name)?.0, size))
+            })
+            .unwrap();
+
+        // the entities are grouped by their components
+        let [follow_table, leader_table] = scene_entities.tables.as_slice() else {
+            panic!("expected two tables");
+        };
+        assert_eq!(follow_table.entities, 0..2);
+        assert_eq!(leader_table.entities, 2..3);
+
+        let entities = &scene_entities.entities;
+        let labels = entities
+            .iter()
+            .map(|entity| entity.label.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [None, None, Some("leader")]);
+        assert_eq!(entities[0].parent_scene_id.as_deref(), Some("2"));
+        assert_eq!(entities[1].parent_scene_id, None);
+
+        // the follower's target is remapped once spawned, and the stray's
+        // missing target is cleared
+        assert_eq!(entities[0].entity_refs.len(), 1);
+        assert_eq!(entities[0].entity_refs[0].component_id, FOLLOW_ID);
+        assert_eq!(entities[0].entity_refs[0].scene_id, "2");
+        assert!(entities[1].entity_refs.is_empty());
+
+        let follow = |table: &SceneTable, row| bytes(table.columns[0].row(row));
+        assert_eq!(
+            follow(follow_table, 0)[..8],
+            PLACEHOLDER_ENTITY_ID.to_ne_bytes()
+        );
+        assert_eq!(follow(follow_table, 0)[12..], 2.5f32.to_ne_bytes());
+        assert_eq!(follow(follow_table, 1)[..8], 0u64.to_ne_bytes());
+
+        assert_eq!(entities[2].asset_refs.len(), 1);
+        assert_eq!(entities[2].asset_refs[0].offset, 8);
+        assert_eq!(entities[2].asset_refs[0].field_type, FieldType::TextureId);
+        assert_eq!(entities[2].asset_refs[0].asset_path, "textures/flag.png");
+
+        // the padding of `flag` is written as zeros
+        let flag = leader_table
+            .columns
+            .iter()
+            .find(|column| column.component_id == FLAG_ID)
+            .unwrap();
+        assert_eq!(bytes(flag.row(0)), [1, 0, 0, 0, 7, 0, 0, 0]);
+
+        // the components of tables may be moved to their entities
+        let entities = scene_entities.into_vec();
+        assert_eq!(entities[2].components.len(), 2);
+        assert_eq!(entities[0].components.len(), 1);
+    }
+
+    #[test]
+    fn opaque_components_are_not_converted() {
+        let components = TestComponents::new();
+        let scene_entity = |component| SceneEntityInfo {
+            scene_id: Some("0".into()),
+            parent_scene_id: None,
+            label: None,
+            components: vec![component],
+            asset_refs: Vec::new(),
+            entity_refs: Vec::new(),
+        };
+
+        let name = ComponentData::new(NAME_ID, vec![MaybeUninit::new(0); 24]);
+        assert!(
+            write_binary_scene(&[scene_entity(name)], |component_id| components
+                .by_id(component_id))
+            .is_err()
+        );
+
+        // the data of components without reflected fields is unknown
+        let follow = ComponentData::new(FOLLOW_ID, vec![MaybeUninit::new(0); 16]);
+        assert!(write_binary_scene(&[scene_entity(follow)], |_| Some(("follow", &[]))).is_err());
+    }
+
+    #[test]
+    fn truncated_scenes_are_rejected() {
+        let scene = binary_scene();
+        assert!(parse_binary_scene(scene.clone()).is_ok());
+
+        for len in 0..scene.len() {
+            assert!(
+                parse_binary_scene(scene[..len].to_vec()).is_err(),
+                "scene truncated to {len} bytes is rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn bad_magic_is_rejected() {
+        let mut scene = binary_scene();
+        scene[0] = b'X';
+
+        assert!(!is_binary_scene(&scene));
+        assert!(parse_binary_scene(scene).is_err());
+    }
+
+    /// Writes a scene with two `follow` entities, where the second is a child
+    /// of `parent` and references `referenced_entity`.
+    fn indexed_scene(
+        component: u32,
+        parent: u32,
+        asset_entity: u32,
+        referenced_entity: u32,
+        reference_offset: u32,
+    ) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.bytes(MAGIC);
+        writer.u32(BINARY_VERSION);
+        writer.string(VERSION).unwrap();
+
+        writer.u32(1);
+        writer.string("follow").unwrap();
+        writer.u32(16);
+
+        writer.u32(1);
+        writer.u32(1);
+        writer.u32(component);
+        writer.u32(2);
+        writer.u32(NO_PARENT);
+        writer.u8(0);
+        writer.u32(parent);
+        writer.u8(0);
+        writer.bytes(&[0; 32]);
+
+        writer.u32(1);
+        writer.u32(asset_entity);
+        writer.u32(0);
+        writer.u32(8);
+        writer.u8(0);
+        writer.string("textures/arrow.png").unwrap();
+
+        writer.u32(1);
+        writer.u32(1);
+        writer.u32(0);
+        writer.u32(reference_offset);
+        writer.u32(referenced_entity);
+
+        writer.data
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        let follow = |name: &str| (name == "follow").then_some((FOLLOW_ID, 16));
+
+        let scene_entities = parse_binary_scene(indexed_scene(0, 0, 0, 0, 0))
+            .unwrap()
+            .into_entities(follow)
+            .unwrap();
+        assert_eq!(scene_entities.entities[1].entity_refs.len(), 1);
+
+        assert!(parse_binary_scene(indexed_scene(1, 0, 0, 0, 0)).is_err());
+        assert!(parse_binary_scene(indexed_scene(0, 2, 0, 0, 0)).is_err());
+        assert!(parse_binary_scene(indexed_scene(0, 0, 2, 0, 0)).is_err());
+        assert!(parse_binary_scene(indexed_scene(0, 0, 0, 2, 0)).is_err());
+
+        // references outside of their components are skipped
+        for offset in [12, u32::MAX] {
+            let scene_entities = parse_binary_scene(indexed_scene(0, 0, 0, 0, offset))
+                .unwrap()
+                .into_entities(follow)
+                .unwrap();
+            assert!(scene_entities.entities[1].entity_refs.is_empty());
+        }
+    }
+}
@@ -1,10 +1,27 @@
-use std::{collections::BTreeMap, error::Error, mem::MaybeUninit};
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    mem::MaybeUninit,
+    ops::Range,
+};
 
 use event::SpawnComponentData;
-use json::JsonValue;
-use void_public::{ComponentData, ComponentId, EcsType, graphics::TextureRender};
+use json::{JsonValue, object};
+use void_public::{
+    ComponentData, ComponentId, EcsType,
+    graphics::TextureRender,
+    reflect::{ComponentField, FieldType},
+};
 
-const VERSION: &str = "0.0.2";
+mod binary;
+mod migration;
+
+pub use binary::{BinaryScene, is_binary_scene, parse_binary_scene, write_binary_scene};
+pub use migration::{SceneMigrationFn, SceneMigrations};
+
+/// The current version of the scene format. Scenes of older versions are
+/// upgraded by [`SceneMigrations`] when parsed.
+pub const VERSION: &str = "0.0.2";
 
 pub struct SceneEntityInfo {
     /// A per-entity `String` that uniquely identifies an entity in the scene file. This allows
@@ -21,84 +38,525 @@ pub struct SceneEntityInfo {
     /// Component data for this entity
     pub components: Vec<ComponentData>,
 
-    /// An optional `String` that indicates the `asset_path` for the `TextureRender` component.
-    pub texture_asset_path: Option<String>,
+    /// The asset reference fields of the components, which must be assigned
+    /// the ids of their assets before the entity is spawned.
+    pub asset_refs: Vec<SceneAssetRef>,
+
+    /// The entity reference fields of the components, which must be assigned
+    /// the ids of the entities they reference once all entities are spawned.
+    pub entity_refs: Vec<SceneEntityRef>,
+}
+
+/// A field of a scene entity's component, which references an asset by its
+/// path or name.
+pub struct SceneAssetRef {
+    pub component_id: ComponentId,
+    /// The byte offset of the field, from the start of the component.
+    pub offset: usize,
+    /// The type of the field, which determines the type of the asset.
+    pub field_type: FieldType,
+    /// The asset path of the asset, or the name of a material.
+    pub asset_path: String,
+}
+
+/// A field of a scene entity's component, which references another entity of
+/// the scene by its `scene_id`.
+pub struct SceneEntityRef {
+    pub component_id: ComponentId,
+    /// The byte offset of the field, from the start of the component.
+    pub offset: usize,
+    pub scene_id: String,
+}
+
+/// The entities of a scene. The components of JSON scenes are stored with
+/// their entities, while the components of binary scenes are stored in
+/// [`SceneTable`]s, so that the entities of each table may be spawned in one
+/// step.
+#[derive(Default)]
+pub struct SceneEntities {
+    pub entities: Vec<SceneEntityInfo>,
+    /// The tables of the entities whose components are not stored with them,
+    /// in the order of their entities.
+    pub tables: Vec<SceneTable>,
+}
+
+impl From<Vec<SceneEntityInfo>> for SceneEntities {
+    fn from(entities: Vec<SceneEntityInfo>) -> Self {
+        Self {
+            entities,
+            tables: Vec::new(),
+        }
+    }
+}
+
+impl SceneEntities {
+    /// Moves the components of the tables to their entities, for scenes whose
+    /// entities are spawned one by one.
+    pub fn into_vec(self) -> Vec<SceneEntityInfo> {
+        let mut entities = self.entities;
+
+        for table in self.tables {
+            for column in &table.columns {
+                for (row, index) in table.entities.clone().enumerate() {
+                    entities[index].components.push(ComponentData::new(
+                        column.component_id,
+                        column.row(row).to_vec(),
+                    ));
+                }
+            }
+        }
+
+        entities
+    }
+}
+
+/// Consecutive entities of a binary scene which have the same components.
+/// The data of each component is stored in a column, which holds the
+/// component of each entity in order.
+pub struct SceneTable {
+    /// The indices of the table's entities in [`SceneEntities::entities`].
+    pub entities: Range<usize>,
+    pub columns: Vec<SceneColumn>,
+}
+
+impl SceneTable {
+    /// Returns the data of a component of the scene entity at `index`, if the
+    /// table holds it.
+    pub fn component_data_mut(
+        &mut self,
+        index: usize,
+        component_id: ComponentId,
+    ) -> Option<&mut [MaybeUninit<u8>]> {
+        if !self.entities.contains(&index) {
+            return None;
+        }
+
+        let row = index - self.entities.start;
+        self.columns
+            .iter_mut()
+            .find(|column| column.component_id == component_id)
+            .map(|column| column.row_mut(row))
+    }
+}
+
+pub struct SceneColumn {
+    pub component_id: ComponentId,
+    /// The size of the component.
+    pub size: usize,
+    pub data: Vec<MaybeUninit<u8>>,
+}
+
+impl SceneColumn {
+    /// Returns the data of the component of the table's entity at `row`.
+    pub fn row(&self, row: usize) -> &[MaybeUninit<u8>] {
+        &self.data[row * self.size..(row + 1) * self.size]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [MaybeUninit<u8>] {
+        &mut self.data[row * self.size..(row + 1) * self.size]
+    }
+}
+
+/// An asset or entity reference field of a component, as returned by
+/// [`asset_fields`] and [`entity_fields`].
+pub struct ReferenceField<'a> {
+    /// The keys of the field in the component's JSON, from the outermost
+    /// struct.
+    pub json_path: Vec<&'a str>,
+    pub field: &'a ComponentField,
+}
+
+/// Returns the fields of a component which reference assets.
+///
+/// The texture of a `TextureRender` is referenced by its `asset_path` key,
+/// rather than by its `texture_id` field.
+pub fn asset_fields<'a>(
+    component_name: &str,
+    fields: &'a [ComponentField],
+) -> Vec<ReferenceField<'a>> {
+    let texture_render_str_id = TextureRender::string_id().to_str().unwrap();
+
+    fields
+        .iter()
+        .filter(|field| field.field_type.is_asset())
+        .filter_map(|field| {
+            let json_path = if component_name == texture_render_str_id
+                && field.name.as_c_str() == c"texture_id"
+            {
+                vec!["asset_path"]
+            } else {
+                field_json_path(fields, field)?
+            };
+
+            Some(ReferenceField { json_path, field })
+        })
+        .collect()
 }
 
-/// Parses a scene from a json file. It returns a `Vec<SceneEntityInfo>` containing parsed entity data
-pub fn parse_scene<F>(
+/// Returns the fields of a component which reference entities.
+pub fn entity_fields(fields: &[ComponentField]) -> Vec<ReferenceField<'_>> {
+    fields
+        .iter()
+        .filter(|field| field.field_type == FieldType::EntityId)
+        .filter_map(|field| {
+            Some(ReferenceField {
+                json_path: field_json_path(fields, field)?,
+                field,
+            })
+        })
+        .collect()
+}
+
+/// Returns the keys of a field in the component's JSON, from the outermost
+/// struct.
+fn field_json_path<'a>(
+    fields: &'a [ComponentField],
+    field: &'a ComponentField,
+) -> Option<Vec<&'a str>> {
+    let mut json_path = vec![field.name.to_str().ok()?];
+    let mut parent = field.parent;
+
+    while let Some(index) = parent {
+        json_path.insert(0, fields[index].name.to_str().ok()?);
+        parent = fields[index].parent;
+    }
+
+    Some(json_path)
+}
+
+/// An entity to be written into a scene file by [`write_scene`].
+pub struct SceneEntityJson {
+    /// The identifier of the entity in the scene file, which must be unique
+    /// within the scene.
+    pub scene_id: String,
+
+    /// The `scene_id` of this entity's parent, if it has one.
+    pub parent_scene_id: Option<String>,
+
+    pub label: Option<String>,
+
+    /// The JSON representation of each component of the entity, keyed by the
+    /// component's string id.
+    pub components: Vec<(String, String)>,
+
+    /// The assets and entities referenced by the components, which replace
+    /// the ids of the assets and entities in the JSON.
+    pub references: Vec<SceneReference>,
+}
+
+/// A reference of a component to an asset, by its asset path or material
+/// name, or to an entity, by its `scene_id`, to be written into a scene file
+/// by [`write_scene`].
+pub struct SceneReference {
+    /// The string id of the component.
+    pub component_name: String,
+    /// The keys of the field in the component's JSON, see [`ReferenceField`].
+    pub json_path: Vec<String>,
+    /// The asset path, material name or `scene_id`, or `None` to write `null`
+    /// for an asset which has no path. Asset fields which are `null` are
+    /// parsed as the fallback asset of their type.
+    pub value: Option<String>,
+}
+
+/// A scene file parsed as JSON, whose components are not deserialized yet.
+/// Unlike components, which are deserialized by their modules, the JSON may be
+/// parsed on any thread.
+pub struct SceneJson {
+    json: JsonValue,
+}
+
+/// Parses a scene from a json file, upgrading it to the current version with
+/// `migrations`. It returns a `Vec<SceneEntityInfo>` containing parsed entity data
+///
+/// `component_fields_func` returns the reflected fields of a component by
+/// name, which are used to find the asset references of the component.
+pub fn parse_scene<'f, C, F>(
     scene_file: &str,
+    migrations: &SceneMigrations,
+    component_fields_func: C,
     parse_ecs_component_func: F,
 ) -> Result<Vec<SceneEntityInfo>, Box<dyn Error + Send + Sync>>
 where
+    C: Fn(&str) -> Option<&'f [ComponentField]>,
     F: Fn(&str, &str) -> Result<ComponentData, Box<dyn Error + Send + Sync>>,
 {
-    let json = json::parse(scene_file)?;
+    parse_scene_json(scene_file)?.into_entities(
+        migrations,
+        component_fields_func,
+        parse_ecs_component_func,
+    )
+}
 
-    let Some(version) = json["version"].as_str() else {
-        return Err("scene does not contain 'version' field".into());
-    };
+/// A scene file in either the JSON or the binary scene format, whose
+/// components are not deserialized yet.
+pub enum SceneFile {
+    Json(SceneJson),
+    Binary(BinaryScene),
+}
 
-    if version != VERSION {
-        return Err(format!("unexpected scene version: '{version}', expected: '{VERSION}'").into());
+/// Parses a scene file, detecting whether it is a JSON or a binary scene.
+pub fn parse_scene_file(scene_data: Vec<u8>) -> Result<SceneFile, Box<dyn Error + Send + Sync>> {
+    if is_binary_scene(&scene_data) {
+        Ok(SceneFile::Binary(parse_binary_scene(scene_data)?))
+    } else {
+        Ok(SceneFile::Json(parse_scene_json(&String::from_utf8(
+            scene_data,
+        )?)?))
     }
+}
 
-    let JsonValue::Array(entities) = &json["entities"] else {
-        return Err("scene does not contain 'entities' array".into());
-    };
+/// Parses a scene json file, without deserializing its components.
+pub fn parse_scene_json(scene_file: &str) -> Result<SceneJson, Box<dyn Error + Send + Sync>> {
+    Ok(SceneJson {
+        json: json::parse(scene_file)?,
+    })
+}
 
-    let texture_render_str_id = TextureRender::string_id().to_str().unwrap();
+impl SceneJson {
+    /// Upgrades the scene to the current version with `migrations`, returning
+    /// whether it was upgraded. Like parsing, upgrading does not deserialize
+    /// components, and may happen on any thread.
+    pub fn upgrade(
+        &mut self,
+        migrations: &SceneMigrations,
+    ) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        migrations.upgrade(&mut self.json)
+    }
 
-    let all_scene_entities = entities
-        .iter()
-        .map(|entity| {
-            let mut texture_asset_path = None;
-            let components = entity["components"]
-                .entries()
-                .map(|(name, val)| {
-                    if name == texture_render_str_id {
-                        // capture the `asset_path` of the `TextureRender` so it can be looked up later
-                        texture_asset_path = Some(
-                            val["asset_path"]
-                                .as_str()
-                                .ok_or("texture_render components require `asset_path`")?
-                                .into(),
-                        );
-                    }
-
-                    parse_ecs_component_func(name, &val.dump())
-                })
-                .filter(|comp_type| {
-                    if let Err(e) = comp_type {
-                        log::error!("{e}");
-                        false
-                    } else {
-                        true
-                    }
+    /// Upgrades the scene to the current version with `migrations`, if it was
+    /// not upgraded yet, and deserializes the components of each entity. It returns a `Vec<SceneEntityInfo>`
+    /// containing parsed entity data
+    ///
+    /// Asset reference fields which contain an asset path are deserialized
+    /// with a placeholder id, and recorded in [`SceneEntityInfo::asset_refs`].
+    /// Likewise, entity reference fields which contain the `scene_id` of an
+    /// entity are recorded in [`SceneEntityInfo::entity_refs`].
+    pub fn into_entities<'f, C, F>(
+        mut self,
+        migrations: &SceneMigrations,
+        component_fields_func: C,
+        parse_ecs_component_func: F,
+    ) -> Result<Vec<SceneEntityInfo>, Box<dyn Error + Send + Sync>>
+    where
+        C: Fn(&str) -> Option<&'f [ComponentField]>,
+        F: Fn(&str, &str) -> Result<ComponentData, Box<dyn Error + Send + Sync>>,
+    {
+        migrations.upgrade(&mut self.json)?;
+
+        let JsonValue::Array(mut entities) = self.json.remove("entities") else {
+            return Err("scene does not contain 'entities' array".into());
+        };
+
+        let texture_render_str_id = TextureRender::string_id().to_str().unwrap();
+
+        // the asset and entity reference fields of each component, by name
+        let mut reference_fields = HashMap::<String, (Vec<_>, Vec<_>)>::new();
+
+        let all_scene_entities = entities
+            .iter_mut()
+            .map(|entity| {
+                let mut asset_refs = Vec::new();
+                let mut entity_refs = Vec::new();
+                let components = entity["components"]
+                    .entries_mut()
+                    .map(|(name, val)| {
+                        if name == texture_render_str_id && !val["asset_path"].is_string() {
+                            return Err("texture_render components require `asset_path`".into());
+                        }
+
+                        if !reference_fields.contains_key(name) {
+                            let fields = component_fields_func(name).unwrap_or_default();
+                            reference_fields.insert(
+                                name.to_owned(),
+                                (asset_fields(name, fields), entity_fields(fields)),
+                            );
+                        }
+
+                        let (component_asset_fields, component_entity_fields) =
+                            &reference_fields[name];
+
+                        // replace the asset paths with placeholder ids, which are assigned
+                        // once the assets are looked up
+                        let mut asset_paths = Vec::new();
+
+                        for asset_field in component_asset_fields {
+                            let Some(value) = json_path_mut(val, &asset_field.json_path) else {
+                                continue;
+                            };
+
+                            if let Some(asset_path) = value.as_str() {
+                                asset_paths.push((asset_field.field, asset_path.to_owned()));
+                                *value = placeholder_asset_id(asset_field.field.field_type).into();
+                            } else if value.is_null() {
+                                *value = placeholder_asset_id(asset_field.field.field_type).into();
+                            }
+                        }
+
+                        let mut scene_ids = Vec::new();
+
+                        for entity_field in component_entity_fields {
+                            let Some(value) = json_path_mut(val, &entity_field.json_path) else {
+                                continue;
+                            };
+
+                            if let Some(scene_id) = value.as_str() {
+                                scene_ids.push((entity_field.field, scene_id.to_owned()));
+                                *value = PLACEHOLDER_ENTITY_ID.into();
+                            }
+                        }
+
+                        let component = parse_ecs_component_func(name, &val.dump())?;
+
+                        asset_refs.extend(asset_paths.into_iter().map(|(field, asset_path)| {
+                            SceneAssetRef {
+                                component_id: component.component_id().unwrap(),
+                                offset: field.offset,
+                                field_type: field.field_type,
+                                asset_path,
+                            }
+                        }));
+
+                        entity_refs.extend(scene_ids.into_iter().map(|(field, scene_id)| {
+                            SceneEntityRef {
+                                component_id: component.component_id().unwrap(),
+                                offset: field.offset,
+                                scene_id,
+                            }
+                        }));
+
+                        Ok(component)
+                    })
+                    .filter(|comp_type| {
+                        if let Err(e) = comp_type {
+                            log::error!("{e}");
+                            false
+                        } else {
+                            true
+                        }
+                    })
+                    .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()
+                    .unwrap();
+
+                // capture the scene_file specific entity identifiers
+                let scene_id = entity["id"].as_str().map(|id| id.to_string());
+                let parent_id = entity["parent_id"]
+                    .as_str()
+                    .map(|parent_str| parent_str.to_string());
+                let label = entity["label"]
+                    .as_str()
+                    .map(|label_str| label_str.to_string());
+
+                Ok(SceneEntityInfo {
+                    scene_id,
+                    components,
+                    label,
+                    asset_refs,
+                    entity_refs,
+                    parent_scene_id: parent_id,
                 })
-                .collect::<Result<Vec<_>, _>>()
-                .unwrap();
-
-            // capture the scene_file specific entity identifiers
-            let scene_id = entity["id"].as_str().map(|id| id.to_string());
-            let parent_id = entity["parent_id"]
-                .as_str()
-                .map(|parent_str| parent_str.to_string());
-            let label = entity["label"]
-                .as_str()
-                .map(|label_str| label_str.to_string());
-
-            Ok(SceneEntityInfo {
-                scene_id,
-                components,
-                label,
-                texture_asset_path,
-                parent_scene_id: parent_id,
             })
-        })
-        .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()?;
+            .collect::<Result<Vec<_>, Box<dyn Error + Send + Sync>>>()?;
+
+        Ok(all_scene_entities)
+    }
+}
+
+/// Returns the value at `json_path`, if it exists.
+fn json_path_mut<'a>(
+    mut value: &'a mut JsonValue,
+    json_path: &[&str],
+) -> Option<&'a mut JsonValue> {
+    for key in json_path {
+        if !value.has_key(key) {
+            return None;
+        }
+        value = &mut value[*key];
+    }
 
-    Ok(all_scene_entities)
+    Some(value)
+}
+
+/// The id deserialized into an asset reference field, until the id of its
+/// asset is assigned. It is the id of the fallback asset of each type, which
+/// is kept if the asset cannot be found.
+fn placeholder_asset_id(field_type: FieldType) -> u32 {
+    match field_type {
+        // `TextId`s are non-zero, and the first id is the missing text
+        FieldType::TextId => 1,
+        _ => 0,
+    }
+}
+
+/// The id deserialized into an entity reference field, until the id of the
+/// entity is assigned. It does not refer to any entity, and is cleared if the
+/// scene does not contain the entity.
+pub const PLACEHOLDER_ENTITY_ID: u64 = u64::MAX;
+
+/// Writes a scene json file, in the format read by [`parse_scene`].
+pub fn write_scene(
+    scene_entities: &[SceneEntityJson],
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let texture_render_str_id = TextureRender::string_id().to_str().unwrap();
+
+    let mut entities = JsonValue::new_array();
+
+    for scene_entity in scene_entities {
+        let mut components = JsonValue::new_object();
+
+        for (name, text) in &scene_entity.components {
+            let mut component = json::parse(text)?;
+
+            // assets and entities are referenced by their asset path and scene id, as their ids
+            // are only valid for the current session
+            let references = scene_entity
+                .references
+                .iter()
+                .filter(|reference| &reference.component_name == name)
+                .collect::<Vec<_>>();
+
+            if name == texture_render_str_id
+                && !references.iter().any(|reference| reference.value.is_some())
+            {
+                log::warn!(
+                    "Skipping {name} of scene entity {} without a texture asset path",
+                    scene_entity.scene_id
+                );
+                continue;
+            }
+
+            for reference in references {
+                let mut value = &mut component;
+                for key in &reference.json_path {
+                    value = &mut value[key.as_str()];
+                }
+                *value = reference.value.as_deref().into();
+            }
+
+            components[name.as_str()] = component;
+        }
+
+        let mut entity = object! {
+            id: scene_entity.scene_id.as_str(),
+            components: components,
+        };
+
+        if let Some(parent_id) = &scene_entity.parent_scene_id {
+            entity["parent_id"] = parent_id.as_str().into();
+        }
+
+        if let Some(label) = &scene_entity.label {
+            entity["label"] = label.as_str().into();
+        }
+
+        entities.push(entity)?;
+    }
+
+    let scene = object! {
+        version: VERSION,
+        entities: entities,
+    };
+
+    Ok(scene.pretty(2))
 }
 
 pub struct SceneEntityComponents<'a> {
@@ -117,3 +575,179 @@ impl SpawnComponentData for SceneEntityComponents<'_> {
             .map(|boxed_data| boxed_data.as_ref())
     }
 }
+
+/// The components of an entity of a [`SceneTable`], with the values of the
+/// components bundled with the table's components.
+pub struct SceneTableRow<'a> {
+    pub component_ids: &'a [ComponentId],
+    pub table: &'a SceneTable,
+    pub row: usize,
+    pub bundled_components: &'a BTreeMap<ComponentId, Box<[MaybeUninit<u8>]>>,
+}
+
+impl SpawnComponentData for SceneTableRow<'_> {
+    fn sorted_component_ids(&self) -> &[ComponentId] {
+        self.component_ids
+    }
+
+    fn component_data(&self, component_id: ComponentId) -> Option<&[MaybeUninit<u8>]> {
+        self.table
+            .columns
+            .iter()
+            .find(|column| column.component_id == component_id)
+            .map(|column| column.row(self.row))
+            .or_else(|| {
+                self.bundled_components
+                    .get(&component_id)
+                    .map(|boxed_data| boxed_data.as_ref())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    const FOLLOW_ID: ComponentId = ComponentId::new(1).unwrap();
+
+    /// The fields of a `follow` component, which follows `target` and is
+    /// displayed with the `icon` texture.
+    fn follow_fields() -> Vec<ComponentField> {
+        let field = |name: &str, field_type, offset, size| ComponentField {
+            name: CString::new(name).unwrap(),
+            type_name: CString::new(name).unwrap(),
+            field_type,
+            offset,
+            size,
+            parent: None,
+        };
+
+        vec![
+            field("target", FieldType::EntityId, 0, size_of::<u64>()),
+            field("icon", FieldType::TextureId, 8, size_of::<u32>()),
+            field("speed", FieldType::F32, 12, size_of::<f32>()),
+        ]
+    }
+
+    fn parse_follow(name: &str, text: &str) -> Result<ComponentData, Box<dyn Error + Send + Sync>> {
+        if name != "follow" {
+            return Err(format!("unknown component {name}").into());
+        }
+
+        let json = json::parse(text)?;
+        let mut data = Vec::new();
+        data.extend(json["target"].as_u64().ok_or("target")?.to_ne_bytes());
+        data.extend(json["icon"].as_u32().ok_or("icon")?.to_ne_bytes());
+        data.extend(json["speed"].as_f32().ok_or("speed")?.to_ne_bytes());
+
+        Ok(ComponentData::new(
+            FOLLOW_ID,
+            data.into_iter().map(MaybeUninit::new).collect(),
+        ))
+    }
+
+    fn reference(json_key: &str, value: Option<&str>) -> SceneReference {
+        SceneReference {
+            component_name: "follow".into(),
+            json_path: vec![json_key.into()],
+            value: value.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn written_scenes_are_parsed() {
+        let fields = follow_fields();
+
+        let scene_entities = [
+            SceneEntityJson {
+                scene_id: "7".into(),
+                parent_scene_id: None,
+                label: Some("leader".into()),
+                components: Vec::new(),
+                references: Vec::new(),
+            },
+            SceneEntityJson {
+                scene_id: "9".into(),
+                parent_scene_id: Some("7".into()),
+                label: None,
+                components: vec![(
+                    "follow".into(),
+                    r#"{ "target": 7, "icon": 12, "speed": 2.5 }"#.into(),
+                )],
+                references: vec![
+                    reference("target", Some("7")),
+                    reference("icon", Some("textures/arrow.png")),
+                ],
+            },
+            SceneEntityJson {
+                scene_id: "10".into(),
+                parent_scene_id: None,
+                label: None,
+                // the texture has no path, and is saved as null
+                components: vec![(
+                    "follow".into(),
+                    r#"{ "target": 0, "icon": 12, "speed": 1 }"#.into(),
+                )],
+                references: vec![reference("icon", None)],
+            },
+        ];
+
+        let scene = write_scene(&scene_entities).unwrap();
+        let mut entities = parse_scene(
+            &scene,
+            &SceneMigrations::default(),
+            |name| (name == "follow").then_some(fields.as_slice()),
+            parse_follow,
+        )
+        .unwrap();
+
+        assert_eq!(entities.len(), 3);
+
+        assert_eq!(entities[0].scene_id.as_deref(), Some("7"));
+        assert_eq!(entities[0].parent_scene_id, None);
+        assert_eq!(entities[0].label.as_deref(), Some("leader"));
+        assert!(entities[0].components.is_empty());
+
+        let follower = &mut entities[1];
+        assert_eq!(follower.scene_id.as_deref(), Some("9"));
+        assert_eq!(follower.parent_scene_id.as_deref(), Some("7"));
+        assert_eq!(follower.label, None);
+
+        assert_eq!(follower.asset_refs.len(), 1);
+        assert_eq!(follower.asset_refs[0].component_id, FOLLOW_ID);
+        assert_eq!(follower.asset_refs[0].offset, 8);
+        assert_eq!(follower.asset_refs[0].field_type, FieldType::TextureId);
+        assert_eq!(follower.asset_refs[0].asset_path, "textures/arrow.png");
+
+        assert_eq!(follower.entity_refs.len(), 1);
+        assert_eq!(follower.entity_refs[0].offset, 0);
+        assert_eq!(follower.entity_refs[0].scene_id, "7");
+
+        let data = read_follow(&mut follower.components[0]);
+        assert_eq!(data, (PLACEHOLDER_ENTITY_ID, 0, 2.5));
+
+        let unnamed = &mut entities[2];
+        assert!(unnamed.asset_refs.is_empty());
+        assert!(unnamed.entity_refs.is_empty());
+        assert_eq!(read_follow(&mut unnamed.components[0]), (0, 0, 1.));
+    }
+
+    fn read_follow(component: &mut ComponentData) -> (u64, u32, f32) {
+        assert_eq!(component.component_id(), Some(FOLLOW_ID));
+        let data = component.component_data_mut();
+        let bytes = |range: std::ops::Range<usize>| {
+            data[range]
+                .iter()
+                .map(|byte| unsafe { byte.assume_init() })
+                .collect::<Vec<_>>()
+        };
+
+        (
+            u64::from_ne_bytes(bytes(0..8).try_into().unwrap()),
+            u32::from_ne_bytes(bytes(8..12).try_into().unwrap()),
+            f32::from_ne_bytes(bytes(12..16).try_into().unwrap()),
+        )
+    }
+}
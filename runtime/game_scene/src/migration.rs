@@ -0,0 +1,321 @@
+use std::{collections::HashMap, error::Error, sync::Arc};
+
+use json::JsonValue;
+
+use crate::VERSION;
+
+/// A transform of the JSON tree of a scene, or of a component of a scene.
+pub type SceneMigrationFn =
+    dyn Fn(&mut JsonValue) -> Result<(), Box<dyn Error + Send + Sync>> + Send + Sync;
+
+/// Upgrades scenes from older versions of the scene format. Each migration
+/// transforms a scene from one version to a newer version, and migrations are
+/// chained until the scene reaches the current version.
+///
+/// Several migrations may be registered between the same versions, e.g. by
+/// the engine and by modules for their own components. They are applied in
+/// the order they were registered.
+///
+/// The engine's own migrations of the scene format are registered by
+/// [`SceneMigrations::default()`], to which modules may add migrations of their
+/// own components.
+#[derive(Clone)]
+pub struct SceneMigrations {
+    /// The migration steps, by the version they upgrade from.
+    steps: HashMap<String, MigrationStep>,
+}
+
+#[derive(Clone)]
+struct MigrationStep {
+    to_version: String,
+    migrations: Vec<Arc<SceneMigrationFn>>,
+}
+
+impl Default for SceneMigrations {
+    /// Returns the engine's migrations of the scene format.
+    fn default() -> Self {
+        let mut migrations = Self {
+            steps: HashMap::new(),
+        };
+
+        register_engine_migrations(&mut migrations);
+
+        migrations
+    }
+}
+
+/// Registers the engine's migrations of the scene format. The format has not
+/// changed since "0.0.2", the first version with migrations, so there are
+/// none yet.
+///
+/// Changes to the format bump [`VERSION`], and register a migration from the
+/// previous version here, e.g. `migrations.register("0.0.2", "0.0.3", ..)`.
+fn register_engine_migrations(_migrations: &mut SceneMigrations) {}
+
+impl SceneMigrations {
+    /// Registers a migration of whole scenes from `from_version` to
+    /// `to_version`. The version field of the scene is updated after all
+    /// migrations between the versions were applied.
+    ///
+    /// Fails if the versions are invalid, if `to_version` is not newer than
+    /// `from_version`, or if a migration from `from_version` to another
+    /// version is already registered.
+    pub fn register<F>(
+        &mut self,
+        from_version: &str,
+        to_version: &str,
+        migration: F,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>
+    where
+        F: Fn(&mut JsonValue) -> Result<(), Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        let from = parse_version(from_version)?;
+        let to = parse_version(to_version)?;
+
+        if to <= from {
+            return Err(format!(
+                "scene migration from '{from_version}' must upgrade to a newer version, not '{to_version}'"
+            )
+            .into());
+        }
+
+        let step = self
+            .steps
+            .entry(from_version.to_owned())
+            .or_insert_with(|| MigrationStep {
+                to_version: to_version.to_owned(),
+                migrations: Vec::new(),
+            });
+
+        if step.to_version != to_version {
+            return Err(format!(
+                "conflicting scene migrations from '{from_version}', to '{}' and to '{to_version}'",
+                step.to_version
+            )
+            .into());
+        }
+
+        step.migrations.push(Arc::new(migration));
+
+        Ok(())
+    }
+
+    /// Registers a migration of a component from `from_version` to
+    /// `to_version`, which is applied to the JSON of the component of each
+    /// entity which has it. See [`SceneMigrations::register()`].
+    pub fn register_component<F>(
+        &mut self,
+        from_version: &str,
+        to_version: &str,
+        component_name: &str,
+        migration: F,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>
+    where
+        F: Fn(&mut JsonValue) -> Result<(), Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        let component_name = component_name.to_owned();
+
+        self.register(from_version, to_version, move |scene| {
+            for entity in scene["entities"].members_mut() {
+                let components = &mut entity["components"];
+
+                if components.has_key(&component_name) {
+                    migration(&mut components[component_name.as_str()])
+                        .map_err(|err| format!("component {component_name}: {err}"))?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Upgrades a scene to the current version, returning whether it was
+    /// upgraded.
+    ///
+    /// Fails if the scene is newer than the current version, or if there is no
+    /// chain of migrations from its version to the current version.
+    pub fn upgrade(&self, scene: &mut JsonValue) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let current = parse_version(VERSION).unwrap();
+        let mut upgraded = false;
+
+        loop {
+            let Some(version) = scene["version"].as_str() else {
+                return Err("scene does not contain 'version' field".into());
+            };
+
+            if version == VERSION {
+                return Ok(upgraded);
+            }
+
+            if parse_version(version)? > current {
+                return Err(format!(
+                    "scene version '{version}' is newer than the latest supported version '{VERSION}', the engine must be updated to load it"
+                )
+                .into());
+            }
+
+            let Some(step) = self.steps.get(version) else {
+                return Err(format!(
+                    "unexpected scene version: '{version}', expected: '{VERSION}', and no migration from '{version}' is registered"
+                )
+                .into());
+            };
+
+            let version = version.to_owned();
+
+            for migration in &step.migrations {
+                migration(scene).map_err(|err| {
+                    format!(
+                        "scene migration from '{version}' to '{}' failed: {err}",
+                        step.to_version
+                    )
+                })?;
+            }
+
+            scene["version"] = step.to_version.as_str().into();
+            upgraded = true;
+        }
+    }
+
+    /// Upgrades a scene json file to the current version. Returns `None` if the
+    /// scene is already at the current version.
+    pub fn upgrade_scene_file(
+        &self,
+        scene_file: &str,
+    ) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        let mut scene = json::parse(scene_file)?;
+
+        Ok(self.upgrade(&mut scene)?.then(|| scene.pretty(2)))
+    }
+}
+
+/// Parses a version of the form `major.minor.patch`, into comparable parts.
+fn parse_version(version: &str) -> Result<Vec<u64>, Box<dyn Error + Send + Sync>> {
+    version
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<_, _>>()
+        .map_err(|err| format!("invalid scene version: '{version}': {err}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use json::object;
+
+    use super::*;
+
+    fn scene(version: &str) -> JsonValue {
+        object! {
+            version: version,
+            entities: [
+                { id: "1", components: { health: { value: 1 } } },
+                { id: "2", components: { armor: { value: 2 } } },
+            ],
+        }
+    }
+
+    #[test]
+    fn current_scenes_are_not_upgraded() {
+        let mut scene = scene(VERSION);
+
+        assert!(!SceneMigrations::default().upgrade(&mut scene).unwrap());
+        assert_eq!(scene, self::scene(VERSION));
+    }
+
+    #[test]
+    fn migrations_are_chained() {
+        let mut migrations = SceneMigrations::default();
+
+        migrations
+            .register("0.0.1", VERSION, |scene| {
+                scene["entities"][0]["label"] = "second".into();
+                Ok(())
+            })
+            .unwrap();
+        migrations
+            .register_component("0.0.0", "0.0.1", "health", |health| {
+                health["value"] = (health["value"].as_u32().unwrap() * 10).into();
+                Ok(())
+            })
+            .unwrap();
+        // migrations between the same versions are applied in order
+        migrations
+            .register("0.0.0", "0.0.1", |scene| {
+                scene["entities"][0]["label"] = "first".into();
+                Ok(())
+            })
+            .unwrap();
+
+        let mut scene = scene("0.0.0");
+        assert!(migrations.upgrade(&mut scene).unwrap());
+
+        assert_eq!(scene["version"], VERSION);
+        assert_eq!(scene["entities"][0]["label"], "second");
+        assert_eq!(scene["entities"][0]["components"]["health"]["value"], 10);
+        assert_eq!(scene["entities"][1]["components"]["armor"]["value"], 2);
+
+        // scenes of intermediate versions are upgraded by the remaining steps
+        let mut scene = self::scene("0.0.1");
+        assert!(migrations.upgrade(&mut scene).unwrap());
+        assert_eq!(scene["entities"][0]["components"]["health"]["value"], 1);
+    }
+
+    #[test]
+    fn scenes_without_migrations_are_rejected() {
+        let mut migrations = SceneMigrations::default();
+        migrations.register("0.0.0", "0.0.1", |_| Ok(())).unwrap();
+
+        // no migration from 0.0.1 to the current version
+        let error = migrations.upgrade(&mut scene("0.0.0")).unwrap_err();
+        assert!(error.to_string().contains("no migration from '0.0.1'"));
+
+        let error = migrations.upgrade(&mut scene("0.0.1")).unwrap_err();
+        assert!(error.to_string().contains("no migration from '0.0.1'"));
+    }
+
+    #[test]
+    fn newer_scenes_are_rejected() {
+        for version in ["0.0.3", "0.1.0", "1.0.0"] {
+            let mut scene = scene(version);
+            let error = SceneMigrations::default().upgrade(&mut scene).unwrap_err();
+
+            assert!(error.to_string().contains("is newer than"));
+            assert_eq!(scene["version"], version);
+        }
+    }
+
+    #[test]
+    fn failed_migrations_are_reported() {
+        let mut migrations = SceneMigrations::default();
+        migrations
+            .register_component("0.0.1", VERSION, "armor", |_| Err("broken".into()))
+            .unwrap();
+
+        let error = migrations.upgrade(&mut scene("0.0.1")).unwrap_err();
+        assert!(error.to_string().contains("component armor: broken"));
+    }
+
+    #[test]
+    fn invalid_migrations_are_not_registered() {
+        let mut migrations = SceneMigrations::default();
+
+        assert!(migrations.register("0.0.1", "0.0.1", |_| Ok(())).is_err());
+        assert!(migrations.register("0.0.1", "0.0.0", |_| Ok(())).is_err());
+        assert!(migrations.register("0.0.x", VERSION, |_| Ok(())).is_err());
+
+        migrations.register("0.0.0", "0.0.1", |_| Ok(())).unwrap();
+        assert!(migrations.register("0.0.0", VERSION, |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn scene_files_are_upgraded() {
+        let mut migrations = SceneMigrations::default();
+        migrations.register("0.0.1", VERSION, |_| Ok(())).unwrap();
+
+        let scene_file = scene("0.0.1").dump();
+        let upgraded = migrations.upgrade_scene_file(&scene_file).unwrap().unwrap();
+        assert_eq!(json::parse(&upgraded).unwrap(), scene(VERSION));
+
+        assert_eq!(migrations.upgrade_scene_file(&upgraded).unwrap(), None);
+    }
+}
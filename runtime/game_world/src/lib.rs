@@ -407,6 +407,49 @@ impl WorldDelegate for SyncWorldDelegate<'_> {
         }
     }
 
+    fn allocate_entity_ids(&self, count: usize) -> Vec<EntityId> {
+        let mut current = self.next_index.load(Ordering::Relaxed);
+
+        loop {
+            // walk the free list, then extend past the end of the entities
+            let mut entity_ids = Vec::with_capacity(count);
+            let mut next = current;
+
+            while entity_ids.len() < count {
+                let (new, lifecycle) = if next >= self.world.entities.len() {
+                    (next + 1, 0)
+                } else {
+                    let entry = &self.world.entities[next];
+
+                    let EntityEntryType::Free(entry_next_entity_index) = entry.entry_type else {
+                        panic!("entity free list corrupted");
+                    };
+
+                    (entry_next_entity_index, entry.lifecycle.0)
+                };
+
+                entity_ids.push(EntityId::new(
+                    next.try_into()
+                        .expect("cannot allocate any more entity ids"),
+                    lifecycle,
+                ));
+                next = new;
+            }
+
+            match self.next_index.compare_exchange_weak(
+                current,
+                next,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return entity_ids,
+                Err(previous) => {
+                    current = previous;
+                }
+            }
+        }
+    }
+
     fn label_entity(&self, label: &CStr) -> Option<EntityId> {
         self.world.entity_label_map.get(label).copied()
     }
@@ -429,4 +472,10 @@ impl WorldDelegate for SyncWorldDelegate<'_> {
             None
         }
     }
+
+    fn children(&self, entity_id: EntityId) -> Option<&[EntityId]> {
+        self.world
+            .get(entity_id)
+            .map(|entity_data| entity_data.child_ids.as_slice())
+    }
 }
@@ -1270,7 +1270,10 @@ pub mod ffi {
     };
 
     use platform::{DeserializeReadFn, EcsModule, EcsSystemFn, SerializeWriteFn};
-    use void_public::{ArgType, ComponentId, ComponentType};
+    use void_public::{
+        ArgType, ComponentId, ComponentType, reflect::ComponentField, state::StateCondition,
+        system::SystemOrdering,
+    };
 
     use super::*;
 
@@ -1285,13 +1288,7 @@ pub mod ffi {
             &mut self,
             ptr: *const *const c_void,
         ) -> Result<(), Box<dyn Error + Send + Sync>> {
-            let res = unsafe { (self.0)(ptr) };
-
-            if res == 0 {
-                Ok(())
-            } else {
-                Err(format!("error code ({res})").into())
-            }
+            platform::module_fn_result(unsafe { (self.0)(ptr) })
         }
     }
 
@@ -1309,23 +1306,11 @@ pub mod ffi {
         }
 
         fn init(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-            let res = init();
-
-            if res == 0 {
-                Ok(())
-            } else {
-                Err(format!("error code ({res})").into())
-            }
+            platform::module_fn_result(init())
         }
 
         fn deinit(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-            let res = deinit();
-
-            if res == 0 {
-                Ok(())
-            } else {
-                Err(format!("error code ({res})").into())
-            }
+            platform::module_fn_result(deinit())
         }
 
         fn module_name(&self) -> Cow<'_, str> {
@@ -1379,6 +1364,14 @@ pub mod ffi {
             Ok(())
         }
 
+        fn component_serialize_json(
+            &self,
+            string_id: &CStr,
+            val: &[MaybeUninit<u8>],
+        ) -> Result<String, Box<dyn Error + Send + Sync>> {
+            Err(format!("unknown component {string_id:?}").into())
+        }
+
         fn component_string_id(&self, index: usize) -> Option<Cow<'_, CStr>> {
             unsafe {
                 let ptr = component_string_id(index);
@@ -1402,6 +1395,17 @@ pub mod ffi {
             unsafe { component_type(string_id.as_ptr()) }
         }
 
+        fn component_fields(&self, string_id: &CStr) -> Vec<ComponentField> {
+            unsafe {
+                let mut fields_len = 0;
+                let fields = component_fields(string_id.as_ptr(), &mut fields_len);
+                std::slice::from_raw_parts(fields, fields_len)
+                    .iter()
+                    .map(|field| ComponentField::from_field_info(field))
+                    .collect()
+            }
+        }
+
         fn component_async_completion_callable(&self, string_id: &CStr) -> Cow<'_, CStr> {
             unsafe {
                 let ptr = component_async_completion_callable(string_id.as_ptr());
@@ -1425,6 +1429,56 @@ pub mod ffi {
             system_is_once(system_index)
         }
 
+        fn system_is_fixed(&self, system_index: usize) -> bool {
+            system_is_fixed(system_index)
+        }
+
+        fn system_orderings_len(&self, system_index: usize) -> usize {
+            system_orderings_len(system_index)
+        }
+
+        fn system_ordering_type(
+            &self,
+            system_index: usize,
+            ordering_index: usize,
+        ) -> SystemOrdering {
+            system_ordering_type(system_index, ordering_index)
+        }
+
+        fn system_ordering_target(
+            &self,
+            system_index: usize,
+            ordering_index: usize,
+        ) -> Cow<'_, CStr> {
+            unsafe { CStr::from_ptr(system_ordering_target(system_index, ordering_index)).into() }
+        }
+
+        fn system_state_conditions_len(&self, system_index: usize) -> usize {
+            system_state_conditions_len(system_index)
+        }
+
+        fn system_state_condition_type(
+            &self,
+            system_index: usize,
+            condition_index: usize,
+        ) -> StateCondition {
+            system_state_condition_type(system_index, condition_index)
+        }
+
+        fn system_state_condition_state(
+            &self,
+            system_index: usize,
+            condition_index: usize,
+        ) -> Cow<'_, CStr> {
+            unsafe {
+                CStr::from_ptr(system_state_condition_state(system_index, condition_index)).into()
+            }
+        }
+
+        fn system_state_condition_value(&self, system_index: usize, condition_index: usize) -> u32 {
+            system_state_condition_value(system_index, condition_index)
+        }
+
         fn system_fn(&self, system_index: usize) -> Box<dyn EcsSystemFn> {
             Box::new(EcsSystemFnC(system_fn(system_index)))
         }
@@ -1,5 +1,6 @@
 use std::{
     borrow::Cow,
+    cell::RefCell,
     error::Error,
     ffi::{CStr, OsStr, c_void},
     future::Future,
@@ -9,15 +10,26 @@ use std::{
     path::Path,
     pin::Pin,
     sync::Arc,
+    time::Duration,
 };
 
-use void_public::{ArgType, ComponentId, ComponentType, callable::TaskId};
+use void_public::{
+    ArgType, ComponentId, ComponentType, callable::TaskId, reflect::ComponentField,
+    state::StateCondition, system::SystemOrdering,
+};
 
 pub trait Platform: Send + Sync + 'static {
+    type Clock: Clock;
     type Executor: Executor;
     type Filesystem: Filesystem;
 }
 
+pub trait Clock {
+    /// Returns the time elapsed since an arbitrary point in time, which does
+    /// not change while the engine runs. The time never decreases.
+    fn now() -> Duration;
+}
+
 pub trait Executor: Send + Sync {
     fn available_parallelism() -> NonZero<usize>;
 
@@ -52,6 +64,32 @@ pub trait EcsSystemFn: Send {
     ) -> Result<(), Box<dyn Error + Send + Sync>>;
 }
 
+thread_local! {
+    /// The error reported by the module function executing on this thread.
+    static MODULE_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Records the error of the module function executing on this thread, such as
+/// a caught panic. Called by modules through the C API.
+pub fn set_module_error(message: String) {
+    MODULE_ERROR.set(Some(message));
+}
+
+/// Converts the return code of a module function to a `Result`. Non-zero codes
+/// are errors, described by the error the function reported with
+/// [`set_module_error()`], if any.
+pub fn module_fn_result(res: i32) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let error = MODULE_ERROR.take();
+
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(error
+            .unwrap_or_else(|| format!("error code ({res})"))
+            .into())
+    }
+}
+
 /// A `FnMut` which reads bytes into a provided byte buffer. Returns the number
 /// of bytes read on success.
 pub type DeserializeReadFn<'a> =
@@ -112,6 +150,15 @@ pub trait EcsModule: Sync {
         json_string: &str,
     ) -> Result<(), Box<dyn Error + Send + Sync>>;
 
+    /// Serialize the component in `val` into JSON, in the representation read
+    /// by `component_deserialize_json`. The name of the component type is given
+    /// by `string_id`.
+    fn component_serialize_json(
+        &self,
+        string_id: &CStr,
+        val: &[MaybeUninit<u8>],
+    ) -> Result<String, Box<dyn Error + Send + Sync>>;
+
     fn component_string_id(&self, index: usize) -> Option<Cow<'_, CStr>>;
 
     fn component_size(&self, string_id: &CStr) -> usize;
@@ -120,6 +167,10 @@ pub trait EcsModule: Sync {
 
     fn component_type(&self, string_id: &CStr) -> ComponentType;
 
+    /// The reflected fields of a component, see `void_public::reflect`. Types
+    /// which are not described are returned without fields.
+    fn component_fields(&self, string_id: &CStr) -> Vec<ComponentField>;
+
     fn component_async_completion_callable(&self, string_id: &CStr) -> Cow<'_, CStr>;
 
     fn systems_len(&self) -> usize;
@@ -128,6 +179,39 @@ pub trait EcsModule: Sync {
 
     fn system_is_once(&self, system_index: usize) -> bool;
 
+    /// Whether a system runs on the fixed timestep, i.e. `#[system(fixed)]`.
+    fn system_is_fixed(&self, system_index: usize) -> bool;
+
+    /// The number of ordering constraints declared on a system, i.e.
+    /// `#[system(after = other_system)]`.
+    fn system_orderings_len(&self, system_index: usize) -> usize;
+
+    fn system_ordering_type(&self, system_index: usize, ordering_index: usize) -> SystemOrdering;
+
+    /// Returns the name of the target system (without the module namespace),
+    /// or the set label.
+    fn system_ordering_target(&self, system_index: usize, ordering_index: usize) -> Cow<'_, CStr>;
+
+    /// The number of state conditions declared on a system, i.e.
+    /// `#[system(run_if = in_state(GameState::Playing))]`.
+    fn system_state_conditions_len(&self, system_index: usize) -> usize;
+
+    fn system_state_condition_type(
+        &self,
+        system_index: usize,
+        condition_index: usize,
+    ) -> StateCondition;
+
+    /// Returns the string id of the condition's state type.
+    fn system_state_condition_state(
+        &self,
+        system_index: usize,
+        condition_index: usize,
+    ) -> Cow<'_, CStr>;
+
+    /// Returns the index of the condition's state variant.
+    fn system_state_condition_value(&self, system_index: usize, condition_index: usize) -> u32;
+
     fn system_fn(&self, system_index: usize) -> Box<dyn EcsSystemFn>;
 
     fn system_args_len(&self, system_index: usize) -> usize;
@@ -180,6 +264,12 @@ pub trait PlatformLibrary {
 
 #[cfg(feature = "test")]
 pub mod test {
+    use std::{
+        path::PathBuf,
+        sync::{Mutex, OnceLock},
+        time::Instant,
+    };
+
     use void_public::ENGINE_VERSION;
 
     use super::*;
@@ -187,10 +277,20 @@ pub mod test {
     pub struct TestPlatform;
 
     impl Platform for TestPlatform {
+        type Clock = TestClock;
         type Executor = TestExecutor;
         type Filesystem = TestFilesystem;
     }
 
+    pub struct TestClock;
+
+    impl Clock for TestClock {
+        fn now() -> Duration {
+            static START: OnceLock<Instant> = OnceLock::new();
+            START.get_or_init(Instant::now).elapsed()
+        }
+    }
+
     pub struct TestExecutor;
 
     impl Executor for TestExecutor {
@@ -217,8 +317,26 @@ pub mod test {
         }
     }
 
+    /// Reads files when [`TestFilesystem::complete_reads()`] is called, so
+    /// that tests control when reads complete.
     pub struct TestFilesystem;
 
+    type PendingRead = Box<dyn FnOnce() + Send>;
+
+    static PENDING_READS: Mutex<Vec<PendingRead>> = Mutex::new(Vec::new());
+
+    impl TestFilesystem {
+        /// Reads the files of all reads started since the last call, and calls
+        /// their completions.
+        pub fn complete_reads() {
+            let pending_reads = std::mem::take(&mut *PENDING_READS.lock().unwrap());
+
+            for read in pending_reads {
+                read();
+            }
+        }
+    }
+
     impl Filesystem for TestFilesystem {
         fn read_async<P, T: 'static>(
             path: P,
@@ -228,7 +346,12 @@ pub mod test {
             P: AsRef<Path>,
             Arc<T>: Send,
         {
-            completion(user_data, std::fs::read(path));
+            let path = PathBuf::from(path.as_ref());
+
+            PENDING_READS
+                .lock()
+                .unwrap()
+                .push(Box::new(move || completion(user_data, std::fs::read(path))));
         }
     }
 
@@ -1,10 +1,13 @@
 use std::{
-    ffi::{CString, c_char, c_void},
+    ffi::{CStr, CString, c_char, c_void},
     marker::PhantomData,
     ops::Deref,
 };
 
 use flatbuffers::{FlatBufferBuilder, Follow, Push, WIPOffset};
+use void_public::event::engine::{EngineError, EngineErrorT};
+#[doc(hidden)]
+pub use void_public::panic::catch_unwind;
 pub use void_public::{ENGINE_VERSION, callable::TaskId};
 
 pub struct Engine;
@@ -60,6 +63,22 @@ impl Engine {
     }
 }
 
+/// Reports a panic caught in a platform library function by sending an
+/// `EngineError` platform event. The task of the function is never completed.
+#[doc(hidden)]
+pub fn report_panic(library_name: &str, function_name: &CStr, message: String) {
+    let mut engine_error = EngineErrorT::default();
+    engine_error.system_name = Some(function_name.to_string_lossy().into_owned());
+    engine_error.module_name = Some(library_name.to_owned());
+    engine_error.message = Some(message);
+
+    unsafe {
+        Engine::send_platform_event_builder(EngineError::get_fully_qualified_name(), |builder| {
+            engine_error.pack(builder)
+        });
+    }
+}
+
 pub struct ParameterData<'a, T: Follow<'a>>(T::Inner);
 
 impl<'a, T: Follow<'a>> ParameterData<'a, T> {
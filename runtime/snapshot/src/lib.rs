@@ -13,14 +13,14 @@
 use std::{
     array::from_fn,
     cmp,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ffi::{CStr, CString, c_void},
     fmt::{Display, Formatter},
     hash::Hash,
     mem::{MaybeUninit, transmute},
     num::{NonZero, Wrapping},
     slice,
-    sync::Arc,
+    sync::{Arc, atomic::AtomicU64},
 };
 
 use aligned_vec::AVec;
@@ -985,6 +985,56 @@ impl<K: Deserialize + Eq + Hash, V: Deserialize> Deserialize for HashMap<K, V> {
     }
 }
 
+impl<T: Serialize> Serialize for HashSet<T> {
+    fn serialize<W>(&self, serializer: &mut Serializer<W>) -> Result<()>
+    where
+        W: WriteUninit,
+    {
+        self.len().serialize(serializer)?;
+
+        for v in self {
+            v.serialize(serializer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Deserialize + Eq + Hash> Deserialize for HashSet<T> {
+    unsafe fn deserialize<R>(deserializer: &mut Deserializer<R>) -> Result<Self>
+    where
+        R: ReadUninit,
+    {
+        let len = unsafe { usize::deserialize(deserializer)? };
+        let mut set = HashSet::with_capacity(len);
+
+        for _ in 0..len {
+            set.insert(unsafe { T::deserialize(deserializer)? });
+        }
+
+        Ok(set)
+    }
+}
+
+impl SerializeMut for AtomicU64 {
+    fn serialize_mut<W>(&mut self, serializer: &mut Serializer<W>) -> Result<()>
+    where
+        W: WriteUninit,
+    {
+        self.get_mut().serialize(serializer)
+    }
+}
+
+impl Deserialize for AtomicU64 {
+    unsafe fn deserialize<R>(deserializer: &mut Deserializer<R>) -> Result<Self>
+    where
+        R: ReadUninit,
+    {
+        let val = unsafe { u64::deserialize(deserializer)? };
+        Ok(AtomicU64::new(val))
+    }
+}
+
 impl<T: Serialize> SerializeMut for AtomicRefCell<T> {
     fn serialize_mut<W>(&mut self, serializer: &mut Serializer<W>) -> Result<()>
     where
@@ -129,6 +129,15 @@ fn serialize_map() {
     ]));
 }
 
+#[test]
+fn serialize_set() {
+    there_and_back_both(HashSet::from([
+        String::from("test1"),
+        String::from("test2"),
+        String::from("test3"),
+    ]));
+}
+
 #[test]
 fn serialize_multiple() {
     let map = HashMap::from([
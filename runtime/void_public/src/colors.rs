@@ -12,7 +12,15 @@ use crate::{
 };
 
 #[repr(C)]
-#[derive(bytemuck::Pod, bytemuck::Zeroable, Debug, Component, PartialEq, serde::Deserialize)]
+#[derive(
+    bytemuck::Pod,
+    bytemuck::Zeroable,
+    Debug,
+    Component,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+)]
 pub struct Color(Vec4);
 
 impl fmt::Display for Color {
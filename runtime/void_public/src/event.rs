@@ -7979,3 +7979,862 @@ pub mod particle_effects {
         }
     }
 } // pub mod ParticleEffects
+
+#[allow(unused_imports, dead_code)]
+pub mod engine {
+
+    use core::{cmp::Ordering, mem};
+
+    extern crate flatbuffers;
+    use self::flatbuffers::{EndianScalar, Follow};
+
+    #[deprecated(
+        since = "2.0.0",
+        note = "Use associated constants instead. This will no longer be generated in 2021."
+    )]
+    pub const ENUM_MIN_SCENE_LOAD_STAGE: u8 = 0;
+    #[deprecated(
+        since = "2.0.0",
+        note = "Use associated constants instead. This will no longer be generated in 2021."
+    )]
+    pub const ENUM_MAX_SCENE_LOAD_STAGE: u8 = 2;
+    #[deprecated(
+        since = "2.0.0",
+        note = "Use associated constants instead. This will no longer be generated in 2021."
+    )]
+    #[allow(non_camel_case_types)]
+    pub const ENUM_VALUES_SCENE_LOAD_STAGE: [SceneLoadStage; 3] = [
+        SceneLoadStage::Reading,
+        SceneLoadStage::LoadingAssets,
+        SceneLoadStage::Failed,
+    ];
+
+    /// The stage of loading a scene instance from an asset path.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    #[repr(transparent)]
+    pub struct SceneLoadStage(pub u8);
+    #[allow(non_upper_case_globals)]
+    impl SceneLoadStage {
+        /// The scene file is being read and parsed.
+        pub const Reading: Self = Self(0);
+        /// The scene's assets are being loaded. Entities are spawned once all
+        /// assets are loaded, or failed to load.
+        pub const LoadingAssets: Self = Self(1);
+        /// The scene file could not be read or parsed, and the scene is not loaded.
+        pub const Failed: Self = Self(2);
+
+        pub const ENUM_MIN: u8 = 0;
+        pub const ENUM_MAX: u8 = 2;
+        pub const ENUM_VALUES: &'static [Self] =
+            &[Self::Reading, Self::LoadingAssets, Self::Failed];
+        /// Returns the variant's name or "" if unknown.
+        pub fn variant_name(self) -> Option<&'static str> {
+            match self {
+                Self::Reading => Some("Reading"),
+                Self::LoadingAssets => Some("LoadingAssets"),
+                Self::Failed => Some("Failed"),
+                _ => None,
+            }
+        }
+    }
+    impl core::fmt::Debug for SceneLoadStage {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            if let Some(name) = self.variant_name() {
+                f.write_str(name)
+            } else {
+                f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+            }
+        }
+    }
+    impl<'a> flatbuffers::Follow<'a> for SceneLoadStage {
+        type Inner = Self;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            let b = flatbuffers::read_scalar_at::<u8>(buf, loc);
+            Self(b)
+        }
+    }
+
+    impl flatbuffers::Push for SceneLoadStage {
+        type Output = SceneLoadStage;
+        #[inline]
+        unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+            flatbuffers::emplace_scalar::<u8>(dst, self.0);
+        }
+    }
+
+    impl flatbuffers::EndianScalar for SceneLoadStage {
+        type Scalar = u8;
+        #[inline]
+        fn to_little_endian(self) -> u8 {
+            self.0.to_le()
+        }
+        #[inline]
+        #[allow(clippy::wrong_self_convention)]
+        fn from_little_endian(v: u8) -> Self {
+            let b = u8::from_le(v);
+            Self(b)
+        }
+    }
+
+    impl<'a> flatbuffers::Verifiable for SceneLoadStage {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            u8::run_verifier(v, pos)
+        }
+    }
+
+    impl flatbuffers::SimpleToVerifyInSlice for SceneLoadStage {}
+    pub enum EngineErrorOffset {}
+    #[derive(Copy, Clone, PartialEq)]
+
+    /// Sent when a system returns an error. The error is handled according to the
+    /// system's error policy.
+    ///
+    /// Also sent when a platform library function panics, in which case
+    /// `system_name` is the function name, `module_name` is the library name, and
+    /// `frame` is 0.
+    pub struct EngineError<'a> {
+        pub _tab: flatbuffers::Table<'a>,
+    }
+
+    impl<'a> flatbuffers::Follow<'a> for EngineError<'a> {
+        type Inner = EngineError<'a>;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            Self {
+                _tab: flatbuffers::Table::new(buf, loc),
+            }
+        }
+    }
+
+    impl<'a> EngineError<'a> {
+        pub const VT_SYSTEM_NAME: flatbuffers::VOffsetT = 4;
+        pub const VT_MODULE_NAME: flatbuffers::VOffsetT = 6;
+        pub const VT_MESSAGE: flatbuffers::VOffsetT = 8;
+        pub const VT_FRAME: flatbuffers::VOffsetT = 10;
+
+        pub const fn get_fully_qualified_name() -> &'static str {
+            "Engine.EngineError"
+        }
+
+        #[inline]
+        pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+            EngineError { _tab: table }
+        }
+        #[allow(unused_mut)]
+        pub fn create<
+            'bldr: 'args,
+            'args: 'mut_bldr,
+            'mut_bldr,
+            A: flatbuffers::Allocator + 'bldr,
+        >(
+            _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+            args: &'args EngineErrorArgs<'args>,
+        ) -> flatbuffers::WIPOffset<EngineError<'bldr>> {
+            let mut builder = EngineErrorBuilder::new(_fbb);
+            builder.add_frame(args.frame);
+            if let Some(x) = args.message {
+                builder.add_message(x);
+            }
+            if let Some(x) = args.module_name {
+                builder.add_module_name(x);
+            }
+            if let Some(x) = args.system_name {
+                builder.add_system_name(x);
+            }
+            builder.finish()
+        }
+
+        pub fn unpack(&self) -> EngineErrorT {
+            let system_name = self.system_name().map(|x| x.to_string());
+            let module_name = self.module_name().map(|x| x.to_string());
+            let message = self.message().map(|x| x.to_string());
+            let frame = self.frame();
+            EngineErrorT {
+                system_name,
+                module_name,
+                message,
+                frame,
+            }
+        }
+
+        /// The namespaced system name, e.g. `MyModule::my_system`.
+        #[inline]
+        pub fn system_name(&self) -> Option<&'a str> {
+            // Safety:
+            // Created from valid Table for this object
+            // which contains a valid value in this slot
+            unsafe {
+                self._tab
+                    .get::<flatbuffers::ForwardsUOffset<&str>>(EngineError::VT_SYSTEM_NAME, None)
+            }
+        }
+        #[inline]
+        pub fn module_name(&self) -> Option<&'a str> {
+            // Safety:
+            // Created from valid Table for this object
+            // which contains a valid value in this slot
+            unsafe {
+                self._tab
+                    .get::<flatbuffers::ForwardsUOffset<&str>>(EngineError::VT_MODULE_NAME, None)
+            }
+        }
+        #[inline]
+        pub fn message(&self) -> Option<&'a str> {
+            // Safety:
+            // Created from valid Table for this object
+            // which contains a valid value in this slot
+            unsafe {
+                self._tab
+                    .get::<flatbuffers::ForwardsUOffset<&str>>(EngineError::VT_MESSAGE, None)
+            }
+        }
+        /// The `FrameConstants::tick_count` of the frame in which the error occurred.
+        #[inline]
+        pub fn frame(&self) -> u64 {
+            // Safety:
+            // Created from valid Table for this object
+            // which contains a valid value in this slot
+            unsafe {
+                self._tab
+                    .get::<u64>(EngineError::VT_FRAME, Some(0))
+                    .unwrap()
+            }
+        }
+    }
+
+    impl flatbuffers::Verifiable for EngineError<'_> {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            v.visit_table(pos)?
+                .visit_field::<flatbuffers::ForwardsUOffset<&str>>(
+                    "system_name",
+                    Self::VT_SYSTEM_NAME,
+                    false,
+                )?
+                .visit_field::<flatbuffers::ForwardsUOffset<&str>>(
+                    "module_name",
+                    Self::VT_MODULE_NAME,
+                    false,
+                )?
+                .visit_field::<flatbuffers::ForwardsUOffset<&str>>(
+                    "message",
+                    Self::VT_MESSAGE,
+                    false,
+                )?
+                .visit_field::<u64>("frame", Self::VT_FRAME, false)?
+                .finish();
+            Ok(())
+        }
+    }
+    pub struct EngineErrorArgs<'a> {
+        pub system_name: Option<flatbuffers::WIPOffset<&'a str>>,
+        pub module_name: Option<flatbuffers::WIPOffset<&'a str>>,
+        pub message: Option<flatbuffers::WIPOffset<&'a str>>,
+        pub frame: u64,
+    }
+    impl<'a> Default for EngineErrorArgs<'a> {
+        #[inline]
+        fn default() -> Self {
+            EngineErrorArgs {
+                system_name: None,
+                module_name: None,
+                message: None,
+                frame: 0,
+            }
+        }
+    }
+
+    pub struct EngineErrorBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+        fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+        start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+    }
+    impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> EngineErrorBuilder<'a, 'b, A> {
+        #[inline]
+        pub fn add_system_name(&mut self, system_name: flatbuffers::WIPOffset<&'b str>) {
+            self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(
+                EngineError::VT_SYSTEM_NAME,
+                system_name,
+            );
+        }
+        #[inline]
+        pub fn add_module_name(&mut self, module_name: flatbuffers::WIPOffset<&'b str>) {
+            self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(
+                EngineError::VT_MODULE_NAME,
+                module_name,
+            );
+        }
+        #[inline]
+        pub fn add_message(&mut self, message: flatbuffers::WIPOffset<&'b str>) {
+            self.fbb_
+                .push_slot_always::<flatbuffers::WIPOffset<_>>(EngineError::VT_MESSAGE, message);
+        }
+        #[inline]
+        pub fn add_frame(&mut self, frame: u64) {
+            self.fbb_.push_slot::<u64>(EngineError::VT_FRAME, frame, 0);
+        }
+        #[inline]
+        pub fn new(
+            _fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+        ) -> EngineErrorBuilder<'a, 'b, A> {
+            let start = _fbb.start_table();
+            EngineErrorBuilder {
+                fbb_: _fbb,
+                start_: start,
+            }
+        }
+        #[inline]
+        pub fn finish(self) -> flatbuffers::WIPOffset<EngineError<'a>> {
+            let o = self.fbb_.end_table(self.start_);
+            flatbuffers::WIPOffset::new(o.value())
+        }
+    }
+
+    impl core::fmt::Debug for EngineError<'_> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            let mut ds = f.debug_struct("EngineError");
+            ds.field("system_name", &self.system_name());
+            ds.field("module_name", &self.module_name());
+            ds.field("message", &self.message());
+            ds.field("frame", &self.frame());
+            ds.finish()
+        }
+    }
+    #[non_exhaustive]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct EngineErrorT {
+        pub system_name: Option<String>,
+        pub module_name: Option<String>,
+        pub message: Option<String>,
+        pub frame: u64,
+    }
+    impl Default for EngineErrorT {
+        fn default() -> Self {
+            Self {
+                system_name: None,
+                module_name: None,
+                message: None,
+                frame: 0,
+            }
+        }
+    }
+    impl EngineErrorT {
+        pub fn pack<'b, A: flatbuffers::Allocator + 'b>(
+            &self,
+            _fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>,
+        ) -> flatbuffers::WIPOffset<EngineError<'b>> {
+            let system_name = self.system_name.as_ref().map(|x| _fbb.create_string(x));
+            let module_name = self.module_name.as_ref().map(|x| _fbb.create_string(x));
+            let message = self.message.as_ref().map(|x| _fbb.create_string(x));
+            let frame = self.frame;
+            EngineError::create(
+                _fbb,
+                &EngineErrorArgs {
+                    system_name,
+                    module_name,
+                    message,
+                    frame,
+                },
+            )
+        }
+    }
+
+    /// Sent when a scene instance is loaded, after its entities are spawned.
+    // struct SceneLoaded, aligned to 8
+    #[repr(transparent)]
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct SceneLoaded(pub [u8; 16]);
+    impl Default for SceneLoaded {
+        fn default() -> Self {
+            Self([0; 16])
+        }
+    }
+    impl core::fmt::Debug for SceneLoaded {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.debug_struct("SceneLoaded")
+                .field("handle", &self.handle())
+                .field("entity_count", &self.entity_count())
+                .finish()
+        }
+    }
+
+    impl flatbuffers::SimpleToVerifyInSlice for SceneLoaded {}
+    impl<'a> flatbuffers::Follow<'a> for SceneLoaded {
+        type Inner = &'a SceneLoaded;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            <&'a SceneLoaded>::follow(buf, loc)
+        }
+    }
+    impl<'a> flatbuffers::Follow<'a> for &'a SceneLoaded {
+        type Inner = &'a SceneLoaded;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            flatbuffers::follow_cast_ref::<SceneLoaded>(buf, loc)
+        }
+    }
+    impl<'b> flatbuffers::Push for SceneLoaded {
+        type Output = SceneLoaded;
+        #[inline]
+        unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+            let src = ::core::slice::from_raw_parts(
+                self as *const SceneLoaded as *const u8,
+                Self::size(),
+            );
+            dst.copy_from_slice(src);
+        }
+    }
+
+    impl<'a> flatbuffers::Verifiable for SceneLoaded {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            v.in_buffer::<Self>(pos)
+        }
+    }
+
+    impl<'a> SceneLoaded {
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(handle: u64, entity_count: u32) -> Self {
+            let mut s = Self([0; 16]);
+            s.set_handle(handle);
+            s.set_entity_count(entity_count);
+            s
+        }
+
+        pub const fn get_fully_qualified_name() -> &'static str {
+            "Engine.SceneLoaded"
+        }
+
+        pub fn handle(&self) -> u64 {
+            let mut mem = core::mem::MaybeUninit::<<u64 as EndianScalar>::Scalar>::uninit();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            EndianScalar::from_little_endian(unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.0[0..].as_ptr(),
+                    mem.as_mut_ptr() as *mut u8,
+                    core::mem::size_of::<<u64 as EndianScalar>::Scalar>(),
+                );
+                mem.assume_init()
+            })
+        }
+
+        pub fn set_handle(&mut self, x: u64) {
+            let x_le = x.to_little_endian();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &x_le as *const _ as *const u8,
+                    self.0[0..].as_mut_ptr(),
+                    core::mem::size_of::<<u64 as EndianScalar>::Scalar>(),
+                );
+            }
+        }
+
+        /// The number of entities spawned from the scene.
+        pub fn entity_count(&self) -> u32 {
+            let mut mem = core::mem::MaybeUninit::<<u32 as EndianScalar>::Scalar>::uninit();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            EndianScalar::from_little_endian(unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.0[8..].as_ptr(),
+                    mem.as_mut_ptr() as *mut u8,
+                    core::mem::size_of::<<u32 as EndianScalar>::Scalar>(),
+                );
+                mem.assume_init()
+            })
+        }
+
+        pub fn set_entity_count(&mut self, x: u32) {
+            let x_le = x.to_little_endian();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &x_le as *const _ as *const u8,
+                    self.0[8..].as_mut_ptr(),
+                    core::mem::size_of::<<u32 as EndianScalar>::Scalar>(),
+                );
+            }
+        }
+
+        pub fn unpack(&self) -> SceneLoadedT {
+            SceneLoadedT {
+                handle: self.handle(),
+                entity_count: self.entity_count(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct SceneLoadedT {
+        pub handle: u64,
+        pub entity_count: u32,
+    }
+    impl SceneLoadedT {
+        pub fn pack(&self) -> SceneLoaded {
+            SceneLoaded::new(self.handle, self.entity_count)
+        }
+    }
+
+    /// Sent when a scene instance is unloaded, after its entities are despawned.
+    // struct SceneUnloaded, aligned to 8
+    #[repr(transparent)]
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct SceneUnloaded(pub [u8; 8]);
+    impl Default for SceneUnloaded {
+        fn default() -> Self {
+            Self([0; 8])
+        }
+    }
+    impl core::fmt::Debug for SceneUnloaded {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.debug_struct("SceneUnloaded")
+                .field("handle", &self.handle())
+                .finish()
+        }
+    }
+
+    impl flatbuffers::SimpleToVerifyInSlice for SceneUnloaded {}
+    impl<'a> flatbuffers::Follow<'a> for SceneUnloaded {
+        type Inner = &'a SceneUnloaded;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            <&'a SceneUnloaded>::follow(buf, loc)
+        }
+    }
+    impl<'a> flatbuffers::Follow<'a> for &'a SceneUnloaded {
+        type Inner = &'a SceneUnloaded;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            flatbuffers::follow_cast_ref::<SceneUnloaded>(buf, loc)
+        }
+    }
+    impl<'b> flatbuffers::Push for SceneUnloaded {
+        type Output = SceneUnloaded;
+        #[inline]
+        unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+            let src = ::core::slice::from_raw_parts(
+                self as *const SceneUnloaded as *const u8,
+                Self::size(),
+            );
+            dst.copy_from_slice(src);
+        }
+    }
+
+    impl<'a> flatbuffers::Verifiable for SceneUnloaded {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            v.in_buffer::<Self>(pos)
+        }
+    }
+
+    impl<'a> SceneUnloaded {
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(handle: u64) -> Self {
+            let mut s = Self([0; 8]);
+            s.set_handle(handle);
+            s
+        }
+
+        pub const fn get_fully_qualified_name() -> &'static str {
+            "Engine.SceneUnloaded"
+        }
+
+        pub fn handle(&self) -> u64 {
+            let mut mem = core::mem::MaybeUninit::<<u64 as EndianScalar>::Scalar>::uninit();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            EndianScalar::from_little_endian(unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.0[0..].as_ptr(),
+                    mem.as_mut_ptr() as *mut u8,
+                    core::mem::size_of::<<u64 as EndianScalar>::Scalar>(),
+                );
+                mem.assume_init()
+            })
+        }
+
+        pub fn set_handle(&mut self, x: u64) {
+            let x_le = x.to_little_endian();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &x_le as *const _ as *const u8,
+                    self.0[0..].as_mut_ptr(),
+                    core::mem::size_of::<<u64 as EndianScalar>::Scalar>(),
+                );
+            }
+        }
+
+        pub fn unpack(&self) -> SceneUnloadedT {
+            SceneUnloadedT {
+                handle: self.handle(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct SceneUnloadedT {
+        pub handle: u64,
+    }
+    impl SceneUnloadedT {
+        pub fn pack(&self) -> SceneUnloaded {
+            SceneUnloaded::new(self.handle)
+        }
+    }
+
+    /// Sent each frame while a scene instance is being loaded from an asset path,
+    /// and once if loading fails. `SceneLoaded` is sent once the scene's entities
+    /// are spawned.
+    // struct SceneLoadProgress, aligned to 8
+    #[repr(transparent)]
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct SceneLoadProgress(pub [u8; 24]);
+    impl Default for SceneLoadProgress {
+        fn default() -> Self {
+            Self([0; 24])
+        }
+    }
+    impl core::fmt::Debug for SceneLoadProgress {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            f.debug_struct("SceneLoadProgress")
+                .field("handle", &self.handle())
+                .field("stage", &self.stage())
+                .field("assets_loaded", &self.assets_loaded())
+                .field("assets_total", &self.assets_total())
+                .finish()
+        }
+    }
+
+    impl flatbuffers::SimpleToVerifyInSlice for SceneLoadProgress {}
+    impl<'a> flatbuffers::Follow<'a> for SceneLoadProgress {
+        type Inner = &'a SceneLoadProgress;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            <&'a SceneLoadProgress>::follow(buf, loc)
+        }
+    }
+    impl<'a> flatbuffers::Follow<'a> for &'a SceneLoadProgress {
+        type Inner = &'a SceneLoadProgress;
+        #[inline]
+        unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+            flatbuffers::follow_cast_ref::<SceneLoadProgress>(buf, loc)
+        }
+    }
+    impl<'b> flatbuffers::Push for SceneLoadProgress {
+        type Output = SceneLoadProgress;
+        #[inline]
+        unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+            let src = ::core::slice::from_raw_parts(
+                self as *const SceneLoadProgress as *const u8,
+                Self::size(),
+            );
+            dst.copy_from_slice(src);
+        }
+    }
+
+    impl<'a> flatbuffers::Verifiable for SceneLoadProgress {
+        #[inline]
+        fn run_verifier(
+            v: &mut flatbuffers::Verifier,
+            pos: usize,
+        ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+            use self::flatbuffers::Verifiable;
+            v.in_buffer::<Self>(pos)
+        }
+    }
+
+    impl<'a> SceneLoadProgress {
+        #[allow(clippy::too_many_arguments)]
+        pub fn new(
+            handle: u64,
+            stage: SceneLoadStage,
+            assets_loaded: u32,
+            assets_total: u32,
+        ) -> Self {
+            let mut s = Self([0; 24]);
+            s.set_handle(handle);
+            s.set_stage(stage);
+            s.set_assets_loaded(assets_loaded);
+            s.set_assets_total(assets_total);
+            s
+        }
+
+        pub const fn get_fully_qualified_name() -> &'static str {
+            "Engine.SceneLoadProgress"
+        }
+
+        pub fn handle(&self) -> u64 {
+            let mut mem = core::mem::MaybeUninit::<<u64 as EndianScalar>::Scalar>::uninit();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            EndianScalar::from_little_endian(unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.0[0..].as_ptr(),
+                    mem.as_mut_ptr() as *mut u8,
+                    core::mem::size_of::<<u64 as EndianScalar>::Scalar>(),
+                );
+                mem.assume_init()
+            })
+        }
+
+        pub fn set_handle(&mut self, x: u64) {
+            let x_le = x.to_little_endian();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &x_le as *const _ as *const u8,
+                    self.0[0..].as_mut_ptr(),
+                    core::mem::size_of::<<u64 as EndianScalar>::Scalar>(),
+                );
+            }
+        }
+
+        pub fn stage(&self) -> SceneLoadStage {
+            let mut mem =
+                core::mem::MaybeUninit::<<SceneLoadStage as EndianScalar>::Scalar>::uninit();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            EndianScalar::from_little_endian(unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.0[8..].as_ptr(),
+                    mem.as_mut_ptr() as *mut u8,
+                    core::mem::size_of::<<SceneLoadStage as EndianScalar>::Scalar>(),
+                );
+                mem.assume_init()
+            })
+        }
+
+        pub fn set_stage(&mut self, x: SceneLoadStage) {
+            let x_le = x.to_little_endian();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &x_le as *const _ as *const u8,
+                    self.0[8..].as_mut_ptr(),
+                    core::mem::size_of::<<SceneLoadStage as EndianScalar>::Scalar>(),
+                );
+            }
+        }
+
+        /// The number of the scene's assets which finished loading, successfully or
+        /// not.
+        pub fn assets_loaded(&self) -> u32 {
+            let mut mem = core::mem::MaybeUninit::<<u32 as EndianScalar>::Scalar>::uninit();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            EndianScalar::from_little_endian(unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.0[12..].as_ptr(),
+                    mem.as_mut_ptr() as *mut u8,
+                    core::mem::size_of::<<u32 as EndianScalar>::Scalar>(),
+                );
+                mem.assume_init()
+            })
+        }
+
+        pub fn set_assets_loaded(&mut self, x: u32) {
+            let x_le = x.to_little_endian();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &x_le as *const _ as *const u8,
+                    self.0[12..].as_mut_ptr(),
+                    core::mem::size_of::<<u32 as EndianScalar>::Scalar>(),
+                );
+            }
+        }
+
+        pub fn assets_total(&self) -> u32 {
+            let mut mem = core::mem::MaybeUninit::<<u32 as EndianScalar>::Scalar>::uninit();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            EndianScalar::from_little_endian(unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.0[16..].as_ptr(),
+                    mem.as_mut_ptr() as *mut u8,
+                    core::mem::size_of::<<u32 as EndianScalar>::Scalar>(),
+                );
+                mem.assume_init()
+            })
+        }
+
+        pub fn set_assets_total(&mut self, x: u32) {
+            let x_le = x.to_little_endian();
+            // Safety:
+            // Created from a valid Table for this object
+            // Which contains a valid value in this slot
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    &x_le as *const _ as *const u8,
+                    self.0[16..].as_mut_ptr(),
+                    core::mem::size_of::<<u32 as EndianScalar>::Scalar>(),
+                );
+            }
+        }
+
+        pub fn unpack(&self) -> SceneLoadProgressT {
+            SceneLoadProgressT {
+                handle: self.handle(),
+                stage: self.stage(),
+                assets_loaded: self.assets_loaded(),
+                assets_total: self.assets_total(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Default)]
+    pub struct SceneLoadProgressT {
+        pub handle: u64,
+        pub stage: SceneLoadStage,
+        pub assets_loaded: u32,
+        pub assets_total: u32,
+    }
+    impl SceneLoadProgressT {
+        pub fn pack(&self) -> SceneLoadProgress {
+            SceneLoadProgress::new(
+                self.handle,
+                self.stage,
+                self.assets_loaded,
+                self.assets_total,
+            )
+        }
+    }
+} // pub mod Engine
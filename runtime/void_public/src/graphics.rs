@@ -15,7 +15,7 @@ use crate::{
     serialize::{
         default_circle_render_num_sides, default_rect_dimensions, default_rect_position,
         default_text_render_alignment, default_text_render_font_size, default_true,
-        deserialize_text_render_text_field,
+        deserialize_text_render_text_field, serialize_text_render_text_field,
     },
     text::TextAlignment,
 };
@@ -43,7 +43,9 @@ pub enum BlendTypes {
 }
 
 #[repr(C)]
-#[derive(Component, Debug, bytemuck::Pod, bytemuck::Zeroable, serde::Deserialize)]
+#[derive(
+    Component, Debug, bytemuck::Pod, bytemuck::Zeroable, serde::Deserialize, serde::Serialize,
+)]
 pub struct Rect {
     #[serde(default = "default_rect_position")]
     pub position: linalg::Vec2,
@@ -70,9 +72,9 @@ impl Default for Rect {
 }
 
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct TextureRender {
-    #[serde(skip_deserializing)]
+    #[serde(skip)]
     pub texture_id: TextureId,
 
     // `uv_region` is the normalized uv rectangle when texturing this Sprite.  Used for spritesheets, animation, etc.
@@ -104,7 +106,7 @@ impl Default for TextureRender {
 }
 
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct ColorRender {
     #[serde(default = "default_true")]
     pub visible: bool,
@@ -119,9 +121,12 @@ impl Default for ColorRender {
 pub(crate) const TEXT_RENDER_SIZE: usize = 256;
 
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct TextRender {
-    #[serde(deserialize_with = "deserialize_text_render_text_field")]
+    #[serde(
+        serialize_with = "serialize_text_render_text_field",
+        deserialize_with = "deserialize_text_render_text_field"
+    )]
     pub text: [u8; TEXT_RENDER_SIZE],
     #[serde(default = "default_true")]
     pub visible: bool,
@@ -180,7 +185,7 @@ impl TextRender {
 }
 
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct CircleRender {
     #[serde(default = "default_circle_render_num_sides")]
     pub num_sides: u32,
@@ -256,6 +261,7 @@ impl TextureHash {
     Pod,
     Zeroable,
     serde::Deserialize,
+    serde::Serialize,
 )]
 pub struct TextureId(pub u32);
 
@@ -300,9 +306,13 @@ pub type ParticleEffectHandle = Option<NonZero<u64>>;
 /// A component representing a `ParticleEffect` registered with `ParticleEffectManager`.  An entity can only have
 /// one `ParticleRender` at a time.
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct ParticleRender {
+    #[reflect(particle_effect)]
     descriptor_id: AssetId,
+    /// Assigned by the particle manager, and only valid for the current
+    /// session.
+    #[serde(skip)]
     handle: ParticleEffectHandle,
     visible: bool,
 }
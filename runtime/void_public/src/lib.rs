@@ -15,14 +15,18 @@
 //! TODO(https://github.com/vaguevoid/engine/issues/385): Separate Rust module
 //! API and the `void_public` module.
 
+// Allows derive macros to refer to `::void_public` within this crate.
+extern crate self as void_public;
+
 use std::{
     cmp::Ordering,
     error::Error,
     ffi::{CStr, CString, c_char, c_int, c_void},
     fmt::{Display, Formatter},
     hash::{Hash, Hasher},
+    iter,
     marker::PhantomData,
-    mem::{MaybeUninit, size_of},
+    mem::{ManuallyDrop, MaybeUninit, size_of},
     num::NonZero,
     ops::{Deref, DerefMut},
     panic::catch_unwind,
@@ -65,8 +69,11 @@ pub mod graphics;
 pub mod input;
 pub mod linalg;
 pub mod material;
+pub mod panic;
 pub mod pipeline;
+pub mod reflect;
 mod serialize;
+pub mod state;
 pub mod system;
 pub mod text;
 
@@ -112,7 +119,10 @@ macro_rules! event_name {
 }
 
 /// The version of Void which this module is designed to support.
-pub const ENGINE_VERSION: u32 = make_api_version(0, 0, 20);
+///
+/// Modules are only loaded when their target version matches exactly, so the
+/// patch version must be bumped whenever the ABI shared with modules changes.
+pub const ENGINE_VERSION: u32 = make_api_version(0, 0, 44);
 
 pub const fn make_api_version(major: u32, minor: u32, patch: u32) -> u32 {
     ((major) << 25) | ((minor) << 15) | (patch)
@@ -143,10 +153,49 @@ pub type ComponentId = NonZero<u16>;
 /// A handle identifying a loaded asset.
 #[repr(transparent)]
 #[derive(
-    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Pod, Zeroable, serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Pod,
+    Zeroable,
+    serde::Deserialize,
+    serde::Serialize,
 )]
 pub struct AssetId(pub u32);
 
+/// A handle identifying a loaded instance of a scene. Each load of a scene
+/// creates a new instance, which owns the entities spawned from the scene.
+#[repr(transparent)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    snapshot::Deserialize,
+    snapshot::Serialize,
+)]
+pub struct SceneHandle(pub NonZero<u64>);
+
+/// Migrates the JSON of a component in a scene of an older version. The
+/// migrated JSON is passed to `write_output`, and `true` is returned on
+/// success. On failure, an error message may be passed to `write_output`, and
+/// `false` is returned.
+pub type SceneMigrationCallback = unsafe extern "C" fn(
+    user_data: *const c_void,
+    component_json: *const c_char,
+    output: *mut c_void,
+    write_output: unsafe extern "C" fn(output: *mut c_void, text: *const c_char),
+) -> bool;
+
 impl Deref for AssetId {
     type Target = u32;
 
@@ -187,6 +236,12 @@ pub trait EcsType {
 
 /// A trait representing an ECS Component. All structs which are to be used as
 /// a Component must `#[derive(Component)]`.
+///
+/// Zero-sized tag components which are frequently added and removed may be
+/// declared with `#[component(sparse)]`. Sparse components are stored outside
+/// of archetypes, so toggling them does not move the entity's data. They may
+/// only be queried with `With` and `Without` filters, systems which access them
+/// in any other way are not registered.
 pub trait Component: EcsType + Copy + Clone + Send + Sync + Sized + 'static {}
 
 /// A trait representing an ECS Resource. All structs which are to be used as
@@ -332,9 +387,11 @@ impl<T: Copy + PartialOrd> PartialOrd for FfiOption<T> {
     PartialEq,
     Eq,
     serde::Deserialize,
+    serde::Serialize,
     snapshot::Deserialize,
     snapshot::Serialize,
 )]
+#[component(custom_reflect)]
 pub struct EntityId(NonZero<u64>);
 
 impl EntityId {
@@ -377,6 +434,15 @@ pub struct FrameConstants {
     /// The number of times the engine update loop been invoked since the last loaded tape. If no tapes
     /// have been loaded, this is the number since the application started.
     pub tick_count: u64,
+
+    /// The duration of a fixed timestep (seconds). `#[system(fixed)]` systems
+    /// should advance by this amount, rather than `delta_time`.
+    pub fixed_delta_time: f32,
+
+    /// How far the frame is between the previous and the next fixed timestep,
+    /// from 0 to 1. Render-side systems may use it to interpolate between the
+    /// previous and current state written by fixed systems.
+    pub fixed_alpha: f32,
 }
 
 #[repr(C)]
@@ -384,19 +450,26 @@ pub struct FrameConstants {
 pub struct FrameConfig {
     /// The frame delta time will be clamped to this maximum (seconds)
     pub max_delta_time: f32,
+
+    /// The duration of a fixed timestep (seconds). `#[system(fixed)]` systems
+    /// run as many times per frame as needed to keep up with the elapsed time.
+    pub fixed_delta_time: f32,
 }
 
 impl Default for FrameConfig {
     fn default() -> Self {
         Self {
             max_delta_time: 0.4,
+            fixed_delta_time: 1. / 60.,
         }
     }
 }
 
 /// A component representing a 3D transform.
 #[repr(C)]
-#[derive(Component, Debug, bytemuck::Pod, bytemuck::Zeroable, serde::Deserialize)]
+#[derive(
+    Component, Debug, bytemuck::Pod, bytemuck::Zeroable, serde::Deserialize, serde::Serialize,
+)]
 pub struct Transform {
     #[serde(default)]
     pub position: linalg::Vec3,
@@ -471,7 +544,9 @@ impl Transform {
 }
 
 #[repr(C)]
-#[derive(Component, Debug, bytemuck::Pod, bytemuck::Zeroable, serde::Deserialize)]
+#[derive(
+    Component, Debug, bytemuck::Pod, bytemuck::Zeroable, serde::Deserialize, serde::Serialize,
+)]
 pub struct LocalToWorld(linalg::Mat4);
 
 impl Default for LocalToWorld {
@@ -546,7 +621,7 @@ impl LocalToWorld {
     }
 }
 
-#[derive(Debug, Copy, Clone, serde::Deserialize)]
+#[derive(Debug, Copy, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Viewport {
     /// A normalized value indicating the start x position of the viewport relative to the window.
     pub x: f32,
@@ -582,7 +657,7 @@ impl Viewport {
 
 /// A component representing a 3D camera.
 #[repr(C)]
-#[derive(Component, Debug, serde::Deserialize)]
+#[derive(Component, Debug, serde::Deserialize, serde::Serialize)]
 pub struct Camera {
     /// The matrix that converts from world-space to camera space (view-space)
     #[serde(default = "default_camera_view_matrix")]
@@ -754,6 +829,10 @@ impl ComponentData {
     pub fn component_id(&self) -> Option<ComponentId> {
         self.component_id
     }
+
+    pub fn component_data_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        &mut self.component_data
+    }
 }
 
 impl<C: Component> From<C> for ComponentData {
@@ -795,6 +874,29 @@ impl ComponentRef<'_> {
     }
 }
 
+/// The component overrides of an entity of a prefab instance, which is
+/// identified by its scene id (its `"id"` in the prefab's scene data). Passed to
+/// `Engine::spawn_prefab_with_overrides()`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PrefabOverride<'a> {
+    pub scene_id: *const c_char,
+    pub components: *const ComponentRef<'a>,
+    pub components_len: usize,
+    marker: PhantomData<&'a CStr>,
+}
+
+impl<'a> PrefabOverride<'a> {
+    pub fn new(scene_id: &'a CStr, components: &'a [ComponentRef<'a>]) -> Self {
+        Self {
+            scene_id: scene_id.as_ptr(),
+            components: components.as_ptr(),
+            components_len: components.len(),
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<'a, C> From<&'a C> for ComponentRef<'a>
 where
     C: Component,
@@ -905,13 +1007,173 @@ impl ComponentBuilder {
 pub struct Engine;
 
 impl Engine {
-    /// Loads a string containing scene data into the engine
-    pub fn load_scene(scene_str: &CStr) {
+    /// Loads scene data into the engine, as a new instance of the scene. Scenes
+    /// are loaded additively, alongside the entities of previously loaded
+    /// scenes. The data may be a JSON scene, or a binary scene converted from a
+    /// JSON scene, which is detected automatically.
+    ///
+    /// Returns the handle of the instance, which may be used to unload it with
+    /// [`Engine::unload_scene()`]. A `SceneLoaded` event is sent once the
+    /// scene's entities are spawned.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so the spawned
+    /// entities will not be iterated by queries on the frame the scene is
+    /// loaded.
+    pub fn load_scene(scene_data: &[u8]) -> SceneHandle {
+        unsafe { _LOAD_SCENE.unwrap_unchecked()(scene_data.as_ptr(), scene_data.len()) }
+    }
+
+    /// Starts loading a scene from an asset path, as a new instance of the
+    /// scene. The scene file is read and parsed asynchronously, and its
+    /// entities are spawned once all of its assets are loaded, or failed to
+    /// load, so loading does not stall the frame. Like [`Engine::load_scene()`],
+    /// the file may be a JSON or a binary scene.
+    ///
+    /// `SceneLoadProgress` events are sent each frame while the scene is
+    /// loading, followed by a `SceneLoaded` event once its entities are
+    /// spawned.
+    pub fn load_scene_from_path(asset_path: &AssetPath) -> SceneHandle {
+        let asset_path = asset_path.as_c_string();
+        unsafe { _LOAD_SCENE_FROM_PATH.unwrap_unchecked()(asset_path.as_ptr()) }
+    }
+
+    /// Despawns the entities spawned from a scene instance, including any
+    /// children added to them since. A `SceneUnloaded` event is sent once the
+    /// entities are despawned.
+    pub fn unload_scene(scene_handle: SceneHandle) {
+        unsafe {
+            _UNLOAD_SCENE.unwrap_unchecked()(scene_handle);
+        }
+    }
+
+    /// Registers a prefab, which is a string containing scene data with a
+    /// single root entity (an entity without a `parent_id`). The prefab may then
+    /// be spawned with [`Engine::spawn_prefab()`] using `name`, which may be any
+    /// string. Use [`Engine::register_prefab_from_path()`] to register a prefab
+    /// by its asset path. Registering a prefab under an existing name replaces
+    /// the previous prefab.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so prefabs
+    /// should be spawned from the frame after they are registered.
+    pub fn register_prefab(name: &CStr, prefab_str: &CStr) {
+        unsafe {
+            _REGISTER_PREFAB.unwrap_unchecked()(name.as_ptr(), prefab_str.as_ptr());
+        }
+    }
+
+    /// Registers the prefab read from the scene file at `asset_path`, which
+    /// must contain a single root entity. The prefab may then be spawned with
+    /// [`Engine::spawn_prefab()`] using its asset path as name. The file is
+    /// read asynchronously, and instances spawned before it is read are spawned
+    /// once it is.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so prefabs
+    /// should be spawned from the frame after they are registered.
+    pub fn register_prefab_from_path(asset_path: &CStr) {
         unsafe {
-            _LOAD_SCENE.unwrap_unchecked()(scene_str.as_ptr());
+            _REGISTER_PREFAB_FROM_PATH.unwrap_unchecked()(asset_path.as_ptr());
         }
     }
 
+    /// Registers a migration of a component, applied to the component's JSON
+    /// in scenes and prefabs of `from_version` when they are loaded, before
+    /// they are upgraded to `to_version`. `migrate` receives the component's
+    /// JSON, and returns its migrated JSON. It may be called on any thread, as
+    /// scenes loaded from asset paths are upgraded on the thread which reads
+    /// them.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so migrations
+    /// should be registered before the scenes they apply to are loaded, e.g.
+    /// from a startup system.
+    pub fn register_scene_migration(
+        from_version: &CStr,
+        to_version: &CStr,
+        component_name: &CStr,
+        migrate: fn(&str) -> Result<String, Box<dyn Error + Send + Sync>>,
+    ) {
+        unsafe extern "C" fn callback(
+            user_data: *const c_void,
+            component_json: *const c_char,
+            output: *mut c_void,
+            write_output: unsafe extern "C" fn(output: *mut c_void, text: *const c_char),
+        ) -> bool {
+            let migrate = || unsafe {
+                let migrate: fn(&str) -> Result<String, Box<dyn Error + Send + Sync>> =
+                    std::mem::transmute(user_data);
+                migrate(&CStr::from_ptr(component_json).to_string_lossy())
+            };
+
+            let (text, success) = match catch_unwind(migrate) {
+                Ok(Ok(json)) => (json, true),
+                Ok(Err(err)) => (err.to_string(), false),
+                Err(_) => ("migration panicked".to_owned(), false),
+            };
+
+            let text = CString::new(text).unwrap_or_default();
+            unsafe {
+                write_output(output, text.as_ptr());
+            }
+
+            success
+        }
+
+        unsafe {
+            _REGISTER_SCENE_MIGRATION.unwrap_unchecked()(
+                from_version.as_ptr(),
+                to_version.as_ptr(),
+                component_name.as_ptr(),
+                callback,
+                migrate as *const c_void,
+            );
+        }
+    }
+
+    /// Spawns an instance of the prefab registered as `name`, including its
+    /// child entities. `components` are added to the root entity of the
+    /// instance, replacing the prefab's components of the same type.
+    ///
+    /// The labels of the prefab's entities are scoped to the instance, see
+    /// [`Engine::prefab_instance_label()`].
+    ///
+    /// Returns the `EntityId` of the root entity of the new instance, or `None`
+    /// if no prefab was registered as `name` by the start of the frame.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so the spawned
+    /// entities will not be iterated by queries on the frame they are spawned.
+    pub fn spawn_prefab(name: &CStr, components: &[ComponentRef<'_>]) -> Option<EntityId> {
+        unsafe {
+            _SPAWN_PREFAB.unwrap_unchecked()(name.as_ptr(), components.as_ptr(), components.len())
+        }
+    }
+
+    /// Spawns an instance of the prefab registered as `name`, like
+    /// [`Engine::spawn_prefab()`]. The components of each of `overrides` are
+    /// added to the prefab entity with the override's scene id, replacing the
+    /// prefab's components of the same type.
+    pub fn spawn_prefab_with_overrides(
+        name: &CStr,
+        components: &[ComponentRef<'_>],
+        overrides: &[PrefabOverride<'_>],
+    ) -> Option<EntityId> {
+        unsafe {
+            _SPAWN_PREFAB_WITH_OVERRIDES.unwrap_unchecked()(
+                name.as_ptr(),
+                components.as_ptr(),
+                components.len(),
+                overrides.as_ptr(),
+                overrides.len(),
+            )
+        }
+    }
+
+    /// Returns the label of the entity labeled `label` in the prefab instance
+    /// whose root entity is `root_entity_id`. Prefab labels are scoped to their
+    /// instance as `"{root entity id}/{label}"`, so that instances of the same
+    /// prefab do not share labels.
+    pub fn prefab_instance_label(root_entity_id: EntityId, label: &str) -> CString {
+        CString::new(format!("{}/{label}", root_entity_id.0)).unwrap()
+    }
+
     /// Spawns an entity with the specified components.
     ///
     /// Returns the `EntityId` of the new entity.
@@ -925,16 +1187,63 @@ impl Engine {
         }
     }
 
-    /// Despawns an entity with the specified `EntityId`.
+    /// Spawns `count` entities, all with the specified components. This is
+    /// much faster than calling [`Engine::spawn()`] `count` times, as entity
+    /// ids are allocated at once and the entities are written to their
+    /// archetype together.
+    ///
+    /// Returns the `EntityId`s of the new entities.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so the spawned
+    /// entities will not be iterated by queries on the frame they are spawned.
+    pub fn spawn_batch(components: &[ComponentRef<'_>], count: usize) -> Vec<EntityId> {
+        let mut entity_ids = Vec::with_capacity(count);
+
+        unsafe {
+            let spawned = _SPAWN_BATCH.unwrap_unchecked()(
+                components.as_ptr(),
+                components.len(),
+                count,
+                entity_ids.as_mut_ptr(),
+            );
+            assert!(spawned, "could not spawn entities");
+
+            entity_ids.set_len(count);
+        }
+
+        entity_ids
+    }
+
+    /// Despawns an entity with the specified `EntityId`, along with all of its
+    /// descendants. Use [`Engine::despawn_non_recursive()`] to keep its
+    /// children.
     ///
     /// NOTE: commands are deferred until the end of the frame, so the despawned
-    /// entity will still be iterated by queries on the frame it is despawned.
+    /// entities will still be iterated by queries on the frame they are
+    /// despawned.
     pub fn despawn(entity_id: EntityId) {
         unsafe {
             _DESPAWN.unwrap_unchecked()(entity_id);
         }
     }
 
+    /// Same as [`Engine::despawn()`].
+    pub fn despawn_recursive(entity_id: EntityId) {
+        Self::despawn(entity_id);
+    }
+
+    /// Despawns an entity with the specified `EntityId`. Its children are not
+    /// despawned, and become root entities which keep their world space
+    /// transform.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so the despawned
+    /// entity will still be iterated by queries on the frame it is despawned.
+    pub fn despawn_non_recursive(entity_id: EntityId) {
+        unsafe {
+            _DESPAWN_NON_RECURSIVE.unwrap_unchecked()(entity_id);
+        }
+    }
+
     /// Adds components to an existing entity.
     ///
     /// NOTE: commands are deferred until the end of the frame, so the new
@@ -961,6 +1270,59 @@ impl Engine {
         }
     }
 
+    /// Returns the reflected fields of any registered component, including
+    /// components declared by other modules. See [`reflect`].
+    pub fn component_fields(component_id: ComponentId) -> Vec<reflect::ComponentField> {
+        unsafe {
+            let fields_len = _COMPONENT_FIELDS_LEN_FN.unwrap_unchecked()(component_id);
+
+            (0..fields_len)
+                .map(|index| {
+                    let mut field = MaybeUninit::uninit();
+                    assert!(_COMPONENT_FIELD_FN.unwrap_unchecked()(
+                        component_id,
+                        index,
+                        field.as_mut_ptr()
+                    ));
+                    reflect::ComponentField::from_field_info(&field.assume_init())
+                })
+                .collect()
+        }
+    }
+
+    /// Inserts a resource, replacing its current value. A resource which has
+    /// been removed with [`Engine::remove_resource()`] becomes available to
+    /// systems again.
+    ///
+    /// NOTE: commands are deferred until the end of the frame, so systems will
+    /// observe the new value from the next frame. The replaced value is not
+    /// dropped.
+    pub fn insert_resource<R: Resource>(resource: R) {
+        let resource = ManuallyDrop::new(resource);
+
+        unsafe {
+            let data = slice::from_raw_parts(
+                (&raw const *resource).cast::<MaybeUninit<u8>>(),
+                size_of::<R>(),
+            );
+
+            _INSERT_RESOURCE_FN.unwrap_unchecked()(R::id(), data.as_ptr(), data.len());
+        }
+    }
+
+    /// Removes a resource. While a resource is removed, systems taking `&R` or
+    /// `&mut R` inputs are not run, and `Option<&R>` or `Option<&mut R>`
+    /// inputs are `None`. Resources of the engine, such as [`FrameConstants`],
+    /// may not be removed.
+    ///
+    /// NOTE: commands are deferred until the end of the frame. The removed
+    /// value is not dropped.
+    pub fn remove_resource<R: Resource>() {
+        unsafe {
+            _REMOVE_RESOURCE_FN.unwrap_unchecked()(R::id());
+        }
+    }
+
     /// Returns the label associated with an entity via the provided closure.
     /// If no label is associated with the entity, `None` is passed to the
     /// closure. The closure is always run, even if no label is associated with
@@ -1124,6 +1486,58 @@ impl Engine {
         }
     }
 
+    /// Get the children of the given entity. If the requested entity has no
+    /// children (or if the given entity doesn't exist) this function will
+    /// return an empty `Vec`.
+    pub fn children(entity_id: EntityId) -> Vec<EntityId> {
+        let mut children = Vec::<EntityId>::new();
+
+        loop {
+            let mut len = 0;
+
+            let found = unsafe {
+                _GET_CHILDREN_FN.unwrap_unchecked()(
+                    entity_id,
+                    children.as_mut_ptr().cast::<u64>(),
+                    children.capacity(),
+                    &mut len,
+                )
+            };
+
+            if !found {
+                return Vec::new();
+            }
+
+            if len <= children.capacity() {
+                unsafe { children.set_len(len) };
+                return children;
+            }
+
+            children.reserve_exact(len);
+        }
+    }
+
+    /// Returns an iterator over the ancestors of the given entity, starting
+    /// with its parent and ending with the root entity.
+    pub fn ancestors(entity_id: EntityId) -> impl Iterator<Item = EntityId> {
+        iter::successors(Self::get_parent(entity_id), |&entity_id| {
+            Self::get_parent(entity_id)
+        })
+    }
+
+    /// Returns a depth-first iterator over the descendants of the given entity.
+    /// Each entity is returned before its children.
+    pub fn descendants(entity_id: EntityId) -> impl Iterator<Item = EntityId> {
+        let mut stack = Self::children(entity_id);
+        stack.reverse();
+
+        iter::from_fn(move || {
+            let entity_id = stack.pop()?;
+            stack.extend(Self::children(entity_id).into_iter().rev());
+            Some(entity_id)
+        })
+    }
+
     fn set_fully_qualified_system_enabled(fully_qualified_system_name: &CStr, enabled: bool) {
         unsafe {
             (_SET_SYSTEM_ENABLED_FN).unwrap_unchecked()(
@@ -1277,12 +1691,287 @@ impl<T> EventWriter<T> {
     }
 }
 
+/// Components of type `T` which were removed from entities during the previous
+/// frame, along with the entity they were removed from. This includes the
+/// components of despawned entities.
+///
+/// Use it to clean up state which mirrors entities, e.g. handles held by an
+/// external system. The [`Added`] query filter covers the opposite case.
+///
+/// Components stored in GPU buffers are not reported.
+pub struct RemovedComponents<T> {
+    handle: *const c_void,
+    marker: PhantomData<T>,
+}
+
+impl<T: Component> RemovedComponents<T> {
+    /// # Safety
+    ///
+    /// `RemovedComponents` should only be constructed from a valid pointer retrieved
+    /// from a corresponding `RemovedComponents` parameter in an ECS system's FFI function.
+    pub unsafe fn new(handle: *const c_void) -> Self {
+        Self {
+            handle,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { _EVENT_COUNT_FN.unwrap_unchecked()(self.handle) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, T)> + '_ {
+        (0..self.len()).map(|i| unsafe {
+            let ptr = _EVENT_GET_FN.unwrap_unchecked()(self.handle, i);
+            // the entity id immediately follows `len`, followed by the component data
+            let entity_id = ptr.offset(1).cast::<EntityId>().read();
+            let component = ptr.offset(2).cast::<T>().read_unaligned();
+            (entity_id, component)
+        })
+    }
+}
+
+/// Exclusive access to the whole ECS world. Systems take it by value, and may
+/// not take any other inputs:
+///
+/// ```ignore
+/// #[system]
+/// fn generate_level(mut world: World) {
+///     let room = world.spawn(bundle!(&Transform::default()));
+///     world.get_mut::<Transform>(room).unwrap().position.x = 100.;
+/// }
+/// ```
+///
+/// Exclusive systems execute one at a time, after all other CPU systems have
+/// executed and their commands have been applied, and before GPU systems.
+/// Unlike [`Engine`] commands, changes made through `World` are applied
+/// immediately, so a spawned entity may be accessed right away.
+///
+/// [`Engine`] functions may also be called from exclusive systems. Their
+/// commands are applied along with the next change made through `World`, or
+/// when the system returns.
+pub struct World {
+    handle: *mut c_void,
+}
+
+/// The entities sharing a set of components, see [`World::archetypes()`].
+#[derive(Clone, Debug, Default)]
+pub struct Archetype {
+    /// The components of the archetype, in ascending order. Sparse components
+    /// are not included.
+    pub component_ids: Vec<ComponentId>,
+    pub entities: Vec<EntityId>,
+}
+
+impl World {
+    /// # Safety
+    ///
+    /// `World` should only be constructed from a valid pointer retrieved
+    /// from a corresponding `World` parameter in an ECS system's FFI function.
+    pub unsafe fn new(handle: *mut c_void) -> Self {
+        Self { handle }
+    }
+
+    /// Spawns an entity with the specified components.
+    ///
+    /// Returns the `EntityId` of the new entity.
+    pub fn spawn(&mut self, components: &[ComponentRef<'_>]) -> EntityId {
+        unsafe {
+            _WORLD_SPAWN_FN.unwrap_unchecked()(self.handle, components.as_ptr(), components.len())
+                .expect("could not spawn entity")
+        }
+    }
+
+    /// Despawns an entity, along with all of its descendants.
+    ///
+    /// Returns `false` if the entity does not exist.
+    pub fn despawn(&mut self, entity_id: EntityId) -> bool {
+        unsafe { _WORLD_DESPAWN_FN.unwrap_unchecked()(self.handle, entity_id, true) }
+    }
+
+    /// Same as [`World::despawn()`].
+    pub fn despawn_recursive(&mut self, entity_id: EntityId) -> bool {
+        self.despawn(entity_id)
+    }
+
+    /// Despawns an entity. Its children are not despawned, and become root
+    /// entities which keep their world space transform.
+    ///
+    /// Returns `false` if the entity does not exist.
+    pub fn despawn_non_recursive(&mut self, entity_id: EntityId) -> bool {
+        unsafe { _WORLD_DESPAWN_FN.unwrap_unchecked()(self.handle, entity_id, false) }
+    }
+
+    /// Adds components to an existing entity, replacing components of the same
+    /// type.
+    ///
+    /// Returns `false` if the entity does not exist.
+    pub fn add_components(&mut self, entity_id: EntityId, components: &[ComponentRef<'_>]) -> bool {
+        unsafe {
+            _WORLD_ADD_COMPONENTS_FN.unwrap_unchecked()(
+                self.handle,
+                entity_id,
+                components.as_ptr(),
+                components.len(),
+            )
+        }
+    }
+
+    /// Removes components from an existing entity.
+    ///
+    /// Returns `false` if the entity does not exist.
+    pub fn remove_components(
+        &mut self,
+        entity_id: EntityId,
+        component_ids: &[ComponentId],
+    ) -> bool {
+        unsafe {
+            _WORLD_REMOVE_COMPONENTS_FN.unwrap_unchecked()(
+                self.handle,
+                entity_id,
+                component_ids.as_ptr(),
+                component_ids.len(),
+            )
+        }
+    }
+
+    /// Returns a component of an entity, or `None` if the entity does not have
+    /// the component.
+    pub fn get<C: Component>(&self, entity_id: EntityId) -> Option<&C> {
+        let ptr = unsafe {
+            _WORLD_COMPONENT_FN.unwrap_unchecked()(self.handle, entity_id, C::id(), false)
+        };
+
+        unsafe { ptr.cast::<C>().as_ref() }
+    }
+
+    /// Returns a component of an entity, or `None` if the entity does not have
+    /// the component. The component is marked as changed.
+    pub fn get_mut<C: Component>(&mut self, entity_id: EntityId) -> Option<&mut C> {
+        let ptr = unsafe {
+            _WORLD_COMPONENT_FN.unwrap_unchecked()(self.handle, entity_id, C::id(), true)
+        };
+
+        unsafe { ptr.cast::<C>().as_mut() }
+    }
+
+    /// Returns a pointer to the data of any component of an entity, or null if
+    /// the entity does not have the component. This allows accessing components
+    /// whose types are only known at runtime, e.g. through
+    /// [`Engine::component_fields()`].
+    ///
+    /// If `mutable` is `true`, the component is marked as changed, and the data
+    /// may be written to until the next structural change.
+    pub fn component_ptr(
+        &mut self,
+        entity_id: EntityId,
+        component_id: ComponentId,
+        mutable: bool,
+    ) -> *mut c_void {
+        unsafe {
+            _WORLD_COMPONENT_FN.unwrap_unchecked()(self.handle, entity_id, component_id, mutable)
+        }
+    }
+
+    /// Returns all entities in the world.
+    pub fn entities(&self) -> Vec<EntityId> {
+        read_vec(|ptr: *mut EntityId, capacity, len| unsafe {
+            _WORLD_ENTITIES_FN.unwrap_unchecked()(self.handle, ptr.cast(), capacity, len);
+            true
+        })
+        .unwrap_or_default()
+    }
+
+    /// Returns the components of an entity, including sparse components, or
+    /// `None` if the entity does not exist.
+    pub fn entity_components(&self, entity_id: EntityId) -> Option<Vec<ComponentId>> {
+        read_vec(|ptr, capacity, len| unsafe {
+            _WORLD_ENTITY_COMPONENTS_FN.unwrap_unchecked()(
+                self.handle,
+                entity_id,
+                ptr,
+                capacity,
+                len,
+            )
+        })
+    }
+
+    /// Returns all archetypes, in unspecified order.
+    pub fn archetypes(&self) -> Vec<Archetype> {
+        let len = unsafe { _WORLD_ARCHETYPES_LEN_FN.unwrap_unchecked()(self.handle) };
+
+        (0..len)
+            .map(|index| Archetype {
+                component_ids: read_vec(|ptr, capacity, len| unsafe {
+                    _WORLD_ARCHETYPE_COMPONENTS_FN.unwrap_unchecked()(
+                        self.handle,
+                        index,
+                        ptr,
+                        capacity,
+                        len,
+                    )
+                })
+                .unwrap_or_default(),
+                entities: read_vec(|ptr: *mut EntityId, capacity, len| unsafe {
+                    _WORLD_ARCHETYPE_ENTITIES_FN.unwrap_unchecked()(
+                        self.handle,
+                        index,
+                        ptr.cast(),
+                        capacity,
+                        len,
+                    )
+                })
+                .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// Reads a variable length array from an engine function which writes up to
+/// `capacity` elements to `ptr`, and the total number of elements to `len`.
+/// Returns `None` if `f` returns `false`.
+fn read_vec<T, F>(mut f: F) -> Option<Vec<T>>
+where
+    F: FnMut(*mut T, usize, &mut usize) -> bool,
+{
+    let mut vec = Vec::new();
+
+    loop {
+        let mut len = 0;
+
+        if !f(vec.as_mut_ptr(), vec.capacity(), &mut len) {
+            return None;
+        }
+
+        if len <= vec.capacity() {
+            unsafe { vec.set_len(len) };
+            return Some(vec);
+        }
+
+        vec.reserve_exact(len);
+    }
+}
+
 /// A query is essentially an iterator over a number of entities, based on the specified
 /// template components. For example, a query of type `Query<&Transform>` will iterate over
 /// all the entities with a Transform component, and provide access to their `Transform` component.
 ///
 /// Generic `Q` specifies the components to include in this query. Components *must* be references.
 /// If the query specifies more than one component, `Q` should be a tuple (i.e. `Query<(&A, &B)>`).
+///
+/// Components may also be optional (`Option<&A>`), in which case entities without that component
+/// are still matched. The [`With`] and [`Without`] filters restrict the matched entities without
+/// providing access to the filtered component, i.e. `Query<(&A, Option<&B>, With<C>, Without<D>)>`.
+/// The [`Changed`] and [`Added`] filters additionally only match entities whose component was
+/// mutably accessed or added since the system last ran.
+///
+/// Accessing a component mutably marks it as changed, even if nothing is written to it. Prefer
+/// `&A` over `&mut A` for components which are only read, so that [`Changed`] filters of other
+/// systems only match entities which may actually have changed.
 #[repr(C)]
 pub struct Query<Q> {
     query_handle: *mut c_void,
@@ -1383,6 +2072,7 @@ impl<Q> Query<Q> {
         if res == 0 {
             Some(QueryComponentsRefMut {
                 q: unsafe { component_ptrs.assume_init() },
+                changed: unsafe { _QUERY_IS_CHANGED_FN.unwrap_unchecked()(self.query_handle) },
                 marker: PhantomData,
             })
         } else {
@@ -1459,6 +2149,7 @@ impl<Q> Query<Q> {
         if res == 0 {
             Some(QueryComponentsRefMut {
                 q: unsafe { component_ptrs.assume_init() },
+                changed: unsafe { _QUERY_IS_CHANGED_FN.unwrap_unchecked()(self.query_handle) },
                 marker: PhantomData,
             })
         } else {
@@ -1466,6 +2157,60 @@ impl<Q> Query<Q> {
         }
     }
 
+    /// Returns mutable references to the sets of components of several
+    /// entities in this query at once, e.g. to make two entities interact.
+    ///
+    /// Returns `None` if the lookup failed (i.e. any entity does not exist in
+    /// this query).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `entity_ids` are not all distinct.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use void_public::{Transform, Query};
+    ///
+    /// fn my_system(mut query: Query<&mut Transform>) {
+    ///     if let Some([mut attacker, mut target]) = query.get_many_mut([attacker_id, target_id]) {
+    ///         target.position = attacker.position;
+    ///     }
+    /// }
+    /// ```
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entity_ids: [EntityId; N],
+    ) -> Option<[QueryComponentsRefMut<'_, Q>; N]> {
+        let mut component_ptrs = MaybeUninit::<[Q; N]>::uninit();
+        let mut changed = [false; N];
+
+        let res = unsafe {
+            _QUERY_GET_MANY_FN.unwrap_unchecked()(
+                self.query_handle,
+                entity_ids.as_ptr(),
+                N,
+                (&mut component_ptrs as *mut MaybeUninit<[Q; N]>).cast(),
+                changed.as_mut_ptr(),
+            )
+        };
+
+        match res {
+            0 => {
+                let component_ptrs = unsafe { component_ptrs.assume_init() };
+                let mut changed = changed.into_iter();
+
+                Some(component_ptrs.map(|q| QueryComponentsRefMut {
+                    q,
+                    changed: changed.next().unwrap(),
+                    marker: PhantomData,
+                }))
+            }
+            2 => panic!("get_many_mut(): entity ids are not disjoint"),
+            _ => None,
+        }
+    }
+
     /// Returns an immutable reference to a set of components in this query.
     ///
     /// `label` is the label of an entity in this query to look up.
@@ -1537,6 +2282,7 @@ impl<Q> Query<Q> {
         if res == 0 {
             Some(QueryComponentsRefMut {
                 q: unsafe { component_ptrs.assume_init() },
+                changed: unsafe { _QUERY_IS_CHANGED_FN.unwrap_unchecked()(self.query_handle) },
                 marker: PhantomData,
             })
         } else {
@@ -1807,6 +2553,7 @@ impl<'a, Q> Iterator for QueryIterMut<'a, Q> {
 
         Some(QueryComponentsRefMut {
             q: unsafe { component_ptrs.assume_init() },
+            changed: unsafe { _QUERY_IS_CHANGED_FN.unwrap_unchecked()(self.query_handle) },
             marker: PhantomData,
         })
     }
@@ -1833,6 +2580,7 @@ impl<Q> DoubleEndedIterator for QueryIterMut<'_, Q> {
 
         Some(QueryComponentsRefMut {
             q: unsafe { component_ptrs.assume_init() },
+            changed: unsafe { _QUERY_IS_CHANGED_FN.unwrap_unchecked()(self.query_handle) },
             marker: PhantomData,
         })
     }
@@ -1889,6 +2637,7 @@ impl<Q> Deref for QueryComponentsRef<'_, Q> {
 #[derive(Debug)]
 pub struct QueryComponentsRefMut<'a, Q> {
     q: Q,
+    changed: bool,
     marker: PhantomData<&'a mut Q>,
 }
 
@@ -1896,6 +2645,13 @@ impl<Q> QueryComponentsRefMut<'_, Q> {
     pub fn unpack(&mut self) -> &mut Q {
         &mut self.q
     }
+
+    /// Returns whether any of these components were added or mutably accessed since the
+    /// current system last ran. Access through this `QueryComponentsRefMut` itself is not
+    /// counted.
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
 }
 
 impl<Q> Deref for QueryComponentsRefMut<'_, Q> {
@@ -1912,6 +2668,118 @@ impl<Q> DerefMut for QueryComponentsRefMut<'_, Q> {
     }
 }
 
+/// A query filter which only matches entities containing component `T`, without accessing it.
+///
+/// Query data is passed to modules as an array of pointers, so filters occupy a (null) pointer
+/// sized slot in the query tuple.
+///
+/// # Examples
+///
+/// ```
+/// use void_public::{Transform, Query, With, colors::Color};
+///
+/// fn my_system(mut query: Query<(&mut Transform, With<Color>)>) {
+///     query.for_each(|(transform, _)| {
+///
+///     });
+/// }
+/// ```
+#[repr(transparent)]
+pub struct With<T> {
+    _ptr: *const c_void,
+    marker: PhantomData<T>,
+}
+
+impl<T> std::fmt::Debug for With<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("With")
+    }
+}
+
+/// A query filter which only matches entities *not* containing component `T`.
+///
+/// Query data is passed to modules as an array of pointers, so filters occupy a (null) pointer
+/// sized slot in the query tuple.
+///
+/// # Examples
+///
+/// ```
+/// use void_public::{Transform, Query, Without, colors::Color};
+///
+/// fn my_system(mut query: Query<(&mut Transform, Without<Color>)>) {
+///     query.for_each(|(transform, _)| {
+///
+///     });
+/// }
+/// ```
+#[repr(transparent)]
+pub struct Without<T> {
+    _ptr: *const c_void,
+    marker: PhantomData<T>,
+}
+
+impl<T> std::fmt::Debug for Without<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Without")
+    }
+}
+
+/// A query filter which only matches entities whose component `T` has been added or mutably
+/// accessed since the system last ran. The first time a system runs, all components are
+/// considered changed.
+///
+/// Components are marked as changed whenever they are accessed mutably through a query, whether
+/// or not they were actually written to.
+///
+/// # Examples
+///
+/// ```
+/// use void_public::{Changed, Transform, Query, colors::Color};
+///
+/// fn my_system(mut query: Query<(&mut Color, Changed<Transform>)>) {
+///     query.for_each(|(color, _)| {
+///
+///     });
+/// }
+/// ```
+#[repr(transparent)]
+pub struct Changed<T> {
+    _ptr: *const c_void,
+    marker: PhantomData<T>,
+}
+
+impl<T> std::fmt::Debug for Changed<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Changed")
+    }
+}
+
+/// A query filter which only matches entities whose component `T` has been added since the system
+/// last ran. The first time a system runs, all components are considered added.
+///
+/// # Examples
+///
+/// ```
+/// use void_public::{Added, Transform, Query};
+///
+/// fn my_system(mut query: Query<(&Transform, Added<Transform>)>) {
+///     query.for_each(|(transform, _)| {
+///
+///     });
+/// }
+/// ```
+#[repr(transparent)]
+pub struct Added<T> {
+    _ptr: *const c_void,
+    marker: PhantomData<T>,
+}
+
+impl<T> std::fmt::Debug for Added<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Added")
+    }
+}
+
 #[repr(i32)]
 #[allow(unused)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -1938,15 +2806,62 @@ impl TryFrom<i32> for ForEachResult {
 // update the codegen crate responsible for generating the FFI boilerplate code,
 // as well as the `get_module_api_proc_addr` function in the `c_api` mod.
 
-pub static mut _LOAD_SCENE: Option<unsafe extern "C" fn(scene_str: *const c_char)> = None;
+pub static mut _LOAD_SCENE: Option<
+    unsafe extern "C" fn(scene_data: *const u8, scene_data_len: usize) -> SceneHandle,
+> = None;
+
+pub static mut _LOAD_SCENE_FROM_PATH: Option<
+    unsafe extern "C" fn(asset_path: *const c_char) -> SceneHandle,
+> = None;
+
+pub static mut _UNLOAD_SCENE: Option<unsafe extern "C" fn(scene_handle: SceneHandle)> = None;
+
+// scene migrations
+pub static mut _REGISTER_SCENE_MIGRATION: Option<
+    unsafe extern "C" fn(
+        from_version: *const c_char,
+        to_version: *const c_char,
+        component_name: *const c_char,
+        migrate_fn: SceneMigrationCallback,
+        user_data: *const c_void,
+    ),
+> = None;
+
+// prefabs
+pub static mut _REGISTER_PREFAB: Option<
+    unsafe extern "C" fn(name: *const c_char, prefab_json: *const c_char),
+> = None;
+
+pub static mut _REGISTER_PREFAB_FROM_PATH: Option<unsafe extern "C" fn(asset_path: *const c_char)> =
+    None;
+
+pub static mut _SPAWN_PREFAB: Option<
+    unsafe extern "C" fn(*const c_char, *const ComponentRef<'_>, usize) -> Option<EntityId>,
+> = None;
+
+pub static mut _SPAWN_PREFAB_WITH_OVERRIDES: Option<
+    unsafe extern "C" fn(
+        *const c_char,
+        *const ComponentRef<'_>,
+        usize,
+        *const PrefabOverride<'_>,
+        usize,
+    ) -> Option<EntityId>,
+> = None;
 
 // spawning
 pub static mut _SPAWN: Option<
     unsafe extern "C" fn(*const ComponentRef<'_>, usize) -> Option<EntityId>,
 > = None;
 
+pub static mut _SPAWN_BATCH: Option<
+    unsafe extern "C" fn(*const ComponentRef<'_>, usize, usize, *mut EntityId) -> bool,
+> = None;
+
 pub static mut _DESPAWN: Option<unsafe extern "C" fn(EntityId)> = None;
 
+pub static mut _DESPAWN_NON_RECURSIVE: Option<unsafe extern "C" fn(EntityId)> = None;
+
 pub static mut _ADD_COMPONENTS_FN: Option<
     unsafe extern "C" fn(EntityId, *const ComponentRef<'_>, usize),
 > = None;
@@ -1955,6 +2870,20 @@ pub static mut _REMOVE_COMPONENTS_FN: Option<
     unsafe extern "C" fn(EntityId, *const ComponentId, usize),
 > = None;
 
+// reflection
+pub static mut _COMPONENT_FIELDS_LEN_FN: Option<unsafe extern "C" fn(ComponentId) -> usize> = None;
+
+pub static mut _COMPONENT_FIELD_FN: Option<
+    unsafe extern "C" fn(ComponentId, usize, *mut reflect::FieldInfo) -> bool,
+> = None;
+
+// resources
+pub static mut _INSERT_RESOURCE_FN: Option<
+    unsafe extern "C" fn(ComponentId, *const MaybeUninit<u8>, usize),
+> = None;
+
+pub static mut _REMOVE_RESOURCE_FN: Option<unsafe extern "C" fn(ComponentId)> = None;
+
 pub static mut _ENTITY_LABEL_FN: Option<unsafe extern "C" fn(EntityId) -> *const c_char> = None;
 
 pub static mut _SET_ENTITY_LABEL_FN: Option<unsafe extern "C" fn(EntityId, *const c_char)> = None;
@@ -1978,10 +2907,16 @@ pub static mut _QUERY_GET_ENTITY_FN: Option<
     unsafe extern "C" fn(*mut c_void, EntityId, *mut *const c_void) -> i32,
 > = None;
 
+pub static mut _QUERY_GET_MANY_FN: Option<
+    unsafe extern "C" fn(*mut c_void, *const EntityId, usize, *mut *const c_void, *mut bool) -> i32,
+> = None;
+
 pub static mut _QUERY_GET_LABEL_FN: Option<
     unsafe extern "C" fn(*mut c_void, *const c_char, *mut *const c_void) -> i32,
 > = None;
 
+pub static mut _QUERY_IS_CHANGED_FN: Option<unsafe extern "C" fn(*const c_void) -> bool> = None;
+
 pub static mut _QUERY_FOR_EACH_FN: Option<
     unsafe extern "C" fn(
         *mut c_void,
@@ -2010,21 +2945,74 @@ pub static mut _SET_PARENT_FN: Option<unsafe extern "C" fn(EntityId, Option<Enti
 
 pub static mut _GET_PARENT_FN: Option<unsafe extern "C" fn(EntityId, *mut u64) -> bool> = None;
 
+pub static mut _GET_CHILDREN_FN: Option<
+    unsafe extern "C" fn(EntityId, *mut u64, usize, *mut usize) -> bool,
+> = None;
+
 // system meta
 pub static mut _SET_SYSTEM_ENABLED_FN: Option<unsafe extern "C" fn(*const c_char, bool)> = None;
 
+pub static mut _SET_MODULE_ERROR_FN: Option<unsafe extern "C" fn(*const c_char, usize)> = None;
+
+// states
+pub static mut _STATE_GET_FN: Option<unsafe extern "C" fn(*const c_char) -> u32> = None;
+
+pub static mut _SET_NEXT_STATE_FN: Option<unsafe extern "C" fn(*const c_char, u32)> = None;
+
+// exclusive world access
+pub static mut _WORLD_SPAWN_FN: Option<
+    unsafe extern "C" fn(*mut c_void, *const ComponentRef<'_>, usize) -> Option<EntityId>,
+> = None;
+
+pub static mut _WORLD_DESPAWN_FN: Option<
+    unsafe extern "C" fn(*mut c_void, EntityId, bool) -> bool,
+> = None;
+
+pub static mut _WORLD_ADD_COMPONENTS_FN: Option<
+    unsafe extern "C" fn(*mut c_void, EntityId, *const ComponentRef<'_>, usize) -> bool,
+> = None;
+
+pub static mut _WORLD_REMOVE_COMPONENTS_FN: Option<
+    unsafe extern "C" fn(*mut c_void, EntityId, *const ComponentId, usize) -> bool,
+> = None;
+
+pub static mut _WORLD_COMPONENT_FN: Option<
+    unsafe extern "C" fn(*mut c_void, EntityId, ComponentId, bool) -> *mut c_void,
+> = None;
+
+pub static mut _WORLD_ENTITIES_FN: Option<
+    unsafe extern "C" fn(*mut c_void, *mut u64, usize, *mut usize),
+> = None;
+
+pub static mut _WORLD_ENTITY_COMPONENTS_FN: Option<
+    unsafe extern "C" fn(*mut c_void, EntityId, *mut ComponentId, usize, *mut usize) -> bool,
+> = None;
+
+pub static mut _WORLD_ARCHETYPES_LEN_FN: Option<unsafe extern "C" fn(*mut c_void) -> usize> = None;
+
+pub static mut _WORLD_ARCHETYPE_COMPONENTS_FN: Option<
+    unsafe extern "C" fn(*mut c_void, usize, *mut ComponentId, usize, *mut usize) -> bool,
+> = None;
+
+pub static mut _WORLD_ARCHETYPE_ENTITIES_FN: Option<
+    unsafe extern "C" fn(*mut c_void, usize, *mut u64, usize, *mut usize) -> bool,
+> = None;
+
 // ENGINE INTERNALS - NOT COPIED TO RELEASE HEADERS
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum ComponentType {
     AsyncCompletion,
     Component,
     Resource,
+    /// A zero-sized tag component, declared with `#[component(sparse)]`, which
+    /// is stored outside of archetypes.
+    SparseComponent,
 }
 
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum ArgType {
     Completion,
     DataAccessMut,
@@ -2032,6 +3020,14 @@ pub enum ArgType {
     EventReader,
     EventWriter,
     Query,
+    DataAccessOptionalMut,
+    DataAccessOptionalRef,
+    With,
+    Without,
+    Added,
+    Changed,
+    RemovedComponents,
+    World,
 }
 
 /// `FfiVec` is intended to be used when transferring a Rust side Vec to C via
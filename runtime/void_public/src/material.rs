@@ -61,7 +61,18 @@ impl From<u32> for ShaderTemplateId {
 /// This is a handle identifying a material.
 #[repr(transparent)]
 #[derive(
-    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Pod, Zeroable, serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Pod,
+    Zeroable,
+    serde::Deserialize,
+    serde::Serialize,
 )]
 pub struct MaterialId(pub u32);
 
@@ -113,7 +124,7 @@ pub const UNIFORM_LIMIT: usize = 256;
 pub const TEXTURE_LIMIT: usize = 16;
 
 #[repr(C)]
-#[derive(Component, Debug, Pod, Zeroable, serde::Deserialize)]
+#[derive(Component, Debug, Pod, Zeroable, serde::Deserialize, serde::Serialize)]
 pub struct MaterialParameters {
     #[serde(default = "default_material_parameters_material_id")]
     material_id: MaterialId,
@@ -0,0 +1,86 @@
+//! Catches panics at the FFI boundary of modules and platform libraries.
+//!
+//! Unwinding out of an `extern "C"` function aborts the process, so the FFI
+//! functions generated by `build_tools` catch panics, and report them to the
+//! engine as errors instead.
+
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::RefCell,
+    panic::{self, UnwindSafe},
+    sync::Once,
+};
+
+use crate::_SET_MODULE_ERROR_FN;
+
+thread_local! {
+    /// The location and backtrace of the last panic on this thread, captured
+    /// by the panic hook.
+    static PANIC_CONTEXT: RefCell<Option<(String, Backtrace)>> = const { RefCell::new(None) };
+}
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+
+/// Calls `f`, returning a description of the panic if it panics. The
+/// description contains the panic payload, its location and a backtrace.
+#[doc(hidden)]
+pub fn catch_unwind<R>(f: impl FnOnce() -> R + UnwindSafe) -> Result<R, String> {
+    INSTALL_PANIC_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(ToString::to_string).unwrap_or_default();
+            PANIC_CONTEXT.set(Some((location, Backtrace::force_capture())));
+
+            previous_hook(info);
+        }));
+    });
+
+    panic::catch_unwind(f).map_err(|payload| {
+        let message = payload_message(payload.as_ref());
+
+        match PANIC_CONTEXT.take() {
+            Some((location, backtrace)) => {
+                format!("panicked at {location}: {message}\n{backtrace}")
+            }
+            None => format!("panicked: {message}"),
+        }
+    })
+}
+
+/// Reports the error of the module function executing on this thread to the
+/// engine, which returns it instead of the function's error code.
+#[doc(hidden)]
+pub fn set_module_error(message: &str) {
+    unsafe {
+        if let Some(set_module_error) = _SET_MODULE_ERROR_FN {
+            set_module_error(message.as_ptr().cast(), message.len());
+        }
+    }
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catch_unwind_describes_panic() {
+        let message = catch_unwind(|| panic!("bad {}", "input")).unwrap_err();
+
+        assert!(message.starts_with("panicked at "));
+        assert!(message.contains("panic.rs"));
+        assert!(message.contains(": bad input\n"));
+        assert_eq!(catch_unwind(|| 1).unwrap(), 1);
+    }
+}
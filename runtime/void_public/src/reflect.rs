@@ -0,0 +1,431 @@
+//! Reflection of component layouts, which allows tools to display and edit any
+//! component generically.
+//!
+//! `#[derive(Component)]` implements [`Reflect`], describing each field of the
+//! component with its name, type and byte offset. Fields of nested structs
+//! which implement [`Reflect`] are described too, and other types are
+//! described as [`FieldType::Opaque`]. Nested structs which are not components
+//! may `#[derive(Reflect)]`.
+//!
+//! ```ignore
+//! #[derive(Reflect)]
+//! #[repr(C)]
+//! struct Stats {
+//!     health: f32,
+//!     armor: f32,
+//! }
+//!
+//! #[derive(Component)]
+//! #[repr(C)]
+//! struct Enemy {
+//!     target: EntityId,
+//!     stats: Stats,
+//! }
+//! ```
+//!
+//! `Enemy` is described by the fields `target`, `stats`, `stats.health` and
+//! `stats.armor`, in that order.
+//!
+//! Components which implement [`Reflect`] manually are declared with
+//! `#[component(custom_reflect)]`.
+//!
+//! Fields holding the ids of assets, like [`TextureId`] or [`MaterialId`], are
+//! described by their own [`FieldType`]. Scenes may reference the asset of such
+//! a field by its asset path or name instead of its id, e.g.
+//! `"icon": "textures/sword.png"`, and the asset is loaded with the scene.
+//! [`AssetId`] fields may hold the id of any kind of asset, so their kind is
+//! declared with an attribute, e.g. `#[reflect(particle_effect)]`.
+//! Likewise, scenes may reference another entity of the scene from an
+//! [`EntityId`] field by the entity's `id`, e.g. `"target": "12"`. References
+//! to entities which are not in the scene are cleared, so such fields should be
+//! `Option<EntityId>`.
+
+use std::{
+    ffi::{CStr, CString, c_char},
+    marker::PhantomData,
+    mem::size_of,
+};
+
+use crate::{
+    AssetId, EntityId, Quat, Vec2, Vec3, Vec4, graphics::TextureId, linalg, material::MaterialId,
+    text::TextId,
+};
+
+/// The type of a reflected field.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, snapshot::Serialize, snapshot::Deserialize)]
+pub enum FieldType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    EntityId,
+    /// A fixed size array. Elements are not described.
+    Array,
+    /// A struct which implements [`Reflect`]. Its fields follow it in the
+    /// field table.
+    Struct,
+    /// A type which does not implement [`Reflect`].
+    Opaque,
+    // Variants are only appended, as the type is shared with modules.
+    /// A [`TextureId`]. Scenes reference the texture by its asset path.
+    TextureId,
+    /// A [`TextId`]. Scenes reference the text by its asset path.
+    TextId,
+    /// A [`MaterialId`]. Scenes reference the material by its name.
+    MaterialId,
+    /// An [`AssetId`] of an unknown kind of asset, which scenes reference by
+    /// its id.
+    AssetId,
+    /// An [`AssetId`] of a particle effect descriptor, declared with
+    /// `#[reflect(particle_effect)]`. Scenes reference the descriptor by its
+    /// asset path.
+    ParticleEffectId,
+}
+
+impl FieldType {
+    /// Whether the field references an asset, which scenes may reference by
+    /// its asset path or name instead of its id.
+    pub fn is_asset(self) -> bool {
+        matches!(
+            self,
+            Self::TextureId | Self::TextId | Self::MaterialId | Self::ParticleEffectId
+        )
+    }
+}
+
+/// A field in a flattened field table. Fields of nested structs immediately
+/// follow the field of the struct itself, and refer to it with `parent`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct FieldInfo {
+    pub name: *const c_char,
+    /// The type as written in the source, e.g. `glam::Vec3`.
+    pub type_name: *const c_char,
+    pub field_type: FieldType,
+    /// The byte offset of the field, from the start of the component.
+    pub offset: usize,
+    pub size: usize,
+    /// The index of the enclosing struct field, or [`FieldInfo::NO_PARENT`].
+    pub parent: usize,
+}
+
+// Strings referenced by `FieldInfo` are static.
+unsafe impl Send for FieldInfo {}
+unsafe impl Sync for FieldInfo {}
+
+impl FieldInfo {
+    pub const NO_PARENT: usize = usize::MAX;
+}
+
+/// An owned copy of a [`FieldInfo`], as stored by the engine.
+#[derive(Clone, Debug, PartialEq, Eq, snapshot::Serialize, snapshot::Deserialize)]
+pub struct ComponentField {
+    pub name: CString,
+    pub type_name: CString,
+    pub field_type: FieldType,
+    pub offset: usize,
+    pub size: usize,
+    pub parent: Option<usize>,
+}
+
+impl ComponentField {
+    /// # Safety
+    ///
+    /// The strings referenced by `field` must be valid C strings.
+    pub unsafe fn from_field_info(field: &FieldInfo) -> Self {
+        unsafe {
+            Self {
+                name: CStr::from_ptr(field.name).to_owned(),
+                type_name: CStr::from_ptr(field.type_name).to_owned(),
+                field_type: field.field_type,
+                offset: field.offset,
+                size: field.size,
+                parent: (field.parent != FieldInfo::NO_PARENT).then_some(field.parent),
+            }
+        }
+    }
+
+    /// Returns a `FieldInfo` which borrows the strings of this field.
+    pub fn field_info(&self) -> FieldInfo {
+        FieldInfo {
+            name: self.name.as_ptr(),
+            type_name: self.type_name.as_ptr(),
+            field_type: self.field_type,
+            offset: self.offset,
+            size: self.size,
+            parent: self.parent.unwrap_or(FieldInfo::NO_PARENT),
+        }
+    }
+}
+
+/// A type whose layout may be described to tools. Implemented by
+/// `#[derive(Component)]` and `#[derive(Reflect)]`.
+pub trait Reflect {
+    const FIELD_TYPE: FieldType = FieldType::Struct;
+
+    /// The flattened field table of this type.
+    fn fields() -> &'static [FieldInfo] {
+        &[]
+    }
+}
+
+macro_rules! reflect_primitive {
+    ($($ty:ty => $field_type:ident,)*) => {
+        $(
+            impl Reflect for $ty {
+                const FIELD_TYPE: FieldType = FieldType::$field_type;
+            }
+        )*
+    };
+}
+
+reflect_primitive! {
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    f32 => F32,
+    f64 => F64,
+    EntityId => EntityId,
+    TextureId => TextureId,
+    TextId => TextId,
+    MaterialId => MaterialId,
+    AssetId => AssetId,
+}
+
+// `None` is stored as 0, as entity ids are non-zero.
+impl Reflect for Option<EntityId> {
+    const FIELD_TYPE: FieldType = FieldType::EntityId;
+}
+
+impl<T, const N: usize> Reflect for [T; N] {
+    const FIELD_TYPE: FieldType = FieldType::Array;
+}
+
+// `glam` vectors and quaternions are laid out as arrays of `f32`.
+macro_rules! reflect_float_vector {
+    ($($ty:ty => [$($field:literal = $index:literal),*],)*) => {
+        $(
+            impl Reflect for $ty {
+                fn fields() -> &'static [FieldInfo] {
+                    const FIELDS: &[FieldInfo] = &[$(FieldInfo {
+                        name: $field.as_ptr(),
+                        type_name: c"f32".as_ptr(),
+                        field_type: FieldType::F32,
+                        offset: $index * size_of::<f32>(),
+                        size: size_of::<f32>(),
+                        parent: FieldInfo::NO_PARENT,
+                    }),*];
+                    FIELDS
+                }
+            }
+        )*
+    };
+}
+
+reflect_float_vector! {
+    Vec2 => [c"x" = 0, c"y" = 1],
+    Vec3 => [c"x" = 0, c"y" = 1, c"z" = 2],
+    Vec4 => [c"x" = 0, c"y" = 1, c"z" = 2, c"w" = 3],
+    Quat => [c"x" = 0, c"y" = 1, c"z" = 2, c"w" = 3],
+    linalg::Vec2 => [c"x" = 0, c"y" = 1],
+    linalg::Vec3 => [c"x" = 0, c"y" = 1, c"z" = 2],
+    linalg::Vec4 => [c"x" = 0, c"y" = 1, c"z" = 2, c"w" = 3],
+}
+
+/// A field of a `Reflect` type, before flattening. Used by the `Reflect`
+/// derive macros.
+#[doc(hidden)]
+pub struct FieldDescriptor {
+    pub name: &'static CStr,
+    pub type_name: &'static CStr,
+    pub field_type: FieldType,
+    pub offset: usize,
+    pub size: usize,
+    pub fields: &'static [FieldInfo],
+}
+
+/// Builds the flattened field table of a type from its direct fields.
+#[doc(hidden)]
+pub fn flatten_fields(descriptors: &[FieldDescriptor]) -> Vec<FieldInfo> {
+    let mut fields = Vec::new();
+
+    for descriptor in descriptors {
+        let index = fields.len();
+
+        fields.push(FieldInfo {
+            name: descriptor.name.as_ptr(),
+            type_name: descriptor.type_name.as_ptr(),
+            field_type: descriptor.field_type,
+            offset: descriptor.offset,
+            size: descriptor.size,
+            parent: FieldInfo::NO_PARENT,
+        });
+
+        fields.extend(descriptor.fields.iter().map(|nested| FieldInfo {
+            offset: descriptor.offset + nested.offset,
+            parent: if nested.parent == FieldInfo::NO_PARENT {
+                index
+            } else {
+                index + 1 + nested.parent
+            },
+            ..*nested
+        }));
+    }
+
+    fields
+}
+
+/// Describes a field type whether or not it implements [`Reflect`], by
+/// autoref specialization: `(&ReflectProbe::<T>::new()).field_type()` resolves
+/// to [`ReflectProbeReflect`] if `T: Reflect`, and [`ReflectProbeOpaque`]
+/// otherwise. `T` must be a concrete type.
+#[doc(hidden)]
+pub struct ReflectProbe<T>(PhantomData<T>);
+
+impl<T> ReflectProbe<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ReflectProbeReflect {
+    fn field_type(&self) -> FieldType;
+
+    fn fields(&self) -> &'static [FieldInfo];
+}
+
+impl<T: Reflect> ReflectProbeReflect for ReflectProbe<T> {
+    fn field_type(&self) -> FieldType {
+        T::FIELD_TYPE
+    }
+
+    fn fields(&self) -> &'static [FieldInfo] {
+        T::fields()
+    }
+}
+
+#[doc(hidden)]
+pub trait ReflectProbeOpaque {
+    fn field_type(&self) -> FieldType;
+
+    fn fields(&self) -> &'static [FieldInfo];
+}
+
+impl<T> ReflectProbeOpaque for &ReflectProbe<T> {
+    fn field_type(&self) -> FieldType {
+        FieldType::Opaque
+    }
+
+    fn fields(&self) -> &'static [FieldInfo] {
+        &[]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use game_module_macro::Reflect;
+
+    use super::*;
+
+    #[derive(Reflect)]
+    #[repr(C)]
+    struct Stats {
+        health: f32,
+        position: Vec2,
+    }
+
+    #[derive(Reflect)]
+    #[repr(C)]
+    struct Enemy {
+        target: EntityId,
+        leader: Option<EntityId>,
+        stats: Stats,
+        name: String,
+    }
+
+    #[derive(Reflect)]
+    #[repr(C)]
+    struct Effect {
+        #[reflect(particle_effect)]
+        descriptor_id: AssetId,
+        other_id: AssetId,
+    }
+
+    #[test]
+    fn asset_kinds_are_declared() {
+        let field_types = Effect::fields()
+            .iter()
+            .map(|field| field.field_type)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            field_types,
+            [FieldType::ParticleEffectId, FieldType::AssetId]
+        );
+        assert!(FieldType::ParticleEffectId.is_asset());
+        assert!(!FieldType::AssetId.is_asset());
+    }
+
+    #[test]
+    fn field_types_are_stable() {
+        // the discriminants are shared with modules and tools
+        assert_eq!(FieldType::EntityId as u8, 11);
+        assert_eq!(FieldType::Opaque as u8, 14);
+        assert_eq!(FieldType::TextureId as u8, 15);
+        assert_eq!(FieldType::ParticleEffectId as u8, 19);
+    }
+
+    #[test]
+    fn nested_fields() {
+        let fields = Enemy::fields()
+            .iter()
+            .map(|field| unsafe { ComponentField::from_field_info(field) })
+            .map(|field| {
+                (
+                    field.name.into_string().unwrap(),
+                    field.field_type,
+                    field.offset,
+                    field.parent,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let stats = std::mem::offset_of!(Enemy, stats);
+        let position = stats + std::mem::offset_of!(Stats, position);
+
+        assert_eq!(
+            fields,
+            [
+                ("target".into(), FieldType::EntityId, 0, None),
+                ("leader".into(), FieldType::EntityId, 8, None),
+                ("stats".into(), FieldType::Struct, stats, None),
+                ("health".into(), FieldType::F32, stats, Some(2)),
+                ("position".into(), FieldType::Struct, position, Some(2)),
+                ("x".into(), FieldType::F32, position, Some(4)),
+                ("y".into(), FieldType::F32, position + 4, Some(4)),
+                (
+                    "name".into(),
+                    FieldType::Opaque,
+                    std::mem::offset_of!(Enemy, name),
+                    None
+                ),
+            ]
+        );
+    }
+}
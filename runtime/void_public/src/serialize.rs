@@ -1,8 +1,9 @@
 use std::fmt::Formatter;
 
 use serde::{
-    Deserialize, Deserializer,
+    Deserialize, Deserializer, Serialize, Serializer,
     de::{Error, MapAccess, SeqAccess, Visitor},
+    ser::SerializeStruct,
 };
 
 use crate::{
@@ -99,6 +100,26 @@ impl<'de, T: Copy + serde::Deserialize<'de>> serde::Deserialize<'de> for FfiOpti
     }
 }
 
+impl<T: Copy + serde::Serialize> serde::Serialize for FfiOption<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.borrow().serialize(serializer)
+    }
+}
+
+pub(crate) fn serialize_text_render_text_field<S>(
+    text: &[u8; crate::graphics::TEXT_RENDER_SIZE],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let text = TextRender::u8array_to_str(text).map_err(serde::ser::Error::custom)?;
+    serializer.serialize_str(text)
+}
+
 pub(crate) fn deserialize_text_render_text_field<'de, D>(
     deserializer: D,
 ) -> Result<[u8; crate::graphics::TEXT_RENDER_SIZE], D::Error>
@@ -468,3 +489,59 @@ impl<'de> Deserialize<'de> for Mat4 {
         deserializer.deserialize_struct("Mat4", FIELDS, Mat4Visitor)
     }
 }
+
+// The vector and matrix types are serialized in the object representation, so
+// that they may be read by the JS side.
+
+impl Serialize for Vec2 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Vec2", 2)?;
+        state.serialize_field("x", &self.x)?;
+        state.serialize_field("y", &self.y)?;
+        state.end()
+    }
+}
+
+impl Serialize for Vec3 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Vec3", 3)?;
+        state.serialize_field("x", &self.x)?;
+        state.serialize_field("y", &self.y)?;
+        state.serialize_field("z", &self.z)?;
+        state.end()
+    }
+}
+
+impl Serialize for Vec4 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Vec4", 4)?;
+        state.serialize_field("x", &self.x)?;
+        state.serialize_field("y", &self.y)?;
+        state.serialize_field("z", &self.z)?;
+        state.serialize_field("w", &self.w)?;
+        state.end()
+    }
+}
+
+impl Serialize for Mat4 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Mat4", 4)?;
+        state.serialize_field("x_axis", &Vec4::new(self.x_axis))?;
+        state.serialize_field("y_axis", &Vec4::new(self.y_axis))?;
+        state.serialize_field("z_axis", &Vec4::new(self.z_axis))?;
+        state.serialize_field("w_axis", &Vec4::new(self.w_axis))?;
+        state.end()
+    }
+}
@@ -0,0 +1,89 @@
+//! Game states, such as a main menu, gameplay or a pause screen.
+//!
+//! A state type is a fieldless enum which derives `States`. The engine stores
+//! the current value of each state type, which begins as the first variant.
+//!
+//! ```ignore
+//! #[derive(Clone, Copy, PartialEq, Eq, States)]
+//! enum GameState {
+//!     Menu,
+//!     Playing,
+//!     Paused,
+//! }
+//!
+//! #[system(run_if = in_state(GameState::Playing))]
+//! fn pause(input_state: &InputState) {
+//!     if input_state.keys[KeyCode::Escape].just_pressed() {
+//!         NextState::set(GameState::Paused);
+//!     }
+//! }
+//!
+//! #[system_on_enter(GameState::Playing)]
+//! fn spawn_level() {
+//!     // runs once, each time the game enters `GameState::Playing`
+//! }
+//! ```
+//!
+//! Transitions requested with [`NextState::set()`] are applied at the start of
+//! the following frame, before any other systems run. The `system_on_exit`
+//! systems of the previous state run first, followed by the `system_on_enter`
+//! systems of the new state.
+
+use std::{ffi::CStr, marker::PhantomData};
+
+use crate::{_SET_NEXT_STATE_FN, _STATE_GET_FN};
+
+/// A state type, whose current value is stored by the engine. Derive this trait
+/// with `#[derive(States)]`.
+pub trait States: Copy + Eq + Send + Sync + 'static {
+    fn string_id() -> &'static CStr;
+
+    /// Returns the index of the variant, in declaration order.
+    fn index(self) -> u32;
+
+    /// Returns the variant at `index`, in declaration order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    fn from_index(index: u32) -> Self;
+}
+
+/// A condition on the state of the game, declared on a system with
+/// `#[system(run_if = in_state(...))]`, `#[system_on_enter(...)]` or
+/// `#[system_on_exit(...)]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateCondition {
+    /// The system only runs while in the target state.
+    InState,
+    /// The system runs once when entering the target state, instead of every
+    /// frame.
+    OnEnter,
+    /// The system runs once when exiting the target state, instead of every
+    /// frame.
+    OnExit,
+}
+
+/// The current value of the state type `S`.
+pub struct State<S>(PhantomData<S>);
+
+impl<S: States> State<S> {
+    pub fn get() -> S {
+        let index = unsafe { _STATE_GET_FN.unwrap_unchecked()(S::string_id().as_ptr()) };
+        S::from_index(index)
+    }
+}
+
+/// Requests a transition of the state type `S`.
+pub struct NextState<S>(PhantomData<S>);
+
+impl<S: States> NextState<S> {
+    /// Sets the next state, which is entered at the start of the following
+    /// frame. If called multiple times within a frame, the last call wins.
+    pub fn set(state: S) {
+        unsafe {
+            _SET_NEXT_STATE_FN.unwrap_unchecked()(S::string_id().as_ptr(), state.index());
+        }
+    }
+}
@@ -1,5 +1,25 @@
 use std::ffi::{CStr, CString};
 
+/// An ordering constraint declared on a system, i.e.
+/// `#[system(after = other_system, before = "render", set = "physics")]`.
+///
+/// System targets (`Before` and `After`) refer to systems in the same module.
+/// Set labels are global, so they can be used to order systems across modules.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SystemOrdering {
+    /// The system runs before the target system.
+    Before,
+    /// The system runs after the target system.
+    After,
+    /// The system runs before all systems in the target set.
+    BeforeSet,
+    /// The system runs after all systems in the target set.
+    AfterSet,
+    /// The system is a member of the target set.
+    InSet,
+}
+
 pub fn system_name_generator(module_name: &str, system_name: &str) -> String {
     format!("{module_name}::{system_name}")
 }
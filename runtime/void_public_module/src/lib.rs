@@ -130,6 +130,28 @@ pub fn component_deserialize_json_ffi(
     }
 }
 
+/// A helper function to serialize a component into JSON using the C FFI.
+pub fn component_serialize_json_ffi(
+    component_serialize_json_c: unsafe extern "C" fn(
+        string_id: *const c_char,
+        val: *const c_void,
+        writer: *mut c_void,
+        write: unsafe extern "C" fn(writer: *mut c_void, buf: *const c_void, len: usize) -> isize,
+    ) -> i32,
+    string_id: &CStr,
+    val: &[MaybeUninit<u8>],
+) -> Result<String, Box<dyn Error + Send + Sync>> {
+    let mut json = Vec::new();
+
+    // The JSON text is written the same way as serialized resources.
+    resource_serialize_ffi(component_serialize_json_c, string_id, val, &mut |buf| {
+        json.extend(buf.iter().map(|byte| unsafe { byte.assume_init() }));
+        Ok(buf.len())
+    })?;
+
+    Ok(String::from_utf8(json)?)
+}
+
 pub mod ffi {
     use super::*;
 
@@ -129,6 +129,12 @@ fn main() {
                 if should_run_frame {
                     engine.frame();
                 }
+
+                if engine.is_aborted() {
+                    // Report the errors which caused the abort before exiting.
+                    tooling_ipc.process_ipc_messages(&mut engine);
+                    window.exit();
+                }
             }
             _ => {}
         })
@@ -0,0 +1,65 @@
+//! Scene upgrade tool.
+//!
+//! This tool upgrades scene json files in place to the current version of the
+//! scene format, by applying the engine's scene migrations, which are
+//! registered by `SceneMigrations::default()`.
+//!
+//! See [`../README.md`]
+
+use std::{
+    fs::{read_to_string, write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use clap::Parser;
+use game_scene::{SceneMigrations, VERSION};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Only report the scenes which need to be upgraded, without writing them.
+    #[arg(short, long)]
+    check: bool,
+
+    /// The scene files to upgrade.
+    #[arg(required = true)]
+    scenes: Vec<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let migrations = SceneMigrations::default();
+    let mut success = true;
+
+    for path in &args.scenes {
+        let upgraded = read_to_string(path)
+            .map_err(Into::into)
+            .and_then(|scene_file| migrations.upgrade_scene_file(&scene_file));
+
+        match upgraded {
+            Ok(None) => println!("{}: up to date", path.display()),
+            Ok(Some(_)) if args.check => {
+                println!("{}: needs upgrade to {VERSION}", path.display());
+                success = false;
+            }
+            Ok(Some(scene_file)) => match write(path, scene_file) {
+                Ok(()) => println!("{}: upgraded to {VERSION}", path.display()),
+                Err(err) => {
+                    eprintln!("{}: could not write scene: {err}", path.display());
+                    success = false;
+                }
+            },
+            Err(err) => {
+                eprintln!("{}: {err}", path.display());
+                success = false;
+            }
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}